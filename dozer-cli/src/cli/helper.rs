@@ -1,3 +1,4 @@
+use crate::config::ConfigLoader;
 use crate::config_helper::combine_config;
 use crate::errors::CliError;
 use crate::errors::CliError::{ConfigurationFilePathNotProvided, FailedToFindConfigurationFiles};
@@ -174,9 +175,11 @@ pub fn load_config_from_file(
 }
 
 fn parse_config(config_template: &str) -> Result<Config, CliError> {
+    let config_template = ConfigLoader::resolve_env_vars(config_template)?;
+
     let mut handlebars = Handlebars::new();
     handlebars
-        .register_template_string("config", config_template)
+        .register_template_string("config", &config_template)
         .map_err(|e| CliError::FailedToParseYaml(Box::new(e)))?;
 
     let mut data = BTreeMap::new();
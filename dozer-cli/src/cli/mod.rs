@@ -1,8 +1,14 @@
 pub mod cloud;
 mod helper;
 mod init;
+mod repl;
+mod status;
 pub mod types;
+mod validate;
 pub use helper::{
     get_base_dir, init_config, init_dozer, list_sources, load_config_from_file, LOGO,
 };
 pub use init::{generate_config_repl, generate_connection};
+pub use repl::run_repl;
+pub use status::print_sink_lags;
+pub use validate::{validate_config_files, validate_config_yaml, ConfigValidationError};
@@ -0,0 +1,266 @@
+use dozer_types::chrono::NaiveDateTime;
+use dozer_types::grpc_types::contract::{
+    contract_service_client::ContractServiceClient, execute_query_response, CommonRequest,
+    ExecuteQueryRequest,
+};
+use dozer_types::grpc_types::types::{value, Operation, OperationType, Schema, Type, Value};
+use dozer_types::json_types::{json_to_string, prost_to_json_value};
+use dozer_types::log::{error, info};
+use dozer_types::prettytable::{Cell, Row, Table};
+use dozer_types::rust_decimal::Decimal;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use tonic::transport::Channel;
+
+use crate::errors::CliError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReplCommand<'a> {
+    Quit,
+    Schema(&'a str),
+    Query(&'a str),
+    Unknown(&'a str),
+}
+
+fn parse_command(line: &str) -> ReplCommand<'_> {
+    let line = line.trim().trim_end_matches(';');
+    if !line.starts_with('.') {
+        return ReplCommand::Query(line);
+    }
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(".quit"), None, None) => ReplCommand::Quit,
+        (Some(".schema"), Some(table), None) => ReplCommand::Schema(table),
+        _ => ReplCommand::Unknown(line),
+    }
+}
+
+/// Reads queries and meta-commands from the terminal and runs them on the app UI server at `url`.
+pub async fn run_repl(url: String, timeout_ms: u64) -> Result<(), CliError> {
+    let mut client = ContractServiceClient::connect(url).await?;
+    let mut rl = DefaultEditor::new()?;
+    loop {
+        let line = match rl.readline("dozer> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        rl.add_history_entry(line.as_str())?;
+
+        let result = match parse_command(&line) {
+            ReplCommand::Quit => return Ok(()),
+            ReplCommand::Schema(table) => print_schema(&mut client, table).await,
+            ReplCommand::Query(sql) => run_query(&mut client, sql, timeout_ms).await,
+            ReplCommand::Unknown(command) => Err(format!(
+                "Unknown command {command}. Use .schema <table> or .quit"
+            )),
+        };
+        if let Err(e) = result {
+            error!("{e}");
+        }
+    }
+}
+
+async fn print_schema(
+    client: &mut ContractServiceClient<Channel>,
+    table_name: &str,
+) -> Result<(), String> {
+    let schemas = client
+        .get_graph_schemas(CommonRequest { cloud_id: None })
+        .await
+        .map_err(|status| status.message().to_string())?
+        .into_inner()
+        .schemas;
+    let schema = [
+        format!("source::source::{table_name}"),
+        format!("sink::table::{table_name}"),
+    ]
+    .iter()
+    .find_map(|key| schemas.get(key))
+    .ok_or_else(|| format!("Table {table_name} not found"))?;
+
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("name"),
+        Cell::new("type"),
+        Cell::new("nullable"),
+        Cell::new("primary key"),
+    ]));
+    for (index, field) in schema.fields.iter().enumerate() {
+        let typ = Type::try_from(field.typ).map_or_else(
+            |_| field.typ.to_string(),
+            |typ| typ.as_str_name().to_string(),
+        );
+        table.add_row(Row::new(vec![
+            Cell::new(&field.name),
+            Cell::new(&typ),
+            Cell::new(&field.nullable.to_string()),
+            Cell::new(&schema.primary_index.contains(&(index as i32)).to_string()),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+async fn run_query(
+    client: &mut ContractServiceClient<Channel>,
+    sql: &str,
+    timeout_ms: u64,
+) -> Result<(), String> {
+    let mut stream = client
+        .execute_query(ExecuteQueryRequest {
+            cloud_id: None,
+            sql: sql.to_string(),
+            timeout_ms,
+        })
+        .await
+        .map_err(|status| status.message().to_string())?
+        .into_inner();
+
+    let mut schema = None;
+    let mut rows = vec![];
+    while let Some(response) = stream
+        .message()
+        .await
+        .map_err(|status| status.message().to_string())?
+    {
+        match response.response {
+            Some(execute_query_response::Response::Schema(s)) => schema = Some(s),
+            Some(execute_query_response::Response::Operation(operation)) => {
+                apply_operation(&mut rows, operation)
+            }
+            None => {}
+        }
+    }
+
+    print_rows(schema.as_ref(), &rows);
+    info!("{} record(s)", rows.len());
+    Ok(())
+}
+
+/// Applies an operation on the query's output to the rows received so far.
+fn apply_operation(rows: &mut Vec<Vec<Value>>, operation: Operation) {
+    let new = operation
+        .new
+        .map(|record| record.values)
+        .unwrap_or_default();
+    let old = operation.old.map(|record| record.values);
+    match OperationType::try_from(operation.typ) {
+        Ok(OperationType::Insert) => rows.push(new),
+        Ok(OperationType::Delete) => {
+            if let Some(index) = rows.iter().position(|row| row == &new) {
+                rows.remove(index);
+            }
+        }
+        Ok(OperationType::Update) => {
+            match old.and_then(|old| rows.iter().position(|row| row == &old)) {
+                Some(index) => rows[index] = new,
+                None => rows.push(new),
+            }
+        }
+        Err(_) => {}
+    }
+}
+
+fn print_rows(schema: Option<&Schema>, rows: &[Vec<Value>]) {
+    let mut table = Table::new();
+    if let Some(schema) = schema {
+        table.set_titles(Row::new(
+            schema
+                .fields
+                .iter()
+                .map(|field| Cell::new(&field.name))
+                .collect(),
+        ));
+    }
+    for row in rows {
+        table.add_row(Row::new(
+            row.iter()
+                .map(|value| Cell::new(&value_to_string(value)))
+                .collect(),
+        ));
+    }
+    table.printstd();
+}
+
+fn value_to_string(value: &Value) -> String {
+    let Some(value) = &value.value else {
+        return "null".to_string();
+    };
+    match value {
+        value::Value::UintValue(n) => n.to_string(),
+        value::Value::IntValue(n) => n.to_string(),
+        value::Value::FloatValue(n) => n.to_string(),
+        value::Value::BoolValue(b) => b.to_string(),
+        value::Value::Uint128Value(s)
+        | value::Value::Int128Value(s)
+        | value::Value::StringValue(s)
+        | value::Value::DateValue(s) => s.clone(),
+        value::Value::BytesValue(b) => format!("{b:?}"),
+        value::Value::DecimalValue(d) => {
+            Decimal::from_parts(d.lo, d.mid, d.hi, d.negative, d.scale).to_string()
+        }
+        value::Value::TimestampValue(ts) => {
+            NaiveDateTime::from_timestamp_opt(ts.seconds, ts.nanos as u32)
+                .map_or_else(|| format!("{ts:?}"), |ts| ts.to_string())
+        }
+        value::Value::PointValue(p) => format!("({}, {})", p.x, p.y),
+        value::Value::DurationValue(d) => format!("{} {}", d.value, d.time_unit),
+        value::Value::JsonValue(json) => json_to_string(&prost_to_json_value(json.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::grpc_types::types::Record;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command(".quit"), ReplCommand::Quit);
+        assert_eq!(
+            parse_command(" .schema users "),
+            ReplCommand::Schema("users")
+        );
+        assert_eq!(parse_command(".schema"), ReplCommand::Unknown(".schema"));
+        assert_eq!(
+            parse_command("SELECT id FROM users;"),
+            ReplCommand::Query("SELECT id FROM users")
+        );
+    }
+
+    fn operation(typ: OperationType, old: Option<u64>, new: u64) -> Operation {
+        let record = |n| Record {
+            values: vec![Value {
+                value: Some(value::Value::UintValue(n)),
+            }],
+            id: 0,
+            version: 0,
+        };
+        Operation {
+            typ: typ as i32,
+            old: old.map(record),
+            new: Some(record(new)),
+            endpoint: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_operation() {
+        let mut rows = vec![];
+        apply_operation(&mut rows, operation(OperationType::Insert, None, 1));
+        apply_operation(&mut rows, operation(OperationType::Insert, None, 2));
+        apply_operation(&mut rows, operation(OperationType::Update, Some(1), 3));
+        apply_operation(&mut rows, operation(OperationType::Delete, None, 2));
+        assert_eq!(
+            rows.iter()
+                .map(|row| value_to_string(&row[0]))
+                .collect::<Vec<_>>(),
+            vec!["3"]
+        );
+    }
+}
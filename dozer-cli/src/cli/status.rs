@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use dozer_types::grpc_types::contract::{
+    contract_service_client::ContractServiceClient, CommonRequest, SinkLag,
+};
+use dozer_types::log::warn;
+use dozer_types::prettytable::{Cell, Row, Table};
+
+use crate::errors::CliError;
+
+/// Prints how far each sink of the app served by the UI server at `url` is behind, warning
+/// about the ones that are further behind than `alert_threshold`.
+pub async fn print_sink_lags(
+    url: String,
+    alert_threshold: Option<Duration>,
+) -> Result<(), CliError> {
+    let mut client = ContractServiceClient::connect(url).await?;
+    let sink_lags = client
+        .get_sink_lags(CommonRequest { cloud_id: None })
+        .await
+        .map_err(CliError::UiServerRequestFailed)?
+        .into_inner()
+        .sink_lags;
+
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![Cell::new("sink"), Cell::new("lag")]));
+    for sink_lag in &sink_lags {
+        let lag = Duration::from_secs_f64(sink_lag.lag_secs.max(0.0));
+        table.add_row(Row::new(vec![
+            Cell::new(&sink_lag.sink),
+            Cell::new(&format!("{lag:?}")),
+        ]));
+    }
+    table.printstd();
+
+    for sink_lag in lagging_sinks(&sink_lags, alert_threshold) {
+        warn!(
+            "Sink {} is {}s behind, over the alert threshold",
+            sink_lag.sink, sink_lag.lag_secs
+        );
+    }
+    Ok(())
+}
+
+fn lagging_sinks(
+    sink_lags: &[SinkLag],
+    alert_threshold: Option<Duration>,
+) -> impl Iterator<Item = &SinkLag> {
+    sink_lags.iter().filter(move |sink_lag| {
+        alert_threshold.is_some_and(|threshold| sink_lag.lag_secs > threshold.as_secs_f64())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lagging_sinks() {
+        let sink_lag = |sink: &str, lag_secs| SinkLag {
+            sink: sink.to_string(),
+            lag_secs,
+        };
+        let sink_lags = [sink_lag("users", 0.5), sink_lag("orders", 12.0)];
+        let names = |threshold| {
+            lagging_sinks(&sink_lags, threshold)
+                .map(|sink_lag| sink_lag.sink.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(None), Vec::<&str>::new());
+        assert_eq!(names(Some(Duration::from_secs(10))), vec!["orders"]);
+        assert_eq!(names(Some(Duration::ZERO)), vec!["users", "orders"]);
+    }
+}
@@ -55,6 +55,44 @@ pub enum Commands {
     Build(Build),
     #[command(about = "Run a replication instance with the provided configuration")]
     Run,
+    #[command(
+        about = "Describe the pipeline's processors",
+        long_about = "Describe the pipeline's processors. Prints each processor's behavior and \
+            input and output schemas as JSON"
+    )]
+    Explain,
+    #[command(
+        about = "Generate the DDL that migrates the sinks to the current schemas",
+        long_about = format!("Generate the DDL that migrates each sink from the schemas in \
+            {LOCK_FILE} to the ones of the current config. Unless `--dry-run` is given, {LOCK_FILE} \
+            is updated afterwards")
+    )]
+    Migrate(Migrate),
+    #[command(
+        about = "Validate the config files",
+        long_about = "Validate the YAML config files against the config schema. Reports every \
+            problem found, with the path of the offending value"
+    )]
+    Validate,
+    #[command(
+        about = "Preview the data of a connection",
+        long_about = "Run a query against a connection and print the first records it returns. \
+            SQL connections take a SELECT statement"
+    )]
+    Query(Query),
+    #[command(
+        about = "Query a running app interactively",
+        long_about = "Connect to the UI server of a running app and run SQL queries against its \
+            sources. Type `.schema <table>` to show a table's fields and `.quit` to exit"
+    )]
+    Repl(Repl),
+    #[command(
+        about = "Show the status of a running app",
+        long_about = "Connect to the UI server of a running app and show its status. `--sinks` \
+            shows how far each sink is behind the operations it received, warning about the ones \
+            over `app.sink_lag_alert_threshold_secs`"
+    )]
+    Status(Status),
     #[command(about = "Run UI server")]
     UI(UI),
 }
@@ -63,6 +101,13 @@ pub enum Commands {
 pub struct UI {
     #[command(subcommand)]
     pub command: Option<UICommands>,
+    #[arg(
+        help = "How often connected clients are sent the current state, in milliseconds",
+        long,
+        default_value_t = 30000,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub refresh_interval_ms: u64,
 }
 
 #[derive(Debug, Subcommand)]
@@ -90,6 +135,56 @@ pub struct Build {
     pub force: Option<Option<String>>,
 }
 
+#[derive(Debug, Args)]
+pub struct Migrate {
+    #[arg(help = format!("Only print the DDL, without updating {LOCK_FILE}"), long)]
+    pub dry_run: bool,
+    #[arg(help = "File to write the DDL to, instead of stdout", short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct Query {
+    #[arg(help = "Name of the connection to query")]
+    pub connection: String,
+    #[arg(help = "Query to run against the connection")]
+    pub query: String,
+    #[arg(
+        help = "Maximum number of records to print",
+        long,
+        default_value_t = 10
+    )]
+    pub limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct Repl {
+    #[arg(
+        help = "Address of the UI server",
+        long,
+        default_value = "http://127.0.0.1:4555"
+    )]
+    pub url: String,
+    #[arg(
+        help = "How long each query runs for, in milliseconds",
+        long,
+        default_value_t = 5000
+    )]
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct Status {
+    #[arg(help = "Show how far each sink is behind", long)]
+    pub sinks: bool,
+    #[arg(
+        help = "Address of the UI server",
+        long,
+        default_value = "http://127.0.0.1:4555"
+    )]
+    pub url: String,
+}
+
 #[derive(Debug, Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub struct Deploy {
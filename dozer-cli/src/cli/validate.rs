@@ -0,0 +1,254 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use dozer_types::log::{error, info};
+use dozer_types::models::get_dozer_schema;
+use dozer_types::{serde_json, serde_yaml};
+use glob::glob;
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::{JSONSchema, ValidationError};
+
+use crate::errors::{CliError, ConfigCombineError};
+
+/// A problem found in a config file, located by the JSON Pointer of the offending value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ConfigValidationError {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ConfigValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+impl From<ValidationError<'_>> for ConfigValidationError {
+    fn from(error: ValidationError<'_>) -> Self {
+        let path = error.instance_path.to_string();
+        match &error.kind {
+            ValidationErrorKind::Required { property } => {
+                let property = property.as_str().map_or_else(
+                    || property.to_string(),
+                    |property| property.replace('~', "~0").replace('/', "~1"),
+                );
+                Self::new(format!("{path}/{property}"), "missing field")
+            }
+            ValidationErrorKind::AdditionalProperties { unexpected } => {
+                Self::new(path, format!("unknown field(s): {}", unexpected.join(", ")))
+            }
+            _ => Self::new(path, error.to_string()),
+        }
+    }
+}
+
+/// Validates a YAML config file against the JSON schema of `Config`.
+///
+/// Unlike deserializing the config, this reports every problem in the file, each with the path of the offending value.
+pub fn validate_config_yaml(path: &Path) -> Vec<ConfigValidationError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => validate_config_str(&contents),
+        Err(e) => vec![ConfigValidationError::new(
+            "",
+            format!("failed to read {}: {e}", path.display()),
+        )],
+    }
+}
+
+/// Validates every YAML file matched by `config_paths`, logging the problems found.
+pub fn validate_config_files(config_paths: &[String]) -> Result<(), CliError> {
+    let mut num_errors = 0;
+    for pattern in config_paths {
+        let files = glob(pattern).map_err(ConfigCombineError::WrongPatternOfConfigFilesGlob)?;
+        for file in files {
+            let file = file.map_err(ConfigCombineError::CannotReadFile)?;
+            if !matches!(
+                file.extension().and_then(|extension| extension.to_str()),
+                Some("yml" | "yaml")
+            ) {
+                continue;
+            }
+
+            let errors = validate_config_yaml(&file);
+            if errors.is_empty() {
+                info!("{} is valid", file.display());
+            }
+            for error in &errors {
+                error!("{}: {error}", file.display());
+            }
+            num_errors += errors.len();
+        }
+    }
+
+    if num_errors == 0 {
+        Ok(())
+    } else {
+        Err(CliError::InvalidConfig(num_errors))
+    }
+}
+
+fn validate_config_str(contents: &str) -> Vec<ConfigValidationError> {
+    let config = match serde_yaml::from_str(contents) {
+        Ok(config) => yaml_to_json(config),
+        Err(e) => return vec![ConfigValidationError::new("", format!("invalid YAML: {e}"))],
+    };
+
+    let schema = get_dozer_schema().expect("config schema must serialize");
+    let mut schema = serde_json::from_str(&schema).expect("config schema must be valid JSON");
+    flatten_externally_tagged_enums(&mut schema);
+    let schema = JSONSchema::compile(&schema).expect("config schema must be a valid JSON schema");
+
+    let mut errors = vec![];
+    if let Err(validation_errors) = schema.validate(&config) {
+        errors.extend(validation_errors.map(ConfigValidationError::from));
+    }
+    errors
+}
+
+/// `schemars` describes an externally tagged enum as a `oneOf` with one object per variant, and
+/// `jsonschema` only reports that none of them matched. Describing the enum as a single object,
+/// with one optional property per variant, lets the errors inside the variant surface.
+fn flatten_externally_tagged_enums(schema: &mut serde_json::Value) {
+    match schema {
+        serde_json::Value::Object(object) => {
+            if let Some(variants) = object
+                .get("oneOf")
+                .and_then(|one_of| one_of.as_array())
+                .and_then(|one_of| {
+                    one_of
+                        .iter()
+                        .map(single_property_object)
+                        .collect::<Option<serde_json::Map<_, _>>>()
+                })
+            {
+                object.remove("oneOf");
+                object.insert("type".to_string(), "object".into());
+                object.insert("properties".to_string(), variants.into());
+                object.insert("additionalProperties".to_string(), false.into());
+                object.insert("minProperties".to_string(), 1.into());
+                object.insert("maxProperties".to_string(), 1.into());
+            }
+            object
+                .values_mut()
+                .for_each(flatten_externally_tagged_enums);
+        }
+        serde_json::Value::Array(array) => {
+            array.iter_mut().for_each(flatten_externally_tagged_enums)
+        }
+        _ => {}
+    }
+}
+
+/// Returns the property of a schema that describes an object with exactly one, required, property.
+fn single_property_object(schema: &serde_json::Value) -> Option<(String, serde_json::Value)> {
+    let required = schema.get("required")?.as_array()?;
+    let properties = schema.get("properties")?.as_object()?;
+    match (required.as_slice(), properties.iter().next()) {
+        ([required], Some((name, property)))
+            if properties.len() == 1 && required.as_str() == Some(name) =>
+        {
+            Some((name.clone(), property.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Converts YAML to the JSON that `Config` would serialize to. YAML tags, which select enum
+/// variants (e.g. `!Postgres`), become single key objects.
+fn yaml_to_json(value: serde_yaml::Value) -> serde_json::Value {
+    use serde_yaml::Value;
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(bool) => serde_json::Value::Bool(bool),
+        Value::Number(number) => serde_json::to_value(number).unwrap_or(serde_json::Value::Null),
+        Value::String(string) => serde_json::Value::String(string),
+        Value::Sequence(sequence) => {
+            serde_json::Value::Array(sequence.into_iter().map(yaml_to_json).collect())
+        }
+        Value::Mapping(mapping) => serde_json::Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Value::String(key) => key,
+                        key => serde_yaml::to_string(&key)
+                            .map(|key| key.trim_end().to_string())
+                            .unwrap_or_default(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+        Value::Tagged(tagged) => {
+            let tag = tagged.tag.to_string();
+            let tag = tag.trim_start_matches('!').to_string();
+            serde_json::Value::Object([(tag, yaml_to_json(tagged.value))].into_iter().collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_config() {
+        let config = r#"
+version: 1
+app_name: test
+connections:
+  - name: pg
+    config: !Postgres
+      user: postgres
+      password: postgres
+      host: localhost
+      port: 5432
+      database: users
+"#;
+        assert_eq!(validate_config_str(config), vec![]);
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let config = r#"
+version: 1
+app_name: test
+sinks:
+  - name: aerospike
+    config: !Aerospike
+      connection: aerospike
+      tables:
+        - source_table_name: users
+          set_name: users
+"#;
+        let errors = validate_config_str(config);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "/sinks/0/config/Aerospike/tables/0/namespace: missing field"
+        );
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let errors = validate_config_str("version: 1\napp_name: test\nname: test\n");
+        assert_eq!(
+            errors,
+            vec![ConfigValidationError::new("", "unknown field(s): name")]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use dozer_types::serde_yaml::{self, Value};
+use glob::glob;
+
+use crate::errors::ConfigError;
+
+const INCLUDE_TAG: &str = "include";
+
+/// Loads dozer config files.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfigLoader;
+
+impl ConfigLoader {
+    /// Replaces every `${VAR_NAME}` in `yaml` with the value of the environment variable `VAR_NAME`.
+    ///
+    /// `${VAR_NAME:-default}` falls back to `default` if the variable is not set. The default can
+    /// reference other variables itself. A `${` without a closing `}` is left as is.
+    pub fn resolve_env_vars(yaml: &str) -> Result<String, ConfigError> {
+        let mut result = String::with_capacity(yaml.len());
+        let mut rest = yaml;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(len) = find_closing_brace(&rest[start + 2..]) else {
+                rest = &rest[start..];
+                break;
+            };
+            let reference = &rest[start + 2..start + 2 + len];
+            result.push_str(&resolve_reference(reference)?);
+            rest = &rest[start + 2 + len + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// Replaces every `!include path/to/file.yaml` value in `yaml`, read from the file at `path`,
+    /// with the content of the included file.
+    ///
+    /// Relative paths are resolved from the directory of the including file. A glob pattern
+    /// includes all matching files as one sequence, flattening files that contain sequences
+    /// themselves. Included files can include other files, but not circularly.
+    pub fn resolve_includes(yaml: Value, path: &Path) -> Result<Value, ConfigError> {
+        let mut include_stack = path.canonicalize().ok().into_iter().collect();
+        resolve_includes(yaml, path, &mut include_stack)
+    }
+}
+
+fn resolve_includes(
+    yaml: Value,
+    path: &Path,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<Value, ConfigError> {
+    match yaml {
+        Value::Tagged(tagged) if tagged.tag == INCLUDE_TAG => match tagged.value {
+            Value::String(pattern) => include(&pattern, path, include_stack),
+            value => Err(ConfigError::InvalidInclude(value)),
+        },
+        Value::Tagged(mut tagged) => {
+            tagged.value = resolve_includes(tagged.value, path, include_stack)?;
+            Ok(Value::Tagged(tagged))
+        }
+        Value::Mapping(mapping) => mapping
+            .into_iter()
+            .map(|(key, value)| Ok((key, resolve_includes(value, path, include_stack)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Mapping),
+        Value::Sequence(sequence) => sequence
+            .into_iter()
+            .map(|value| resolve_includes(value, path, include_stack))
+            .collect::<Result<_, _>>()
+            .map(Value::Sequence),
+        value => Ok(value),
+    }
+}
+
+fn include(
+    pattern: &str,
+    including_path: &Path,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<Value, ConfigError> {
+    let pattern = including_path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(pattern);
+    let pattern = pattern.to_string_lossy();
+    let paths = glob(&pattern)?.collect::<Result<Vec<_>, _>>()?;
+    if paths.is_empty() {
+        return Err(ConfigError::IncludeNotFound(pattern.into_owned()));
+    }
+
+    let is_glob = pattern.contains(['*', '?', '[']);
+    let mut values = vec![];
+    for path in paths {
+        let value = include_file(&path, include_stack)?;
+        match value {
+            Value::Sequence(sequence) if is_glob => values.extend(sequence),
+            value => values.push(value),
+        }
+    }
+    if is_glob {
+        Ok(Value::Sequence(values))
+    } else {
+        Ok(values.remove(0))
+    }
+}
+
+fn include_file(path: &Path, include_stack: &mut Vec<PathBuf>) -> Result<Value, ConfigError> {
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|e| ConfigError::CannotReadInclude(path.to_path_buf(), e))?;
+    if include_stack.contains(&canonical_path) {
+        return Err(ConfigError::CircularInclude(path.to_path_buf()));
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ConfigError::CannotReadInclude(path.to_path_buf(), e))?;
+    let value = serde_yaml::from_str(&content)
+        .map_err(|e| ConfigError::ParseInclude(path.to_path_buf(), e))?;
+
+    include_stack.push(canonical_path);
+    let result = resolve_includes(value, path, include_stack);
+    include_stack.pop();
+    result
+}
+
+/// Returns the position of the `}` closing a reference whose content starts `s`, skipping nested references.
+fn find_closing_brace(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    let bytes = s.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'$' if bytes.get(index + 1) == Some(&b'{') => {
+                depth += 1;
+                index += 1;
+            }
+            b'}' if depth == 0 => return Some(index),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+fn resolve_reference(reference: &str) -> Result<String, ConfigError> {
+    let (name, default) = match reference.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (reference, None),
+    };
+    match (env::var(name), default) {
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => ConfigLoader::resolve_env_vars(default),
+        (Err(_), None) => Err(ConfigError::MissingEnvVar(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_env_vars() {
+        env::set_var("DOZER_TEST_RESOLVE_USER", "dozer");
+        env::set_var("DOZER_TEST_RESOLVE_PASSWORD", "secret");
+        let yaml = "user: ${DOZER_TEST_RESOLVE_USER}\npassword: ${DOZER_TEST_RESOLVE_PASSWORD}\nport: ${DOZER_TEST_RESOLVE_PORT:-5432}\nhost: ${DOZER_TEST_RESOLVE_HOST:-}\n";
+        assert_eq!(
+            ConfigLoader::resolve_env_vars(yaml).unwrap(),
+            "user: dozer\npassword: secret\nport: 5432\nhost: \n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_nested_env_vars() {
+        env::set_var("DOZER_TEST_NESTED_FALLBACK", "fallback");
+        assert_eq!(
+            ConfigLoader::resolve_env_vars(
+                "value: ${DOZER_TEST_NESTED_MISSING:-${DOZER_TEST_NESTED_FALLBACK}}"
+            )
+            .unwrap(),
+            "value: fallback"
+        );
+        assert_eq!(
+            ConfigLoader::resolve_env_vars(
+                "value: ${DOZER_TEST_NESTED_MISSING:-${DOZER_TEST_NESTED_OTHER:-default}}"
+            )
+            .unwrap(),
+            "value: default"
+        );
+        // Unterminated references are left alone.
+        assert_eq!(
+            ConfigLoader::resolve_env_vars("value: ${DOZER_TEST_NESTED_FALLBACK").unwrap(),
+            "value: ${DOZER_TEST_NESTED_FALLBACK"
+        );
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_resolve_includes() {
+        let dir = TempDir::new("test_resolve_includes").unwrap();
+        let config = write(
+            dir.path(),
+            "dozer-config.yaml",
+            "app_name: test\nconnections: !include connections.yaml\nsinks: !include sinks/*.yaml\n",
+        );
+        write(
+            dir.path(),
+            "connections.yaml",
+            "- name: aerospike\n  config: !include secrets/aerospike.yaml\n",
+        );
+        write(
+            dir.path(),
+            "secrets/aerospike.yaml",
+            "hosts: localhost:3000\n",
+        );
+        write(dir.path(), "sinks/a.yaml", "- name: a\n- name: b\n");
+        write(dir.path(), "sinks/c.yaml", "name: c\n");
+
+        let yaml = serde_yaml::from_str(&std::fs::read_to_string(&config).unwrap()).unwrap();
+        let resolved = ConfigLoader::resolve_includes(yaml, &config).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            "app_name: test
+connections:
+  - name: aerospike
+    config:
+      hosts: localhost:3000
+sinks:
+  - name: a
+  - name: b
+  - name: c
+",
+        )
+        .unwrap();
+        assert_eq!(resolved, expected);
+    }
+
+    #[test]
+    fn test_circular_include() {
+        let dir = TempDir::new("test_circular_include").unwrap();
+        let config = write(dir.path(), "dozer-config.yaml", "sinks: !include a.yaml\n");
+        write(dir.path(), "a.yaml", "- !include b.yaml\n");
+        write(dir.path(), "b.yaml", "- !include dozer-config.yaml\n");
+
+        let yaml = serde_yaml::from_str(&std::fs::read_to_string(&config).unwrap()).unwrap();
+        assert!(matches!(
+            ConfigLoader::resolve_includes(yaml, &config),
+            Err(ConfigError::CircularInclude(path)) if path.ends_with("dozer-config.yaml")
+        ));
+
+        let yaml = serde_yaml::from_str("sinks: !include missing.yaml").unwrap();
+        assert!(matches!(
+            ConfigLoader::resolve_includes(yaml, &config),
+            Err(ConfigError::IncludeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_missing_env_var() {
+        assert!(matches!(
+            ConfigLoader::resolve_env_vars("password: ${DOZER_TEST_MISSING_PASSWORD}"),
+            Err(ConfigError::MissingEnvVar(name)) if name == "DOZER_TEST_MISSING_PASSWORD"
+        ));
+        assert!(matches!(
+            ConfigLoader::resolve_env_vars("password: ${DOZER_TEST_MISSING_OUTER:-${DOZER_TEST_MISSING_INNER}}"),
+            Err(ConfigError::MissingEnvVar(name)) if name == "DOZER_TEST_MISSING_INNER"
+        ));
+    }
+}
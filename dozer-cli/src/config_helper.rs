@@ -1,3 +1,4 @@
+use crate::config::ConfigLoader;
 use crate::errors::ConfigCombineError;
 use crate::errors::ConfigCombineError::{
     CannotReadConfig, CannotReadFile, CannotSerializeToString, SqlIsNotStringType,
@@ -8,6 +9,7 @@ use dozer_types::serde_yaml;
 use dozer_types::serde_yaml::mapping::Entry;
 use dozer_types::serde_yaml::{Mapping, Value};
 use glob::glob;
+use std::path::Path;
 
 pub fn combine_config(
     config_paths: Vec<String>,
@@ -44,6 +46,8 @@ pub fn combine_config(
     if let Some(stdin_content) = stdin_yaml {
         let stdin_yaml: serde_yaml::Value = serde_yaml::from_str(&stdin_content)
             .map_err(|e| ConfigCombineError::ParseYaml(stdin_name.to_string(), e))?; //deserialise yaml content from stdin
+                                                                                     // Paths included from stdin are relative to the current directory.
+        let stdin_yaml = ConfigLoader::resolve_includes(stdin_yaml, Path::new(stdin_name))?;
         merge_yaml(stdin_yaml, &mut combined_yaml)?; //merge with yaml from config-paths
     }
 
@@ -65,6 +69,7 @@ pub fn add_file_content_to_config(
         let content_string = String::from_utf8(content)?;
         let yaml: serde_yaml::Value = serde_yaml::from_str(&content_string)
             .map_err(|e| ConfigCombineError::ParseYaml(name.to_string(), e))?;
+        let yaml = ConfigLoader::resolve_includes(yaml, Path::new(name))?;
         merge_yaml(yaml, combined_yaml)?;
     } else if name.contains(".sql") {
         let mapping = combined_yaml.as_mapping_mut().expect("Should be mapping");
@@ -12,10 +12,10 @@ use crate::{
     ui::{app::AppUIError, live::LiveError},
 };
 
-use dozer_core::errors::ExecutionError;
+use dozer_core::errors::{ExecutionError, MigrationError};
 use dozer_sql::errors::PipelineError;
 use dozer_types::{constants::LOCK_FILE, thiserror::Error};
-use dozer_types::{errors::internal::BoxedError, serde_json};
+use dozer_types::{errors::internal::BoxedError, node::NodeHandle, serde_json};
 use dozer_types::{serde_yaml, thiserror};
 
 use crate::pipeline::connector_source::ConnectorSourceFactoryError;
@@ -88,6 +88,10 @@ pub enum OrchestrationError {
     LockedOutdatedLockfile,
     #[error("{LOCK_FILE} does not exist. `--locked` requires a lock file.")]
     LockedNoLockFile,
+    #[error("{LOCK_FILE} does not exist. Run `dozer build` before `dozer migrate`.")]
+    MigrateNoLockFile,
+    #[error("Cannot migrate sink {0}: {1}")]
+    Migration(NodeHandle, #[source] MigrationError),
     #[error("Command was aborted")]
     Aborted,
 }
@@ -104,8 +108,14 @@ pub enum CliError {
     FailedToParseYaml(#[source] BoxedError),
     #[error("Failed to validate dozer config: {0:?}")]
     FailedToParseValidateYaml(#[source] BoxedError),
+    #[error("Found {0} problem(s) in dozer config")]
+    InvalidConfig(usize),
     #[error("Failed to read line: {0}")]
     ReadlineError(#[from] rustyline::error::ReadlineError),
+    #[error("Cannot connect to the UI server: {0}")]
+    CannotConnectToUiServer(#[from] tonic::transport::Error),
+    #[error("UI server request failed: {}", .0.message())]
+    UiServerRequestFailed(#[source] tonic::Status),
     #[error("File system error {0:?}: {1}")]
     FileSystem(PathBuf, #[source] std::io::Error),
     #[error("Failed to create tokio runtime: {0}")]
@@ -114,6 +124,8 @@ pub enum CliError {
     Reqwest(#[from] reqwest::Error),
     #[error(transparent)]
     ConfigCombineError(#[from] ConfigCombineError),
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
     #[error("Failed to serialize config to json: {0}")]
     SerializeConfigToJson(#[source] serde_json::Error),
     #[error("Missing config options to be overridden: {0}")]
@@ -186,6 +198,36 @@ pub enum ConfigCombineError {
 
     #[error("Failed to read config to string")]
     CannotReadUtf8String(#[from] FromUtf8Error),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Environment variable {0} referenced in config is not set")]
+    MissingEnvVar(String),
+
+    #[error("Expected a path to include, found {0:?}")]
+    InvalidInclude(serde_yaml::Value),
+
+    #[error("Wrong pattern of included files: {0}")]
+    WrongPatternOfIncludeGlob(#[from] PatternError),
+
+    #[error("Cannot read included file: {0}")]
+    CannotReadIncludeGlob(#[from] GlobError),
+
+    #[error("No file found to include at {0}")]
+    IncludeNotFound(String),
+
+    #[error("Cannot read included file {0:?}: {1}")]
+    CannotReadInclude(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to parse included file {0:?}: {1}")]
+    ParseInclude(PathBuf, #[source] serde_yaml::Error),
+
+    #[error("File {0:?} includes itself")]
+    CircularInclude(PathBuf),
 }
 
 #[derive(Debug, Error)]
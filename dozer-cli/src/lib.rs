@@ -17,6 +17,7 @@ use std::{
 };
 use tokio::task::JoinHandle;
 pub mod cloud;
+pub mod config;
 pub mod config_helper;
 pub mod console_helper;
 pub use dozer_core::shutdown;
@@ -1,7 +1,10 @@
 use clap::Parser;
 use dozer_cli::cli::init_config;
 use dozer_cli::cli::init_dozer;
+use dozer_cli::cli::print_sink_lags;
+use dozer_cli::cli::run_repl;
 use dozer_cli::cli::types::{Cli, Commands, UICommands};
+use dozer_cli::cli::validate_config_files;
 use dozer_cli::errors::{CliError, CloudError, OrchestrationError};
 use dozer_cli::ui;
 use dozer_cli::ui::app::AppUIError;
@@ -14,6 +17,7 @@ use dozer_types::tracing::{error, error_span, info};
 use futures::TryFutureExt;
 use std::process;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 fn main() {
@@ -72,11 +76,24 @@ fn run() -> Result<(), OrchestrationError> {
                 &runtime,
                 shutdown_receiver,
                 false,
+                Duration::from_millis(run.refresh_interval_ms),
             ))?;
         }
         return Ok(());
     }
 
+    // the REPL talks to an app that's already running
+    if let Commands::Repl(repl) = &cli.cmd {
+        return runtime
+            .block_on(run_repl(repl.url.clone(), repl.timeout_ms))
+            .map_err(OrchestrationError::CliError);
+    }
+
+    // validating the config must not require it to load
+    if let Commands::Validate = &cli.cmd {
+        return validate_config_files(&cli.config_paths).map_err(OrchestrationError::CliError);
+    }
+
     let (config, config_files) = config_res?;
     info!("Loaded config from: {}", config_files.join(", "));
 
@@ -99,8 +116,33 @@ fn run() -> Result<(), OrchestrationError> {
                 .runtime
                 .block_on(dozer.build(force, shutdown_receiver, build.locked))
         }
+        Commands::Explain => dozer.runtime.block_on(dozer.explain(shutdown_receiver)),
+        Commands::Migrate(migrate) => dozer.runtime.block_on(dozer.migrate(
+            shutdown_receiver,
+            migrate.dry_run,
+            migrate.output.as_deref(),
+        )),
+        Commands::Query(query) => {
+            dozer
+                .runtime
+                .block_on(dozer.query(&query.connection, &query.query, query.limit))
+        }
+        Commands::Status(status) if status.sinks => {
+            let alert_threshold = config
+                .app
+                .sink_lag_alert_threshold_secs
+                .map(Duration::from_secs);
+            dozer
+                .runtime
+                .block_on(print_sink_lags(status.url, alert_threshold))
+                .map_err(OrchestrationError::CliError)
+        }
+        Commands::Status(_) => {
+            info!("Nothing to show. Use --sinks to show how far each sink is behind");
+            Ok(())
+        }
         Commands::Clean => dozer.clean(),
-        Commands::UI(_) => {
+        Commands::UI(_) | Commands::Validate | Commands::Repl(_) => {
             panic!("This should not happen as it is handled earlier");
         }
     })
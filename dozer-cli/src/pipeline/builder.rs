@@ -6,15 +6,19 @@ use dozer_core::app::App;
 use dozer_core::app::AppPipeline;
 use dozer_core::app::PipelineEntryPoint;
 use dozer_core::node::SinkFactory;
+use dozer_core::pipeline::FactoryRegistry;
 use dozer_core::shutdown::ShutdownReceiver;
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_ingestion::SchemaCache;
 use dozer_sql::builder::statement_to_pipeline;
 use dozer_sql::builder::{OutputNodeInfo, QueryContext};
+use dozer_sql::wire::register_factories;
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::log::debug;
 use dozer_types::models::connection::Connection;
 use dozer_types::models::connection::ConnectionConfig;
 use dozer_types::models::flags::Flags;
+use dozer_types::models::ingestion_types::GenerateSeriesConfig;
 use dozer_types::models::sink::Sink;
 use dozer_types::models::sink::SinkConfig;
 use dozer_types::models::source::Source;
@@ -27,7 +31,9 @@ use crate::pipeline::dummy_sink::DummySinkFactory;
 use dozer_sink_aerospike::AerospikeSinkFactory;
 use dozer_sink_clickhouse::ClickhouseSinkFactory;
 use dozer_sink_oracle::OracleSinkFactory;
+use dozer_sink_qdrant::QdrantSinkFactory;
 
+use super::connector_source::register_connector_source;
 use super::source_builder::SourceBuilder;
 use crate::errors::OrchestrationError;
 
@@ -57,6 +63,9 @@ pub struct PipelineBuilder<'a> {
     labels: LabelsAndProgress,
     flags: Flags,
     udfs: &'a [UdfConfig],
+    /// Name of the output table of a top level query without an `INTO` clause.
+    output_table_name: Option<String>,
+    schema_cache: SchemaCache,
 }
 
 impl<'a> PipelineBuilder<'a> {
@@ -77,15 +86,30 @@ impl<'a> PipelineBuilder<'a> {
             labels,
             flags,
             udfs,
+            output_table_name: None,
+            schema_cache: SchemaCache::default(),
         }
     }
 
+    /// Lets the SQL be a single query without an `INTO` clause, whose output table is named `name`.
+    pub fn with_output_table_name(mut self, name: String) -> Self {
+        self.output_table_name = Some(name);
+        self
+    }
+
+    /// Shares the source schemas the connectors fetch with other builders using `schema_cache`.
+    pub fn with_schema_cache(mut self, schema_cache: SchemaCache) -> Self {
+        self.schema_cache = schema_cache;
+        self
+    }
+
     // Based on used_sources, map it to the connection name and create sources
     // For not breaking current functionality, current format is to be still supported.
     pub async fn get_grouped_tables(
         &self,
         _runtime: &Arc<Runtime>,
         original_sources: &[String],
+        generated_series: &HashMap<String, GenerateSeriesConfig>,
     ) -> Result<HashMap<Connection, Vec<Source>>, OrchestrationError> {
         let mut grouped_connections: HashMap<Connection, Vec<Source>> = HashMap::new();
 
@@ -103,6 +127,24 @@ impl<'a> PipelineBuilder<'a> {
         }
 
         for table_name in original_sources {
+            // `FROM generate_series(...)` gets a connection of its own, named after the source.
+            if let Some(config) = generated_series.get(table_name) {
+                let connection = Connection {
+                    config: ConnectionConfig::GenerateSeries(config.clone()),
+                    name: table_name.clone(),
+                };
+                grouped_connections
+                    .entry(connection)
+                    .or_default()
+                    .push(Source {
+                        name: table_name.clone(),
+                        table_name: "generate_series".to_string(),
+                        connection: table_name.clone(),
+                        ..Default::default()
+                    });
+                continue;
+            }
+
             let mut table_found = false;
             for (connection, tables) in connector_map.iter() {
                 if let Some(source) = tables
@@ -141,9 +183,14 @@ impl<'a> PipelineBuilder<'a> {
         let mut transformed_sources = vec![];
 
         if let Some(sql) = &self.sql {
-            let query_context =
-                statement_to_pipeline(sql, &mut pipeline, None, self.udfs.to_vec(), runtime)
-                    .map_err(OrchestrationError::PipelineError)?;
+            let query_context = statement_to_pipeline(
+                sql,
+                &mut pipeline,
+                self.output_table_name.clone(),
+                self.udfs.to_vec(),
+                runtime,
+            )
+            .map_err(OrchestrationError::PipelineError)?;
 
             query_ctx = Some(query_context.clone());
 
@@ -183,8 +230,17 @@ impl<'a> PipelineBuilder<'a> {
         let calculated_sources = self.calculate_sources(runtime.clone())?;
 
         debug!("Used Sources: {:?}", calculated_sources.original_sources);
+        let generated_series = calculated_sources
+            .query_context
+            .as_ref()
+            .map(|query_context| query_context.generated_series.clone())
+            .unwrap_or_default();
         let grouped_connections = self
-            .get_grouped_tables(runtime, &calculated_sources.original_sources)
+            .get_grouped_tables(
+                runtime,
+                &calculated_sources.original_sources,
+                &generated_series,
+            )
             .await?;
 
         let mut pipelines: Vec<AppPipeline> = vec![];
@@ -210,7 +266,7 @@ impl<'a> PipelineBuilder<'a> {
             let query_context = statement_to_pipeline(
                 sql,
                 &mut pipeline,
-                None,
+                self.output_table_name.clone(),
                 self.udfs.to_vec(),
                 runtime.clone(),
             )
@@ -312,12 +368,32 @@ impl<'a> PipelineBuilder<'a> {
                         vec![(table_info, DEFAULT_PORT_HANDLE)],
                     );
                 }
+                SinkConfig::Qdrant(config) => {
+                    let sink = Box::new(QdrantSinkFactory::new(config.clone(), runtime.clone()));
+                    let table_info = get_table_info(&config.source_table_name)?;
+                    add_sink_to_pipeline(
+                        &mut pipeline,
+                        sink,
+                        id,
+                        vec![(table_info, DEFAULT_PORT_HANDLE)],
+                    );
+                }
             }
         }
 
         pipelines.push(pipeline);
 
-        let source_builder = SourceBuilder::new(grouped_connections, self.labels);
+        // So the pipeline can be sent to other nodes, which rebuild it with these factories.
+        register_connector_source(
+            FactoryRegistry::global(),
+            runtime.clone(),
+            self.labels.clone(),
+            shutdown.clone(),
+        );
+        register_factories(FactoryRegistry::global(), runtime.clone());
+
+        let source_builder =
+            SourceBuilder::new(grouped_connections, self.labels, self.schema_cache);
         let asm = source_builder
             .build_source_manager(runtime, shutdown)
             .await?;
@@ -348,6 +424,7 @@ fn table_names(sink: &Sink) -> Vec<&String> {
             .collect(),
         SinkConfig::Clickhouse(sink) => vec![&sink.source_table_name],
         SinkConfig::Oracle(sink) => vec![&sink.table_name],
+        SinkConfig::Qdrant(sink) => vec![&sink.source_table_name],
     }
 }
 
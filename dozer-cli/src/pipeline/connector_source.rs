@@ -1,18 +1,22 @@
 use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_core::pipeline::{FactoryRegistry, WireFormatFactory};
 use dozer_core::shutdown::ShutdownReceiver;
 use dozer_ingestion::{
-    get_connector, CdcType, Connector, IngestionIterator, TableIdentifier, TableInfo,
+    get_connector, CachedConnector, CdcType, Connector, IngestionIterator, SchemaCache,
+    TableIdentifier, TableInfo,
 };
 use dozer_ingestion::{IngestionConfig, Ingestor};
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::log::{error, info};
+use dozer_types::log::{error, info, warn};
 use dozer_types::models::connection::Connection;
 use dozer_types::models::ingestion_types::IngestionMessage;
 use dozer_types::node::OpIdentifier;
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json;
 use dozer_types::thiserror::{self, Error};
 use dozer_types::tracing::{span, Level};
-use dozer_types::types::{Operation, Schema, SourceDefinition};
+use dozer_types::types::{Operation, Record, Schema, SourceDefinition};
 use futures::stream::{AbortHandle, Abortable, Aborted};
 use metrics::counter;
 use metrics::describe_counter;
@@ -22,7 +26,10 @@ use tokio::runtime::Runtime;
 use tokio::sync::mpsc::Sender;
 use tonic::async_trait;
 
-#[derive(Debug)]
+pub const CONNECTOR_SOURCE_TYPE_NAME: &str = "Connector";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
 struct Table {
     schema_name: Option<String>,
     name: String,
@@ -62,9 +69,11 @@ impl ConnectorSourceFactory {
         runtime: Arc<Runtime>,
         labels: LabelsAndProgress,
         shutdown: ShutdownReceiver,
+        schema_cache: SchemaCache,
     ) -> Result<Self, ConnectorSourceFactoryError> {
-        let mut connector = get_connector(runtime.clone(), connection.clone(), None)
+        let connector = get_connector(runtime.clone(), connection.clone(), None)
             .map_err(|e| ConnectorSourceFactoryError::Connector(e.into()))?;
+        let mut connector = CachedConnector::new(connector, connection.clone(), schema_cache);
 
         // Fill column names if not provided.
         let table_identifiers = table_and_ports
@@ -118,6 +127,53 @@ impl ConnectorSourceFactory {
             shutdown,
         })
     }
+
+    /// Rebuilds a factory from the configuration `to_wire` sent, without asking the connector for the schemas again.
+    fn from_wire(
+        config: &[u8],
+        runtime: Arc<Runtime>,
+        labels: LabelsAndProgress,
+        shutdown: ShutdownReceiver,
+    ) -> Result<Self, BoxedError> {
+        let config: ConnectorSourceWireConfig = serde_json::from_slice(config)?;
+        Ok(Self {
+            connection: config.connection,
+            runtime,
+            tables: config.tables,
+            labels,
+            shutdown,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct ConnectorSourceWireConfig {
+    connection: Connection,
+    tables: Vec<Table>,
+}
+
+/// Registers the connector source factory, whose sources run on `runtime`, so pipelines sent to this node can read
+/// from connectors.
+pub fn register_connector_source(
+    registry: &FactoryRegistry,
+    runtime: Arc<Runtime>,
+    labels: LabelsAndProgress,
+    shutdown: ShutdownReceiver,
+) {
+    registry.register_source(
+        CONNECTOR_SOURCE_TYPE_NAME,
+        Box::new(
+            move |config: &[u8]| -> Result<Box<dyn SourceFactory>, BoxedError> {
+                Ok(Box::new(ConnectorSourceFactory::from_wire(
+                    config,
+                    runtime.clone(),
+                    labels.clone(),
+                    shutdown.clone(),
+                )?))
+            },
+        ),
+    );
 }
 
 impl SourceFactory for ConnectorSourceFactory {
@@ -182,12 +238,14 @@ impl SourceFactory for ConnectorSourceFactory {
             })
             .collect();
         let ports = self.tables.iter().map(|table| table.port).collect();
+        let cdc_types = self.tables.iter().map(|table| table.cdc_type).collect();
 
         let connector = get_connector(self.runtime.clone(), self.connection.clone(), state)?;
 
         Ok(Box::new(ConnectorSource {
             tables,
             ports,
+            cdc_types,
             connector,
             connection_name: self.connection.name.clone(),
             labels: self.labels.clone(),
@@ -195,12 +253,24 @@ impl SourceFactory for ConnectorSourceFactory {
             ingestion_config: IngestionConfig::default(),
         }))
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        let config = ConnectorSourceWireConfig {
+            connection: self.connection.clone(),
+            tables: self.tables.clone(),
+        };
+        Some(WireFormatFactory {
+            type_name: CONNECTOR_SOURCE_TYPE_NAME.to_string(),
+            config: serde_json::to_vec(&config).ok()?,
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct ConnectorSource {
     tables: Vec<TableInfo>,
     ports: Vec<PortHandle>,
+    cdc_types: Vec<CdcType>,
     connector: Box<dyn Connector>,
     connection_name: String,
     labels: LabelsAndProgress,
@@ -223,6 +293,7 @@ impl Source for ConnectorSource {
         let connection_name = self.connection_name.clone();
         let tables = self.tables.clone();
         let ports = self.ports.clone();
+        let cdc_types = self.cdc_types.clone();
         let labels = self.labels.clone();
         let handle = tokio::spawn(forward_message_to_pipeline(
             iterator,
@@ -230,6 +301,7 @@ impl Source for ConnectorSource {
             connection_name,
             tables,
             ports,
+            cdc_types,
             labels,
         ));
 
@@ -276,6 +348,7 @@ async fn forward_message_to_pipeline(
     connection_name: String,
     tables: Vec<TableInfo>,
     ports: Vec<PortHandle>,
+    cdc_types: Vec<CdcType>,
     labels: LabelsAndProgress,
 ) {
     let mut bars = vec![];
@@ -290,16 +363,28 @@ async fn forward_message_to_pipeline(
     );
 
     let mut counter = vec![(0u64, 0u64); tables.len()];
-    while let Some(message) = iterator.receiver.recv().await {
+    // Number of columns the connector added to each table after the pipeline was built.
+    let mut added_columns = vec![0usize; tables.len()];
+    while let Some(mut message) = iterator.receiver.recv().await {
         let span = span!(Level::TRACE, "pipeline_source_start", connection_name);
         let _enter = span.enter();
 
-        match &message {
+        match &mut message {
             IngestionMessage::OperationEvent {
                 table_index, op, ..
             } => {
+                if added_columns[*table_index] > 0 {
+                    drop_added_columns(op, added_columns[*table_index]);
+                }
                 let port = ports[*table_index];
                 let table_name = &tables[*table_index].name;
+                let cdc_type = cdc_types[*table_index];
+                if !cdc_type.allows(op) {
+                    error!(
+                        "Dropping an operation on table {table_name} of connection {connection_name}, as the table is {cdc_type:?}: {op:?}"
+                    );
+                    continue;
+                }
 
                 // Update metrics
                 let mut labels = labels.labels().clone();
@@ -319,6 +404,15 @@ async fn forward_message_to_pipeline(
                     Operation::BatchInsert { .. } => {
                         labels.push(OPERATION_TYPE_LABEL, "insert");
                     }
+                    Operation::Upsert { .. } => {
+                        labels.push(OPERATION_TYPE_LABEL, "upsert");
+                    }
+                    Operation::AddField { .. } => {
+                        labels.push(OPERATION_TYPE_LABEL, "add_field");
+                    }
+                    Operation::RemoveField { .. } => {
+                        labels.push(OPERATION_TYPE_LABEL, "remove_field");
+                    }
                 }
 
                 let counter_number: u64 = match op {
@@ -350,6 +444,35 @@ async fn forward_message_to_pipeline(
                     break;
                 }
             }
+            IngestionMessage::ColumnAdded {
+                table_index,
+                column,
+            } => {
+                warn!(
+                    "Column {} was added to table {} of connection {}, but the pipeline's schema can't change while it runs. Its values are dropped until the pipeline is restarted with the column in its schema",
+                    column.name, tables[*table_index].name, connection_name
+                );
+                added_columns[*table_index] += 1;
+            }
+        }
+    }
+}
+
+/// Removes the values of the last `num_columns` columns from the records of `op`.
+fn drop_added_columns(op: &mut Operation, num_columns: usize) {
+    let truncate = |record: &mut Record| {
+        record
+            .values
+            .truncate(record.values.len().saturating_sub(num_columns))
+    };
+    match op {
+        Operation::Delete { old } => truncate(old),
+        Operation::Insert { new } | Operation::Upsert { new } => truncate(new),
+        Operation::Update { old, new } => {
+            truncate(old);
+            truncate(new);
         }
+        Operation::BatchInsert { new } => new.iter_mut().for_each(truncate),
+        Operation::AddField { .. } | Operation::RemoveField { .. } => {}
     }
 }
@@ -2,7 +2,7 @@ use crate::pipeline::connector_source::ConnectorSourceFactory;
 use crate::OrchestrationError;
 use dozer_core::appsource::{AppSourceManager, AppSourceMappings};
 use dozer_core::shutdown::ShutdownReceiver;
-use dozer_ingestion::TableInfo;
+use dozer_ingestion::{SchemaCache, TableInfo};
 
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::models::connection::Connection;
@@ -14,6 +14,7 @@ use tokio::runtime::Runtime;
 pub struct SourceBuilder {
     grouped_connections: HashMap<Connection, Vec<Source>>,
     labels: LabelsAndProgress,
+    schema_cache: SchemaCache,
 }
 
 const SOURCE_PORTS_RANGE_START: u16 = 1000;
@@ -22,10 +23,12 @@ impl SourceBuilder {
     pub fn new(
         grouped_connections: HashMap<Connection, Vec<Source>>,
         labels: LabelsAndProgress,
+        schema_cache: SchemaCache,
     ) -> Self {
         Self {
             grouped_connections,
             labels,
+            schema_cache,
         }
     }
 
@@ -75,6 +78,7 @@ impl SourceBuilder {
                 runtime.clone(),
                 self.labels.clone(),
                 shutdown.clone(),
+                self.schema_cache.clone(),
             )
             .await?;
 
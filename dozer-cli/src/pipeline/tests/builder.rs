@@ -76,10 +76,11 @@ fn load_multi_sources() {
         .unwrap();
     let runtime = Arc::new(runtime);
     let grouped_connections = runtime
-        .block_on(builder.get_grouped_tables(&runtime, &used_sources))
+        .block_on(builder.get_grouped_tables(&runtime, &used_sources, &Default::default()))
         .unwrap();
 
-    let source_builder = SourceBuilder::new(grouped_connections, Default::default());
+    let source_builder =
+        SourceBuilder::new(grouped_connections, Default::default(), Default::default());
     let (_sender, shutdown_receiver) = shutdown::new(&runtime);
     let asm = runtime
         .block_on(source_builder.build_source_manager(&runtime, shutdown_receiver))
@@ -4,7 +4,11 @@ use dozer_core::{
     dag_schemas::DagSchemas,
     daggy,
     node::PortHandle,
-    petgraph::{algo::is_isomorphic_matching, visit::IntoNodeReferences},
+    petgraph::{
+        algo::is_isomorphic_matching,
+        visit::{EdgeRef, IntoEdgesDirected, IntoNodeReferences},
+        Direction,
+    },
 };
 use dozer_types::{models::connection::Connection, node::NodeHandle, types::Schema};
 use dozer_types::{
@@ -125,6 +129,23 @@ impl Contract {
         })
     }
 
+    /// The schemas the sink `handle` was built with, by input port.
+    pub fn get_sink_input_schemas(
+        &self,
+        handle: &NodeHandle,
+    ) -> Option<HashMap<PortHandle, Schema>> {
+        let (node_index, _) = self.pipeline.0.node_references().find(|(_, node)| {
+            matches!(node.kind, NodeKind::Sink { .. }) && &node.handle == handle
+        })?;
+        Some(
+            self.pipeline
+                .0
+                .edges_directed(node_index, Direction::Incoming)
+                .map(|edge| (edge.weight().to_port, edge.weight().schema.clone()))
+                .collect(),
+        )
+    }
+
     pub fn serialize(&self, path: &Path) -> Result<(), BuildError> {
         serde_json_to_path(path, &self)?;
         Ok(())
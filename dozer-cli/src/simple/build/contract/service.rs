@@ -1,9 +1,13 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
 
 use dozer_core::{
     daggy,
     petgraph::{
         dot,
+        graph::NodeIndex,
         visit::{EdgeRef, IntoEdgeReferences, IntoEdgesDirected, IntoNodeReferences},
         Direction,
     },
@@ -82,6 +86,27 @@ impl Contract {
         dot::Dot::new(&self.create_ui_graph()).to_string()
     }
 
+    /// Renders the part of the graph that is reachable from or to the nodes named `root`, at most
+    /// `depth` edges away from them. Returns `None` if there's no node named `root`.
+    pub fn generate_subgraph_dot(&self, root: &str, depth: Option<u32>) -> Option<String> {
+        let graph = self.create_ui_graph();
+        let roots = graph
+            .node_references()
+            .filter(|(_, node)| node.kind.name() == root || node.kind.to_string() == root)
+            .map(|(node_index, _)| node_index)
+            .collect::<Vec<_>>();
+        if roots.is_empty() {
+            return None;
+        }
+        let mut nodes = reachable_nodes(&graph, &roots, Direction::Outgoing, depth);
+        nodes.extend(reachable_nodes(&graph, &roots, Direction::Incoming, depth));
+        let subgraph = graph.graph().filter_map(
+            |node_index, node| nodes.contains(&node_index).then(|| node.clone()),
+            |_, _| Some(UiEdgeType),
+        );
+        Some(dot::Dot::new(&subgraph).to_string())
+    }
+
     fn create_ui_graph(&self) -> UiGraph {
         let mut ui_graph = UiGraph::new();
         let mut pipeline_node_index_to_ui_node_index = HashMap::new();
@@ -244,6 +269,18 @@ enum UiNodeKind {
     SinkTable { name: String },
 }
 
+impl UiNodeKind {
+    fn name(&self) -> &str {
+        match self {
+            UiNodeKind::Connection { name, .. }
+            | UiNodeKind::Source { name }
+            | UiNodeKind::Processor { name, .. }
+            | UiNodeKind::Sink { name, .. }
+            | UiNodeKind::SinkTable { name } => name,
+        }
+    }
+}
+
 impl Display for UiNodeKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -267,6 +304,36 @@ impl Display for UiEdgeType {
 
 type UiGraph = daggy::Dag<UiNodeType, UiEdgeType>;
 
+/// The nodes reachable from `roots` following edges in `direction`, at most `depth` edges away.
+/// Includes `roots`.
+fn reachable_nodes(
+    graph: &UiGraph,
+    roots: &[NodeIndex],
+    direction: Direction,
+    depth: Option<u32>,
+) -> HashSet<NodeIndex> {
+    let mut visited = roots.iter().copied().collect::<HashSet<_>>();
+    let mut queue = roots
+        .iter()
+        .map(|node_index| (*node_index, 0))
+        .collect::<VecDeque<_>>();
+    while let Some((node_index, node_depth)) = queue.pop_front() {
+        if depth.is_some_and(|depth| node_depth >= depth) {
+            continue;
+        }
+        for edge in graph.edges_directed(node_index, direction) {
+            let next = match direction {
+                Direction::Outgoing => edge.target(),
+                Direction::Incoming => edge.source(),
+            };
+            if visited.insert(next) {
+                queue.push_back((next, node_depth + 1));
+            }
+        }
+    }
+    visited
+}
+
 fn remove_from_processor(graph: &UiGraph) -> UiGraph {
     let mut output = UiGraph::new();
 
@@ -1,5 +1,6 @@
 use dozer_core::checkpoint::{CheckpointOptions, OptionCheckpoint};
 use dozer_core::shutdown::ShutdownReceiver;
+use dozer_ingestion::SchemaCache;
 use dozer_log::home_dir::HomeDir;
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::models::flags::Flags;
@@ -69,6 +70,7 @@ impl<'a> Executor<'a> {
         executor_options: ExecutorOptions,
         shutdown: ShutdownReceiver,
         flags: Flags,
+        schema_cache: SchemaCache,
     ) -> Result<DagExecutor, OrchestrationError> {
         let builder = PipelineBuilder::new(
             self.connections,
@@ -78,7 +80,8 @@ impl<'a> Executor<'a> {
             self.labels.clone(),
             flags,
             self.udfs,
-        );
+        )
+        .with_schema_cache(schema_cache);
 
         let dag = builder.build(runtime, shutdown).await?;
         let exec = DagExecutor::new(dag, self.checkpoint, executor_options).await?;
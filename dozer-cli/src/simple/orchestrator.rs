@@ -5,16 +5,21 @@ use crate::pipeline::connector_source::ConnectorSourceFactoryError;
 use crate::pipeline::PipelineBuilder;
 use crate::simple::build;
 use crate::simple::helper::validate_config;
-use crate::utils::{get_checkpoint_options, get_executor_options};
+use crate::utils::{get_checkpoint_options, get_executor_options, get_schema_cache_ttl};
 
 use crate::flatten_join_handle;
 use dozer_core::app::AppPipeline;
+use dozer_core::checkpoint::{CheckpointFactory, OptionCheckpoint};
 use dozer_core::dag_schemas::DagSchemas;
+use dozer_core::errors::MigrationError;
+use dozer_core::petgraph::visit::{EdgeRef, IntoEdgesDirected, IntoNodeReferences};
+use dozer_core::petgraph::Direction;
 use dozer_core::shutdown::ShutdownReceiver;
 use dozer_log::camino::Utf8PathBuf;
 use dozer_log::home_dir::{BuildId, HomeDir};
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::constants::LOCK_FILE;
+use dozer_types::serde_json;
 use futures::future::{select, Either};
 
 use crate::console_helper::get_colored_text;
@@ -22,11 +27,15 @@ use crate::console_helper::GREEN;
 use crate::console_helper::PURPLE;
 use crate::console_helper::RED;
 use dozer_core::errors::ExecutionError;
-use dozer_ingestion::{get_connector, SourceSchema, TableInfo};
+use dozer_ingestion::{
+    get_connector, CachedConnector, Connector, SchemaCache, SourceSchema, TableInfo,
+};
 use dozer_sql::builder::statement_to_pipeline;
 use dozer_sql::errors::PipelineError;
-use dozer_types::log::info;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::{info, warn};
 use dozer_types::models::config::{default_cache_dir, default_home_dir, Config};
+use dozer_types::prettytable::{Cell, Row, Table};
 use dozer_types::tracing::error;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
@@ -43,6 +52,8 @@ pub struct SimpleOrchestrator {
     pub config: Config,
     pub runtime: Arc<Runtime>,
     pub labels: LabelsAndProgress,
+    /// Source schemas shared by the pipelines this orchestrator builds.
+    pub schema_cache: SchemaCache,
 }
 
 impl SimpleOrchestrator {
@@ -52,11 +63,13 @@ impl SimpleOrchestrator {
         runtime: Arc<Runtime>,
         labels: LabelsAndProgress,
     ) -> Self {
+        let schema_cache = SchemaCache::new(get_schema_cache_ttl(&config));
         Self {
             base_directory,
             config,
             runtime,
             labels,
+            schema_cache,
         }
     }
 
@@ -82,6 +95,24 @@ impl SimpleOrchestrator {
         lockfile_path(self.base_directory.clone())
     }
 
+    /// Opens the checkpoints of the latest build.
+    pub async fn checkpoint_factory(&self) -> Result<CheckpointFactory, OrchestrationError> {
+        let home_dir = HomeDir::new(self.home_dir(), self.cache_dir());
+        let build_path = home_dir
+            .find_latest_build_path()
+            .map_err(|(path, error)| OrchestrationError::FileSystem(path.into(), error))?
+            .ok_or(OrchestrationError::NoBuildFound)?;
+        let checkpoint = OptionCheckpoint::new(
+            build_path.data_dir.to_string(),
+            get_checkpoint_options(&self.config),
+        )
+        .await?;
+        let (storage, prefix) = checkpoint.into_storage();
+        let (checkpoint_factory, _) =
+            CheckpointFactory::new(storage, prefix, Default::default()).await?;
+        Ok(checkpoint_factory)
+    }
+
     pub async fn run_apps(
         &self,
         shutdown: ShutdownReceiver,
@@ -105,6 +136,7 @@ impl SimpleOrchestrator {
                 get_executor_options(&self.config),
                 shutdown.clone(),
                 self.config.flags.clone(),
+                self.schema_cache.clone(),
             )
             .await?;
 
@@ -141,10 +173,11 @@ impl SimpleOrchestrator {
             .filter(|conn| connections.contains(&conn.name))
         {
             // We're not really going to start ingestion, so passing `None` as state here is OK.
-            let mut connector = get_connector(self.runtime.clone(), connection.clone(), None)
+            let connector = get_connector(self.runtime.clone(), connection.clone(), None)
                 .map_err(|e| ConnectorSourceFactoryError::Connector(e.into()))?;
-            let schema_tuples = connector
-                .list_all_schemas()
+            let mut connector =
+                CachedConnector::new(connector, connection.clone(), self.schema_cache.clone());
+            let schema_tuples = list_all_schemas_paginated(&mut connector)
                 .await
                 .map_err(ConnectorSourceFactoryError::Connector)?;
             schema_map.insert(connection.name.clone(), schema_tuples);
@@ -153,6 +186,42 @@ impl SimpleOrchestrator {
         Ok(schema_map)
     }
 
+    /// Runs `query` against the connection named `connection_name` and prints the first `limit` records.
+    pub async fn query(
+        &self,
+        connection_name: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<(), OrchestrationError> {
+        let connection = self
+            .config
+            .connections
+            .iter()
+            .find(|connection| connection.name == connection_name)
+            .ok_or_else(|| OrchestrationError::ConnectionNotFound(connection_name.to_string()))?;
+        // We're not really going to start ingestion, so passing `None` as state here is OK.
+        let connector = get_connector(self.runtime.clone(), connection.clone(), None)
+            .map_err(|e| ConnectorSourceFactoryError::Connector(e.into()))?;
+        let records = connector
+            .test_query(query, limit)
+            .await
+            .map_err(ConnectorSourceFactoryError::Connector)?;
+
+        let mut table = Table::new();
+        for record in &records {
+            table.add_row(Row::new(
+                record
+                    .values
+                    .iter()
+                    .map(|field| Cell::new(&field.to_string()))
+                    .collect(),
+            ));
+        }
+        table.printstd();
+        info!("{} record(s)", records.len());
+        Ok(())
+    }
+
     pub async fn build(
         &self,
         force: bool,
@@ -180,7 +249,8 @@ impl SimpleOrchestrator {
             self.labels.clone(),
             self.config.flags.clone(),
             &self.config.udfs,
-        );
+        )
+        .with_schema_cache(self.schema_cache.clone());
         let dag = builder.build(&self.runtime, shutdown).await?;
         // Populate schemas.
         let dag_schemas = DagSchemas::new(dag).await?;
@@ -211,6 +281,107 @@ impl SimpleOrchestrator {
         Ok(())
     }
 
+    /// Prints a JSON description of every processor in the pipeline.
+    pub async fn explain(&self, shutdown: ShutdownReceiver) -> Result<(), OrchestrationError> {
+        validate_config(&self.config)?;
+
+        let builder = PipelineBuilder::new(
+            &self.config.connections,
+            &self.config.sources,
+            self.config.sql.as_deref(),
+            &self.config.sinks,
+            self.labels.clone(),
+            self.config.flags.clone(),
+            &self.config.udfs,
+        )
+        .with_schema_cache(self.schema_cache.clone());
+        let dag = builder.build(&self.runtime, shutdown).await?;
+        let explanations = DagSchemas::new(dag).await?.explain().await?;
+
+        let explanations =
+            serde_json::to_string_pretty(&explanations).map_err(BuildError::SerdeJson)?;
+        println!("{explanations}");
+        Ok(())
+    }
+
+    /// Generates the DDL that migrates every sink whose input schemas changed since the last
+    /// build, and writes it to `output` or stdout. Updates the lock file unless `dry_run`.
+    pub async fn migrate(
+        &self,
+        shutdown: ShutdownReceiver,
+        dry_run: bool,
+        output: Option<&str>,
+    ) -> Result<(), OrchestrationError> {
+        validate_config(&self.config)?;
+
+        let contract_path = self.lockfile_path();
+        let Ok(existing_contract) = Contract::deserialize(contract_path.as_std_path()) else {
+            return Err(OrchestrationError::MigrateNoLockFile);
+        };
+
+        let builder = PipelineBuilder::new(
+            &self.config.connections,
+            &self.config.sources,
+            self.config.sql.as_deref(),
+            &self.config.sinks,
+            self.labels.clone(),
+            self.config.flags.clone(),
+            &self.config.udfs,
+        )
+        .with_schema_cache(self.schema_cache.clone());
+        let dag = builder.build(&self.runtime, shutdown).await?;
+        let dag_schemas = DagSchemas::new(dag).await?;
+
+        let mut ddl = String::new();
+        let graph = dag_schemas.graph();
+        for (node_index, node) in graph.node_references() {
+            let dozer_core::NodeKind::Sink(sink) = &node.kind else {
+                continue;
+            };
+            let Some(old_schemas) = existing_contract.get_sink_input_schemas(&node.handle) else {
+                info!("Sink {} is new, nothing to migrate", node.handle);
+                continue;
+            };
+            for edge in graph.edges_directed(node_index, Direction::Incoming) {
+                let port = edge.weight().input_port;
+                let new_schema = &edge.weight().schema;
+                let Some(old_schema) = old_schemas.get(&port) else {
+                    continue;
+                };
+                if old_schema == new_schema {
+                    continue;
+                }
+                match sink.generate_migration_ddl(&port, old_schema, new_schema) {
+                    Ok(statements) if statements.is_empty() => {}
+                    Ok(statements) => {
+                        ddl.push_str(&format!("-- {}\n{statements}\n", node.handle));
+                    }
+                    Err(MigrationError::Unsupported(typ)) => {
+                        warn!(
+                            "Sink {} of type {typ} doesn't generate migrations, skipping",
+                            node.handle
+                        );
+                    }
+                    Err(e) => return Err(OrchestrationError::Migration(node.handle.clone(), e)),
+                }
+            }
+        }
+
+        match output {
+            Some(path) => {
+                fs::write(path, &ddl).map_err(|e| BuildError::FileSystem(path.into(), e))?
+            }
+            None => print!("{ddl}"),
+        }
+
+        if !dry_run {
+            let version = self.config.version as usize;
+            let contract = build::Contract::new(version, &dag_schemas, &self.config.connections)?;
+            contract.serialize(contract_path.as_std_path())?;
+        }
+        Ok(())
+    }
+
     // Cleaning the entire folder as there will be inconsistencies
     // between pipeline, cache and generated proto files.
     pub fn clean(&self) -> Result<(), OrchestrationError> {
@@ -285,6 +456,33 @@ pub fn validate_sql(sql: String, runtime: Arc<Runtime>) -> Result<(), PipelineEr
     )
 }
 
+/// Number of tables `list_connectors` gets the schemas of at a time.
+const LIST_TABLES_PAGE_SIZE: usize = 100;
+
+/// Like `Connector::list_all_schemas`, but lists the tables and gets their schemas page by page.
+async fn list_all_schemas_paginated(
+    connector: &mut dyn Connector,
+) -> Result<(Vec<TableInfo>, Vec<SourceSchema>), BoxedError> {
+    let mut table_infos = vec![];
+    let mut schemas = vec![];
+    let mut cursor = None;
+    loop {
+        let (tables, next_cursor) = connector
+            .list_tables_paginated(LIST_TABLES_PAGE_SIZE, cursor)
+            .await?;
+        let page_table_infos = connector.list_columns(tables).await?;
+        for schema in connector.get_schemas(&page_table_infos).await? {
+            schemas.push(schema?);
+        }
+        table_infos.extend(page_table_infos);
+
+        cursor = next_cursor;
+        if cursor.is_none() {
+            return Ok((table_infos, schemas));
+        }
+    }
+}
+
 pub fn lockfile_path(base_directory: Utf8PathBuf) -> Utf8PathBuf {
     base_directory.join(LOCK_FILE)
 }
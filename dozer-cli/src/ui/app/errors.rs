@@ -1,6 +1,6 @@
 use crate::errors::{BuildError, CliError, OrchestrationError};
 use crate::ui::downloader::DownloaderError;
-use dozer_core::errors::ExecutionError;
+use dozer_core::errors::{ExecutionError, NodeBuildError};
 use dozer_sql::errors::PipelineError;
 
 use dozer_types::thiserror;
@@ -33,14 +33,25 @@ pub enum AppUIError {
     ConnectionNotFound(String),
     #[error("Sink {0} not found")]
     SinkNotFound(String),
+    #[error("Node {0} not found in the pipeline graph")]
+    NodeNotFound(String),
     #[error("Error in initializing app ui server: {0}")]
     Transport(#[from] tonic::transport::Error),
     #[error("Error in reading or extracting from Zip file: {0}")]
     ZipError(#[from] ZipError),
     #[error("Reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
+    #[error("Cannot parse the app's metrics: {0}")]
+    CannotParseMetrics(#[source] std::io::Error),
     #[error("Cannot start ui server: {0}")]
     CannotStartUiServer(#[source] std::io::Error),
+    #[error("Pipeline thread panicked while starting")]
+    RunThreadPanicked,
+    #[error("Source {source_name} can't be queried because its connection {connection} listens for pushed changes, which the running app already does")]
+    PushSourceInQuery {
+        source_name: String,
+        connection: String,
+    },
 
     #[error(transparent)]
     Build(#[from] BuildError),
@@ -55,6 +66,24 @@ pub enum AppUIError {
     DownloaderError(#[from] DownloaderError),
 }
 
+impl AppUIError {
+    /// The nodes that failed to build, if that's why the pipeline couldn't start.
+    pub fn node_build_errors(&self) -> Option<&[NodeBuildError]> {
+        let error = match self {
+            AppUIError::ExecutionError(error) => error,
+            AppUIError::OrchestrationError(error) => match error.as_ref() {
+                OrchestrationError::ExecutionError(error) => error,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match error {
+            ExecutionError::NodesFailedToBuild(errors) => Some(errors),
+            _ => None,
+        }
+    }
+}
+
 impl From<OrchestrationError> for AppUIError {
     fn from(error: OrchestrationError) -> Self {
         AppUIError::OrchestrationError(Box::new(error))
@@ -1,7 +1,11 @@
 mod errors;
+mod query;
 mod server;
+mod sink_lags;
 mod state;
 mod watcher;
+use crate::cli::get_base_dir;
+use crate::simple::orchestrator::lockfile_path;
 use crate::ui::{
     app::{server::APP_UI_PORT, state::AppUIState},
     downloader::{self, LOCAL_APP_UI_DIR},
@@ -11,6 +15,7 @@ use dozer_types::{grpc_types::app_ui::ConnectResponse, log::info};
 pub use errors::AppUIError;
 use futures::stream::{AbortHandle, Abortable};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 const APP_UI_WEB_PORT: u16 = 62888;
@@ -19,10 +24,21 @@ pub async fn start_app_ui_server(
     runtime: &Arc<Runtime>,
     shutdown: ShutdownReceiver,
     disable_ui: bool,
+    refresh_interval: Duration,
 ) -> Result<(), AppUIError> {
     let (sender, receiver) = tokio::sync::broadcast::channel::<ConnectResponse>(100);
     let state = Arc::new(AppUIState::new());
     state.set_sender(sender.clone()).await;
+    // Show the topology of the last build until the app builds again.
+    let lockfile_path = lockfile_path(get_base_dir()?);
+    if lockfile_path.exists() {
+        if let Err(e) = state
+            .load_contract_snapshot(lockfile_path.as_std_path())
+            .await
+        {
+            info!("Failed to load contract from {}: {}", lockfile_path, e);
+        }
+    }
     // Ignore if build fails
     let res = state.build(runtime.clone()).await;
     if let Err(e) = res {
@@ -48,17 +64,21 @@ pub async fn start_app_ui_server(
     }
     info!("Starting app ui server on port : {}", APP_UI_PORT);
     let rshudown = shutdown.clone();
-    tokio::spawn(async {
+    tokio::spawn(async move {
         let (abort_handle, abort_registration) = AbortHandle::new_pair();
         tokio::spawn(async move {
             rshudown.create_shutdown_future().await;
             abort_handle.abort();
         });
-        let res: Result<(), AppUIError> =
-            match Abortable::new(server::serve(receiver, state2), abort_registration).await {
-                Ok(result) => result.map_err(AppUIError::Transport),
-                Err(_) => Ok(()),
-            };
+        let res: Result<(), AppUIError> = match Abortable::new(
+            server::serve(receiver, state2, refresh_interval),
+            abort_registration,
+        )
+        .await
+        {
+            Ok(result) => result.map_err(AppUIError::Transport),
+            Err(_) => Ok(()),
+        };
 
         res.unwrap();
     });
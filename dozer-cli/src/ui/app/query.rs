@@ -0,0 +1,256 @@
+use std::{collections::HashMap, time::Duration};
+
+use dozer_core::{
+    checkpoint::OptionCheckpoint,
+    epoch::Epoch,
+    executor::DagExecutor,
+    node::{PortHandle, Sink, SinkFactory},
+    shutdown, DEFAULT_PORT_HANDLE,
+};
+use dozer_log::storage::Queue;
+use dozer_types::{
+    errors::internal::BoxedError,
+    grpc_types::{
+        contract::{execute_query_response, ExecuteQueryResponse},
+        conversions::{field_to_grpc, map_schema},
+        types::{self, OperationType},
+    },
+    log::warn,
+    models::{
+        self,
+        connection::ConnectionConfig,
+        flags::Flags,
+        sink::{DummySinkConfig, SinkConfig},
+    },
+    node::OpIdentifier,
+    types::{Operation, Record, Schema, TableOperation},
+};
+use tempdir::TempDir;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use super::AppUIError;
+use crate::{async_trait::async_trait, pipeline::PipelineBuilder, simple::SimpleOrchestrator};
+
+const QUERY_OUTPUT_TABLE_NAME: &str = "query_output";
+
+/// Runs `sql` against the app's sources in a pipeline of its own, which is stopped after `timeout`.
+///
+/// The returned receiver gets the schema of the query's output, then the operations on it.
+pub async fn execute_query(
+    dozer: SimpleOrchestrator,
+    sql: String,
+    timeout: Duration,
+) -> Result<Receiver<ExecuteQueryResponse>, AppUIError> {
+    let sinks = [models::sink::Sink {
+        name: QUERY_OUTPUT_TABLE_NAME.to_string(),
+        config: SinkConfig::Dummy(DummySinkConfig {
+            table_name: QUERY_OUTPUT_TABLE_NAME.to_string(),
+        }),
+    }];
+    let builder = PipelineBuilder::new(
+        &dozer.config.connections,
+        &dozer.config.sources,
+        Some(&sql),
+        &sinks,
+        Default::default(),
+        Flags::default(),
+        &dozer.config.udfs,
+    )
+    .with_output_table_name(QUERY_OUTPUT_TABLE_NAME.to_string())
+    .with_schema_cache(dozer.schema_cache.clone());
+    reject_push_sources(&dozer, &builder)?;
+    let (shutdown_sender, shutdown_receiver) = shutdown::new(&dozer.runtime);
+    let mut dag = builder
+        .build(&dozer.runtime, shutdown_receiver.clone())
+        .await?;
+
+    let (sender, receiver) = channel(100);
+    dag.replace_sinks(|_, _| {
+        Box::new(QuerySinkFactory {
+            sender: sender.clone(),
+        })
+    });
+
+    // The directory is removed when dropped, so it must outlive the run
+    let checkpoint_dir = TempDir::new("execute_query")?;
+    let checkpoint_path = checkpoint_dir.path().to_string_lossy().into_owned();
+    let checkpoint = OptionCheckpoint::new(checkpoint_path, Default::default()).await?;
+    let handle = DagExecutor::new(dag, checkpoint, Default::default())
+        .await?
+        .start(
+            Box::pin(shutdown_receiver.create_shutdown_future()),
+            Default::default(),
+            dozer.runtime.clone(),
+        )
+        .await?;
+
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        shutdown_sender.shutdown();
+    });
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = handle.join() {
+            warn!("Query pipeline failed: {e}");
+        }
+        drop(checkpoint_dir);
+    });
+
+    Ok(receiver)
+}
+
+/// The query gets connectors of its own, but connectors that listen for pushed changes, like the
+/// Aerospike XDR listener, would clash with the running app's on the port they listen on.
+fn reject_push_sources(
+    dozer: &SimpleOrchestrator,
+    builder: &PipelineBuilder,
+) -> Result<(), AppUIError> {
+    let used_sources = builder
+        .calculate_sources(dozer.runtime.clone())?
+        .original_sources;
+    for source in &dozer.config.sources {
+        if !used_sources.contains(&source.name) {
+            continue;
+        }
+        let Some(connection) = dozer
+            .config
+            .connections
+            .iter()
+            .find(|connection| connection.name == source.connection)
+        else {
+            continue;
+        };
+        if matches!(
+            connection.config,
+            ConnectionConfig::Aerospike(_) | ConnectionConfig::Webhook(_)
+        ) {
+            return Err(AppUIError::PushSourceInQuery {
+                source_name: source.name.clone(),
+                connection: connection.name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct QuerySinkFactory {
+    sender: Sender<ExecuteQueryResponse>,
+}
+
+#[async_trait]
+impl SinkFactory for QuerySinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_input_port_name(&self, _port: &PortHandle) -> String {
+        QUERY_OUTPUT_TABLE_NAME.to_string()
+    }
+
+    fn prepare(&self, _input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        let schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .ok_or("query output schema not found")?;
+        self.sender
+            .send(ExecuteQueryResponse {
+                response: Some(execute_query_response::Response::Schema(map_schema(schema))),
+            })
+            .await?;
+        Ok(Box::new(QuerySink {
+            sender: self.sender.clone(),
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "query".to_string()
+    }
+}
+
+/// Sends the operations on the query's output to the client.
+#[derive(Debug)]
+struct QuerySink {
+    sender: Sender<ExecuteQueryResponse>,
+}
+
+impl QuerySink {
+    fn send(&self, typ: OperationType, old: Option<Record>, new: Record) {
+        let operation = types::Operation {
+            typ: typ as i32,
+            old: old.map(map_record),
+            new: Some(map_record(new)),
+            endpoint: QUERY_OUTPUT_TABLE_NAME.to_string(),
+        };
+        // The client may have gone away, in which case the pipeline runs until it times out.
+        let _ = self.sender.blocking_send(ExecuteQueryResponse {
+            response: Some(execute_query_response::Response::Operation(operation)),
+        });
+    }
+}
+
+fn map_record(record: Record) -> types::Record {
+    types::Record {
+        values: record.values.into_iter().map(field_to_grpc).collect(),
+        id: 0,
+        version: 0,
+    }
+}
+
+impl Sink for QuerySink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        match op.op {
+            Operation::Insert { new } => self.send(OperationType::Insert, None, new),
+            Operation::BatchInsert { new } => {
+                for new in new {
+                    self.send(OperationType::Insert, None, new);
+                }
+            }
+            Operation::Delete { old } => self.send(OperationType::Delete, None, old),
+            Operation::Update { old, new } => self.send(OperationType::Update, Some(old), new),
+            Operation::Upsert { new } => self.send(OperationType::Update, None, new),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {}
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        Ok(None)
+    }
+}
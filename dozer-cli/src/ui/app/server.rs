@@ -2,25 +2,31 @@ use dozer_types::{
     grpc_types::{
         app_ui::{
             code_service_server::{CodeService, CodeServiceServer},
-            ConnectResponse, Label, Labels, RunRequest,
+            ConnectResponse, Label, Labels, NodeError, RunErrorDetails, RunRequest,
         },
         contract::{
             contract_service_server::{ContractService, ContractServiceServer},
-            CommonRequest, DotResponse, SinkTablesRequest, SourcesRequest,
+            CheckpointsResponse, CommonRequest, DotRequest, DotResponse, ExecuteQueryRequest,
+            ExecuteQueryResponse, PruneCheckpointsRequest, SinkLagsResponse, SinkTablesRequest,
+            SourcesRequest,
         },
         types::SchemasResponse,
     },
     log::info,
+    prost::Message,
 };
 use futures::stream::BoxStream;
+use futures::StreamExt;
 use metrics::IntoLabels;
 use std::sync::Arc;
-use tokio::sync::broadcast::Receiver;
+use std::time::Duration;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tokio::time::MissedTickBehavior;
 
-use super::state::AppUIState;
+use super::{state::AppUIState, AppUIError};
 use dozer_types::tracing::Level;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{Request, Response, Status};
+use tonic::{Code, Request, Response, Status};
 use tower_http::trace::{self, TraceLayer};
 pub const APP_UI_PORT: u16 = 4555;
 
@@ -56,10 +62,11 @@ impl ContractService for ContractServer {
 
     async fn generate_dot(
         &self,
-        _request: Request<CommonRequest>,
+        request: Request<DotRequest>,
     ) -> Result<Response<DotResponse>, Status> {
+        let req = request.into_inner();
         let state = self.state.clone();
-        let res = state.generate_dot().await;
+        let res = state.generate_dot(req.root, req.depth).await;
 
         match res {
             Ok(res) => Ok(Response::new(res)),
@@ -79,16 +86,77 @@ impl ContractService for ContractServer {
             Err(e) => Err(Status::internal(e.to_string())),
         }
     }
+
+    async fn list_checkpoints(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<CheckpointsResponse>, Status> {
+        let res = self.state.list_checkpoints().await;
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        request: Request<PruneCheckpointsRequest>,
+    ) -> Result<Response<CheckpointsResponse>, Status> {
+        let req = request.into_inner();
+        let res = self.state.prune_checkpoints(req.keep_latest as usize).await;
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn get_sink_lags(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<SinkLagsResponse>, Status> {
+        let res = self.state.get_sink_lags().await;
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::unavailable(e.to_string())),
+        }
+    }
+
+    type ExecuteQueryStream = BoxStream<'static, Result<ExecuteQueryResponse, Status>>;
+
+    async fn execute_query(
+        &self,
+        request: Request<ExecuteQueryRequest>,
+    ) -> Result<Response<Self::ExecuteQueryStream>, Status> {
+        let req = request.into_inner();
+        let res = self
+            .state
+            .execute_query(req.sql, Duration::from_millis(req.timeout_ms))
+            .await;
+        match res {
+            Ok(receiver) => Ok(Response::new(ReceiverStream::new(receiver).map(Ok).boxed())),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
 }
 
 struct AppUiServer {
     receiver: Receiver<ConnectResponse>,
     state: Arc<AppUIState>,
+    /// How often connected clients are sent the current state, whether it changed or not.
+    refresh_interval: Duration,
 }
 
 impl AppUiServer {
-    pub fn new(receiver: Receiver<ConnectResponse>, state: Arc<AppUIState>) -> AppUiServer {
-        Self { receiver, state }
+    pub fn new(
+        receiver: Receiver<ConnectResponse>,
+        state: Arc<AppUIState>,
+        refresh_interval: Duration,
+    ) -> AppUiServer {
+        Self {
+            receiver,
+            state,
+            refresh_interval,
+        }
     }
     async fn start(&self, req: RunRequest) -> Result<Response<Labels>, Status> {
         let state = self.state.clone();
@@ -105,11 +173,32 @@ impl AppUiServer {
                     .collect();
                 Ok(Response::new(Labels { labels }))
             }
-            Err(e) => Err(Status::internal(e.to_string())),
+            Err(e) => Err(run_error_status(&e)),
         }
     }
 }
 
+/// Lists the nodes that failed to build in the status details, so the UI can point at them.
+fn run_error_status(error: &AppUIError) -> Status {
+    let Some(node_build_errors) = error.node_build_errors() else {
+        return Status::internal(error.to_string());
+    };
+    let details = RunErrorDetails {
+        node_errors: node_build_errors
+            .iter()
+            .map(|e| NodeError {
+                node: e.node.to_string(),
+                error: e.error.to_string(),
+            })
+            .collect(),
+    };
+    Status::with_details(
+        Code::Internal,
+        error.to_string(),
+        details.encode_to_vec().into(),
+    )
+}
+
 #[tonic::async_trait]
 impl CodeService for AppUiServer {
     type AppUIConnectStream = BoxStream<'static, Result<ConnectResponse, Status>>;
@@ -121,25 +210,25 @@ impl CodeService for AppUiServer {
         let (tx, rx) = tokio::sync::mpsc::channel(1);
         let mut receiver = self.receiver.resubscribe();
 
-        let initial_state = self.state.clone();
+        let state = self.state.clone();
+        // The first tick completes immediately and sends the initial state.
+        let mut refresh = tokio::time::interval(self.refresh_interval);
+        refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
         tokio::spawn(async move {
-            let initial_state = initial_state.get_current().await;
-            if let Err(e) = tx
-                .send(Ok(ConnectResponse {
-                    app_ui: Some(initial_state),
-                    build: None,
-                }))
-                .await
-            {
-                info!("Error getting initial state");
-                info!("{}", e.to_string());
-                return {};
-            }
             loop {
-                let Ok(connect_response) = receiver.recv().await else {
-                    break;
+                let connect_response = tokio::select! {
+                    _ = refresh.tick() => ConnectResponse {
+                        app_ui: Some(state.get_current().await),
+                        build: None,
+                    },
+                    connect_response = receiver.recv() => match connect_response {
+                        Ok(connect_response) => connect_response,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
                 };
-                if tx.send(Ok(connect_response)).await.is_err() {
+                if let Err(e) = tx.send(Ok(connect_response)).await {
+                    info!("App UI client disconnected: {e}");
                     break;
                 }
             }
@@ -167,12 +256,13 @@ impl CodeService for AppUiServer {
 pub async fn serve(
     receiver: Receiver<ConnectResponse>,
     state: Arc<AppUIState>,
+    refresh_interval: Duration,
 ) -> Result<(), tonic::transport::Error> {
     let addr = format!("0.0.0.0:{APP_UI_PORT}").parse().unwrap();
     let contract_server = ContractServer {
         state: state.clone(),
     };
-    let app_ui_server = AppUiServer::new(receiver, state);
+    let app_ui_server = AppUiServer::new(receiver, state, refresh_interval);
     let contract_service = ContractServiceServer::new(contract_server);
     let code_service = CodeServiceServer::new(app_ui_server);
     // Enable CORS for local development
@@ -0,0 +1,33 @@
+use dozer_core::executor::SINK_WRITE_AHEAD_LAG_GAUGE_NAME;
+use dozer_types::grpc_types::contract::{SinkLag, SinkLagsResponse};
+use prometheus_parse::Value;
+
+use super::AppUIError;
+
+const METRICS_ENDPOINT: &str = "http://localhost:9000/metrics";
+
+/// Reads the sinks' write-ahead lag from the metrics endpoint of the running app.
+pub async fn get_sink_lags() -> Result<SinkLagsResponse, AppUIError> {
+    let text = reqwest::get(METRICS_ENDPOINT)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let lines = text.lines().map(|line| Ok(line.to_string()));
+    let metrics = prometheus_parse::Scrape::parse(lines).map_err(AppUIError::CannotParseMetrics)?;
+
+    let mut sink_lags = metrics
+        .samples
+        .into_iter()
+        .filter(|sample| sample.metric == SINK_WRITE_AHEAD_LAG_GAUGE_NAME)
+        .filter_map(|sample| match sample.value {
+            Value::Gauge(lag_secs) => Some(SinkLag {
+                sink: sample.labels.get("endpoint")?.to_string(),
+                lag_secs,
+            }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    sink_lags.sort_by(|a, b| a.sink.cmp(&b.sink));
+    Ok(SinkLagsResponse { sink_lags })
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc, thread::JoinHandle};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 
 use clap::Parser;
 
@@ -9,7 +9,7 @@ use dozer_tracing::{Labels, LabelsAndProgress};
 use dozer_types::{
     grpc_types::{
         app_ui::{AppUi, AppUiResponse, BuildResponse, BuildStatus, ConnectResponse, RunRequest},
-        contract::DotResponse,
+        contract::{CheckpointsResponse, DotResponse, ExecuteQueryResponse, SinkLagsResponse},
         types::SchemasResponse,
     },
     log::info,
@@ -20,14 +20,18 @@ use dozer_types::{
     },
 };
 use tempdir::TempDir;
-use tokio::{runtime::Runtime, sync::RwLock};
+use tokio::{
+    runtime::Runtime,
+    sync::{mpsc, oneshot, RwLock},
+};
 
-use super::AppUIError;
+use super::{query, sink_lags, AppUIError};
 use crate::{
     cli::{init_config, init_dozer, types::Cli},
     errors::OrchestrationError,
     pipeline::PipelineBuilder,
     simple::{helper::validate_config, Contract, SimpleOrchestrator},
+    ui::checkpoints_response,
 };
 struct DozerAndContract {
     dozer: SimpleOrchestrator,
@@ -47,6 +51,8 @@ pub enum BroadcastType {
 }
 pub struct AppUIState {
     dozer: RwLock<Option<DozerAndContract>>,
+    /// Contract loaded from a previous build. Answers contract queries while there's no built contract.
+    contract_snapshot: RwLock<Option<Contract>>,
     run_thread: RwLock<Option<ShutdownAndTempDir>>,
     error_message: RwLock<Option<String>>,
     sender: RwLock<Option<tokio::sync::broadcast::Sender<ConnectResponse>>>,
@@ -61,6 +67,7 @@ impl AppUIState {
     pub fn new() -> Self {
         Self {
             dozer: RwLock::new(None),
+            contract_snapshot: RwLock::new(None),
             run_thread: RwLock::new(None),
             sender: RwLock::new(None),
             error_message: RwLock::new(None),
@@ -78,6 +85,31 @@ impl AppUIState {
         Ok(())
     }
 
+    pub async fn load_contract_snapshot(&self, path: &Path) -> Result<(), AppUIError> {
+        let contract = Contract::deserialize(path)?;
+        *self.contract_snapshot.write().await = Some(contract);
+        Ok(())
+    }
+
+    /// Runs `f` on the built contract, or on the contract snapshot if the app can't be built.
+    async fn with_contract<T>(
+        &self,
+        f: impl FnOnce(&Contract) -> Result<T, AppUIError>,
+    ) -> Result<T, AppUIError> {
+        let created = self.create_contract_if_missing().await;
+        let dozer = self.dozer.read().await;
+        if let Ok(contract) = get_contract(&dozer) {
+            return f(contract);
+        }
+
+        let snapshot = self.contract_snapshot.read().await;
+        let Some(contract) = snapshot.as_ref() else {
+            created?;
+            return Err(AppUIError::NotInitialized);
+        };
+        f(contract)
+    }
+
     pub async fn set_sender(&self, sender: tokio::sync::broadcast::Sender<ConnectResponse>) {
         *self.sender.write().await = Some(sender);
     }
@@ -189,54 +221,101 @@ impl AppUIState {
         &self,
         sink_name: String,
     ) -> Result<SchemasResponse, AppUIError> {
-        self.create_contract_if_missing().await?;
-        let dozer = self.dozer.read().await;
-        let contract = get_contract(&dozer)?;
-
-        contract
-            .get_sink_table_schemas(&sink_name)
-            .ok_or(AppUIError::SinkNotFound(sink_name))
-            .map(|schemas| SchemasResponse {
-                schemas,
-                errors: HashMap::new(),
-            })
+        self.with_contract(|contract| {
+            contract
+                .get_sink_table_schemas(&sink_name)
+                .ok_or(AppUIError::SinkNotFound(sink_name))
+                .map(|schemas| SchemasResponse {
+                    schemas,
+                    errors: HashMap::new(),
+                })
+        })
+        .await
     }
     pub async fn get_source_schemas(
         &self,
         connection_name: String,
     ) -> Result<SchemasResponse, AppUIError> {
-        self.create_contract_if_missing().await?;
-        let dozer = self.dozer.read().await;
-        let contract = get_contract(&dozer)?;
+        self.with_contract(|contract| {
+            contract
+                .get_source_schemas(&connection_name)
+                .ok_or(AppUIError::ConnectionNotFound(connection_name))
+                .map(|schemas| SchemasResponse {
+                    schemas,
+                    errors: HashMap::new(),
+                })
+        })
+        .await
+    }
 
-        contract
-            .get_source_schemas(&connection_name)
-            .ok_or(AppUIError::ConnectionNotFound(connection_name))
-            .map(|schemas| SchemasResponse {
-                schemas,
+    pub async fn get_graph_schemas(&self) -> Result<SchemasResponse, AppUIError> {
+        self.with_contract(|contract| {
+            Ok(SchemasResponse {
+                schemas: contract.get_graph_schemas(),
                 errors: HashMap::new(),
             })
+        })
+        .await
     }
 
-    pub async fn get_graph_schemas(&self) -> Result<SchemasResponse, AppUIError> {
-        self.create_contract_if_missing().await?;
-        let dozer = self.dozer.read().await;
-        let contract = get_contract(&dozer)?;
-
-        Ok(SchemasResponse {
-            schemas: contract.get_graph_schemas(),
-            errors: HashMap::new(),
+    /// Renders the pipeline graph, or only the part of it around `root` if it's set.
+    pub async fn generate_dot(
+        &self,
+        root: Option<String>,
+        depth: Option<u32>,
+    ) -> Result<DotResponse, AppUIError> {
+        self.with_contract(|contract| {
+            let dot = match root {
+                Some(root) => contract
+                    .generate_subgraph_dot(&root, depth)
+                    .ok_or(AppUIError::NodeNotFound(root))?,
+                None => contract.generate_dot(),
+            };
+            Ok(DotResponse { dot })
         })
+        .await
     }
 
-    pub async fn generate_dot(&self) -> Result<DotResponse, AppUIError> {
-        self.create_contract_if_missing().await?;
+    pub async fn list_checkpoints(&self) -> Result<CheckpointsResponse, AppUIError> {
         let dozer = self.dozer.read().await;
-        let contract = get_contract(&dozer)?;
+        let dozer = &dozer.as_ref().ok_or(AppUIError::NotInitialized)?.dozer;
+        let checkpoint_factory = dozer.checkpoint_factory().await?;
+        Ok(checkpoints_response(
+            checkpoint_factory.list_checkpoints().await?,
+        ))
+    }
 
-        Ok(DotResponse {
-            dot: contract.generate_dot(),
-        })
+    pub async fn prune_checkpoints(
+        &self,
+        keep_latest: usize,
+    ) -> Result<CheckpointsResponse, AppUIError> {
+        let dozer = self.dozer.read().await;
+        let dozer = &dozer.as_ref().ok_or(AppUIError::NotInitialized)?.dozer;
+        let checkpoint_factory = dozer.checkpoint_factory().await?;
+        Ok(checkpoints_response(
+            checkpoint_factory.prune_checkpoints(keep_latest).await?,
+        ))
+    }
+
+    /// Runs `sql` against the app's sources for up to `timeout`. See `query::execute_query`.
+    pub async fn execute_query(
+        &self,
+        sql: String,
+        timeout: Duration,
+    ) -> Result<mpsc::Receiver<ExecuteQueryResponse>, AppUIError> {
+        let dozer = self
+            .dozer
+            .read()
+            .await
+            .as_ref()
+            .ok_or(AppUIError::NotInitialized)?
+            .dozer
+            .clone();
+        query::execute_query(dozer, sql, timeout).await
+    }
+
+    pub async fn get_sink_lags(&self) -> Result<SinkLagsResponse, AppUIError> {
+        sink_lags::get_sink_lags().await
     }
 
     pub async fn run(&self, request: RunRequest) -> Result<Labels, AppUIError> {
@@ -251,13 +330,15 @@ impl AppUIState {
             .into_iter()
             .collect();
         let (shutdown_sender, shutdown_receiver) = shutdown::new(&dozer.runtime);
-        let _handle = run(
+        let started = run(
             dozer.clone(),
             labels.clone(),
             request,
             shutdown_receiver,
             temp_dir_path,
         )?;
+        // Wait for the pipeline to be built, so that its failures reach the client.
+        started.await.map_err(|_| AppUIError::RunThreadPanicked)??;
 
         let mut lock = self.run_thread.write().await;
         if let Some(shutdown_and_tempdir) = lock.take() {
@@ -308,7 +389,8 @@ pub async fn create_dag(dozer: &SimpleOrchestrator) -> Result<Dag, Orchestration
         Default::default(),
         Flags::default(),
         &dozer.config.udfs,
-    );
+    )
+    .with_schema_cache(dozer.schema_cache.clone());
     let (_shutdown_sender, shutdown_receiver) = shutdown::new(&dozer.runtime);
     builder.build(&dozer.runtime, shutdown_receiver).await
 }
@@ -319,17 +401,20 @@ fn run(
     request: RunRequest,
     shutdown_receiver: ShutdownReceiver,
     temp_dir: &str,
-) -> Result<JoinHandle<()>, OrchestrationError> {
+) -> Result<oneshot::Receiver<Result<(), OrchestrationError>>, OrchestrationError> {
     let dozer = get_dozer_run_instance(dozer, labels, request, temp_dir)?;
 
     validate_config(&dozer.config)?;
     let runtime = dozer.runtime.clone();
 
-    let handle: JoinHandle<()> = std::thread::spawn(move || {
-        runtime.block_on(async move { dozer.run_all(shutdown_receiver, false).await.unwrap() });
+    // `run_all` returns once the pipeline is built and running.
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let result = runtime.block_on(dozer.run_all(shutdown_receiver, false));
+        let _ = sender.send(result);
     });
 
-    Ok(handle)
+    Ok(receiver)
 }
 
 fn get_dozer_run_instance(
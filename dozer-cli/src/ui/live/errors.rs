@@ -22,6 +22,8 @@ pub enum LiveError {
     ConnectionNotFound(String),
     #[error("Sink {0} not found")]
     SinkNotFound(String),
+    #[error("Node {0} not found in the pipeline graph")]
+    NodeNotFound(String),
     #[error("Error in initializing live server: {0}")]
     Transport(#[from] tonic::transport::Error),
     #[error("Error in reading or extracting from Zip file: {0}")]
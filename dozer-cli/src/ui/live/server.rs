@@ -2,7 +2,9 @@ use dozer_types::{
     grpc_types::{
         contract::{
             contract_service_server::{ContractService, ContractServiceServer},
-            CommonRequest, DotResponse, SinkTablesRequest, SourcesRequest,
+            CheckpointsResponse, CommonRequest, DotRequest, DotResponse, ExecuteQueryRequest,
+            ExecuteQueryResponse, PruneCheckpointsRequest, SinkLagsResponse, SinkTablesRequest,
+            SourcesRequest,
         },
         live::{
             code_service_server::{CodeService, CodeServiceServer},
@@ -58,10 +60,11 @@ impl ContractService for ContractServer {
 
     async fn generate_dot(
         &self,
-        _request: Request<CommonRequest>,
+        request: Request<DotRequest>,
     ) -> Result<Response<DotResponse>, Status> {
+        let req = request.into_inner();
         let state = self.state.clone();
-        let res = state.generate_dot().await;
+        let res = state.generate_dot(req.root, req.depth).await;
 
         match res {
             Ok(res) => Ok(Response::new(res)),
@@ -81,6 +84,49 @@ impl ContractService for ContractServer {
             Err(e) => Err(Status::internal(e.to_string())),
         }
     }
+
+    async fn list_checkpoints(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<CheckpointsResponse>, Status> {
+        let res = self.state.list_checkpoints().await;
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        request: Request<PruneCheckpointsRequest>,
+    ) -> Result<Response<CheckpointsResponse>, Status> {
+        let req = request.into_inner();
+        let res = self.state.prune_checkpoints(req.keep_latest as usize).await;
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    type ExecuteQueryStream = BoxStream<'static, Result<ExecuteQueryResponse, Status>>;
+
+    async fn execute_query(
+        &self,
+        _request: Request<ExecuteQueryRequest>,
+    ) -> Result<Response<Self::ExecuteQueryStream>, Status> {
+        Err(Status::unimplemented(
+            "queries are only supported by the app UI server",
+        ))
+    }
+
+    async fn get_sink_lags(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<SinkLagsResponse>, Status> {
+        Err(Status::unimplemented(
+            "sink lags are only reported by the app UI server",
+        ))
+    }
 }
 
 struct LiveServer {
@@ -8,7 +8,7 @@ use dozer_log::camino::Utf8Path;
 use dozer_tracing::{Labels, LabelsAndProgress};
 use dozer_types::{
     grpc_types::{
-        contract::DotResponse,
+        contract::{CheckpointsResponse, DotResponse},
         live::{BuildResponse, BuildStatus, ConnectResponse, LiveApp, LiveResponse, RunRequest},
         types::SchemasResponse,
     },
@@ -27,6 +27,7 @@ use crate::{
     errors::OrchestrationError,
     pipeline::PipelineBuilder,
     simple::{helper::validate_config, Contract, SimpleOrchestrator},
+    ui::checkpoints_response,
 };
 
 use super::{progress::progress_stream, LiveError};
@@ -233,14 +234,44 @@ impl LiveState {
         })
     }
 
-    pub async fn generate_dot(&self) -> Result<DotResponse, LiveError> {
+    /// Renders the pipeline graph, or only the part of it around `root` if it's set.
+    pub async fn generate_dot(
+        &self,
+        root: Option<String>,
+        depth: Option<u32>,
+    ) -> Result<DotResponse, LiveError> {
         self.create_contract_if_missing().await?;
         let dozer = self.dozer.read().await;
         let contract = get_contract(&dozer)?;
 
-        Ok(DotResponse {
-            dot: contract.generate_dot(),
-        })
+        let dot = match root {
+            Some(root) => contract
+                .generate_subgraph_dot(&root, depth)
+                .ok_or(LiveError::NodeNotFound(root))?,
+            None => contract.generate_dot(),
+        };
+        Ok(DotResponse { dot })
+    }
+
+    pub async fn list_checkpoints(&self) -> Result<CheckpointsResponse, LiveError> {
+        let dozer = self.dozer.read().await;
+        let dozer = &dozer.as_ref().ok_or(LiveError::NotInitialized)?.dozer;
+        let checkpoint_factory = dozer.checkpoint_factory().await?;
+        Ok(checkpoints_response(
+            checkpoint_factory.list_checkpoints().await?,
+        ))
+    }
+
+    pub async fn prune_checkpoints(
+        &self,
+        keep_latest: usize,
+    ) -> Result<CheckpointsResponse, LiveError> {
+        let dozer = self.dozer.read().await;
+        let dozer = &dozer.as_ref().ok_or(LiveError::NotInitialized)?.dozer;
+        let checkpoint_factory = dozer.checkpoint_factory().await?;
+        Ok(checkpoints_response(
+            checkpoint_factory.prune_checkpoints(keep_latest).await?,
+        ))
     }
 
     pub async fn run(&self, request: RunRequest) -> Result<Labels, LiveError> {
@@ -321,7 +352,8 @@ pub async fn create_dag(dozer: &SimpleOrchestrator) -> Result<Dag, Orchestration
         Default::default(),
         Flags::default(),
         &dozer.config.udfs,
-    );
+    )
+    .with_schema_cache(dozer.schema_cache.clone());
     let (_shutdown_sender, shutdown_receiver) = shutdown::new(&dozer.runtime);
     builder.build(&dozer.runtime, shutdown_receiver).await
 }
@@ -1,3 +1,24 @@
 pub mod app;
 pub mod downloader;
 pub mod live;
+
+use dozer_core::checkpoint::CheckpointSummary;
+use dozer_types::grpc_types::contract::{Checkpoint, CheckpointsResponse};
+use std::time::UNIX_EPOCH;
+
+fn checkpoints_response(checkpoints: Vec<CheckpointSummary>) -> CheckpointsResponse {
+    CheckpointsResponse {
+        checkpoints: checkpoints
+            .into_iter()
+            .map(|checkpoint| Checkpoint {
+                epoch_id: checkpoint.epoch_id,
+                created_at: checkpoint
+                    .created_at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+                size_bytes: checkpoint.size_bytes,
+            })
+            .collect(),
+    }
+}
@@ -38,6 +38,17 @@ fn get_error_threshold(config: &Config) -> u32 {
         .unwrap_or_else(default_error_threshold)
 }
 
+fn get_sink_lag_alert_threshold(config: &Config) -> Option<Duration> {
+    config
+        .app
+        .sink_lag_alert_threshold_secs
+        .map(Duration::from_secs)
+}
+
+pub fn get_schema_cache_ttl(config: &Config) -> Option<Duration> {
+    config.app.schema_cache_ttl_secs.map(Duration::from_secs)
+}
+
 pub fn get_checkpoint_options(config: &Config) -> CheckpointOptions {
     let app = &config.app;
     CheckpointOptions {
@@ -62,5 +73,7 @@ pub fn get_executor_options(config: &Config) -> ExecutorOptions {
         commit_time_threshold: get_commit_time_threshold(config),
         error_threshold: Some(get_error_threshold(config)),
         checkpoint_factory_options: get_checkpoint_factory_options(config),
+        sink_lag_alert_threshold: get_sink_lag_alert_threshold(config),
+        ..Default::default()
     }
 }
@@ -1,4 +1,7 @@
-use dozer_types::models::flags::{EnableProbabilisticOptimizations, Flags};
+use dozer_types::models::flags::{
+    EnableProbabilisticOptimizations, Flags, ProjectionErrorPolicy, ProjectionOptions,
+    SchemaChangePolicy,
+};
 use dozer_types::node::NodeHandle;
 
 use crate::appsource::{self, AppSourceManager};
@@ -93,12 +96,18 @@ impl AppPipeline {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PipelineFlags {
     pub enable_probabilistic_optimizations: EnableProbabilisticOptimizations,
+    pub on_projection_error: ProjectionErrorPolicy,
+    pub on_schema_change: SchemaChangePolicy,
+    pub projections: Vec<ProjectionOptions>,
 }
 
 impl From<&Flags> for PipelineFlags {
     fn from(flags: &Flags) -> Self {
         Self {
             enable_probabilistic_optimizations: flags.enable_probabilistic_optimizations.clone(),
+            on_projection_error: flags.on_projection_error,
+            on_schema_change: flags.on_schema_change,
+            projections: flags.projections.clone(),
         }
     }
 }
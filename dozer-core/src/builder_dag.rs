@@ -1,12 +1,14 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     fmt::Debug,
+    time::Duration,
 };
 
 use daggy::{
     petgraph::visit::{IntoNodeIdentifiers, IntoNodeReferences},
     NodeIndex,
 };
+use dozer_log::tokio;
 use dozer_types::{
     log::warn,
     node::{NodeHandle, OpIdentifier},
@@ -15,8 +17,9 @@ use dozer_types::{
 use crate::{
     checkpoint::OptionCheckpoint,
     dag_schemas::{DagHaveSchemas, DagSchemas, EdgeType},
-    errors::ExecutionError,
+    errors::{ExecutionError, NodeBuildError},
     node::{Processor, Sink, SinkFactory, Source},
+    upsert_rewrite::UpsertRewriteOptimizer,
     NodeKind as DagNodeKind,
 };
 
@@ -52,6 +55,7 @@ impl BuilderDag {
     pub async fn new(
         checkpoint: &OptionCheckpoint,
         dag_schemas: DagSchemas,
+        warm_up_timeout: Duration,
     ) -> Result<Self, ExecutionError> {
         // Collect input output schemas.
         let mut input_schemas = HashMap::new();
@@ -61,6 +65,19 @@ impl BuilderDag {
             output_schemas.insert(node_index, dag_schemas.get_node_output_schemas(node_index));
         }
 
+        // Warm up processors, one after the other.
+        for (node_index, node) in dag_schemas.graph().node_references() {
+            if let DagNodeKind::Processor(processor) = &node.kind {
+                tokio::time::timeout(
+                    warm_up_timeout,
+                    processor.warm_up(&input_schemas[&node_index]),
+                )
+                .await
+                .map_err(|_| ExecutionError::WarmUpTimeout(node.handle.clone(), warm_up_timeout))?
+                .map_err(ExecutionError::Factory)?;
+            }
+        }
+
         // Load processor checkpoint data.
         let mut checkpoint_data = HashMap::new();
         for (node_index, node) in dag_schemas.graph().node_references() {
@@ -70,6 +87,8 @@ impl BuilderDag {
             }
         }
 
+        let topology = dag_schemas.topology();
+
         // Collect sources that may affect a node.
         let mut affecting_sources = dag_schemas
             .graph()
@@ -84,7 +103,9 @@ impl BuilderDag {
             .map(|node| Some(node.weight))
             .collect::<Vec<_>>();
 
-        // Build the sinks and load checkpoint.
+        // Build the sinks and load checkpoint. Nodes that fail to build are skipped, so that
+        // the failures of all of them are reported together.
+        let mut build_errors = vec![];
         let mut graph = daggy::Dag::new();
         let mut source_states = HashMap::new();
         let mut source_op_ids = HashMap::new();
@@ -99,14 +120,24 @@ impl BuilderDag {
                 let source = sources.into_iter().next().expect("sink must have a source");
 
                 let node_index = NodeIndex::new(node_index);
-                let mut sink = sink
-                    .build(
-                        input_schemas
-                            .remove(&node_index)
-                            .expect("we collected all input schemas"),
-                    )
-                    .await
-                    .map_err(ExecutionError::Factory)?;
+                let sink_input_schemas = input_schemas
+                    .remove(&node_index)
+                    .expect("we collected all input schemas");
+                let upsert_rewrite_schemas =
+                    sink.supports_upsert().then(|| sink_input_schemas.clone());
+                let mut sink = match sink.build(sink_input_schemas).await {
+                    Ok(sink) => sink,
+                    Err(error) => {
+                        build_errors.push(NodeBuildError {
+                            node: handle,
+                            error,
+                        });
+                        continue;
+                    }
+                };
+                if let Some(schemas) = upsert_rewrite_schemas {
+                    sink = Box::new(UpsertRewriteOptimizer::new(sink, &schemas));
+                }
 
                 let state = sink.get_source_state().map_err(ExecutionError::Sink)?;
                 if let Some(state) = state {
@@ -154,14 +185,26 @@ impl BuilderDag {
             let node_index = NodeIndex::new(node_index);
             let node = match node.kind {
                 DagNodeKind::Source(source) => {
-                    let source = source
-                        .build(
-                            output_schemas
-                                .remove(&node_index)
-                                .expect("we collected all output schemas"),
-                            source_states.remove(&node.handle),
-                        )
-                        .map_err(ExecutionError::Factory)?;
+                    let source = source.build(
+                        output_schemas
+                            .remove(&node_index)
+                            .expect("we collected all output schemas"),
+                        source_states.remove(&node.handle),
+                    );
+                    let mut source = match source {
+                        Ok(source) => source,
+                        Err(error) => {
+                            build_errors.push(NodeBuildError {
+                                node: node.handle,
+                                error,
+                            });
+                            continue;
+                        }
+                    };
+                    // Sinks are all built by now, so the source can check what they expect.
+                    source
+                        .on_pipeline_start(&topology)
+                        .map_err(ExecutionError::Source)?;
 
                     // Write state to relevant sink.
                     let state = source
@@ -200,8 +243,17 @@ impl BuilderDag {
                                 .remove(&node_index)
                                 .expect("we collected all processor checkpoint data"),
                         )
-                        .await
-                        .map_err(ExecutionError::Factory)?;
+                        .await;
+                    let processor = match processor {
+                        Ok(processor) => processor,
+                        Err(error) => {
+                            build_errors.push(NodeBuildError {
+                                node: node.handle,
+                                error,
+                            });
+                            continue;
+                        }
+                    };
                     NodeType {
                         handle: node.handle,
                         kind: NodeKind::Processor(processor),
@@ -213,6 +265,10 @@ impl BuilderDag {
             node_index_map.insert(node_index, new_node_index);
         }
 
+        if !build_errors.is_empty() {
+            return Err(ExecutionError::NodesFailedToBuild(build_errors));
+        }
+
         // Connect the edges.
         for edge in edges {
             graph
@@ -1,8 +1,10 @@
 use dozer_log::{
     camino::Utf8Path,
-    reader::{list_record_store_slices, processor_prefix},
-    replication::create_data_storage,
-    storage::{self, Object, Queue, Storage},
+    reader::{list_record_store_slices, processor_prefix, record_store_key},
+    storage::{
+        self, BucketLocationConstraint, ListedObject, LocalStorage, Object, Queue, S3Storage,
+        Storage,
+    },
     tokio::task::JoinHandle,
 };
 use dozer_types::types::Field;
@@ -11,19 +13,148 @@ use dozer_types::{
     log::info,
     models::app_config::DataStorage,
     node::{NodeHandle, OpIdentifier, SourceState, SourceStates},
-    tonic::codegen::tokio_stream::StreamExt,
+    tonic::{async_trait, codegen::tokio_stream::StreamExt},
 };
-use std::sync::Arc;
+use std::{fmt::Debug, sync::Arc, time::SystemTime};
 use tempdir::TempDir;
 
 use crate::errors::ExecutionError;
 
+/// Directory under the processor prefix that holds the sinks' custom metadata.
+const CUSTOM_METADATA_DIR: &str = "custom_metadata";
+
+/// Error returned by checkpoint storage operations.
+pub type StorageError = storage::Error;
+
+/// Key level operations used by checkpointing.
+///
+/// Checkpoints can live on local disk (`LocalCheckpointStorage`) or in an object store
+/// (`S3CheckpointStorage`, which also works with S3 compatible services such as GCS).
+#[async_trait]
+pub trait CheckpointStorage: Debug + Send + Sync {
+    /// The backend the operations go through.
+    fn storage(&self) -> &dyn Storage;
+    /// A handle to the backend for the persist queue to upload through.
+    fn boxed_storage(&self) -> Box<dyn Storage>;
+
+    async fn write(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        self.storage()
+            .put_object(key.to_string(), data.to_vec())
+            .await
+    }
+
+    async fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.storage().download_object(key.to_string()).await
+    }
+
+    /// Lists all objects whose key starts with `prefix`, following continuation tokens.
+    async fn list(&self, prefix: &str) -> Result<Vec<ListedObject>, StorageError> {
+        let mut objects = vec![];
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .storage()
+                .list_objects(prefix.to_string(), continuation_token)
+                .await?;
+            objects.extend(output.objects);
+
+            continuation_token = output.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.delete_all(vec![key.to_string()]).await
+    }
+
+    /// Deletes all `keys` in a single request.
+    async fn delete_all(&self, keys: Vec<String>) -> Result<(), StorageError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        self.storage().delete_objects(keys).await
+    }
+}
+
+/// Keeps checkpoints in a directory on local disk.
+#[derive(Debug, Clone)]
+pub struct LocalCheckpointStorage(LocalStorage);
+
+impl LocalCheckpointStorage {
+    pub async fn new(root: String) -> Result<Self, StorageError> {
+        LocalStorage::new(root).await.map(Self)
+    }
+}
+
+impl CheckpointStorage for LocalCheckpointStorage {
+    fn storage(&self) -> &dyn Storage {
+        &self.0
+    }
+
+    fn boxed_storage(&self) -> Box<dyn Storage> {
+        Box::new(self.0.clone())
+    }
+}
+
+/// Keeps checkpoints in an S3 bucket.
+#[derive(Debug, Clone)]
+pub struct S3CheckpointStorage(S3Storage);
+
+impl S3CheckpointStorage {
+    pub async fn new(
+        region: BucketLocationConstraint,
+        bucket_name: String,
+    ) -> Result<Self, StorageError> {
+        S3Storage::new(region, bucket_name).await.map(Self)
+    }
+}
+
+impl CheckpointStorage for S3CheckpointStorage {
+    fn storage(&self) -> &dyn Storage {
+        &self.0
+    }
+
+    fn boxed_storage(&self) -> Box<dyn Storage> {
+        Box::new(self.0.clone())
+    }
+}
+
+/// Creates the checkpoint storage configured by `data_storage`, and the prefix checkpoints are kept under in it.
+pub async fn create_checkpoint_storage(
+    data_storage: DataStorage,
+    checkpoint_dir: String,
+) -> Result<(Box<dyn CheckpointStorage>, String), StorageError> {
+    match data_storage {
+        DataStorage::Local => Ok((
+            Box::new(LocalCheckpointStorage::new(checkpoint_dir).await?),
+            String::default(),
+        )),
+        DataStorage::S3(s3) => Ok((
+            Box::new(S3CheckpointStorage::new(s3.region.as_str().into(), s3.bucket_name).await?),
+            checkpoint_dir,
+        )),
+    }
+}
+
 #[derive(Debug)]
 pub struct CheckpointFactory {
     queue: Queue,
+    storage: Box<dyn CheckpointStorage>,
     prefix: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointSummary {
+    pub epoch_id: u64,
+    /// When the checkpoint's record store slice was written.
+    pub created_at: SystemTime,
+    /// Total size of the record store slice and all processor objects.
+    pub size_bytes: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct CheckpointFactoryOptions {
     pub persist_queue_capacity: usize,
@@ -46,7 +177,7 @@ struct Checkpoint {
 
 #[derive(Debug)]
 pub struct OptionCheckpoint {
-    storage: Box<dyn Storage>,
+    storage: Box<dyn CheckpointStorage>,
     prefix: String,
     checkpoint: Option<Checkpoint>,
 }
@@ -62,8 +193,16 @@ impl OptionCheckpoint {
         options: CheckpointOptions,
     ) -> Result<Self, ExecutionError> {
         let (storage, prefix) =
-            create_data_storage(options.data_storage, checkpoint_dir.to_string()).await?;
-        let checkpoint = read_record_store_slices(&*storage, &prefix).await?;
+            create_checkpoint_storage(options.data_storage, checkpoint_dir.to_string()).await?;
+        Self::from_storage(storage, prefix).await
+    }
+
+    /// Loads the checkpoint from a storage backend created by the caller.
+    pub async fn from_storage(
+        storage: Box<dyn CheckpointStorage>,
+        prefix: String,
+    ) -> Result<Self, ExecutionError> {
+        let checkpoint = read_record_store_slices(storage.storage(), &prefix).await?;
         if let Some(checkpoint) = &checkpoint {
             info!(
                 "Restored record store from epoch id {}, processor states are stored in {}",
@@ -79,7 +218,12 @@ impl OptionCheckpoint {
     }
 
     pub fn storage(&self) -> &dyn Storage {
-        &*self.storage
+        self.storage.storage()
+    }
+
+    /// Gives up the checkpoint, returning the storage and prefix to create a `CheckpointFactory` with.
+    pub fn into_storage(self) -> (Box<dyn CheckpointStorage>, String) {
+        (self.storage, self.prefix)
     }
 
     pub fn prefix(&self) -> &str {
@@ -125,7 +269,7 @@ impl OptionCheckpoint {
         if let Some(checkpoint) = &self.checkpoint {
             let key = processor_key(&checkpoint.processor_prefix, node_handle);
             info!("Loading processor {node_handle} checkpoint from {key}");
-            self.storage.download_object(key).await.map(Some)
+            self.storage.read(&key).await.map(Some)
         } else {
             Ok(None)
         }
@@ -139,25 +283,47 @@ impl OptionCheckpoint {
         if let Some(checkpoint) = &self.checkpoint {
             let key = record_writer_key(&checkpoint.processor_prefix, node_handle, port_name);
             info!("Loading record writer {node_handle}-{port_name} checkpoint from {key}");
-            self.storage.download_object(key).await.map(Some)
+            self.storage.read(&key).await.map(Some)
         } else {
             Ok(None)
         }
     }
+
+    /// Reads metadata a sink wrote with `CheckpointWriter::write_custom_metadata`.
+    /// Returns `Ok(None)` if there's no checkpoint or the key wasn't written in it.
+    pub async fn read_custom_metadata(&self, key: &str) -> Result<Option<Vec<u8>>, storage::Error> {
+        let Some(checkpoint) = &self.checkpoint else {
+            return Ok(None);
+        };
+        let key = custom_metadata_key(&checkpoint.processor_prefix, key);
+        if !self
+            .storage
+            .list(&key)
+            .await?
+            .iter()
+            .any(|object| object.key == key)
+        {
+            return Ok(None);
+        }
+        info!("Loading custom metadata from {key}");
+        self.storage.read(&key).await.map(Some)
+    }
 }
 
 impl CheckpointFactory {
     // We need tokio runtime so mark the function as async.
     pub async fn new(
-        checkpoint: OptionCheckpoint,
+        storage: Box<dyn CheckpointStorage>,
+        prefix: String,
         options: CheckpointFactoryOptions,
     ) -> Result<(Self, JoinHandle<()>), ExecutionError> {
-        let (queue, worker) = Queue::new(checkpoint.storage, options.persist_queue_capacity);
+        let (queue, worker) = Queue::new(storage.boxed_storage(), options.persist_queue_capacity);
 
         Ok((
             Self {
                 queue,
-                prefix: checkpoint.prefix,
+                storage,
+                prefix,
             },
             worker,
         ))
@@ -166,6 +332,57 @@ impl CheckpointFactory {
     pub fn queue(&self) -> &Queue {
         &self.queue
     }
+
+    /// Lists all checkpoints in storage, ordered by epoch id.
+    pub async fn list_checkpoints(&self) -> Result<Vec<CheckpointSummary>, ExecutionError> {
+        let stream = list_record_store_slices(self.storage.storage(), &self.prefix);
+        let mut stream = std::pin::pin!(stream);
+
+        let mut checkpoints = vec![];
+        while let Some(meta) = stream.next().await {
+            let meta = meta?;
+            let processor_objects = self
+                .storage
+                .list(&format!("{}/", meta.processor_prefix))
+                .await?;
+            checkpoints.push(CheckpointSummary {
+                epoch_id: meta.epoch_id,
+                created_at: meta.last_modified,
+                size_bytes: meta.size + processor_objects.iter().map(|o| o.size).sum::<u64>(),
+            });
+        }
+
+        Ok(checkpoints)
+    }
+
+    /// Deletes all but the `keep_latest` most recent checkpoints. Returns the deleted checkpoints.
+    pub async fn prune_checkpoints(
+        &self,
+        keep_latest: usize,
+    ) -> Result<Vec<CheckpointSummary>, ExecutionError> {
+        let mut checkpoints = self.list_checkpoints().await?;
+        let num_to_prune = checkpoints.len().saturating_sub(keep_latest);
+        checkpoints.truncate(num_to_prune);
+
+        for checkpoint in &checkpoints {
+            // Delete the record store slice first, so a partially deleted checkpoint is never picked up on restore.
+            let record_store_key = record_store_key(&self.prefix, checkpoint.epoch_id);
+            self.storage.delete(record_store_key.as_str()).await?;
+
+            let processor_prefix = processor_prefix(&self.prefix, checkpoint.epoch_id);
+            let keys = self
+                .storage
+                .list(&format!("{}/", processor_prefix))
+                .await?
+                .into_iter()
+                .map(|object| object.key)
+                .collect();
+            self.storage.delete_all(keys).await?;
+            info!("Pruned checkpoint of epoch {}", checkpoint.epoch_id);
+        }
+
+        Ok(checkpoints)
+    }
 }
 
 #[derive(Debug)]
@@ -195,6 +412,13 @@ fn record_writer_key(processor_prefix: &str, node_handle: &NodeHandle, port_name
         .into_string()
 }
 
+fn custom_metadata_key(processor_prefix: &str, key: &str) -> String {
+    AsRef::<Utf8Path>::as_ref(processor_prefix)
+        .join(CUSTOM_METADATA_DIR)
+        .join(key)
+        .into_string()
+}
+
 impl CheckpointWriter {
     pub fn new(factory: Arc<CheckpointFactory>, epoch_id: u64) -> Self {
         let processor_prefix = processor_prefix(&factory.prefix, epoch_id).into();
@@ -226,6 +450,17 @@ impl CheckpointWriter {
         Object::new(self.factory.queue.clone(), key)
             .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)
     }
+
+    /// Stores `value` under `key` in this checkpoint, for sinks to annotate checkpoints with
+    /// their own state. A later write to the same key replaces the value.
+    pub fn write_custom_metadata(&self, key: &str, value: &[u8]) -> Result<(), ExecutionError> {
+        let key = custom_metadata_key(&self.processor_prefix, key);
+        let mut object = Object::new(self.factory.queue.clone(), key)
+            .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)?;
+        object
+            .write(value)
+            .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)
+    }
 }
 
 impl Drop for CheckpointWriter {
@@ -278,7 +513,8 @@ pub async fn create_checkpoint_factory_for_test(
     let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), Default::default())
         .await
         .unwrap();
-    let (checkpoint_factory, handle) = CheckpointFactory::new(checkpoint, Default::default())
+    let (storage, prefix) = checkpoint.into_storage();
+    let (checkpoint_factory, handle) = CheckpointFactory::new(storage, prefix, Default::default())
         .await
         .unwrap();
     let factory = Arc::new(checkpoint_factory);
@@ -302,10 +538,107 @@ pub async fn create_checkpoint_factory_for_test(
     let last_checkpoint = checkpoint.checkpoint.as_ref().unwrap();
     assert_eq!(last_checkpoint.epoch_id, epoch_id);
     assert_eq!(last_checkpoint.source_states, source_states);
-    let (checkpoint_factory, handle) = CheckpointFactory::new(checkpoint, Default::default())
+    let (storage, prefix) = checkpoint.into_storage();
+    let (checkpoint_factory, handle) = CheckpointFactory::new(storage, prefix, Default::default())
         .await
         .unwrap();
     (temp_dir, Arc::new(checkpoint_factory), handle)
 }
 
 pub mod serialize;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_and_prune_checkpoints() {
+        let (_temp_dir, checkpoint) = create_checkpoint_for_test().await;
+        let (storage, prefix) = checkpoint.into_storage();
+        let (factory, _) = CheckpointFactory::new(storage, prefix, Default::default())
+            .await
+            .unwrap();
+        let node_handle = NodeHandle::new(Some(1), "id".to_string());
+
+        for epoch_id in 0..3 {
+            let prefix = processor_prefix(&factory.prefix, epoch_id);
+            factory
+                .storage
+                .write(&processor_key(prefix.as_str(), &node_handle), &[0; 10])
+                .await
+                .unwrap();
+            factory
+                .storage
+                .write(
+                    record_store_key(&factory.prefix, epoch_id).as_str(),
+                    &[0; 5],
+                )
+                .await
+                .unwrap();
+        }
+
+        let checkpoints = factory.list_checkpoints().await.unwrap();
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.epoch_id, checkpoint.size_bytes))
+                .collect::<Vec<_>>(),
+            vec![(0, 15), (1, 15), (2, 15)]
+        );
+
+        let pruned = factory.prune_checkpoints(1).await.unwrap();
+        assert_eq!(
+            pruned
+                .iter()
+                .map(|checkpoint| checkpoint.epoch_id)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        let checkpoints = factory.list_checkpoints().await.unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].epoch_id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_custom_metadata() {
+        let (temp_dir, checkpoint) = create_checkpoint_for_test().await;
+        let (storage, prefix) = checkpoint.into_storage();
+        let (factory, handle) = CheckpointFactory::new(storage, prefix, Default::default())
+            .await
+            .unwrap();
+        let record_store_slice = RecordStoreSlice {
+            source_states: Default::default(),
+            data: vec![],
+        };
+        factory
+            .storage
+            .write(
+                record_store_key(&factory.prefix, 0).as_str(),
+                &bincode::encode_to_vec(&record_store_slice, bincode::config::legacy()).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let factory = Arc::new(factory);
+        std::thread::spawn(move || {
+            let writer = CheckpointWriter::new(factory, 0);
+            writer.write_custom_metadata("sink", b"offset").unwrap();
+        })
+        .join()
+        .unwrap();
+        handle.await.unwrap();
+
+        let checkpoint_dir = temp_dir.path().to_str().unwrap().to_string();
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            checkpoint.read_custom_metadata("sink").await.unwrap(),
+            Some(b"offset".to_vec())
+        );
+        assert_eq!(
+            checkpoint.read_custom_metadata("other").await.unwrap(),
+            None
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use daggy::petgraph::visit::{Bfs, EdgeRef, IntoEdges};
 use daggy::Walker;
 use dozer_types::node::NodeHandle;
+use dozer_types::serde::{Deserialize, Serialize};
 
 use crate::errors::ExecutionError;
 use crate::node::{PortHandle, ProcessorFactory, SinkFactory, SourceFactory};
@@ -9,7 +10,8 @@ use std::fmt::{Debug, Display};
 
 pub const DEFAULT_PORT_HANDLE: u16 = 0xffff_u16;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct Endpoint {
     pub node: NodeHandle,
     pub port: PortHandle,
@@ -21,7 +23,8 @@ impl Endpoint {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct Edge {
     pub from: Endpoint,
     pub to: Endpoint,
@@ -255,6 +258,19 @@ impl Dag {
         })
     }
 
+    /// Replaces every sink with the one `f` creates from it.
+    pub fn replace_sinks(
+        &mut self,
+        mut f: impl FnMut(&NodeHandle, &dyn SinkFactory) -> Box<dyn SinkFactory>,
+    ) {
+        for node_index in self.node_lookup_table.values() {
+            let node = &mut self.graph[*node_index];
+            if let NodeKind::Sink(sink) = &mut node.kind {
+                *sink = f(&node.handle, &**sink);
+            }
+        }
+    }
+
     /// Returns an iterator over all edge handles.
     pub fn edge_handles(&self) -> Vec<Edge> {
         let get_endpoint = |node_index: daggy::NodeIndex, port_handle| {
@@ -1,11 +1,12 @@
-use crate::errors::ExecutionError;
+use crate::errors::{ExecutionError, NodeBuildError};
 use crate::{Dag, EdgeHavePorts, NodeKind};
 
-use crate::node::{OutputPortType, PortHandle};
+use crate::node::{OutputPortType, PortHandle, ProcessorExplanation};
 use daggy::petgraph::graph::EdgeReference;
 use daggy::petgraph::visit::{EdgeRef, IntoEdges, IntoEdgesDirected, IntoNodeReferences, Topo};
 use daggy::petgraph::Direction;
 use daggy::{NodeIndex, Walker};
+use dozer_types::errors::internal::BoxedError;
 use dozer_types::log::{error, info};
 use dozer_types::node::NodeHandle;
 use dozer_types::serde::{Deserialize, Serialize};
@@ -71,6 +72,62 @@ impl EdgeHaveSchema for EdgeType {
     }
 }
 
+/// The nodes of a pipeline and the schemas between them, as passed to `Source::on_pipeline_start`.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineTopology {
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyNode {
+    pub handle: NodeHandle,
+    pub kind: TopologyNodeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyNodeKind {
+    Source,
+    Processor,
+    Sink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyEdge {
+    pub from: NodeHandle,
+    pub output_port: PortHandle,
+    pub to: NodeHandle,
+    pub input_port: PortHandle,
+    pub schema: Schema,
+}
+
+impl PipelineTopology {
+    /// Returns the edges into sinks that the records of `source` reach, directly or through processors.
+    pub fn sink_inputs(&self, source: &NodeHandle) -> Vec<&TopologyEdge> {
+        let is_sink = |handle: &NodeHandle| {
+            self.nodes
+                .iter()
+                .any(|node| &node.handle == handle && node.kind == TopologyNodeKind::Sink)
+        };
+        let mut visited = HashSet::new();
+        let mut pending = vec![source];
+        let mut sink_inputs = vec![];
+        while let Some(handle) = pending.pop() {
+            if !visited.insert(handle) {
+                continue;
+            }
+            for edge in self.edges.iter().filter(|edge| &edge.from == handle) {
+                if is_sink(&edge.to) {
+                    sink_inputs.push(edge);
+                } else {
+                    pending.push(&edge.to);
+                }
+            }
+        }
+        sink_inputs
+    }
+}
+
 #[derive(Debug)]
 /// `DagSchemas` is a `Dag` with validated schema on the edge.
 pub struct DagSchemas {
@@ -105,11 +162,59 @@ impl DagSchemas {
         }
     }
 
+    pub fn topology(&self) -> PipelineTopology {
+        let nodes = self
+            .graph
+            .node_references()
+            .map(|(_, node)| TopologyNode {
+                handle: node.handle.clone(),
+                kind: match node.kind {
+                    NodeKind::Source(_) => TopologyNodeKind::Source,
+                    NodeKind::Processor(_) => TopologyNodeKind::Processor,
+                    NodeKind::Sink(_) => TopologyNodeKind::Sink,
+                },
+            })
+            .collect();
+        let edges = self
+            .graph
+            .graph()
+            .edge_references()
+            .map(|edge| TopologyEdge {
+                from: self.graph[edge.source()].handle.clone(),
+                output_port: edge.weight().output_port,
+                to: self.graph[edge.target()].handle.clone(),
+                input_port: edge.weight().input_port,
+                schema: edge.weight().schema.clone(),
+            })
+            .collect();
+        PipelineTopology { nodes, edges }
+    }
+
     pub fn collect_ancestor_sources(&self, node_index: NodeIndex) -> HashSet<NodeHandle> {
         let mut sources = HashSet::new();
         collect_ancestor_sources_recursive(self, node_index, &mut sources);
         sources
     }
+
+    /// Builds every processor, without checkpoint data, and returns their explanations in topological order.
+    pub async fn explain(&self) -> Result<Vec<ProcessorExplanation>, ExecutionError> {
+        let mut explanations = vec![];
+        for node_index in Topo::new(&self.graph).iter(&self.graph) {
+            let NodeKind::Processor(processor) = &self.graph[node_index].kind else {
+                continue;
+            };
+            let processor = processor
+                .build(
+                    self.get_node_input_schemas(node_index),
+                    self.get_node_output_schemas(node_index),
+                    None,
+                )
+                .await
+                .map_err(ExecutionError::Factory)?;
+            explanations.push(processor.explain());
+        }
+        Ok(explanations)
+    }
 }
 
 fn collect_ancestor_sources_recursive(
@@ -209,11 +314,21 @@ fn validate_connectivity(dag: &Dag) {
     }
 }
 
+fn node_failed(node: &NodeHandle, error: BoxedError) -> ExecutionError {
+    ExecutionError::NodesFailedToBuild(vec![NodeBuildError {
+        node: node.clone(),
+        error,
+    }])
+}
+
 /// In topological order, pass output schemas to downstream nodes' input schemas.
+///
+/// Nothing depends on sinks, so the failures of all sinks are reported together.
 async fn populate_schemas(
     dag: daggy::Dag<NodeType, DagEdgeType>,
 ) -> Result<daggy::Dag<NodeType, EdgeType>, ExecutionError> {
     let mut edges = vec![None; dag.graph().edge_count()];
+    let mut sink_errors = vec![];
 
     for node_index in Topo::new(&dag).iter(&dag) {
         let node = &dag.graph()[node_index];
@@ -228,7 +343,7 @@ async fn populate_schemas(
                     let port_name = source.get_output_port_name(&port);
                     let schema = source
                         .get_output_schema(&port)
-                        .map_err(ExecutionError::Factory)?;
+                        .map_err(|e| node_failed(&node.handle, e))?;
                     create_edge(
                         &mut edges,
                         edge,
@@ -249,7 +364,7 @@ async fn populate_schemas(
                     let schema = processor
                         .get_output_schema(&edge.weight().from, &input_schemas)
                         .await
-                        .map_err(ExecutionError::Factory)?;
+                        .map_err(|e| node_failed(&node.handle, e))?;
                     create_edge(&mut edges, edge, EdgeKind::FromProcessor, schema);
                 }
             }
@@ -257,11 +372,18 @@ async fn populate_schemas(
             NodeKind::Sink(sink) => {
                 let input_schemas =
                     validate_input_schemas(&dag, &edges, node_index, sink.get_input_ports())?;
-                sink.prepare(input_schemas)
-                    .map_err(ExecutionError::Factory)?;
+                if let Err(error) = sink.prepare(input_schemas) {
+                    sink_errors.push(NodeBuildError {
+                        node: node.handle.clone(),
+                        error,
+                    });
+                }
             }
         }
     }
+    if !sink_errors.is_empty() {
+        return Err(ExecutionError::NodesFailedToBuild(sink_errors));
+    }
 
     Ok(dag.map_owned(
         |_, node| node,
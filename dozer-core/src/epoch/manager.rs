@@ -1,9 +1,10 @@
-use dozer_types::log::info;
+use dozer_types::log::{error, info, warn};
 use dozer_types::node::{NodeHandle, SourceState, SourceStates};
-use dozer_types::parking_lot::Mutex;
+use dozer_types::parking_lot::{Condvar, Mutex};
+use metrics::{describe_counter, describe_gauge, gauge, increment_counter};
+use std::collections::HashSet;
 use std::ops::DerefMut;
-use std::sync::{Arc, Barrier};
-use std::thread::sleep;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use crate::checkpoint::{CheckpointFactory, CheckpointWriter};
@@ -13,10 +14,24 @@ use super::EpochCommonInfo;
 #[derive(Debug)]
 struct EpochManagerState {
     kind: EpochManagerStateKind,
+    /// The number of records the sources reported, over all epochs.
+    num_records: usize,
     /// Initialized to 0.
     next_record_index_to_persist: usize,
     /// The instant when epoch manager decided to persist the last epoch. Initialized to the epoch manager's start time.
     last_persisted_epoch_decision_instant: SystemTime,
+    /// The instant when the first source arrived at the current `Closing` state. `None` if no source has arrived yet.
+    first_source_closing_instant: Option<SystemTime>,
+    /// How long the first source waited on the barrier in the last closed epoch.
+    last_barrier_wait_duration: Option<Duration>,
+    /// The number of epochs closed so far. Sources compare it across the barrier to tell whether
+    /// the epoch they closed already proceeded without them.
+    num_closed_epochs: u64,
+    /// The last epoch that proceeded to the next one, for the sources that pick it up after that:
+    /// the ones that timed out confirming it, and the ones that arrived at it more than once.
+    last_closed_epoch: Option<ClosedEpoch>,
+    /// Every source that has arrived at an epoch close, so the ones missing from a close can be named.
+    known_sources: HashSet<NodeHandle>,
 }
 
 #[derive(Debug)]
@@ -30,8 +45,11 @@ enum EpochManagerStateKind {
         should_commit: bool,
         /// The collected source states.
         source_states: SourceStates,
-        /// Sources wait on this barrier to synchronize an epoch close.
-        barrier: Arc<Barrier>,
+        /// The sources that have arrived at this epoch close. Sources wait on the barrier until all
+        /// of them have, or until the slow source timeout closes the epoch without the others.
+        arrived_sources: HashSet<NodeHandle>,
+        /// Instant when this epoch was opened.
+        opened_instant: SystemTime,
     },
     Closed {
         /// Whether sources should terminate.
@@ -44,8 +62,13 @@ enum EpochManagerStateKind {
         source_states: Arc<SourceStates>,
         /// Instant when the epoch was closed.
         instant: SystemTime,
-        /// Number of sources that have confirmed the epoch close.
-        num_source_confirmations: usize,
+        /// The sources that have confirmed the epoch close.
+        confirmed_sources: HashSet<NodeHandle>,
+        /// The number of confirmations the next epoch waits for.
+        num_expected_confirmations: usize,
+        /// The sources that didn't arrive at the close in time. They don't confirm it, and are
+        /// non-restartable in the next epoch.
+        late_sources: HashSet<NodeHandle>,
     },
 }
 
@@ -58,7 +81,7 @@ impl EpochManagerStateKind {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Action {
     Commit,
     CommitAndPersist,
@@ -75,14 +98,40 @@ impl Action {
     }
 }
 
+/// Decides whether an epoch that is being committed should also be persisted.
+///
+/// It's persisted once `max_num_records_before_persist` records arrived since the last persisted epoch,
+/// or `max_interval_before_persist_in_seconds` passed since the last decision to persist.
+/// If `next_record_index_to_persist` is ahead of `num_records`, no new records are counted.
+fn decide_commit_action(
+    num_records: usize,
+    next_record_index_to_persist: usize,
+    last_persisted_epoch_decision_instant: SystemTime,
+    now: SystemTime,
+    options: &EpochManagerOptions,
+) -> Action {
+    let num_new_records = num_records.saturating_sub(next_record_index_to_persist);
+    let interval = now
+        .duration_since(last_persisted_epoch_decision_instant)
+        .unwrap_or(Duration::from_secs(0));
+    if num_new_records >= options.max_num_records_before_persist
+        || interval >= Duration::from_secs(options.max_interval_before_persist_in_seconds)
+    {
+        Action::CommitAndPersist
+    } else {
+        Action::Commit
+    }
+}
+
 impl EpochManagerStateKind {
-    fn new_closing(epoch_id: u64, num_sources: usize) -> EpochManagerStateKind {
+    fn new_closing(epoch_id: u64) -> EpochManagerStateKind {
         EpochManagerStateKind::Closing {
             epoch_id,
             should_terminate: true,
             should_commit: false,
             source_states: Default::default(),
-            barrier: Arc::new(Barrier::new(num_sources)),
+            arrived_sources: HashSet::new(),
+            opened_instant: SystemTime::now(),
         }
     }
 }
@@ -92,6 +141,9 @@ pub struct EpochManagerOptions {
     pub max_num_records_before_persist: usize,
     pub max_interval_before_persist_in_seconds: u64,
     pub enable_app_checkpoints: bool,
+    /// How long sources wait for the others to arrive at or confirm an epoch close before proceeding
+    /// without them. Sources that timed out are non-restartable in the next epoch. Unset means forever.
+    pub slow_source_timeout_ms: Option<u64>,
 }
 
 impl Default for EpochManagerOptions {
@@ -100,16 +152,31 @@ impl Default for EpochManagerOptions {
             max_num_records_before_persist: 100_000,
             max_interval_before_persist_in_seconds: 60,
             enable_app_checkpoints: false,
+            slow_source_timeout_ms: None,
         }
     }
 }
 
+const EPOCH_BARRIER_WAIT_GAUGE_NAME: &str = "epoch_barrier_wait";
+const EPOCH_SOURCE_TIMEOUT_COUNTER_NAME: &str = "dozer_epoch_source_timeout_total";
+const SOURCE_LABEL: &str = "source";
+
+/// Lets sources that run on threads of their own agree on when to close an epoch, and decides
+/// whether the closed epoch commits and persists.
+///
+/// The executor doesn't use it. Its source node receives from all sources on one thread and
+/// closes an epoch whenever a source commits, so the barrier, its wait metric, the slow source
+/// timeout and the epoch's elapsed time only apply to callers that run a thread per source.
 #[derive(Debug)]
 pub struct EpochManager {
     num_sources: usize,
     checkpoint_factory: Arc<CheckpointFactory>,
     options: EpochManagerOptions,
     state: Mutex<EpochManagerState>,
+    /// Notified when the last source confirms a closed epoch and the next `Closing` state is installed.
+    closing_installed: Condvar,
+    /// Notified when the last source arrives at an epoch close, or the close times out.
+    epoch_closed: Condvar,
 }
 
 #[derive(Debug, Clone)]
@@ -129,16 +196,32 @@ impl EpochManager {
         options: EpochManagerOptions,
     ) -> Self {
         debug_assert!(num_sources > 0);
+        describe_gauge!(
+            EPOCH_BARRIER_WAIT_GAUGE_NAME,
+            "Time in seconds from the first source arriving at an epoch close to all sources arriving"
+        );
+        describe_counter!(
+            EPOCH_SOURCE_TIMEOUT_COUNTER_NAME,
+            "Number of times an epoch proceeded without a source that didn't confirm its close in time"
+        );
         let next_record_index_to_persist = 0;
         Self {
             num_sources,
             checkpoint_factory,
             options,
             state: Mutex::new(EpochManagerState {
-                kind: EpochManagerStateKind::new_closing(epoch_id, num_sources),
+                kind: EpochManagerStateKind::new_closing(epoch_id),
+                num_records: 0,
                 next_record_index_to_persist,
                 last_persisted_epoch_decision_instant: SystemTime::now(),
+                first_source_closing_instant: None,
+                last_barrier_wait_duration: None,
+                num_closed_epochs: 0,
+                last_closed_epoch: None,
+                known_sources: HashSet::new(),
             }),
+            closing_installed: Condvar::new(),
+            epoch_closed: Condvar::new(),
         }
     }
 
@@ -146,148 +229,396 @@ impl EpochManager {
         self.state.lock().kind.epoch_id()
     }
 
+    /// Returns how long the current epoch has been open.
+    ///
+    /// Returns zero while the last closed epoch is still waiting for source confirmations, as the next epoch hasn't opened yet.
+    pub fn current_epoch_elapsed(&self) -> Duration {
+        match &self.state.lock().kind {
+            EpochManagerStateKind::Closing { opened_instant, .. } => {
+                opened_instant.elapsed().unwrap_or(Duration::from_secs(0))
+            }
+            EpochManagerStateKind::Closed { .. } => Duration::from_secs(0),
+        }
+    }
+
+    /// Returns the sources that have confirmed the close of the last closed epoch, while it is
+    /// still waiting for the others to. Empty once all sources have confirmed it.
+    pub fn confirmed_sources(&self) -> Vec<NodeHandle> {
+        match &self.state.lock().kind {
+            EpochManagerStateKind::Closing { .. } => vec![],
+            EpochManagerStateKind::Closed {
+                confirmed_sources, ..
+            } => confirmed_sources.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns how long sources were blocked at the barrier in the last closed epoch,
+    /// measured from the first source arriving to the barrier releasing.
+    pub fn last_barrier_wait_duration(&self) -> Option<Duration> {
+        self.state.lock().last_barrier_wait_duration
+    }
+
     /// Waits for the epoch to close until all sources do so.
     ///
     /// Returns whether the participant should terminate, the epoch id if the source should commit, and the instant when the decision was made.
     ///
     /// # Arguments
     ///
+    /// - `num_records`: The number of records the source sent since it last arrived at an epoch close. The records of all sources count towards `max_num_records_before_persist`.
     /// - `request_termination`: Whether the source wants to terminate. The `EpochManager` checks if all sources want to terminate and returns `true` if so.
     /// - `request_commit`: Whether the source wants to commit. The `EpochManager` checks if any source wants to commit and returns `Some` if so.
     pub fn wait_for_epoch_close(
         &self,
         source_state: (NodeHandle, SourceState),
+        num_records: usize,
         request_termination: bool,
         request_commit: bool,
     ) -> ClosedEpoch {
-        let barrier = loop {
-            let mut state = self.state.lock();
-            match &mut state.kind {
-                EpochManagerStateKind::Closing {
-                    should_terminate,
-                    should_commit,
-                    source_states,
-                    barrier,
-                    ..
-                } => {
-                    // If anyone doesn't want to terminate, we don't terminate.
-                    *should_terminate = *should_terminate && request_termination;
-                    // If anyone wants to commit, we commit.
-                    *should_commit = *should_commit || request_commit;
-                    // Collect source states.
-                    source_states.insert(source_state.0, source_state.1);
-                    break barrier.clone();
-                }
-                EpochManagerStateKind::Closed { .. } => {
-                    // This thread wants to close a new epoch while some other thread hasn't got confirmation of last epoch closing.
-                    // Just release the lock and put this thread to sleep.
-                    drop(state);
-                    sleep(Duration::from_millis(1));
-                }
+        let node_handle = source_state.0.clone();
+        let mut guard = self.state.lock();
+        // This thread wants to close a new epoch while some other thread hasn't got confirmation of last epoch closing.
+        // Block until the last confirmation installs the next `Closing` state, or the wait times out.
+        while let EpochManagerStateKind::Closed { instant, .. } = &guard.kind {
+            let Some(timeout_ms) = self.options.slow_source_timeout_ms else {
+                self.closing_installed.wait(&mut guard);
+                continue;
+            };
+            let timeout = Duration::from_millis(timeout_ms);
+            let waited = instant.elapsed().unwrap_or(Duration::from_secs(0));
+            if waited >= timeout {
+                self.time_out_unconfirmed_sources(guard.deref_mut(), timeout_ms);
+            } else {
+                self.closing_installed
+                    .wait_for(&mut guard, timeout - waited);
             }
-        };
+        }
 
-        barrier.wait();
+        let num_closed_epochs = guard.num_closed_epochs;
+        let first_source_closing_instant = *guard
+            .first_source_closing_instant
+            .get_or_insert_with(SystemTime::now);
+        self.arrive(
+            guard.deref_mut(),
+            source_state,
+            num_records,
+            request_termination,
+            request_commit,
+        );
 
-        let mut state = self.state.lock();
-        let state = state.deref_mut();
-        if let EpochManagerStateKind::Closing {
+        // Wait on the barrier for the other sources to arrive, or for the timeout to close the epoch without them.
+        while guard.num_closed_epochs == num_closed_epochs {
+            let Some(timeout_ms) = self.options.slow_source_timeout_ms else {
+                self.epoch_closed.wait(&mut guard);
+                continue;
+            };
+            let timeout = Duration::from_millis(timeout_ms);
+            let waited = first_source_closing_instant
+                .elapsed()
+                .unwrap_or(Duration::from_secs(0));
+            if waited >= timeout {
+                self.time_out_late_sources(guard.deref_mut(), timeout_ms);
+                self.epoch_closed.notify_all();
+            } else {
+                self.epoch_closed.wait_for(&mut guard, timeout - waited);
+            }
+        }
+
+        let state = guard.deref_mut();
+        if state.num_closed_epochs == num_closed_epochs + 1
+            && matches!(state.kind, EpochManagerStateKind::Closed { .. })
+        {
+            self.confirm(state, &node_handle)
+        } else {
+            // The epoch this thread arrived at already proceeded without its confirmation.
+            state
+                .last_closed_epoch
+                .clone()
+                .expect("An epoch only proceeds after it was closed")
+        }
+    }
+
+    /// Records a source arriving at the current epoch close, and closes the epoch if it's the last
+    /// source to arrive. A source that arrives more than once is only counted once.
+    fn arrive(
+        &self,
+        state: &mut EpochManagerState,
+        source_state: (NodeHandle, SourceState),
+        num_records: usize,
+        request_termination: bool,
+        request_commit: bool,
+    ) {
+        let EpochManagerStateKind::Closing {
             epoch_id,
             should_terminate,
             should_commit,
             source_states,
+            arrived_sources,
             ..
         } = &mut state.kind
-        {
-            let instant = SystemTime::now();
-            let action = if *should_commit {
-                let num_records = 0;
-                if num_records - state.next_record_index_to_persist
-                    >= self.options.max_num_records_before_persist
-                    || instant
-                        .duration_since(state.last_persisted_epoch_decision_instant)
-                        .unwrap_or(Duration::from_secs(0))
-                        >= Duration::from_secs(self.options.max_interval_before_persist_in_seconds)
-                {
-                    state.next_record_index_to_persist = num_records;
-                    state.last_persisted_epoch_decision_instant = instant;
-                    info!(
-                        "Persisting epoch {}, source states: {:?}",
-                        epoch_id, source_states
-                    );
-                    Action::CommitAndPersist
-                } else {
-                    Action::Commit
-                }
-            } else {
-                Action::Nothing
-            };
+        else {
+            unreachable!("Sources only arrive at a `Closing` epoch")
+        };
+        let (node_handle, source_state) = source_state;
+        // If anyone doesn't want to terminate, we don't terminate.
+        *should_terminate = *should_terminate && request_termination;
+        // If anyone wants to commit, we commit.
+        *should_commit = *should_commit || request_commit;
+        // Collect source states. A source that timed out of the last epoch stays non-restartable.
+        if source_states.get(&node_handle) != Some(&SourceState::NonRestartable) {
+            source_states.insert(node_handle.clone(), source_state);
+        }
+        state.num_records += num_records;
+        if !arrived_sources.insert(node_handle.clone()) {
+            warn!(
+                "Source {} arrived at the close of epoch {} more than once",
+                node_handle, epoch_id
+            );
+        }
+        let all_arrived = arrived_sources.len() == self.num_sources;
+        state.known_sources.insert(node_handle);
+        if all_arrived {
+            // This thread is the last one to arrive, and closes the epoch.
+            self.close_epoch(state, HashSet::new());
+            self.epoch_closed.notify_all();
+        }
+    }
 
-            state.kind = EpochManagerStateKind::Closed {
-                terminating: *should_terminate,
-                action,
-                epoch_id: *epoch_id,
-                source_states: Arc::new(std::mem::take(source_states)),
-                instant,
-                num_source_confirmations: 0,
-            };
+    /// Records a source confirming the closed epoch, and opens the next epoch if it's the last
+    /// source to confirm. A source that confirms more than once is only counted once.
+    fn confirm(&self, state: &mut EpochManagerState, node_handle: &NodeHandle) -> ClosedEpoch {
+        let EpochManagerStateKind::Closed {
+            terminating,
+            action,
+            epoch_id,
+            source_states,
+            instant,
+            confirmed_sources,
+            num_expected_confirmations,
+            late_sources,
+        } = &mut state.kind
+        else {
+            unreachable!("Sources only confirm a `Closed` epoch")
+        };
+        let result = self.closed_epoch(*terminating, *action, *epoch_id, source_states, *instant);
+
+        if !confirmed_sources.insert(node_handle.clone()) {
+            warn!(
+                "Source {} confirmed the close of epoch {} more than once",
+                node_handle, epoch_id
+            );
+        }
+        if confirmed_sources.len() == *num_expected_confirmations {
+            // This thread is the last one in this critical area.
+            let late_sources = std::mem::take(late_sources);
+            self.install_next_closing(state, late_sources);
+            state.last_closed_epoch = Some(result.clone());
         }
 
-        match &mut state.kind {
-            EpochManagerStateKind::Closed {
-                terminating,
-                action,
-                epoch_id,
-                source_states,
+        result
+    }
+
+    /// Closes the current epoch, deciding whether it commits and persists. `late_sources` didn't
+    /// arrive at the close, and only the sources that did are waited for to confirm it.
+    fn close_epoch(&self, state: &mut EpochManagerState, late_sources: HashSet<NodeHandle>) {
+        let EpochManagerStateKind::Closing {
+            epoch_id,
+            should_terminate,
+            should_commit,
+            source_states,
+            arrived_sources,
+            ..
+        } = &mut state.kind
+        else {
+            return;
+        };
+        let instant = SystemTime::now();
+        if let Some(first_source_closing_instant) = state.first_source_closing_instant.take() {
+            let barrier_wait_duration = instant
+                .duration_since(first_source_closing_instant)
+                .unwrap_or(Duration::from_secs(0));
+            gauge!(
+                EPOCH_BARRIER_WAIT_GAUGE_NAME,
+                barrier_wait_duration.as_secs_f64()
+            );
+            state.last_barrier_wait_duration = Some(barrier_wait_duration);
+        }
+        // Only the thread that closes the epoch gets here, with the lock held, so the decision
+        // and the update of the persist bookkeeping happen exactly once per epoch.
+        let action = if *should_commit {
+            let action = decide_commit_action(
+                state.num_records,
+                state.next_record_index_to_persist,
+                state.last_persisted_epoch_decision_instant,
                 instant,
-                num_source_confirmations,
-            } => {
-                let common_info = action.should_commit().then(|| {
-                    let checkpoint_writer = (action.should_persist()
-                        && self.options.enable_app_checkpoints
-                        && is_restartable(source_states))
-                    .then(|| {
-                        Arc::new(CheckpointWriter::new(
-                            self.checkpoint_factory.clone(),
-                            *epoch_id,
-                        ))
-                    });
-                    let sink_persist_queue = action
-                        .should_persist()
-                        .then(|| self.checkpoint_factory.queue().clone());
-                    EpochCommonInfo {
-                        id: *epoch_id,
-                        checkpoint_writer,
-                        sink_persist_queue,
-                        source_states: source_states.clone(),
-                    }
-                });
+                &self.options,
+            );
+            if action.should_persist() {
+                state.next_record_index_to_persist = state.num_records;
+                state.last_persisted_epoch_decision_instant = instant;
+                info!(
+                    "Persisting epoch {}, source states: {:?}",
+                    epoch_id, source_states
+                );
+            }
+            action
+        } else {
+            Action::Nothing
+        };
 
-                let result = ClosedEpoch {
-                    should_terminate: *terminating,
-                    common_info,
-                    decision_instant: *instant,
-                };
-
-                *num_source_confirmations += 1;
-                if *num_source_confirmations == self.num_sources {
-                    // This thread is the last one in this critical area.
-                    state.kind = EpochManagerStateKind::new_closing(
-                        if action.should_commit() {
-                            *epoch_id + 1
-                        } else {
-                            *epoch_id
-                        },
-                        self.num_sources,
-                    );
-                }
+        let num_expected_confirmations = arrived_sources.len();
+        state.kind = EpochManagerStateKind::Closed {
+            terminating: *should_terminate,
+            action,
+            epoch_id: *epoch_id,
+            source_states: Arc::new(std::mem::take(source_states)),
+            instant,
+            confirmed_sources: HashSet::new(),
+            num_expected_confirmations,
+            late_sources,
+        };
+        state.num_closed_epochs += 1;
+    }
 
-                result
-            }
-            EpochManagerStateKind::Closing { .. } => {
-                unreachable!("We just modified `EpochManagerState` to `Closed`")
+    /// Installs the `Closing` state of the epoch after the closed one, in which `non_restartable_sources` are
+    /// non-restartable.
+    fn install_next_closing(
+        &self,
+        state: &mut EpochManagerState,
+        non_restartable_sources: impl IntoIterator<Item = NodeHandle>,
+    ) {
+        let EpochManagerStateKind::Closed {
+            action, epoch_id, ..
+        } = &state.kind
+        else {
+            return;
+        };
+        let next_epoch_id = if action.should_commit() {
+            *epoch_id + 1
+        } else {
+            *epoch_id
+        };
+        let mut kind = EpochManagerStateKind::new_closing(next_epoch_id);
+        if let EpochManagerStateKind::Closing { source_states, .. } = &mut kind {
+            source_states.extend(
+                non_restartable_sources
+                    .into_iter()
+                    .map(|node_handle| (node_handle, SourceState::NonRestartable)),
+            );
+        }
+        state.kind = kind;
+        self.closing_installed.notify_all();
+    }
+
+    fn closed_epoch(
+        &self,
+        terminating: bool,
+        action: Action,
+        epoch_id: u64,
+        source_states: &Arc<SourceStates>,
+        instant: SystemTime,
+    ) -> ClosedEpoch {
+        let common_info = action.should_commit().then(|| {
+            let checkpoint_writer = (action.should_persist()
+                && self.options.enable_app_checkpoints
+                && source_states.len() == self.num_sources
+                && is_restartable(source_states))
+            .then(|| {
+                Arc::new(CheckpointWriter::new(
+                    self.checkpoint_factory.clone(),
+                    epoch_id,
+                ))
+            });
+            let sink_persist_queue = action
+                .should_persist()
+                .then(|| self.checkpoint_factory.queue().clone());
+            EpochCommonInfo {
+                id: epoch_id,
+                checkpoint_writer,
+                sink_persist_queue,
+                source_states: source_states.clone(),
             }
+        });
+
+        ClosedEpoch {
+            should_terminate: terminating,
+            common_info,
+            decision_instant: instant,
+        }
+    }
+
+    /// Installs the next `Closing` state without waiting for the sources that haven't confirmed the
+    /// closed epoch. They get the closed epoch when they're done, and are non-restartable in the next one.
+    fn time_out_unconfirmed_sources(&self, state: &mut EpochManagerState, timeout_ms: u64) {
+        let EpochManagerStateKind::Closed {
+            terminating,
+            action,
+            epoch_id,
+            source_states,
+            instant,
+            confirmed_sources,
+            late_sources,
+            ..
+        } = &mut state.kind
+        else {
+            return;
+        };
+        let timed_out: Vec<_> = source_states
+            .keys()
+            .filter(|node_handle| {
+                !confirmed_sources.contains(node_handle) && !late_sources.contains(node_handle)
+            })
+            .cloned()
+            .collect();
+        for node_handle in &timed_out {
+            error!(
+                "Source {} didn't confirm the close of epoch {} within {}ms, proceeding without it",
+                node_handle, epoch_id, timeout_ms
+            );
+            increment_counter!(EPOCH_SOURCE_TIMEOUT_COUNTER_NAME, SOURCE_LABEL => node_handle.to_string());
+        }
+
+        let closed_epoch =
+            self.closed_epoch(*terminating, *action, *epoch_id, source_states, *instant);
+        let late_sources = std::mem::take(late_sources);
+        self.install_next_closing(state, timed_out.into_iter().chain(late_sources));
+        state.last_closed_epoch = Some(closed_epoch);
+    }
+
+    /// Closes the current epoch without waiting for the sources that haven't arrived at its close.
+    /// They are non-restartable in it and in the next epoch, and pick up the next epoch when they arrive.
+    fn time_out_late_sources(&self, state: &mut EpochManagerState, timeout_ms: u64) {
+        let EpochManagerStateKind::Closing {
+            epoch_id,
+            source_states,
+            arrived_sources,
+            ..
+        } = &mut state.kind
+        else {
+            return;
+        };
+        let late_sources: HashSet<_> = state
+            .known_sources
+            .difference(arrived_sources)
+            .cloned()
+            .collect();
+        // Sources that never arrived at any close can't be named, and keep the epoch from being
+        // checkpointed by their missing state.
+        warn!(
+            "{} of {} sources didn't arrive at the close of epoch {} within {}ms, proceeding without them",
+            self.num_sources - arrived_sources.len(),
+            self.num_sources,
+            epoch_id,
+            timeout_ms
+        );
+        for node_handle in &late_sources {
+            error!(
+                "Source {} didn't arrive at the close of epoch {} within {}ms, proceeding without it",
+                node_handle, epoch_id, timeout_ms
+            );
+            increment_counter!(EPOCH_SOURCE_TIMEOUT_COUNTER_NAME, SOURCE_LABEL => node_handle.to_string());
+            source_states.insert(node_handle.clone(), SourceState::NonRestartable);
         }
+        self.close_epoch(state, late_sources);
     }
 }
 
@@ -326,13 +657,15 @@ mod tests {
         termination_gen: &(impl Fn(u16) -> bool + Sync),
         commit_gen: &(impl Fn(u16) -> bool + Sync),
         source_state_gen: &(impl Fn(u16) -> (NodeHandle, SourceState) + Sync),
+        num_threads: u16,
     ) -> ClosedEpoch {
         scope(|scope| {
-            let handles = (0..NUM_THREADS)
+            let handles = (0..num_threads)
                 .map(|index| {
                     scope.spawn(move || {
                         epoch_manager.wait_for_epoch_close(
                             source_state_gen(index),
+                            0,
                             termination_gen(index),
                             commit_gen(index),
                         )
@@ -375,8 +708,10 @@ mod tests {
             &|_| false,
             &|_| false,
             &generate_source_state,
+            NUM_THREADS,
         );
         assert!(common_info.is_none());
+        assert!(epoch_manager.last_barrier_wait_duration().is_some());
 
         // One source has new data, epoch should be closed.
         let ClosedEpoch { common_info, .. } = run_epoch_manager(
@@ -384,6 +719,7 @@ mod tests {
             &|_| false,
             &|index| index == 0,
             &generate_source_state,
+            NUM_THREADS,
         );
         let common_info = common_info.unwrap();
         assert_eq!(common_info.id, 0);
@@ -402,6 +738,7 @@ mod tests {
             &|index| index != 0,
             &|_| false,
             &generate_source_state,
+            NUM_THREADS,
         );
         assert!(!should_terminate);
 
@@ -413,10 +750,235 @@ mod tests {
             &|_| true,
             &|_| false,
             &generate_source_state,
+            NUM_THREADS,
         );
         assert!(should_terminate);
     }
 
+    #[tokio::test]
+    async fn test_duplicate_arrival_and_confirmation() {
+        let (_temp_dir, epoch_manager) = create_epoch_manager(2, Default::default()).await;
+        let mut guard = epoch_manager.state.lock();
+        let state = guard.deref_mut();
+        let source_0 = generate_source_state(0);
+        let source_1 = generate_source_state(1);
+
+        // Source 0 arriving twice doesn't close the epoch without source 1.
+        epoch_manager.arrive(state, source_0.clone(), 0, false, true);
+        epoch_manager.arrive(state, source_0.clone(), 0, false, true);
+        assert!(matches!(state.kind, EpochManagerStateKind::Closing { .. }));
+        epoch_manager.arrive(state, source_1.clone(), 0, false, true);
+        assert!(matches!(state.kind, EpochManagerStateKind::Closed { .. }));
+
+        // Source 0 confirming twice doesn't open the next epoch without source 1's confirmation.
+        let closed_epoch = epoch_manager.confirm(state, &source_0.0);
+        assert_eq!(closed_epoch.common_info.unwrap().id, 0);
+        epoch_manager.confirm(state, &source_0.0);
+        assert!(matches!(state.kind, EpochManagerStateKind::Closed { .. }));
+        epoch_manager.confirm(state, &source_1.0);
+        assert!(matches!(
+            state.kind,
+            EpochManagerStateKind::Closing { epoch_id: 1, .. }
+        ));
+
+        // A duplicate that wakes up after that picks up the closed epoch.
+        let last_closed_epoch = state.last_closed_epoch.clone().unwrap();
+        assert_eq!(last_closed_epoch.common_info.unwrap().id, 0);
+    }
+
+    #[tokio::test]
+    async fn test_current_epoch_elapsed() {
+        let (_temp_dir, epoch_manager) = create_epoch_manager(1, Default::default()).await;
+
+        std::thread::sleep(Duration::from_millis(10));
+        let elapsed = epoch_manager.current_epoch_elapsed();
+        assert!(elapsed >= Duration::from_millis(10));
+
+        // Closing an epoch opens a new one, which resets the elapsed time.
+        std::thread::spawn(move || {
+            epoch_manager.wait_for_epoch_close(generate_source_state(0), 0, false, true);
+            assert!(epoch_manager.current_epoch_elapsed() < elapsed);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_many_sources_with_tiny_epochs() {
+        const NUM_SOURCES: u16 = 32;
+        const NUM_EPOCHS: u64 = 200;
+
+        let (_temp_dir, epoch_manager) =
+            create_epoch_manager(NUM_SOURCES as usize, Default::default()).await;
+
+        // Sources that race ahead into the next epoch block until every source has confirmed the
+        // last one, so each source sees every epoch exactly once and in order.
+        scope(|scope| {
+            for index in 0..NUM_SOURCES {
+                let epoch_manager = &epoch_manager;
+                scope.spawn(move || {
+                    for expected_id in 0..NUM_EPOCHS {
+                        let closed_epoch = epoch_manager.wait_for_epoch_close(
+                            generate_source_state(index),
+                            1,
+                            false,
+                            true,
+                        );
+                        assert!(!closed_epoch.should_terminate);
+                        assert_eq!(closed_epoch.common_info.unwrap().id, expected_id);
+                    }
+                });
+            }
+        });
+        assert_eq!(epoch_manager.epoch_id(), NUM_EPOCHS);
+    }
+
+    #[tokio::test]
+    async fn test_slow_source_timeout() {
+        let (_temp_dir, epoch_manager) = create_epoch_manager(
+            2,
+            EpochManagerOptions {
+                slow_source_timeout_ms: Some(10),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // Epoch 0 was closed long ago, and only source 0 confirmed it.
+        {
+            let mut state = epoch_manager.state.lock();
+            state.kind = EpochManagerStateKind::Closed {
+                terminating: false,
+                action: Action::Commit,
+                epoch_id: 0,
+                source_states: Arc::new((0..2).map(generate_source_state).collect()),
+                instant: SystemTime::now() - Duration::from_secs(1),
+                confirmed_sources: [generate_source_state(0).0].into(),
+                num_expected_confirmations: 2,
+                late_sources: HashSet::new(),
+            };
+            state.num_closed_epochs = 1;
+        }
+
+        // Source 0 proceeds to epoch 1 without source 1, which is non-restartable in it.
+        let closed_epoch = run_epoch_manager(
+            &epoch_manager,
+            &|_| false,
+            &|_| true,
+            &generate_source_state,
+            2,
+        );
+        let common_info = closed_epoch.common_info.unwrap();
+        assert_eq!(common_info.id, 1);
+        assert_eq!(
+            common_info.source_states[&generate_source_state(1).0],
+            SourceState::NonRestartable
+        );
+        assert_eq!(
+            common_info.source_states[&generate_source_state(0).0],
+            SourceState::NotStarted
+        );
+        let timed_out_epoch = epoch_manager
+            .state
+            .lock()
+            .last_closed_epoch
+            .clone()
+            .unwrap();
+        assert_eq!(timed_out_epoch.common_info.unwrap().id, 0);
+    }
+
+    #[tokio::test]
+    async fn test_late_source_timeout() {
+        let (_temp_dir, epoch_manager) = create_epoch_manager(
+            2,
+            EpochManagerOptions {
+                slow_source_timeout_ms: Some(100),
+                ..Default::default()
+            },
+        )
+        .await;
+        let late_source = generate_source_state(1).0;
+
+        // Both sources close epoch 0, so they're known.
+        run_epoch_manager(
+            &epoch_manager,
+            &|_| false,
+            &|_| true,
+            &generate_source_state,
+            2,
+        );
+
+        // Source 0 closes epoch 1 without source 1, which doesn't arrive.
+        let closed_epoch = run_epoch_manager(
+            &epoch_manager,
+            &|_| false,
+            &|_| true,
+            &generate_source_state,
+            1,
+        );
+        let common_info = closed_epoch.common_info.unwrap();
+        assert_eq!(common_info.id, 1);
+        assert_eq!(
+            common_info.source_states[&late_source],
+            SourceState::NonRestartable
+        );
+
+        // Source 1 arrives late and catches up in epoch 2, where it's still non-restartable.
+        let closed_epoch = run_epoch_manager(
+            &epoch_manager,
+            &|_| false,
+            &|_| true,
+            &generate_source_state,
+            2,
+        );
+        let common_info = closed_epoch.common_info.unwrap();
+        assert_eq!(common_info.id, 2);
+        assert_eq!(
+            common_info.source_states[&late_source],
+            SourceState::NonRestartable
+        );
+    }
+
+    #[test]
+    fn test_decide_commit_action() {
+        let options = EpochManagerOptions {
+            max_num_records_before_persist: 10,
+            max_interval_before_persist_in_seconds: 60,
+            enable_app_checkpoints: false,
+            slow_source_timeout_ms: None,
+        };
+        let last_instant = SystemTime::UNIX_EPOCH;
+        let soon = last_instant + Duration::from_secs(1);
+        let later = last_instant + Duration::from_secs(60);
+
+        assert_eq!(
+            decide_commit_action(0, 0, last_instant, soon, &options),
+            Action::Commit
+        );
+        assert_eq!(
+            decide_commit_action(15, 5, last_instant, soon, &options),
+            Action::CommitAndPersist
+        );
+        assert_eq!(
+            decide_commit_action(14, 5, last_instant, soon, &options),
+            Action::Commit
+        );
+        assert_eq!(
+            decide_commit_action(0, 0, last_instant, later, &options),
+            Action::CommitAndPersist
+        );
+        // An index ahead of the record count doesn't underflow.
+        assert_eq!(
+            decide_commit_action(0, 5, last_instant, soon, &options),
+            Action::Commit
+        );
+        // A clock going backwards counts as no time passing.
+        assert_eq!(
+            decide_commit_action(0, 0, soon, last_instant, &options),
+            Action::Commit
+        );
+    }
+
     #[tokio::test]
     async fn test_epoch_manager_persist_message() {
         let (_temp_dir, epoch_manager) = create_epoch_manager(
@@ -425,6 +987,7 @@ mod tests {
                 max_num_records_before_persist: 1,
                 max_interval_before_persist_in_seconds: 1,
                 enable_app_checkpoints: true,
+                slow_source_timeout_ms: None,
             },
         )
         .await;
@@ -433,20 +996,20 @@ mod tests {
         let source_state = generate_source_state(0);
         std::thread::spawn(move || {
             // No record, no persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 0, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_none());
 
             // One record, persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 1, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_some());
             assert!(common_info.sink_persist_queue.is_some());
 
             // Time passes, persist.
             std::thread::sleep(Duration::from_secs(1));
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 0, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_some());
             assert!(common_info.sink_persist_queue.is_some());
@@ -461,6 +1024,7 @@ mod tests {
                 max_num_records_before_persist: 1,
                 max_interval_before_persist_in_seconds: 1,
                 enable_app_checkpoints: false,
+                slow_source_timeout_ms: None,
             },
         )
         .await;
@@ -468,20 +1032,20 @@ mod tests {
         let source_state = generate_source_state(0);
         std::thread::spawn(move || {
             // No record, no persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 0, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_none());
 
             // One record, persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 1, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_some());
 
             // Time passes, persist.
             std::thread::sleep(Duration::from_secs(1));
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), 0, false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_some());
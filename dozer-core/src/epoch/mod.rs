@@ -45,3 +45,7 @@ impl Epoch {
 }
 
 use crate::checkpoint::CheckpointWriter;
+
+mod manager;
+
+pub use manager::{EpochManager, EpochManagerOptions};
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::checkpoint::serialize::{DeserializationError, SerializationError};
 use crate::node::PortHandle;
@@ -6,8 +7,34 @@ use dozer_log::reader::CheckpointedLogReaderError;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::node::NodeHandle;
 use dozer_types::thiserror::Error;
+use dozer_types::types::FieldType;
 use dozer_types::{bincode, thiserror};
 
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Sink {0} doesn't generate migrations")]
+    Unsupported(String),
+    #[error("Column {column} has type {typ}, which the sink can't migrate to")]
+    UnsupportedType { column: String, typ: FieldType },
+}
+
+/// A source, processor or sink whose factory failed.
+#[derive(Error, Debug)]
+#[error("{node}: {error}")]
+pub struct NodeBuildError {
+    pub node: NodeHandle,
+    #[source]
+    pub error: BoxedError,
+}
+
+fn display_node_build_errors(errors: &[NodeBuildError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 #[derive(Error, Debug)]
 pub enum ExecutionError {
     #[error("Adding this edge would have created a cycle")]
@@ -32,6 +59,10 @@ pub enum ExecutionError {
     AppSourceConnectionAlreadyExists(String),
     #[error("Factory error: {0}")]
     Factory(#[source] BoxedError),
+    #[error("Failed to build {} node(s): {}", .0.len(), display_node_build_errors(.0))]
+    NodesFailedToBuild(Vec<NodeBuildError>),
+    #[error("Warming up processor {0} timed out after {1:?}")]
+    WarmUpTimeout(NodeHandle, Duration),
     #[error("Failed to restore record writer: {0}")]
     RestoreRecordWriter(#[source] DeserializationError),
     #[error("Source error: {0}")]
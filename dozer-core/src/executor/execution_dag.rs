@@ -2,6 +2,7 @@ use std::{
     collections::{hash_map::Entry, HashMap},
     fmt::Debug,
     sync::Arc,
+    time::Duration,
 };
 
 use crate::{
@@ -56,6 +57,7 @@ pub struct ExecutionDag {
     initial_epoch_id: u64,
     error_manager: Arc<ErrorManager>,
     labels: LabelsAndProgress,
+    sink_lag_alert_threshold: Option<Duration>,
 }
 
 impl ExecutionDag {
@@ -65,6 +67,7 @@ impl ExecutionDag {
         labels: LabelsAndProgress,
         channel_buffer_sz: usize,
         error_threshold: Option<u32>,
+        sink_lag_alert_threshold: Option<Duration>,
     ) -> Result<Self, ExecutionError> {
         // We only create record writer once for every output port. Every `HashMap` in this `Vec` tracks if a node's output ports already have the record writer created.
         let mut all_record_writers = vec![
@@ -158,6 +161,7 @@ impl ExecutionDag {
                 ErrorManager::new_unlimited()
             }),
             labels,
+            sink_lag_alert_threshold,
         })
     }
 
@@ -181,6 +185,10 @@ impl ExecutionDag {
         &self.labels
     }
 
+    pub fn sink_lag_alert_threshold(&self) -> Option<Duration> {
+        self.sink_lag_alert_threshold
+    }
+
     pub fn collect_senders(&self, node_index: daggy::NodeIndex) -> Vec<SenderWithPortMapping> {
         // Map from target node index to `SenderWithPortMapping`.
         let mut senders = HashMap::<daggy::NodeIndex, SenderWithPortMapping>::new();
@@ -22,6 +22,10 @@ pub struct ExecutorOptions {
     pub commit_time_threshold: Duration,
     pub error_threshold: Option<u32>,
     pub checkpoint_factory_options: CheckpointFactoryOptions,
+    /// How long every processor's `warm_up` may take.
+    pub warm_up_timeout: Duration,
+    /// Sinks whose write-ahead lag exceeds this are reported with a warning on every commit.
+    pub sink_lag_alert_threshold: Option<Duration>,
 }
 
 impl Default for ExecutorOptions {
@@ -32,6 +36,8 @@ impl Default for ExecutorOptions {
             commit_time_threshold: Duration::from_millis(50),
             error_threshold: Some(0),
             checkpoint_factory_options: Default::default(),
+            warm_up_timeout: Duration::from_secs(300),
+            sink_lag_alert_threshold: None,
         }
     }
 }
@@ -47,6 +53,7 @@ mod source_node;
 use node::Node;
 use processor_node::ProcessorNode;
 use sink_node::SinkNode;
+pub use sink_node::SINK_WRITE_AHEAD_LAG_GAUGE_NAME;
 
 use self::execution_dag::ExecutionDag;
 use self::source_node::{create_source_node, SourceNode};
@@ -69,7 +76,8 @@ impl DagExecutor {
     ) -> Result<Self, ExecutionError> {
         let dag_schemas = DagSchemas::new(dag).await?;
 
-        let builder_dag = BuilderDag::new(&checkpoint, dag_schemas).await?;
+        let builder_dag =
+            BuilderDag::new(&checkpoint, dag_schemas, options.warm_up_timeout).await?;
 
         Ok(Self {
             builder_dag,
@@ -96,6 +104,7 @@ impl DagExecutor {
             labels,
             self.options.channel_buffer_sz,
             self.options.error_threshold,
+            self.options.sink_lag_alert_threshold,
         )
         .await?;
         let node_indexes = execution_dag.graph().node_identifiers().collect::<Vec<_>>();
@@ -115,7 +124,7 @@ impl DagExecutor {
                     join_handles.push(start_processor(processor_node)?);
                 }
                 NodeKind::Sink(_) => {
-                    let sink_node = SinkNode::new(&mut execution_dag, node_index);
+                    let sink_node = SinkNode::new(&mut execution_dag, node_index, runtime.clone());
                     join_handles.push(start_sink(sink_node)?);
                 }
             }
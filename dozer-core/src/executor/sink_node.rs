@@ -1,8 +1,9 @@
 use crossbeam::channel::{Receiver, Sender, TryRecvError};
 use daggy::NodeIndex;
+use dozer_log::tokio::runtime::Runtime;
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::{
-    log::debug,
+    log::{debug, warn},
     node::{NodeHandle, OpIdentifier},
     types::{Operation, TableOperation},
 };
@@ -16,8 +17,13 @@ use std::{
 };
 
 use crate::{
-    builder_dag::NodeKind, epoch::Epoch, error_manager::ErrorManager, errors::ExecutionError,
-    executor::receiver_loop::init_select, executor_operation::ExecutorOperation, node::Sink,
+    builder_dag::NodeKind,
+    epoch::Epoch,
+    error_manager::ErrorManager,
+    errors::ExecutionError,
+    executor::receiver_loop::init_select,
+    executor_operation::ExecutorOperation,
+    node::{CommitFailureAction, Sink},
 };
 
 use super::execution_dag::ExecutionDag;
@@ -95,6 +101,10 @@ pub struct SinkNode {
     error_manager: Arc<ErrorManager>,
     /// The metrics labels.
     labels: LabelsAndProgress,
+    /// Write-ahead lag above which the sink is reported.
+    lag_alert_threshold: Option<Duration>,
+    /// The runtime `on_pipeline_stop` runs on.
+    runtime: Arc<Runtime>,
 
     last_op_was_commit: bool,
     flush_on_next_commit: bool,
@@ -104,9 +114,10 @@ pub struct SinkNode {
 
 const SINK_OPERATION_COUNTER_NAME: &str = "sink_operation";
 const PIPELINE_LATENCY_GAUGE_NAME: &str = "pipeline_latency";
+pub const SINK_WRITE_AHEAD_LAG_GAUGE_NAME: &str = "sink_write_ahead_lag";
 
 impl SinkNode {
-    pub fn new(dag: &mut ExecutionDag, node_index: NodeIndex) -> Self {
+    pub fn new(dag: &mut ExecutionDag, node_index: NodeIndex, runtime: Arc<Runtime>) -> Self {
         let node = dag.node_weight_mut(node_index);
         let Some(kind) = node.kind.take() else {
             panic!("Must pass in a node")
@@ -126,6 +137,10 @@ impl SinkNode {
             PIPELINE_LATENCY_GAUGE_NAME,
             "The pipeline processing latency in seconds"
         );
+        describe_gauge!(
+            SINK_WRITE_AHEAD_LAG_GAUGE_NAME,
+            "How far the sink's writes are behind the operations it received, in seconds"
+        );
 
         let (schedule_sender, schedule_receiver) = crossbeam::channel::bounded(10);
         let (should_flush_sender, should_flush_receiver) = crossbeam::channel::bounded(0);
@@ -146,6 +161,8 @@ impl SinkNode {
             sink,
             error_manager: dag.error_manager().clone(),
             labels: dag.labels().clone(),
+            lag_alert_threshold: dag.sink_lag_alert_threshold(),
+            runtime,
             last_op_was_commit: false,
             flush_on_next_commit: false,
             flush_scheduler_sender: schedule_sender,
@@ -166,6 +183,55 @@ impl SinkNode {
             .unwrap();
         Ok(())
     }
+
+    /// Commits the sink, letting it decide what happens when the commit fails.
+    fn commit(&mut self, epoch: &Epoch) {
+        loop {
+            let Err(e) = self.sink.commit(epoch) else {
+                if let Some(checkpoint_writer) = &epoch.common_info.checkpoint_writer {
+                    if let Err(e) = self.sink.write_checkpoint_metadata(checkpoint_writer) {
+                        self.error_manager.report(e);
+                    }
+                }
+                return;
+            };
+            match self.sink.on_commit_failed(e.as_ref(), epoch) {
+                CommitFailureAction::Retry(backoff) => {
+                    warn!(
+                        "[{}] Commit of epoch {} failed, retrying in {backoff:?}: {e}",
+                        self.node_handle, epoch.common_info.id
+                    );
+                    std::thread::sleep(backoff);
+                }
+                CommitFailureAction::Skip => {
+                    warn!(
+                        "[{}] Commit of epoch {} failed, skipping it: {e}",
+                        self.node_handle, epoch.common_info.id
+                    );
+                    return;
+                }
+                CommitFailureAction::Halt => {
+                    self.error_manager.report(e);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn report_write_ahead_lag(&self, lag: Duration) {
+        let mut labels = self.labels.labels().clone();
+        labels.push("endpoint", self.node_handle.id.clone());
+        gauge!(SINK_WRITE_AHEAD_LAG_GAUGE_NAME, lag.as_secs_f64(), labels);
+        if self
+            .lag_alert_threshold
+            .is_some_and(|threshold| lag > threshold)
+        {
+            warn!(
+                "[{}] Sink is {:?} behind the operations it received",
+                self.node_handle, lag
+            );
+        }
+    }
 }
 
 impl Name for SinkNode {
@@ -279,10 +345,20 @@ impl ReceiverLoop for SinkNode {
             Operation::BatchInsert { .. } => {
                 labels.push(OPERATION_TYPE_LABEL, "insert");
             }
+            Operation::Upsert { .. } => {
+                labels.push(OPERATION_TYPE_LABEL, "upsert");
+            }
+            Operation::AddField { .. } => {
+                labels.push(OPERATION_TYPE_LABEL, "add_field");
+            }
+            Operation::RemoveField { .. } => {
+                labels.push(OPERATION_TYPE_LABEL, "remove_field");
+            }
         }
 
         let counter_number: u64 = match &op.op {
             Operation::BatchInsert { new } => new.len() as u64,
+            Operation::AddField { .. } | Operation::RemoveField { .. } => 0,
             _ => 1,
         };
 
@@ -296,9 +372,7 @@ impl ReceiverLoop for SinkNode {
 
     fn on_commit(&mut self, epoch: Epoch) -> Result<(), ExecutionError> {
         // debug!("[{}] Checkpointing - {}", self.node_handle, epoch);
-        if let Err(e) = self.sink.commit(&epoch) {
-            self.error_manager.report(e);
-        }
+        self.commit(&epoch);
         self.last_op_was_commit = true;
 
         if let Ok(duration) = epoch.decision_instant.elapsed() {
@@ -306,6 +380,9 @@ impl ReceiverLoop for SinkNode {
             labels.push("endpoint", self.node_handle.id.clone());
             gauge!(PIPELINE_LATENCY_GAUGE_NAME, duration.as_secs_f64(), labels);
         }
+        if let Some(lag) = self.sink.get_write_ahead_lag() {
+            self.report_write_ahead_lag(lag);
+        }
 
         if let Some(queue) = epoch.common_info.sink_persist_queue.as_ref() {
             if let Err(e) = self.sink.persist(&epoch, queue) {
@@ -320,6 +397,9 @@ impl ReceiverLoop for SinkNode {
     }
 
     fn on_terminate(&mut self) -> Result<(), ExecutionError> {
+        if let Err(e) = self.runtime.block_on(self.sink.on_pipeline_stop()) {
+            self.error_manager.report(e);
+        }
         Ok(())
     }
 
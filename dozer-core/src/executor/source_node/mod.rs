@@ -91,6 +91,8 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
                     };
                     let source = &mut self.sources[index];
                     match message {
+                        // The dag's schemas are fixed, so the records keep the schema they were built with
+                        IngestionMessage::ColumnAdded { .. } => {}
                         IngestionMessage::OperationEvent { op, id, .. } => {
                             source.state = SourceState::NonRestartable;
                             source
@@ -14,8 +14,10 @@ pub mod executor_operation;
 pub mod forwarder;
 mod hash_map_to_vec;
 pub mod node;
+pub mod pipeline;
 pub mod record_store;
 pub mod shutdown;
+pub mod upsert_rewrite;
 pub use tokio;
 
 #[cfg(test)]
@@ -1,5 +1,10 @@
 use crate::channels::ProcessorChannelForwarder;
+use crate::checkpoint::CheckpointWriter;
+use crate::dag_schemas::PipelineTopology;
 use crate::epoch::Epoch;
+use crate::errors::MigrationError;
+use crate::pipeline::WireFormatFactory;
+use crate::DEFAULT_PORT_HANDLE;
 
 use dozer_log::storage::{Object, Queue};
 use dozer_log::tokio::sync::mpsc::Sender;
@@ -11,6 +16,7 @@ use dozer_types::tonic::async_trait;
 use dozer_types::types::{Schema, TableOperation};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
 
 pub use dozer_types::types::PortHandle;
 
@@ -53,6 +59,11 @@ pub trait SourceFactory: Send + Sync + Debug {
         output_schemas: HashMap<PortHandle, Schema>,
         state: Option<Vec<u8>>,
     ) -> Result<Box<dyn Source>, BoxedError>;
+    /// The type name and configuration a `FactoryRegistry` constructor can rebuild this factory
+    /// from on another node. `None` if the factory can't be sent to another node.
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        None
+    }
 }
 
 #[async_trait]
@@ -64,6 +75,12 @@ pub trait Source: Send + Sync + Debug {
         sender: Sender<(PortHandle, IngestionMessage)>,
         last_checkpoint: Option<OpIdentifier>,
     ) -> Result<(), BoxedError>;
+
+    /// Called before `start`, once every sink has been built, with the pipeline the source feeds.
+    /// An error stops the pipeline before any record is ingested.
+    fn on_pipeline_start(&mut self, _topology: &PipelineTopology) -> Result<(), BoxedError> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -75,6 +92,16 @@ pub trait ProcessorFactory: Send + Sync + Debug {
     ) -> Result<Schema, BoxedError>;
     fn get_input_ports(&self) -> Vec<PortHandle>;
     fn get_output_ports(&self) -> Vec<PortHandle>;
+    /// Prepares what the processor needs before it's built, such as reference data to pre-load.
+    ///
+    /// Called for every processor, one after the other, before any node is built. Fails the
+    /// pipeline if it takes longer than `ExecutorOptions::warm_up_timeout`.
+    async fn warm_up(
+        &self,
+        _input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
     async fn build(
         &self,
         input_schemas: HashMap<PortHandle, Schema>,
@@ -83,6 +110,27 @@ pub trait ProcessorFactory: Send + Sync + Debug {
     ) -> Result<Box<dyn Processor>, BoxedError>;
     fn type_name(&self) -> String;
     fn id(&self) -> String;
+    /// Whether the processor re-emits its input unchanged, so the node can be removed from the DAG.
+    fn is_passthrough(&self) -> bool {
+        false
+    }
+    /// The type name and configuration a `FactoryRegistry` constructor can rebuild this factory
+    /// from on another node. `None` if the factory can't be sent to another node.
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        None
+    }
+}
+
+/// A human-readable description of what a processor does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ProcessorExplanation {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Schema,
+    pub output_schema: Schema,
+    /// Approximate size of the processor's state, if it keeps any and can estimate it.
+    pub state_size_bytes: Option<usize>,
 }
 
 pub trait Processor: Send + Sync + Debug {
@@ -93,6 +141,119 @@ pub trait Processor: Send + Sync + Debug {
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), BoxedError>;
     fn serialize(&mut self, object: Object) -> Result<(), BoxedError>;
+
+    /// Describes the processor's behavior. The default implementation only reports the processor's type name.
+    fn explain(&self) -> ProcessorExplanation {
+        let type_name = std::any::type_name::<Self>();
+        ProcessorExplanation {
+            name: type_name
+                .rsplit("::")
+                .next()
+                .unwrap_or(type_name)
+                .to_string(),
+            description: String::new(),
+            input_schema: Schema::default(),
+            output_schema: Schema::default(),
+            state_size_bytes: None,
+        }
+    }
+}
+
+/// A processor that re-emits every operation unchanged, as a placeholder in the DAG.
+#[derive(Debug, Clone)]
+pub struct PassthroughProcessorFactory {
+    id: String,
+}
+
+pub(crate) const PASSTHROUGH_TYPE_NAME: &str = "Passthrough";
+
+impl PassthroughProcessorFactory {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait]
+impl ProcessorFactory for PassthroughProcessorFactory {
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        Ok(input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or("Passthrough processor has no input schema")?
+            .clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        Ok(Box::new(PassthroughProcessor))
+    }
+
+    fn type_name(&self) -> String {
+        PASSTHROUGH_TYPE_NAME.to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn is_passthrough(&self) -> bool {
+        true
+    }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        Some(WireFormatFactory {
+            type_name: PASSTHROUGH_TYPE_NAME.to_string(),
+            config: self.id.clone().into_bytes(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct PassthroughProcessor;
+
+impl Processor for PassthroughProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        mut op: TableOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        op.port = DEFAULT_PORT_HANDLE;
+        fw.send(op);
+        Ok(())
+    }
+
+    fn serialize(&mut self, _object: Object) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn explain(&self) -> ProcessorExplanation {
+        ProcessorExplanation {
+            name: "Passthrough".to_string(),
+            description: "Re-emits every operation unchanged".to_string(),
+            input_schema: Schema::default(),
+            output_schema: Schema::default(),
+            state_size_bytes: None,
+        }
+    }
 }
 
 #[async_trait]
@@ -105,8 +266,43 @@ pub trait SinkFactory: Send + Sync + Debug {
         input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, BoxedError>;
     fn type_name(&self) -> String;
+
+    /// Whether the sink can write a record without knowing if it already exists.
+    /// If so, updates that keep the primary key are sent to it as `Operation::Upsert`.
+    fn supports_upsert(&self) -> bool {
+        false
+    }
+    /// The type name and configuration a `FactoryRegistry` constructor can rebuild this factory
+    /// from on another node. `None` if the factory can't be sent to another node.
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        None
+    }
+
+    /// The DDL that migrates what the sink writes on `port` from `old_schema` to `new_schema`.
+    /// Empty if the sink's store has no schema to migrate.
+    fn generate_migration_ddl(
+        &self,
+        _port: &PortHandle,
+        _old_schema: &Schema,
+        _new_schema: &Schema,
+    ) -> Result<String, MigrationError> {
+        Err(MigrationError::Unsupported(self.type_name()))
+    }
+}
+
+/// What to do after `Sink::commit` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitFailureAction {
+    /// Commit the same epoch again after waiting for the duration.
+    Retry(Duration),
+    /// Give up on committing the epoch and carry on.
+    Skip,
+    /// Report the error like other sink errors, which halts the pipeline once the error threshold
+    /// is reached.
+    Halt,
 }
 
+#[async_trait]
 pub trait Sink: Send + Sync + Debug {
     fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError>;
     fn process(&mut self, op: TableOperation) -> Result<(), BoxedError>;
@@ -130,4 +326,33 @@ pub trait Sink: Send + Sync + Debug {
     fn flush_batch(&mut self) -> Result<(), BoxedError> {
         Ok(())
     }
+
+    /// Called when `commit` failed, to decide whether the commit is retried, skipped or the
+    /// error is reported.
+    fn on_commit_failed(
+        &mut self,
+        _error: &(dyn std::error::Error + 'static),
+        _epoch: &Epoch,
+    ) -> CommitFailureAction {
+        CommitFailureAction::Halt
+    }
+
+    /// Called after a successful commit of an epoch that is checkpointed, so the sink can
+    /// annotate the checkpoint with `CheckpointWriter::write_custom_metadata`.
+    /// The metadata is read back with `OptionCheckpoint::read_custom_metadata`.
+    fn write_checkpoint_metadata(&self, _writer: &CheckpointWriter) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    /// Called once when the pipeline stops, after the last epoch was committed and before the
+    /// sink is dropped. Sinks that write asynchronously should finish their writes here.
+    async fn on_pipeline_stop(&mut self) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    /// How far the sink's writes to its store are behind the operations it was given, for sinks
+    /// that write asynchronously. `None` if the sink doesn't track it.
+    fn get_write_ahead_lag(&self) -> Option<Duration> {
+        None
+    }
 }
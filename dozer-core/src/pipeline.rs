@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use dozer_log::storage::Queue;
+use dozer_log::tokio::runtime::Runtime;
+use dozer_log::tokio::sync::oneshot;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::warn;
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::thiserror::{self, Error};
+use dozer_types::tonic::async_trait;
+use dozer_types::types::{Operation, Schema, TableOperation};
+use tempdir::TempDir;
+
+use crate::checkpoint::{CheckpointWriter, OptionCheckpoint};
+use crate::epoch::Epoch;
+use crate::errors::ExecutionError;
+use crate::executor::DagExecutor;
+use crate::node::{
+    PassthroughProcessorFactory, PortHandle, ProcessorFactory, Sink, SinkFactory, SourceFactory,
+    PASSTHROUGH_TYPE_NAME,
+};
+use crate::{Dag, Edge, Endpoint, NodeKind};
+
+const MONITORING_SINK_HANDLE_ID: &str = "monitoring_sink";
+
+/// What a dry run saw before it stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunReport {
+    /// Number of records that reached the sinks.
+    pub records_seen: usize,
+    /// Input schema of every sink port, by port name.
+    pub schemas_seen: HashMap<String, Schema>,
+    pub errors: Vec<String>,
+    pub duration: Duration,
+}
+
+/// A `Pipeline` description that can be sent to another node, and rebuilt there with
+/// `Pipeline::from_wire_format`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct WireFormatPipeline {
+    pub nodes: Vec<WireFormatNode>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct WireFormatNode {
+    pub handle: NodeHandle,
+    pub kind: WireFormatNodeKind,
+    /// `None` if the factory can't be sent to another node.
+    pub factory: Option<WireFormatFactory>,
+    /// Output schema of every output port of a source. Empty for processors and sinks, whose
+    /// schemas depend on their inputs.
+    pub output_schemas: Vec<(PortHandle, Schema)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum WireFormatNodeKind {
+    Source,
+    Processor,
+    Sink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct WireFormatFactory {
+    /// The name the factory's constructor is registered under in the `FactoryRegistry`.
+    pub type_name: String,
+    /// The factory's configuration, in whatever format its constructor reads.
+    pub config: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+pub enum DeserializationError {
+    #[error("Node {0} can't be sent to another node")]
+    FactoryNotSerializable(NodeHandle),
+    #[error("No {kind:?} factory registered as {type_name}")]
+    UnknownFactory {
+        kind: WireFormatNodeKind,
+        type_name: String,
+    },
+    #[error("Cannot build the factory of node {0}: {1}")]
+    CannotBuildFactory(NodeHandle, #[source] BoxedError),
+    #[error("Node {0} appears more than once")]
+    DuplicateNode(NodeHandle),
+    #[error("Invalid edge: {0}")]
+    InvalidEdge(#[from] ExecutionError),
+}
+
+/// Builds a factory from its `WireFormatFactory::config`.
+pub type FactoryConstructor<T> = Box<dyn Fn(&[u8]) -> Result<Box<T>, BoxedError> + Send + Sync>;
+pub type SourceFactoryConstructor = FactoryConstructor<dyn SourceFactory>;
+pub type ProcessorFactoryConstructor = FactoryConstructor<dyn ProcessorFactory>;
+pub type SinkFactoryConstructor = FactoryConstructor<dyn SinkFactory>;
+
+/// Constructors that build factories from their `WireFormatFactory::config`, by type name.
+#[derive(Default)]
+pub struct FactoryRegistry {
+    sources: RwLock<HashMap<String, SourceFactoryConstructor>>,
+    processors: RwLock<HashMap<String, ProcessorFactoryConstructor>>,
+    sinks: RwLock<HashMap<String, SinkFactoryConstructor>>,
+}
+
+impl Debug for FactoryRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn type_names<T>(map: &RwLock<HashMap<String, T>>) -> Vec<String> {
+            map.read()
+                .unwrap_or_else(|e| e.into_inner())
+                .keys()
+                .cloned()
+                .collect()
+        }
+        f.debug_struct("FactoryRegistry")
+            .field("sources", &type_names(&self.sources))
+            .field("processors", &type_names(&self.processors))
+            .field("sinks", &type_names(&self.sinks))
+            .finish()
+    }
+}
+
+impl FactoryRegistry {
+    /// The registry of the process, which has the built-in factories of `dozer-core` registered.
+    pub fn global() -> &'static FactoryRegistry {
+        static REGISTRY: OnceLock<FactoryRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            let registry = FactoryRegistry::default();
+            registry.register_processor(PASSTHROUGH_TYPE_NAME, Box::new(build_passthrough));
+            registry
+        })
+    }
+
+    pub fn register_source(&self, type_name: &str, constructor: SourceFactoryConstructor) {
+        self.sources
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(type_name.to_string(), constructor);
+    }
+
+    pub fn register_processor(&self, type_name: &str, constructor: ProcessorFactoryConstructor) {
+        self.processors
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(type_name.to_string(), constructor);
+    }
+
+    pub fn register_sink(&self, type_name: &str, constructor: SinkFactoryConstructor) {
+        self.sinks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(type_name.to_string(), constructor);
+    }
+
+    fn build(
+        &self,
+        handle: &NodeHandle,
+        kind: WireFormatNodeKind,
+        factory: &WireFormatFactory,
+    ) -> Result<NodeKind, DeserializationError> {
+        fn build<T: ?Sized>(
+            constructors: &RwLock<HashMap<String, FactoryConstructor<T>>>,
+            handle: &NodeHandle,
+            kind: WireFormatNodeKind,
+            factory: &WireFormatFactory,
+        ) -> Result<Box<T>, DeserializationError> {
+            let constructors = constructors.read().unwrap_or_else(|e| e.into_inner());
+            let constructor = constructors.get(&factory.type_name).ok_or_else(|| {
+                DeserializationError::UnknownFactory {
+                    kind,
+                    type_name: factory.type_name.clone(),
+                }
+            })?;
+            constructor(&factory.config)
+                .map_err(|e| DeserializationError::CannotBuildFactory(handle.clone(), e))
+        }
+
+        Ok(match kind {
+            WireFormatNodeKind::Source => {
+                NodeKind::Source(build(&self.sources, handle, kind, factory)?)
+            }
+            WireFormatNodeKind::Processor => {
+                NodeKind::Processor(build(&self.processors, handle, kind, factory)?)
+            }
+            WireFormatNodeKind::Sink => NodeKind::Sink(build(&self.sinks, handle, kind, factory)?),
+        })
+    }
+}
+
+fn build_passthrough(config: &[u8]) -> Result<Box<dyn ProcessorFactory>, BoxedError> {
+    let id = String::from_utf8(config.to_vec())?;
+    Ok(Box::new(PassthroughProcessorFactory::new(id)))
+}
+
+/// A DAG that can be run without its sinks writing anything.
+#[derive(Debug)]
+pub struct Pipeline {
+    dag: Dag,
+    runtime: Arc<Runtime>,
+}
+
+impl Pipeline {
+    pub fn new(dag: Dag, runtime: Arc<Runtime>) -> Self {
+        Self { dag, runtime }
+    }
+
+    /// Describes the pipeline's nodes, edges and source schemas, for sending it to another node.
+    /// Fails if the output schema of a source port can't be determined.
+    pub fn to_wire_format(&self) -> Result<WireFormatPipeline, ExecutionError> {
+        let nodes = self
+            .dag
+            .nodes()
+            .map(|node| {
+                let (kind, factory, output_schemas) = match &node.kind {
+                    NodeKind::Source(source) => (
+                        WireFormatNodeKind::Source,
+                        source.to_wire(),
+                        source
+                            .get_output_ports()
+                            .into_iter()
+                            .map(|port| {
+                                let schema = source
+                                    .get_output_schema(&port.handle)
+                                    .map_err(ExecutionError::Factory)?;
+                                Ok((port.handle, schema))
+                            })
+                            .collect::<Result<_, ExecutionError>>()?,
+                    ),
+                    NodeKind::Processor(processor) => {
+                        (WireFormatNodeKind::Processor, processor.to_wire(), vec![])
+                    }
+                    NodeKind::Sink(sink) => (WireFormatNodeKind::Sink, sink.to_wire(), vec![]),
+                };
+                Ok(WireFormatNode {
+                    handle: node.handle.clone(),
+                    kind,
+                    factory,
+                    output_schemas,
+                })
+            })
+            .collect::<Result<_, ExecutionError>>()?;
+        Ok(WireFormatPipeline {
+            nodes,
+            edges: self.dag.edge_handles(),
+        })
+    }
+
+    /// Rebuilds a pipeline sent by `to_wire_format`, with the factory constructors of `factory_registry`.
+    pub fn from_wire_format(
+        wf: WireFormatPipeline,
+        factory_registry: &FactoryRegistry,
+        runtime: Arc<Runtime>,
+    ) -> Result<Pipeline, DeserializationError> {
+        let mut dag = Dag::new();
+        for node in wf.nodes {
+            let factory = node
+                .factory
+                .as_ref()
+                .ok_or_else(|| DeserializationError::FactoryNotSerializable(node.handle.clone()))?;
+            if dag.node_handles().any(|handle| *handle == node.handle) {
+                return Err(DeserializationError::DuplicateNode(node.handle));
+            }
+            match factory_registry.build(&node.handle, node.kind, factory)? {
+                NodeKind::Source(source) => dag.add_source(node.handle, source),
+                NodeKind::Processor(processor) => dag.add_processor(node.handle, processor),
+                NodeKind::Sink(sink) => dag.add_sink(node.handle, sink),
+            };
+        }
+        for edge in wf.edges {
+            dag.connect(edge.from, edge.to)?;
+        }
+        Ok(Self::new(dag, runtime))
+    }
+
+    /// Attaches a sink that receives a copy of every operation sent to the existing sinks.
+    ///
+    /// The factory is given one input port per input port of the existing sinks, named
+    /// `{sink}.{port}`. The monitoring sink doesn't take part in checkpointing, and its errors,
+    /// including failed commits, are logged rather than reported, so it can't affect the other
+    /// sinks. Like every sink, it runs on its own thread.
+    pub fn add_monitoring_sink(
+        &mut self,
+        factory: Box<dyn SinkFactory>,
+    ) -> Result<(), ExecutionError> {
+        let sink_edges = self
+            .dag
+            .edge_handles()
+            .into_iter()
+            .filter_map(|edge| {
+                let (_, sink) = self
+                    .dag
+                    .sinks()
+                    .find(|(handle, _)| **handle == edge.to.node)?;
+                let name = format!(
+                    "{}.{}",
+                    edge.to.node,
+                    sink.get_input_port_name(&edge.to.port)
+                );
+                Some((edge.from, name))
+            })
+            .collect::<Vec<_>>();
+
+        let handle = NodeHandle::new(None, MONITORING_SINK_HANDLE_ID.to_string());
+        let input_ports = (0..sink_edges.len() as PortHandle)
+            .zip(sink_edges.iter().map(|(_, name)| name.clone()))
+            .collect();
+        self.dag.add_sink(
+            handle.clone(),
+            Box::new(MonitoringSinkFactory {
+                factory,
+                input_ports,
+            }),
+        );
+        for (port, (from, _)) in sink_edges.into_iter().enumerate() {
+            self.dag
+                .connect(from, Endpoint::new(handle.clone(), port as PortHandle))?;
+        }
+        Ok(())
+    }
+
+    /// Runs the pipeline with every sink replaced by one that drops the records, until
+    /// `max_records` records reached the sinks or a source finished snapshotting.
+    ///
+    /// Checkpoints are written to a temporary directory, so the run starts from scratch and
+    /// leaves nothing behind.
+    pub fn dry_run_snapshot(self, max_records: usize) -> Result<DryRunReport, BoxedError> {
+        let start = Instant::now();
+        let (stop_sender, stop_receiver) = oneshot::channel();
+        let state = Arc::new(DryRunState {
+            max_records,
+            records_seen: AtomicUsize::new(0),
+            schemas_seen: Mutex::new(HashMap::new()),
+            stop_sender: Mutex::new(Some(stop_sender)),
+        });
+
+        let mut dag = self.dag;
+        dag.replace_sinks(|_, sink| {
+            Box::new(NullSinkFactory {
+                input_ports: sink
+                    .get_input_ports()
+                    .into_iter()
+                    .map(|port| (port, sink.get_input_port_name(&port)))
+                    .collect(),
+                state: state.clone(),
+            })
+        });
+
+        // The directory is removed when dropped, so it must outlive the run
+        let checkpoint_dir = TempDir::new("dry_run_snapshot")?;
+        let checkpoint_path = checkpoint_dir.path().to_string_lossy().into_owned();
+        let runtime = self.runtime.clone();
+        let result = self.runtime.block_on(async move {
+            let checkpoint = OptionCheckpoint::new(checkpoint_path, Default::default()).await?;
+            DagExecutor::new(dag, checkpoint, Default::default())
+                .await?
+                .start(stop_receiver, Default::default(), runtime)
+                .await
+        });
+
+        let mut errors = vec![];
+        if let Err(e) = result.and_then(|handle| handle.join()) {
+            errors.push(e.to_string());
+        }
+        let schemas_seen = std::mem::take(&mut *state.schemas_seen.lock().unwrap());
+        Ok(DryRunReport {
+            records_seen: state.records_seen.load(Ordering::Relaxed),
+            schemas_seen,
+            errors,
+            duration: start.elapsed(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct DryRunState {
+    max_records: usize,
+    records_seen: AtomicUsize,
+    schemas_seen: Mutex<HashMap<String, Schema>>,
+    /// Taken by the first sink that stops the run.
+    stop_sender: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl DryRunState {
+    fn stop(&self) {
+        if let Some(sender) = self.stop_sender.lock().unwrap().take() {
+            let _ = sender.send(());
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NullSinkFactory {
+    input_ports: Vec<(PortHandle, String)>,
+    state: Arc<DryRunState>,
+}
+
+#[async_trait]
+impl SinkFactory for NullSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        self.input_ports.iter().map(|(port, _)| *port).collect()
+    }
+
+    fn get_input_port_name(&self, port: &PortHandle) -> String {
+        self.input_ports
+            .iter()
+            .find(|(input_port, _)| input_port == port)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| port.to_string())
+    }
+
+    fn prepare(&self, input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        let mut schemas_seen = self.state.schemas_seen.lock().unwrap();
+        for (port, schema) in input_schemas {
+            schemas_seen.insert(self.get_input_port_name(&port), schema);
+        }
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(NullSink {
+            state: self.state.clone(),
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "null".to_string()
+    }
+}
+
+/// A sink that counts the records it receives and drops them.
+#[derive(Debug)]
+struct NullSink {
+    state: Arc<DryRunState>,
+}
+
+impl Sink for NullSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        let num_records = match &op.op {
+            Operation::BatchInsert { new } => new.len(),
+            Operation::Delete { .. }
+            | Operation::Insert { .. }
+            | Operation::Update { .. }
+            | Operation::Upsert { .. } => 1,
+            Operation::AddField { .. } | Operation::RemoveField { .. } => 0,
+        };
+        let records_seen = self
+            .state
+            .records_seen
+            .fetch_add(num_records, Ordering::Relaxed)
+            + num_records;
+        if records_seen >= self.state.max_records {
+            self.state.stop();
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        self.state.stop();
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug)]
+struct MonitoringSinkFactory {
+    factory: Box<dyn SinkFactory>,
+    input_ports: Vec<(PortHandle, String)>,
+}
+
+#[async_trait]
+impl SinkFactory for MonitoringSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        self.input_ports.iter().map(|(port, _)| *port).collect()
+    }
+
+    fn get_input_port_name(&self, port: &PortHandle) -> String {
+        self.input_ports
+            .iter()
+            .find(|(input_port, _)| input_port == port)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| port.to_string())
+    }
+
+    fn prepare(&self, input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        self.factory.prepare(input_schemas)
+    }
+
+    async fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(MonitoringSink {
+            sink: self.factory.build(input_schemas).await?,
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        self.factory.type_name()
+    }
+}
+
+/// Wraps a monitoring sink, logging its errors instead of returning them.
+#[derive(Debug)]
+struct MonitoringSink {
+    sink: Box<dyn Sink>,
+}
+
+impl MonitoringSink {
+    fn log_error(&self, action: &str, result: Result<(), BoxedError>) {
+        if let Err(e) = result {
+            warn!("Monitoring sink failed to {action}: {e}");
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for MonitoringSink {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError> {
+        let result = self.sink.commit(epoch_details);
+        self.log_error("commit", result);
+        Ok(())
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        let result = self.sink.process(op);
+        self.log_error("process an operation", result);
+        Ok(())
+    }
+
+    fn persist(&mut self, epoch: &Epoch, queue: &Queue) -> Result<(), BoxedError> {
+        let result = self.sink.persist(epoch, queue);
+        self.log_error("persist", result);
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        connection_name: String,
+    ) -> Result<(), BoxedError> {
+        let result = self.sink.on_source_snapshotting_started(connection_name);
+        self.log_error("start snapshotting", result);
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        connection_name: String,
+        id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        let result = self.sink.on_source_snapshotting_done(connection_name, id);
+        self.log_error("finish snapshotting", result);
+        Ok(())
+    }
+
+    // The monitoring sink doesn't decide where sources resume from.
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        Ok(None)
+    }
+
+    fn flush_batch(&mut self) -> Result<(), BoxedError> {
+        let result = self.sink.flush_batch();
+        self.log_error("flush", result);
+        Ok(())
+    }
+
+    fn write_checkpoint_metadata(&self, writer: &CheckpointWriter) -> Result<(), BoxedError> {
+        let result = self.sink.write_checkpoint_metadata(writer);
+        self.log_error("write checkpoint metadata", result);
+        Ok(())
+    }
+
+    async fn on_pipeline_stop(&mut self) -> Result<(), BoxedError> {
+        let result = self.sink.on_pipeline_stop().await;
+        self.log_error("stop", result);
+        Ok(())
+    }
+
+    fn get_write_ahead_lag(&self) -> Option<Duration> {
+        self.sink.get_write_ahead_lag()
+    }
+}
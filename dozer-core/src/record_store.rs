@@ -12,6 +12,8 @@ use std::fmt::{Debug, Formatter};
 pub enum RecordWriterError {
     #[error("Record not found")]
     RecordNotFound,
+    #[error("Primary key field {0} can't be removed")]
+    PrimaryKeyRemoved(String),
 }
 
 pub trait RecordWriter: Send + Sync {
@@ -104,6 +106,48 @@ impl RecordWriter for PrimaryKeyLookupRecordWriter {
                 }
                 Ok(Operation::BatchInsert { new: new_records })
             }
+            Operation::Upsert { new } => {
+                let new_key = new.get_key(&self.schema.primary_index);
+                self.index.insert(new_key, new.clone());
+                Ok(Operation::Upsert { new })
+            }
+            Operation::AddField {
+                table_index,
+                field,
+                default_value,
+            } => {
+                self.schema.field(field.clone(), false);
+                Ok(Operation::AddField {
+                    table_index,
+                    field,
+                    default_value,
+                })
+            }
+            Operation::RemoveField {
+                table_index,
+                field_name,
+            } => {
+                if let Some(removed) = self
+                    .schema
+                    .fields
+                    .iter()
+                    .position(|field| field.name == field_name)
+                {
+                    if self.schema.primary_index.contains(&removed) {
+                        return Err(RecordWriterError::PrimaryKeyRemoved(field_name));
+                    }
+                    self.schema.fields.remove(removed);
+                    for index in &mut self.schema.primary_index {
+                        if *index > removed {
+                            *index -= 1;
+                        }
+                    }
+                }
+                Ok(Operation::RemoveField {
+                    table_index,
+                    field_name,
+                })
+            }
         }
     }
 
@@ -1,3 +1,6 @@
+use crate::checkpoint::create_checkpoint_for_test;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
 use crate::node::{
     OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Source, SourceFactory,
 };
@@ -12,11 +15,13 @@ use dozer_types::node::NodeHandle;
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
 
+use futures::future::pending;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Duration;
 
-use super::run_dag;
+use super::{create_test_runtime, run_dag};
 
 #[derive(Debug)]
 struct CreateErrSourceFactory {
@@ -282,3 +287,149 @@ fn test_create_proc_panic() {
 
     run_dag(dag).unwrap();
 }
+
+#[derive(Debug)]
+struct StuckWarmUpProcessorFactory;
+
+#[async_trait]
+impl ProcessorFactory for StuckWarmUpProcessorFactory {
+    fn type_name(&self) -> String {
+        "StuckWarmUp".to_owned()
+    }
+
+    async fn get_output_schema(
+        &self,
+        port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        NoopProcessorFactory {}
+            .get_output_schema(port, input_schemas)
+            .await
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn warm_up(
+        &self,
+        _input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<(), BoxedError> {
+        pending().await
+    }
+
+    async fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        output_schemas: HashMap<PortHandle, Schema>,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        NoopProcessorFactory {}
+            .build(input_schemas, output_schemas, checkpoint_data)
+            .await
+    }
+
+    fn id(&self) -> String {
+        "StuckWarmUp".to_owned()
+    }
+}
+
+#[test]
+fn test_proc_warm_up_timeout() {
+    let count: u64 = 1_000_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Box::new(StuckWarmUpProcessorFactory));
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        warm_up_timeout: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let result = create_test_runtime().block_on(async move {
+        let (_temp_dir, checkpoint) = create_checkpoint_for_test().await;
+        DagExecutor::new(dag, checkpoint, options).await
+    });
+    assert!(matches!(
+        result,
+        Err(ExecutionError::WarmUpTimeout(handle, _)) if handle == proc_handle
+    ));
+}
+
+#[test]
+fn test_create_errors_are_reported_per_node() {
+    let count: u64 = 1_000_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(CreateErrSourceFactory::new(false)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Box::new(CreateErrProcessorFactory::new(false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let result = create_test_runtime().block_on(async move {
+        let (_temp_dir, checkpoint) = create_checkpoint_for_test().await;
+        DagExecutor::new(dag, checkpoint, Default::default()).await
+    });
+    let Err(ExecutionError::NodesFailedToBuild(errors)) = result else {
+        panic!("expected the source and the processor to fail");
+    };
+    assert_eq!(
+        errors.iter().map(|e| &e.node).collect::<Vec<_>>(),
+        vec![&source_handle, &proc_handle]
+    );
+}
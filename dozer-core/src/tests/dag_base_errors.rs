@@ -1,8 +1,8 @@
 use crate::channels::ProcessorChannelForwarder;
 use crate::epoch::Epoch;
 use crate::node::{
-    OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Sink, SinkFactory,
-    Source, SourceFactory,
+    CommitFailureAction, OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory,
+    Sink, SinkFactory, Source, SourceFactory,
 };
 use crate::tests::dag_base_run::NoopProcessorFactory;
 use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
@@ -23,6 +23,7 @@ use std::panic;
 
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::run_dag;
 
@@ -578,3 +579,142 @@ fn test_run_dag_sink_err_panic() {
 
     run_dag(dag).unwrap();
 }
+
+#[derive(Debug)]
+struct CommitErrSinkFactory {
+    failing_commits: u32,
+    action: CommitFailureAction,
+}
+
+#[async_trait]
+impl SinkFactory for CommitErrSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![COUNTING_SINK_INPUT_PORT]
+    }
+
+    fn get_input_port_name(&self, _port: &PortHandle) -> String {
+        "commit_error".to_string()
+    }
+
+    fn prepare(&self, _input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(CommitErrSink {
+            failing_commits: self.failing_commits,
+            action: self.action,
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "commit_error".to_string()
+    }
+}
+
+/// A sink whose first `failing_commits` commits fail, and that answers `action` to each failure.
+#[derive(Debug)]
+struct CommitErrSink {
+    failing_commits: u32,
+    action: CommitFailureAction,
+}
+
+impl Sink for CommitErrSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        if self.failing_commits > 0 {
+            self.failing_commits -= 1;
+            return Err("Generated commit error".to_string().into());
+        }
+        Ok(())
+    }
+
+    fn process(&mut self, _op: TableOperation) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        Ok(None)
+    }
+
+    fn on_commit_failed(
+        &mut self,
+        _error: &(dyn std::error::Error + 'static),
+        _epoch: &Epoch,
+    ) -> CommitFailureAction {
+        self.action
+    }
+}
+
+fn run_dag_with_commit_errors(failing_commits: u32, action: CommitFailureAction) {
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(10_000, latch, false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CommitErrSinkFactory {
+            failing_commits,
+            action,
+        }),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    run_dag(dag).unwrap();
+}
+
+#[test]
+fn test_run_dag_sink_commit_err_retry() {
+    run_dag_with_commit_errors(3, CommitFailureAction::Retry(Duration::ZERO));
+}
+
+#[test]
+fn test_run_dag_sink_commit_err_skip() {
+    run_dag_with_commit_errors(3, CommitFailureAction::Skip);
+}
+
+#[test]
+#[should_panic]
+fn test_run_dag_sink_commit_err_halt() {
+    run_dag_with_commit_errors(1, CommitFailureAction::Halt);
+}
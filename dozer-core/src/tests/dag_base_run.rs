@@ -2,7 +2,7 @@ use crate::channels::ProcessorChannelForwarder;
 use crate::checkpoint::create_checkpoint_for_test;
 use crate::epoch::Epoch;
 use crate::executor::DagExecutor;
-use crate::node::{PortHandle, Processor, ProcessorFactory};
+use crate::node::{PassthroughProcessorFactory, PortHandle, Processor, ProcessorFactory};
 use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
 use crate::tests::sources::{
     DualPortGeneratorSourceFactory, GeneratorSourceFactory,
@@ -123,6 +123,46 @@ fn test_run_dag() {
     run_dag(dag).unwrap();
 }
 
+#[test]
+fn test_run_dag_passthrough() {
+    let count: u64 = 1_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    let passthrough = PassthroughProcessorFactory::new(proc_handle.id.clone());
+    assert!(passthrough.is_passthrough());
+    assert!(!NoopProcessorFactory {}.is_passthrough());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Box::new(passthrough));
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    run_dag(dag).unwrap();
+}
+
 #[test]
 fn test_run_dag_and_stop() {
     let count: u64 = 1_000_000;
@@ -1,4 +1,4 @@
-use crate::dag_schemas::{DagHaveSchemas, DagSchemas};
+use crate::dag_schemas::{DagHaveSchemas, DagSchemas, TopologyNodeKind};
 use crate::node::{
     OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, SinkFactory, Source,
     SourceFactory,
@@ -207,18 +207,18 @@ async fn test_extract_dag_schemas() {
     let sink_index = dag.add_sink(sink_handle.clone(), Box::new(TestSinkFactory {}));
 
     dag.connect(
-        Endpoint::new(users_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(users_handle.clone(), DEFAULT_PORT_HANDLE),
         Endpoint::new(join_handle.clone(), 1),
     )
     .unwrap();
     dag.connect(
-        Endpoint::new(countries_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(countries_handle.clone(), DEFAULT_PORT_HANDLE),
         Endpoint::new(join_handle.clone(), 2),
     )
     .unwrap();
     dag.connect(
-        Endpoint::new(join_handle, DEFAULT_PORT_HANDLE),
-        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(join_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle.clone(), DEFAULT_PORT_HANDLE),
     )
     .unwrap();
 
@@ -255,4 +255,19 @@ async fn test_extract_dag_schemas() {
         sink_input.get(&DEFAULT_PORT_HANDLE).unwrap().fields.len(),
         5
     );
+
+    let topology = dag_schemas.topology();
+    assert_eq!(topology.nodes.len(), 4);
+    assert_eq!(topology.edges.len(), 3);
+    for source_handle in [&users_handle, &countries_handle] {
+        let sink_inputs = topology.sink_inputs(source_handle);
+        assert_eq!(sink_inputs.len(), 1);
+        assert_eq!(sink_inputs[0].to, sink_handle);
+        assert_eq!(sink_inputs[0].schema.fields.len(), 5);
+    }
+    assert!(topology.sink_inputs(&sink_handle).is_empty());
+    assert!(topology
+        .nodes
+        .iter()
+        .any(|node| node.handle == join_handle && node.kind == TopologyNodeKind::Processor));
 }
@@ -0,0 +1,192 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::NodeHandle;
+use dozer_types::serde_json;
+
+use crate::node::{PassthroughProcessorFactory, SinkFactory, SourceFactory};
+use crate::pipeline::{DeserializationError, FactoryRegistry, Pipeline, WireFormatPipeline};
+use crate::tests::sinks::{
+    ConnectivityTestSinkFactory, CountingSinkFactory, COUNTING_SINK_INPUT_PORT,
+};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+
+use super::create_test_runtime;
+
+#[test]
+fn test_dry_run_snapshot() {
+    let count: u64 = 1_000;
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch)),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let report = Pipeline::new(dag, create_test_runtime())
+        .dry_run_snapshot(10)
+        .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(report.records_seen >= 10);
+    assert_eq!(
+        report.schemas_seen["counting"].fields[0].name,
+        "id".to_string()
+    );
+}
+
+#[test]
+fn test_add_monitoring_sink() {
+    let count: u64 = 1_000;
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch.clone())),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let mut pipeline = Pipeline::new(dag, create_test_runtime());
+    pipeline
+        .add_monitoring_sink(Box::new(CountingSinkFactory::new(count, latch)))
+        .unwrap();
+    let report = pipeline.dry_run_snapshot(10).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    // The monitoring sink gets the schema of the sink it observes
+    assert_eq!(
+        report.schemas_seen["1_2.counting"],
+        report.schemas_seen["counting"]
+    );
+}
+
+#[test]
+fn test_wire_format_round_trip() {
+    let count: u64 = 1_000;
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Box::new(PassthroughProcessorFactory::new("passthrough".to_string())),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch.clone())),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let runtime = create_test_runtime();
+    let wire_format = Pipeline::new(dag, runtime.clone())
+        .to_wire_format()
+        .unwrap();
+    assert_eq!(wire_format.nodes[0].output_schemas.len(), 1);
+
+    let json = serde_json::to_vec(&wire_format).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<WireFormatPipeline>(&json).unwrap(),
+        wire_format
+    );
+    let bytes = bincode::serde::encode_to_vec(&wire_format, bincode::config::legacy()).unwrap();
+    let (decoded, _): (WireFormatPipeline, _) =
+        bincode::serde::decode_from_slice(&bytes, bincode::config::legacy()).unwrap();
+    assert_eq!(decoded, wire_format);
+
+    // The passthrough processor is registered by default.
+    let registry = FactoryRegistry::global();
+    let source_latch = latch.clone();
+    registry.register_source(
+        "generator",
+        Box::new(
+            move |config: &[u8]| -> Result<Box<dyn SourceFactory>, BoxedError> {
+                let (count, stateful) = serde_json::from_slice(config)?;
+                Ok(Box::new(GeneratorSourceFactory::new(
+                    count,
+                    source_latch.clone(),
+                    stateful,
+                )))
+            },
+        ),
+    );
+    registry.register_sink(
+        "counting",
+        Box::new(
+            move |config: &[u8]| -> Result<Box<dyn SinkFactory>, BoxedError> {
+                let expected = u64::from_le_bytes(config.try_into()?);
+                Ok(Box::new(CountingSinkFactory::new(expected, latch.clone())))
+            },
+        ),
+    );
+
+    let pipeline = Pipeline::from_wire_format(decoded, registry, runtime).unwrap();
+    let report = pipeline.dry_run_snapshot(10).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(report.records_seen >= 10);
+}
+
+#[test]
+fn test_wire_format_without_serializable_factory() {
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(10, latch, false)),
+    );
+    dag.add_sink(sink_handle.clone(), Box::new(ConnectivityTestSinkFactory));
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let runtime = create_test_runtime();
+    let wire_format = Pipeline::new(dag, runtime.clone())
+        .to_wire_format()
+        .unwrap();
+    assert!(matches!(
+        Pipeline::from_wire_format(wire_format, &FactoryRegistry::default(), runtime),
+        Err(DeserializationError::FactoryNotSerializable(handle)) if handle == sink_handle
+    ));
+}
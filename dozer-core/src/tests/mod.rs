@@ -14,6 +14,7 @@ mod dag_base_errors;
 mod dag_base_run;
 mod dag_ports;
 mod dag_schemas;
+mod dry_run;
 pub mod processors;
 pub mod sinks;
 pub mod sources;
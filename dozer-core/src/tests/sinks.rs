@@ -1,5 +1,6 @@
 use crate::epoch::Epoch;
 use crate::node::{PortHandle, Sink, SinkFactory};
+use crate::pipeline::WireFormatFactory;
 use crate::DEFAULT_PORT_HANDLE;
 use dozer_log::storage::Queue;
 use dozer_types::errors::internal::BoxedError;
@@ -58,6 +59,13 @@ impl SinkFactory for CountingSinkFactory {
     fn type_name(&self) -> String {
         "counting".to_string()
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        Some(WireFormatFactory {
+            type_name: "counting".to_string(),
+            config: self.expected.to_le_bytes().to_vec(),
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -1,9 +1,11 @@
 use crate::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use crate::pipeline::WireFormatFactory;
 use crate::DEFAULT_PORT_HANDLE;
 use dozer_log::tokio::{self, sync::mpsc::Sender};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
 use dozer_types::node::OpIdentifier;
+use dozer_types::serde_json;
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
@@ -83,6 +85,13 @@ impl SourceFactory for GeneratorSourceFactory {
             running: self.running.clone(),
         }))
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        Some(WireFormatFactory {
+            type_name: "generator".to_string(),
+            config: serde_json::to_vec(&(self.count, self.stateful)).unwrap(),
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_log::storage::Queue;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::OpIdentifier;
+use dozer_types::tonic::async_trait;
+use dozer_types::types::{Operation, Record, Schema, TableOperation};
+
+use crate::checkpoint::CheckpointWriter;
+use crate::epoch::Epoch;
+use crate::node::{CommitFailureAction, PortHandle, Sink};
+
+/// Wraps a sink that supports upserts, and rewrites the updates that keep the primary key to
+/// `Operation::Upsert`, so the sink doesn't have to look at the old record.
+#[derive(Debug)]
+pub struct UpsertRewriteOptimizer {
+    sink: Box<dyn Sink>,
+    /// Primary index of each input port. Updates on ports without a primary key are not rewritten.
+    primary_indexes: HashMap<PortHandle, Vec<usize>>,
+}
+
+impl UpsertRewriteOptimizer {
+    pub fn new(sink: Box<dyn Sink>, input_schemas: &HashMap<PortHandle, Schema>) -> Self {
+        let primary_indexes = input_schemas
+            .iter()
+            .map(|(port, schema)| (*port, schema.primary_index.clone()))
+            .collect();
+        Self {
+            sink,
+            primary_indexes,
+        }
+    }
+
+    fn rewrite(&self, port: PortHandle, op: Operation) -> Operation {
+        match op {
+            Operation::Update { old, new } if self.keeps_primary_key(port, &old, &new) => {
+                Operation::Upsert { new }
+            }
+            op => op,
+        }
+    }
+
+    fn keeps_primary_key(&self, port: PortHandle, old: &Record, new: &Record) -> bool {
+        match self.primary_indexes.get(&port) {
+            Some(primary_index) if !primary_index.is_empty() => primary_index
+                .iter()
+                .all(|index| old.values[*index] == new.values[*index]),
+            _ => false,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for UpsertRewriteOptimizer {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError> {
+        self.sink.commit(epoch_details)
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        let TableOperation { id, op, port } = op;
+        let op = self.rewrite(port, op);
+        self.sink.process(TableOperation { id, op, port })
+    }
+
+    fn persist(&mut self, epoch: &Epoch, queue: &Queue) -> Result<(), BoxedError> {
+        self.sink.persist(epoch, queue)
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        connection_name: String,
+    ) -> Result<(), BoxedError> {
+        self.sink.on_source_snapshotting_started(connection_name)
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        connection_name: String,
+        id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        self.sink.on_source_snapshotting_done(connection_name, id)
+    }
+
+    fn set_source_state(&mut self, source_state: &[u8]) -> Result<(), BoxedError> {
+        self.sink.set_source_state(source_state)
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        self.sink.get_source_state()
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        self.sink.get_latest_op_id()
+    }
+
+    fn flush_batch(&mut self) -> Result<(), BoxedError> {
+        self.sink.flush_batch()
+    }
+
+    fn on_commit_failed(
+        &mut self,
+        error: &(dyn std::error::Error + 'static),
+        epoch: &Epoch,
+    ) -> CommitFailureAction {
+        self.sink.on_commit_failed(error, epoch)
+    }
+
+    fn write_checkpoint_metadata(&self, writer: &CheckpointWriter) -> Result<(), BoxedError> {
+        self.sink.write_checkpoint_metadata(writer)
+    }
+
+    async fn on_pipeline_stop(&mut self) -> Result<(), BoxedError> {
+        self.sink.on_pipeline_stop().await
+    }
+
+    fn get_write_ahead_lag(&self) -> Option<Duration> {
+        self.sink.get_write_ahead_lag()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use dozer_types::types::{Field, FieldDefinition, FieldType, SourceDefinition};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RecordingSink {
+        ops: Arc<Mutex<Vec<Operation>>>,
+    }
+
+    impl Sink for RecordingSink {
+        fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+            self.ops.lock().unwrap().push(op.op);
+            Ok(())
+        }
+
+        fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        fn on_source_snapshotting_started(
+            &mut self,
+            _connection_name: String,
+        ) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        fn on_source_snapshotting_done(
+            &mut self,
+            _connection_name: String,
+            _id: Option<OpIdentifier>,
+        ) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+            Ok(None)
+        }
+
+        fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+            Ok(None)
+        }
+    }
+
+    fn update(old: [i64; 2], new: [i64; 2]) -> TableOperation {
+        let record = |values: [i64; 2]| Record::new(values.map(Field::Int).to_vec());
+        TableOperation::without_id(
+            Operation::Update {
+                old: record(old),
+                new: record(new),
+            },
+            0,
+        )
+    }
+
+    #[test]
+    fn test_upsert_rewrite() {
+        let field = |name: &str| {
+            FieldDefinition::new(
+                name.to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            )
+        };
+        let mut schema = Schema::new();
+        schema.field(field("id"), true).field(field("value"), false);
+
+        let ops = Arc::new(Mutex::new(vec![]));
+        let sink = RecordingSink { ops: ops.clone() };
+        let mut optimizer =
+            UpsertRewriteOptimizer::new(Box::new(sink), &HashMap::from([(0, schema)]));
+
+        optimizer.process(update([1, 1], [1, 2])).unwrap();
+        optimizer.process(update([1, 2], [2, 2])).unwrap();
+
+        let ops = ops.lock().unwrap();
+        assert_eq!(
+            ops[0],
+            Operation::Upsert {
+                new: Record::new(vec![Field::Int(1), Field::Int(2)])
+            }
+        );
+        assert!(matches!(ops[1], Operation::Update { .. }));
+    }
+}
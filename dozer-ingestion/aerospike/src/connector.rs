@@ -1,26 +1,36 @@
 use dozer_ingestion_connector::dozer_types::errors::internal::BoxedError;
-use dozer_ingestion_connector::dozer_types::log::{error, info};
-use dozer_ingestion_connector::dozer_types::models::connection::AerospikeConnection;
+use dozer_ingestion_connector::dozer_types::log::{debug, error, info, warn};
+use dozer_ingestion_connector::dozer_types::models::connection::{
+    AerospikeConnection, Base64Alphabet, ReplicationTlsConfig, RequestFormat,
+};
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
-    IngestionMessage, TransactionInfo,
+    IngestionMessage, OverflowPolicy, TransactionInfo,
 };
 use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
-use dozer_ingestion_connector::dozer_types::types::Operation::Insert;
+use dozer_ingestion_connector::dozer_types::types::Operation::{Insert, RemoveField};
 use dozer_ingestion_connector::dozer_types::types::{Field, FieldDefinition, FieldType, Schema};
 use dozer_ingestion_connector::{
     async_trait, dozer_types, Connector, Ingestor, SourceSchema, SourceSchemaResult,
     TableIdentifier, TableInfo,
 };
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
 use std::num::TryFromIntError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use metrics::{describe_counter, describe_gauge, gauge, increment_counter};
 
-use dozer_ingestion_connector::dozer_types::serde::Deserialize;
+use dozer_ingestion_connector::dozer_types::serde::{Deserialize, Serialize};
 
 use actix_web::dev::Server;
 use actix_web::post;
 use actix_web::web;
 use actix_web::App;
 use actix_web::HttpRequest;
+use actix_web::HttpResponseBuilder;
 use actix_web::HttpServer;
 use actix_web::{get, HttpResponse};
 
@@ -37,11 +47,22 @@ use dozer_ingestion_connector::dozer_types::chrono::{
 use dozer_ingestion_connector::dozer_types::thiserror::{self, Error};
 use dozer_ingestion_connector::schema_parser::SchemaParser;
 
+use crate::msgpack::{MessagePackAerospikeEvent, MsgPack};
+
 #[derive(Debug, Error)]
 pub enum AerospikeConnectorError {
     #[error("Cannot start server: {0}")]
     CannotStartServer(#[from] std::io::Error),
 
+    #[error("Cannot read TLS file {0}: {1}")]
+    CannotReadTlsFile(String, #[source] std::io::Error),
+
+    #[error("No private key found in {0}")]
+    NoPrivateKey(String),
+
+    #[error("Invalid TLS config: {0}")]
+    InvalidTlsConfig(#[from] rustls::Error),
+
     #[error("No set name find in key: {0:?}")]
     NoSetNameFindInKey(Vec<Option<String>>),
 
@@ -102,37 +123,145 @@ pub enum AerospikeConnectorError {
     #[error("Schema not found: {0}")]
     SchemaNotFound(String),
 
+    #[error("Bins of set {set_name} don't match its schema. Unexpected bins: {unexpected:?}, missing bins: {missing:?}")]
+    SchemaMismatch {
+        set_name: String,
+        unexpected: Vec<String>,
+        missing: Vec<String>,
+    },
+
+    #[error("Columns {columns:?} of set {set_name} only differ by case and can't be matched case-insensitively")]
+    CaseInsensitiveCollision {
+        set_name: String,
+        columns: Vec<String>,
+    },
+
     #[error("Failed parsing timestamp: {0}")]
     TimestampParsingError(#[from] dozer_ingestion_connector::dozer_types::chrono::ParseError),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "dozer_types::serde")]
 pub struct AerospikeEvent {
-    msg: String,
-    key: Vec<Option<String>>,
-    // gen: u32,
+    pub(crate) msg: String,
+    pub(crate) key: Vec<Option<String>>,
+    #[serde(default)]
+    pub(crate) gen: Option<u32>,
     // exp: u32,
-    lut: u64,
-    bins: Vec<Bin>,
+    pub(crate) lut: u64,
+    pub(crate) bins: Vec<Bin>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "dozer_types::serde")]
 pub struct Bin {
-    name: String,
-    value: Option<dozer_types::serde_json::Value>,
-    r#type: String,
+    pub(crate) name: String,
+    pub(crate) value: Option<dozer_types::serde_json::Value>,
+    pub(crate) r#type: String,
+}
+
+/// Version of the format written by `serialize_state`.
+const STATE_VERSION: u32 = 1;
+
+/// What the connector has ingested, across restarts. Saved with every checkpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(crate = "dozer_types::serde")]
+pub(crate) struct IngestionProgress {
+    /// Highest last update time of the events ingested from each set, keyed by `namespace.set`.
+    pub(crate) max_lut: BTreeMap<String, u64>,
+    pub(crate) total_events: u64,
+    /// When the last event was ingested, in milliseconds since the epoch.
+    pub(crate) last_event_at: Option<i64>,
+}
+
+impl IngestionProgress {
+    fn record_event(&mut self, namespace: &str, set_name: &str, lut: u64) {
+        let max_lut = self
+            .max_lut
+            .entry(format!("{namespace}.{set_name}"))
+            .or_default();
+        *max_lut = (*max_lut).max(lut);
+        self.total_events += 1;
+        self.last_event_at = Some(Utc::now().timestamp_millis());
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "dozer_types::serde")]
+struct PersistedState {
+    version: u32,
+    progress: IngestionProgress,
+}
+
+pub(crate) fn serialize_progress(
+    progress: &IngestionProgress,
+) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(&PersistedState {
+        version: STATE_VERSION,
+        progress: progress.clone(),
+    })
+}
+
+/// Returns `None` for states written in another version of the format, which are dropped.
+pub(crate) fn deserialize_progress(
+    state: &[u8],
+) -> Result<Option<IngestionProgress>, serde_json::Error> {
+    // Only the version is read first, so newer formats don't fail to parse
+    #[derive(Deserialize)]
+    #[serde(crate = "dozer_types::serde")]
+    struct Version {
+        version: u32,
+    }
+    if serde_json::from_slice::<Version>(state)?.version != STATE_VERSION {
+        return Ok(None);
+    }
+    Ok(Some(
+        serde_json::from_slice::<PersistedState>(state)?.progress,
+    ))
 }
 
 #[derive(Debug)]
 pub struct AerospikeConnector {
     pub config: AerospikeConnection,
+    /// The state saved with the checkpoint the connector is restarted from.
+    state: Option<Vec<u8>>,
+    progress: Arc<Mutex<IngestionProgress>>,
 }
 
 impl AerospikeConnector {
-    pub fn new(config: AerospikeConnection) -> Self {
-        Self { config }
+    pub fn new(config: AerospikeConnection, state: Option<Vec<u8>>) -> Self {
+        Self {
+            config,
+            state,
+            progress: Default::default(),
+        }
+    }
+
+    /// Picks up the progress saved with the checkpoint, if any.
+    fn resume_progress(&self) {
+        let Some(state) = self.state.as_deref().filter(|state| !state.is_empty()) else {
+            return;
+        };
+        match deserialize_progress(state) {
+            Ok(Some(progress)) => {
+                info!(
+                    "Resuming aerospike ingestion after {} events, last one at {:?}. Highest last update time by set: {:?}",
+                    progress.total_events, progress.last_event_at, progress.max_lut
+                );
+                *self.progress.lock().unwrap_or_else(|e| e.into_inner()) = progress;
+            }
+            Ok(None) => warn!("Ignoring aerospike connector state of an unknown version"),
+            Err(e) => warn!("Ignoring invalid aerospike connector state: {e}"),
+        }
+    }
+
+    fn table_namespace<'a>(&'a self, table: &'a TableInfo) -> &'a str {
+        table.schema.as_deref().unwrap_or(&self.config.namespace)
+    }
+
+    /// `namespace.set`, the key under which the table's schema can be configured.
+    fn qualified_table_name(&self, table: &TableInfo) -> String {
+        format!("{}.{}", self.table_namespace(table), table.name)
     }
 
     fn start_server(&self, server_state: ServerState) -> Result<Server, AerospikeConnectorError> {
@@ -143,20 +272,181 @@ impl AerospikeConnector {
 
         info!("Starting aerospike replication server on {}", address);
 
-        Ok(HttpServer::new(move || {
-            App::new()
+        describe_gauge!(
+            INGESTION_LAG_GAUGE_NAME,
+            "Seconds between an event's last update time and its ingestion"
+        );
+        describe_counter!(
+            SKIPPED_EVENTS_COUNTER_NAME,
+            "Number of events skipped because they lacked a required bin"
+        );
+        describe_counter!(
+            SKIPPED_OLD_EVENTS_COUNTER_NAME,
+            "Number of events skipped because they were older than `min_lut` or `min_generation`"
+        );
+
+        let request_format = self.config.replication.request_format;
+        let server = HttpServer::new(move || {
+            let app = App::new()
                 .app_data(web::Data::new(server_state.clone()))
-                .service(healthcheck)
-                .service(event_request_handler)
+                .service(healthcheck);
+            match request_format {
+                RequestFormat::Json => app.service(event_request_handler),
+                RequestFormat::MsgPack => app.service(msgpack_event_request_handler),
+            }
+        });
+        let server = match &self.config.replication.tls {
+            Some(tls) => server.bind_rustls_021(address, load_tls_config(tls)?)?,
+            None => server.bind(address)?,
+        };
+
+        Ok(server.run())
+    }
+}
+
+fn load_tls_config(
+    tls: &ReplicationTlsConfig,
+) -> Result<rustls::ServerConfig, AerospikeConnectorError> {
+    let open = |path: &str| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| AerospikeConnectorError::CannotReadTlsFile(path.to_string(), e))
+    };
+
+    let certs = rustls_pemfile::certs(&mut open(&tls.cert_path)?)
+        .map_err(|e| AerospikeConnectorError::CannotReadTlsFile(tls.cert_path.clone(), e))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let key = rustls_pemfile::read_all(&mut open(&tls.key_path)?)
+        .map_err(|e| AerospikeConnectorError::CannotReadTlsFile(tls.key_path.clone(), e))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+            _ => None,
         })
-        .bind(address)?
-        .run())
+        .ok_or_else(|| AerospikeConnectorError::NoPrivateKey(tls.key_path.clone()))?;
+
+    Ok(rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?)
+}
+
+const INGESTION_LAG_GAUGE_NAME: &str = "aerospike_ingestion_lag";
+const SKIPPED_EVENTS_COUNTER_NAME: &str = "aerospike_skipped_events";
+const SKIPPED_OLD_EVENTS_COUNTER_NAME: &str = "aerospike_skipped_old_events";
+const SET_LABEL: &str = "set";
+
+/// Reports how far behind the connector is, as now minus the event's last update time.
+fn report_ingestion_lag(set_name: String, lut: u64) {
+    let lag_millis = Utc::now()
+        .timestamp_millis()
+        .saturating_sub(lut as i64)
+        .max(0);
+    gauge!(
+        INGESTION_LAG_GAUGE_NAME,
+        lag_millis as f64 / 1000.0,
+        SET_LABEL => set_name
+    );
+}
+
+impl AerospikeConnectorError {
+    /// Whether the error was caused by the event itself, so sending it again can't succeed.
+    fn is_client_error(&self) -> bool {
+        match self {
+            Self::NoSetNameFindInKey(_)
+            | Self::SetNameIsNone(_)
+            | Self::NoPkInKey(_)
+            | Self::InvalidKeyValue(_)
+            | Self::PkIsNone(_)
+            | Self::UnsupportedTypeForFieldType { .. }
+            | Self::UnsupportedType(_)
+            | Self::InvalidTimestamp(_)
+            | Self::InvalidDate(_)
+            | Self::BytesDecodingError(_)
+            | Self::FloatParsingError(_)
+            | Self::IntParsingError(_)
+            | Self::IntCastError(_)
+            | Self::ParsingDaysError
+            | Self::ParsingTimestampFailed
+            | Self::ParsingIntFailed
+            | Self::ParsingUIntFailed
+            | Self::ParsingFloatFailed
+            | Self::SchemaMismatch { .. }
+            | Self::TimestampParsingError(_) => true,
+            Self::CannotStartServer(_)
+            | Self::CannotReadTlsFile(_, _)
+            | Self::NoPrivateKey(_)
+            | Self::InvalidTlsConfig(_)
+            | Self::SchemaNotFound(_)
+            | Self::CaseInsensitiveCollision { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "snake_case")]
+pub(crate) enum ErrorKind {
+    /// The event can't be ingested, and sending it again won't help.
+    InvalidEvent,
+    /// The event may be ingested if sent again.
+    Internal,
+}
+
+/// The body of the answers to failed events, if `verbose_errors` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub(crate) struct ErrorBody {
+    pub(crate) kind: ErrorKind,
+    pub(crate) message: String,
+    /// The key of the event that failed.
+    pub(crate) key: Vec<Option<String>>,
+}
+
+fn error_response(
+    mut response: HttpResponseBuilder,
+    kind: ErrorKind,
+    message: String,
+    key: Vec<Option<String>>,
+    verbose_errors: bool,
+) -> HttpResponse {
+    if verbose_errors {
+        response.json(ErrorBody { kind, message, key })
+    } else {
+        response.finish()
     }
 }
 
-fn map_error(error: AerospikeConnectorError) -> HttpResponse {
-    error!("Aerospike ingestion error: {:?}", error);
-    HttpResponse::InternalServerError().finish()
+/// Invalid events are answered with 400, so XDR doesn't keep retrying them. Other errors are
+/// answered with 500.
+pub(crate) fn map_error(
+    error: AerospikeConnectorError,
+    key: Vec<Option<String>>,
+    verbose_errors: bool,
+) -> HttpResponse {
+    if error.is_client_error() {
+        warn!("Aerospike ingestion rejected an invalid event: {:?}", error);
+        error_response(
+            HttpResponse::BadRequest(),
+            ErrorKind::InvalidEvent,
+            error.to_string(),
+            key,
+            verbose_errors,
+        )
+    } else {
+        error!("Aerospike ingestion error: {:?}", error);
+        error_response(
+            HttpResponse::InternalServerError(),
+            ErrorKind::Internal,
+            error.to_string(),
+            key,
+            verbose_errors,
+        )
+    }
 }
 
 #[get("/")]
@@ -169,29 +459,61 @@ async fn event_request_handler(
     json: web::Json<AerospikeEvent>,
     data: web::Data<ServerState>,
 ) -> HttpResponse {
-    let event = json.into_inner();
-    let state = data.into_inner();
+    handle_event(json.into_inner(), data.into_inner()).await
+}
+
+#[post("/")]
+async fn msgpack_event_request_handler(
+    msgpack: MsgPack<MessagePackAerospikeEvent>,
+    data: web::Data<ServerState>,
+) -> HttpResponse {
+    handle_event(msgpack.0.into(), data.into_inner()).await
+}
 
+async fn handle_event(event: AerospikeEvent, state: Arc<ServerState>) -> HttpResponse {
     // TODO: Handle delete
     if event.msg != "write" {
         return HttpResponse::Ok().finish();
     }
 
+    let key = event.key.clone();
+    let namespace = event.key.first().cloned().flatten();
+    let set_name = event.key.get(1).cloned().flatten();
+    let lut = event.lut;
     let operation_events = map_events(event, state.tables_index_map.clone()).await;
 
     match operation_events {
         Ok(None) => HttpResponse::Ok().finish(),
         Ok(Some(events)) => {
             for event in events {
-                if let Err(e) = state.ingestor.handle_message(event).await {
+                if let Err(e) = state
+                    .ingestor
+                    .handle_message_with_policy(event, state.overflow_policy)
+                    .await
+                {
                     error!("Aerospike ingestion message send error: {:?}", e);
-                    return HttpResponse::InternalServerError().finish();
+                    return error_response(
+                        HttpResponse::InternalServerError(),
+                        ErrorKind::Internal,
+                        e.to_string(),
+                        key,
+                        state.verbose_errors,
+                    );
                 }
             }
 
+            if let (Some(namespace), Some(set_name)) = (namespace, set_name) {
+                state
+                    .progress
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .record_event(&namespace, &set_name, lut);
+                report_ingestion_lag(set_name, lut);
+            }
+
             HttpResponse::Ok().finish()
         }
-        Err(e) => map_error(e),
+        Err(e) => map_error(e, key, state.verbose_errors),
     }
 }
 
@@ -199,12 +521,31 @@ async fn event_request_handler(
 struct TableIndexMap {
     table_index: usize,
     columns_map: HashMap<String, (usize, FieldType)>,
+    /// Set in strict schema mode. Flipped once the bins of an event have matched the columns.
+    schema_validated: Option<Arc<AtomicBool>>,
+    /// `columns_map` is keyed by lowercased names and bin names are lowercased before lookup.
+    case_insensitive_bins: bool,
+    base64_alphabet: Base64Alphabet,
+    /// Keys of the bins an event must have to be ingested.
+    required_bins: Vec<String>,
+    /// Set in dynamic schema mode. The keys of the columns added for unknown bins, in the order
+    /// they were appended after the columns of `columns_map`.
+    added_columns: Option<Arc<Mutex<Vec<String>>>>,
+    /// Set if columns are removed when their bins go missing.
+    missing_bins: Option<Arc<Mutex<MissingBins>>>,
+    /// Events older than these are skipped.
+    min_lut: Option<u64>,
+    min_generation: Option<u32>,
 }
 
 #[derive(Clone)]
 struct ServerState {
-    tables_index_map: HashMap<String, TableIndexMap>,
+    /// Keyed by namespace and set name.
+    tables_index_map: HashMap<(String, String), TableIndexMap>,
     ingestor: Ingestor,
+    progress: Arc<Mutex<IngestionProgress>>,
+    verbose_errors: bool,
+    overflow_policy: OverflowPolicy,
 }
 
 #[async_trait]
@@ -234,9 +575,12 @@ impl Connector for AerospikeConnector {
             .config
             .sets
             .iter()
-            .map(|set| TableIdentifier {
-                schema: Some(self.config.namespace.clone()),
-                name: set.to_string(),
+            .map(|set| {
+                let (namespace, set) = split_set_name(&self.config.namespace, set);
+                TableIdentifier {
+                    schema: Some(namespace.to_string()),
+                    name: set.to_string(),
+                }
             })
             .collect())
     }
@@ -264,7 +608,7 @@ impl Connector for AerospikeConnector {
             None => table_infos
                 .iter()
                 .map(|table_info| {
-                    let table_name = table_info.name.clone();
+                    let table_name = self.qualified_table_name(table_info);
                     let primary_index = table_info
                         .column_names
                         .iter()
@@ -301,9 +645,10 @@ impl Connector for AerospikeConnector {
         Ok(table_infos
             .iter()
             .map(|table_info| {
-                let table_name = table_info.name.clone();
+                let table_name = self.qualified_table_name(table_info);
                 let schema = schemas
                     .get(&table_name)
+                    .or_else(|| schemas.get(&table_info.name))
                     .cloned()
                     .ok_or(AerospikeConnectorError::SchemaNotFound(table_name.clone()))?;
 
@@ -312,16 +657,9 @@ impl Connector for AerospikeConnector {
                 } else {
                     let primary_key_field_names: Vec<String> = schema
                         .schema
-                        .primary_index
-                        .iter()
-                        .map(|idx| {
-                            schema
-                                .schema
-                                .fields
-                                .get(*idx)
-                                .map(|field| field.name.clone())
-                                .expect("Field should be present")
-                        })
+                        .primary_key_field_names()
+                        .into_iter()
+                        .map(str::to_string)
                         .collect();
 
                     let filtered_fields: Vec<FieldDefinition> = schema
@@ -358,7 +696,9 @@ impl Connector for AerospikeConnector {
     }
 
     async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
-        Ok(vec![])
+        Ok(serialize_progress(
+            &self.progress.lock().unwrap_or_else(|e| e.into_inner()),
+        )?)
     }
 
     async fn start(
@@ -367,44 +707,74 @@ impl Connector for AerospikeConnector {
         tables: Vec<TableInfo>,
         _last_checkpoint: Option<OpIdentifier>,
     ) -> Result<(), BoxedError> {
+        self.resume_progress();
         let mapped_schema = self.get_schemas(&tables).await?;
-        ingestor
-            .handle_message(IngestionMessage::TransactionInfo(
-                TransactionInfo::SnapshottingStarted,
-            ))
-            .await?;
-        ingestor
-            .handle_message(IngestionMessage::TransactionInfo(
-                TransactionInfo::SnapshottingDone { id: None },
-            ))
-            .await?;
-
-        let tables_index_map: HashMap<String, TableIndexMap> = mapped_schema
+        if !self.config.skip_snapshotting_markers {
+            ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingStarted,
+                ))
+                .await?;
+            ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingDone { id: None },
+                ))
+                .await?;
+        }
+
+        let tables_index_map: HashMap<(String, String), TableIndexMap> = mapped_schema
             .into_iter()
             .enumerate()
             .map(|(table_index, schema)| {
-                let columns_map: HashMap<String, (usize, FieldType)> = schema
-                    .expect("Schema should be present")
-                    .schema
-                    .fields
+                let fields = schema.expect("Schema should be present").schema.fields;
+                let columns_map = build_columns_map(
+                    &tables[table_index].name,
+                    &fields,
+                    self.config.case_insensitive_bins,
+                )?;
+                let namespace = self.table_namespace(&tables[table_index]);
+                let required_bins = self
+                    .config
+                    .required_bins
                     .iter()
-                    .enumerate()
-                    .map(|(i, field)| (field.name.clone(), (i, field.typ)))
+                    .filter(|(set, _)| {
+                        split_set_name(&self.config.namespace, set)
+                            == (namespace, tables[table_index].name.as_str())
+                    })
+                    .flat_map(|(_, bins)| bins)
+                    .map(|bin| column_key(bin, self.config.case_insensitive_bins).into_owned())
                     .collect();
+                let missing_bins = self.config.remove_missing_bins_after.map(|threshold| {
+                    let columns = fields.into_iter().map(|field| field.name).collect();
+                    Arc::new(Mutex::new(MissingBins::new(columns, threshold)))
+                });
 
-                (
-                    tables[table_index].name.clone(),
+                Ok((
+                    (namespace.to_string(), tables[table_index].name.clone()),
                     TableIndexMap {
                         table_index,
                         columns_map,
+                        schema_validated: (self.config.strict_schema
+                            && !self.config.dynamic_schema)
+                            .then(|| Arc::new(AtomicBool::new(false))),
+                        case_insensitive_bins: self.config.case_insensitive_bins,
+                        base64_alphabet: self.config.base64_alphabet,
+                        required_bins,
+                        added_columns: self.config.dynamic_schema.then(Default::default),
+                        missing_bins,
+                        min_lut: self.config.min_lut,
+                        min_generation: self.config.min_generation,
                     },
-                )
+                ))
             })
-            .collect();
+            .collect::<Result<_, AerospikeConnectorError>>()?;
 
         let server_state = ServerState {
             tables_index_map: tables_index_map.clone(),
             ingestor: ingestor.clone(),
+            progress: self.progress.clone(),
+            verbose_errors: self.config.replication.verbose_errors,
+            overflow_policy: self.config.replication.overflow_policy,
         };
 
         let _server = self.start_server(server_state)?.await;
@@ -415,28 +785,87 @@ impl Connector for AerospikeConnector {
 
 async fn map_events(
     event: AerospikeEvent,
-    tables_map: HashMap<String, TableIndexMap>,
+    tables_map: HashMap<(String, String), TableIndexMap>,
 ) -> Result<Option<Vec<IngestionMessage>>, AerospikeConnectorError> {
     let key = event.key;
-    let [_, Some(ref set_name), _, ref pk_in_key] = key.clone()[..] else {
+    let [Some(ref namespace), Some(ref set_name), _, ref pk_in_key] = key.clone()[..] else {
         return Err(AerospikeConnectorError::InvalidKeyValue(key.clone()));
     };
 
     if let Some(TableIndexMap {
         columns_map,
         table_index,
-    }) = tables_map.get(set_name.as_str())
+        schema_validated,
+        case_insensitive_bins,
+        base64_alphabet,
+        required_bins,
+        added_columns,
+        missing_bins,
+        min_lut,
+        min_generation,
+    }) = tables_map.get(&(namespace.clone(), set_name.clone()))
     {
-        let mut fields = vec![Field::Null; columns_map.len()];
-        if let Some((pk, _)) = columns_map.get("PK") {
+        if is_older_than(&event, *min_lut, *min_generation) {
+            increment_counter!(SKIPPED_OLD_EVENTS_COUNTER_NAME, SET_LABEL => set_name.clone());
+            return Ok(None);
+        }
+
+        if let Some(schema_validated) = schema_validated {
+            if !schema_validated.load(Ordering::Relaxed) {
+                validate_bins(set_name, columns_map, &event.bins, *case_insensitive_bins)?;
+                schema_validated.store(true, Ordering::Relaxed);
+            }
+        }
+
+        if let Some(bin) = missing_required_bin(required_bins, &event.bins, *case_insensitive_bins)
+        {
+            debug!("Skipping event of set {set_name}, as it has no value for required bin {bin}");
+            increment_counter!(SKIPPED_EVENTS_COUNTER_NAME, SET_LABEL => set_name.clone());
+            return Ok(Some(vec![]));
+        }
+
+        let mut messages = vec![];
+        let added_columns = match added_columns {
+            Some(added_columns) => add_columns(
+                *table_index,
+                columns_map,
+                &mut added_columns.lock().unwrap_or_else(|e| e.into_inner()),
+                &event.bins,
+                *case_insensitive_bins,
+                &mut messages,
+            ),
+            None => vec![],
+        };
+        let removed_columns = match missing_bins {
+            Some(missing_bins) => remove_missing_columns(
+                *table_index,
+                &mut missing_bins.lock().unwrap_or_else(|e| e.into_inner()),
+                &event.bins,
+                *case_insensitive_bins,
+                &mut messages,
+            ),
+            None => vec![],
+        };
+
+        let mut fields = vec![Field::Null; columns_map.len() + added_columns.len()];
+        let column = |name: &str| {
+            let key = column_key(name, *case_insensitive_bins);
+            columns_map.get::<str>(&key).copied().or_else(|| {
+                added_columns
+                    .iter()
+                    .position(|column| *column == key)
+                    .map(|i| (columns_map.len() + i, FieldType::String))
+            })
+        };
+        if let Some((pk, typ)) = column("PK") {
             if let Some(pk_in_key) = pk_in_key {
-                fields[*pk] = Field::String(pk_in_key.clone());
+                fields[pk] = map_key_to_field(pk_in_key, typ)?;
             } else {
                 return Err(AerospikeConnectorError::PkIsNone(key.clone()));
             }
         }
 
-        if let Some((index, _)) = columns_map.get("inserted_at") {
+        if let Some((index, _)) = column("inserted_at") {
             // Create a NaiveDateTime from the timestamp
             let naive = NaiveDateTime::from_timestamp_millis(event.lut as i64)
                 .ok_or(AerospikeConnectorError::InvalidTimestamp(event.lut as i64))?;
@@ -445,19 +874,25 @@ async fn map_events(
             let datetime: DateTime<FixedOffset> =
                 DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc).fixed_offset();
 
-            fields[*index] = Field::Timestamp(datetime);
+            fields[index] = Field::Timestamp(datetime);
         }
 
         for bin in event.bins {
-            if let Some((i, typ)) = columns_map.get(bin.name.as_str()) {
-                fields[*i] = match bin.value {
-                    Some(value) => map_value_to_field(bin.r#type.as_str(), value, *typ)?,
+            if let Some((i, typ)) = column(bin.name.as_str()) {
+                fields[i] = match bin.value {
+                    Some(value) => {
+                        map_value_to_field(bin.r#type.as_str(), value, typ, *base64_alphabet)?
+                    }
                     None => Field::Null,
                 };
             }
         }
 
-        Ok(Some(vec![
+        for index in removed_columns.iter().rev() {
+            fields.remove(*index);
+        }
+
+        messages.extend([
             IngestionMessage::OperationEvent {
                 table_index: *table_index,
                 op: Insert {
@@ -466,17 +901,284 @@ async fn map_events(
                 id: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
-        ]))
+        ]);
+        Ok(Some(messages))
     } else {
         Ok(None)
     }
 }
 
+/// Splits a `namespace.set` entry of the `sets` config. Bare set names belong to `default_namespace`.
+pub(crate) fn split_set_name<'a>(default_namespace: &'a str, set: &'a str) -> (&'a str, &'a str) {
+    set.split_once('.').unwrap_or((default_namespace, set))
+}
+
+/// Columns that are filled from the event metadata rather than from bins.
+const METADATA_COLUMNS: [&str; 2] = ["PK", "inserted_at"];
+
+/// The key a column or bin name is looked up by in `columns_map`.
+fn column_key(name: &str, case_insensitive: bool) -> Cow<'_, str> {
+    if case_insensitive {
+        Cow::Owned(name.to_lowercase())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Maps column names to their index and type. Fails if columns only differ by case in case-insensitive mode.
+pub(crate) fn build_columns_map(
+    set_name: &str,
+    fields: &[FieldDefinition],
+    case_insensitive: bool,
+) -> Result<HashMap<String, (usize, FieldType)>, AerospikeConnectorError> {
+    let mut columns_map: HashMap<String, (usize, FieldType)> = HashMap::new();
+    for (i, field) in fields.iter().enumerate() {
+        let key = column_key(&field.name, case_insensitive).into_owned();
+        if let Some((existing, _)) = columns_map.insert(key, (i, field.typ)) {
+            return Err(AerospikeConnectorError::CaseInsensitiveCollision {
+                set_name: set_name.to_string(),
+                columns: vec![fields[existing].name.clone(), field.name.clone()],
+            });
+        }
+    }
+    Ok(columns_map)
+}
+
+/// Appends a nullable string column for each bin that isn't a column yet, and announces it with a
+/// `ColumnAdded` message. Returns the keys of all the columns added so far.
+pub(crate) fn add_columns(
+    table_index: usize,
+    columns_map: &HashMap<String, (usize, FieldType)>,
+    added_columns: &mut Vec<String>,
+    bins: &[Bin],
+    case_insensitive: bool,
+    messages: &mut Vec<IngestionMessage>,
+) -> Vec<String> {
+    for bin in bins {
+        let key = column_key(&bin.name, case_insensitive);
+        if columns_map.contains_key::<str>(&key) || added_columns.iter().any(|c| *c == key) {
+            continue;
+        }
+        added_columns.push(key.into_owned());
+        messages.push(IngestionMessage::ColumnAdded {
+            table_index,
+            column: FieldDefinition {
+                name: bin.name.clone(),
+                typ: FieldType::String,
+                nullable: true,
+                source: Default::default(),
+            },
+        });
+    }
+    added_columns.clone()
+}
+
+/// Counts, for each column, the consecutive events whose bins didn't include it.
+#[derive(Debug)]
+pub(crate) struct MissingBins {
+    /// The names of the table's columns, in schema order.
+    columns: Vec<String>,
+    /// Number of consecutive events a column must be missing from to be removed.
+    threshold: u32,
+    counts: HashMap<usize, u32>,
+    /// Indexes of the removed columns, in ascending order.
+    removed: Vec<usize>,
+}
+
+impl MissingBins {
+    pub(crate) fn new(columns: Vec<String>, threshold: u32) -> Self {
+        Self {
+            columns,
+            threshold,
+            counts: HashMap::new(),
+            removed: vec![],
+        }
+    }
+}
+
+/// Counts the columns whose bin is missing from `bins`, and announces the ones that reached the
+/// threshold with a `RemoveField` operation. Returns the indexes of all the columns removed so far.
+pub(crate) fn remove_missing_columns(
+    table_index: usize,
+    missing_bins: &mut MissingBins,
+    bins: &[Bin],
+    case_insensitive: bool,
+    messages: &mut Vec<IngestionMessage>,
+) -> Vec<usize> {
+    let bin_names: HashSet<Cow<'_, str>> = bins
+        .iter()
+        .map(|bin| column_key(&bin.name, case_insensitive))
+        .collect();
+    for (index, name) in missing_bins.columns.iter().enumerate() {
+        let key = column_key(name, case_insensitive);
+        if missing_bins.removed.contains(&index)
+            || METADATA_COLUMNS
+                .iter()
+                .any(|column| column_key(column, case_insensitive) == key)
+        {
+            continue;
+        }
+        if bin_names.contains(&key) {
+            missing_bins.counts.remove(&index);
+            continue;
+        }
+        let count = missing_bins.counts.entry(index).or_default();
+        *count += 1;
+        if *count >= missing_bins.threshold {
+            missing_bins.counts.remove(&index);
+            let position = missing_bins
+                .removed
+                .partition_point(|removed| *removed < index);
+            missing_bins.removed.insert(position, index);
+            messages.push(IngestionMessage::OperationEvent {
+                table_index,
+                op: RemoveField {
+                    table_index,
+                    field_name: name.clone(),
+                },
+                id: None,
+            });
+        }
+    }
+    missing_bins.removed.clone()
+}
+
+/// Returns the first of `required_bins` that the event doesn't have a non-null value for.
+/// Whether the event was written before `min_lut` or has a generation lower than `min_generation`.
+pub(crate) fn is_older_than(
+    event: &AerospikeEvent,
+    min_lut: Option<u64>,
+    min_generation: Option<u32>,
+) -> bool {
+    min_lut.is_some_and(|min_lut| event.lut < min_lut)
+        || matches!((event.gen, min_generation), (Some(gen), Some(min)) if gen < min)
+}
+
+pub(crate) fn missing_required_bin<'a>(
+    required_bins: &'a [String],
+    bins: &[Bin],
+    case_insensitive: bool,
+) -> Option<&'a str> {
+    required_bins
+        .iter()
+        .find(|required| {
+            !bins.iter().any(|bin| {
+                column_key(&bin.name, case_insensitive) == required.as_str()
+                    && !matches!(bin.value, None | Some(Value::Null))
+            })
+        })
+        .map(String::as_str)
+}
+
+/// Compares the received bin names against the configured columns.
+pub(crate) fn validate_bins(
+    set_name: &str,
+    columns_map: &HashMap<String, (usize, FieldType)>,
+    bins: &[Bin],
+    case_insensitive: bool,
+) -> Result<(), AerospikeConnectorError> {
+    let bin_names: HashSet<Cow<'_, str>> = bins
+        .iter()
+        .map(|bin| column_key(&bin.name, case_insensitive))
+        .collect();
+    let mut unexpected: Vec<String> = bin_names
+        .iter()
+        .filter(|name| !columns_map.contains_key::<str>(name))
+        .map(|name| name.to_string())
+        .collect();
+    let mut missing: Vec<String> = columns_map
+        .keys()
+        .filter(|name| {
+            !METADATA_COLUMNS
+                .iter()
+                .any(|column| column_key(column, case_insensitive) == name.as_str())
+                && !bin_names.contains(name.as_str())
+        })
+        .cloned()
+        .collect();
+    if unexpected.is_empty() && missing.is_empty() {
+        return Ok(());
+    }
+
+    unexpected.sort();
+    missing.sort();
+    Err(AerospikeConnectorError::SchemaMismatch {
+        set_name: set_name.to_string(),
+        unexpected,
+        missing,
+    })
+}
+
+/// Bin types that `map_value_to_field` knows how to interpret.
+const KNOWN_BIN_TYPES: [&str; 8] = [
+    "str", "bool", "int", "float", "blob", "list", "map", "geojson",
+];
+
+/// Logs a warning the first time an opaque bin type is stored as binary.
+fn warn_opaque_bin_type(bin_type: &str) {
+    static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let mut warned = WARNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if warned.insert(bin_type.to_string()) {
+        warn!(
+            "Aerospike bin type {:?} is not supported, storing its value as binary",
+            bin_type
+        );
+    }
+}
+
+/// Decodes `s` with the configured alphabet. `Auto` tries the standard alphabet first.
+pub(crate) fn decode_base64(
+    s: &str,
+    alphabet: Base64Alphabet,
+) -> Result<Vec<u8>, base64::DecodeError> {
+    match alphabet {
+        Base64Alphabet::Standard => BASE64_STANDARD.decode(s),
+        Base64Alphabet::UrlSafe => BASE64_URL_SAFE.decode(s),
+        // Report the error of the standard alphabet if neither decodes.
+        Base64Alphabet::Auto => BASE64_STANDARD
+            .decode(s)
+            .or_else(|e| BASE64_URL_SAFE.decode(s).map_err(|_| e)),
+    }
+}
+
+/// Converts the user key of a record to the type of the `PK` column.
+///
+/// Keys arrive as strings, whatever their type in Aerospike. A double key is read as `Float` if the
+/// `PK` column is declared so, even though the Aerospike sink refuses `Float` primary keys, so that
+/// sets keyed by doubles can still be ingested.
+pub(crate) fn map_key_to_field(
+    key: &str,
+    typ: FieldType,
+) -> Result<Field, AerospikeConnectorError> {
+    match typ {
+        FieldType::UInt => Ok(Field::UInt(key.parse()?)),
+        FieldType::U128 => Ok(Field::U128(key.parse()?)),
+        FieldType::Int => Ok(Field::Int(key.parse()?)),
+        FieldType::I128 => Ok(Field::I128(key.parse()?)),
+        FieldType::Float => Ok(Field::Float(OrderedFloat(key.parse()?))),
+        FieldType::String => Ok(Field::String(key.to_string())),
+        FieldType::Text => Ok(Field::Text(key.to_string())),
+        typ => Err(AerospikeConnectorError::UnsupportedType(typ)),
+    }
+}
+
 pub(crate) fn map_value_to_field(
     bin_type: &str,
     value: Value,
     typ: FieldType,
+    base64_alphabet: Base64Alphabet,
 ) -> Result<Field, AerospikeConnectorError> {
+    // Opaque types (e.g. HLL) are sent as base64 blobs. Pass them through untouched.
+    if typ == FieldType::Binary && !KNOWN_BIN_TYPES.contains(&bin_type) {
+        if let Value::String(s) = &value {
+            warn_opaque_bin_type(bin_type);
+            return Ok(Field::Binary(decode_base64(s, base64_alphabet)?));
+        }
+    }
+
     match value {
         Value::Null => Ok(Field::Null),
         Value::Bool(b) => match typ {
@@ -570,7 +1272,7 @@ pub(crate) fn map_value_to_field(
                     Err(AerospikeConnectorError::UnsupportedType(typ))
                 }
                 FieldType::Binary => {
-                    let bytes = BASE64_STANDARD.decode(s.as_bytes())?;
+                    let bytes = decode_base64(&s, base64_alphabet)?;
                     Ok(Field::Binary(bytes))
                 }
                 typ => Err(AerospikeConnectorError::UnsupportedType(typ)),
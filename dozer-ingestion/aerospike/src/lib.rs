@@ -1,4 +1,6 @@
 pub mod connector;
+pub mod msgpack;
+pub mod xdr_client;
 
 #[cfg(test)]
 mod tests;
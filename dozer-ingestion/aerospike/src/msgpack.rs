@@ -0,0 +1,84 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::dev::Payload;
+use actix_web::{error, web, FromRequest, HttpRequest};
+use base64::prelude::*;
+use dozer_ingestion_connector::dozer_types::serde::de::DeserializeOwned;
+use dozer_ingestion_connector::dozer_types::serde::Deserialize;
+use dozer_ingestion_connector::dozer_types::serde_json::Value;
+
+use crate::connector::{AerospikeEvent, Bin};
+
+/// Extracts a MessagePack encoded request body, like `web::Json` does for JSON.
+#[derive(Debug)]
+pub struct MsgPack<T>(pub T);
+
+impl<T: DeserializeOwned + 'static> FromRequest for MsgPack<T> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            rmp_serde::from_slice(&body)
+                .map(MsgPack)
+                .map_err(error::ErrorBadRequest)
+        })
+    }
+}
+
+/// An `AerospikeEvent` as encoded by XDR in MessagePack.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+pub struct MessagePackAerospikeEvent {
+    msg: String,
+    key: Vec<Option<String>>,
+    #[serde(default)]
+    gen: Option<u32>,
+    lut: u64,
+    bins: Vec<MessagePackBin>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+struct MessagePackBin {
+    name: String,
+    value: Option<MessagePackValue>,
+    r#type: String,
+}
+
+/// MessagePack can encode blobs as binary, which has no JSON counterpart.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde", untagged)]
+enum MessagePackValue {
+    Value(Value),
+    Blob(#[serde(with = "dozer_ingestion_connector::dozer_types::serde_bytes")] Vec<u8>),
+}
+
+impl From<MessagePackAerospikeEvent> for AerospikeEvent {
+    fn from(event: MessagePackAerospikeEvent) -> Self {
+        AerospikeEvent {
+            msg: event.msg,
+            key: event.key,
+            gen: event.gen,
+            lut: event.lut,
+            bins: event
+                .bins
+                .into_iter()
+                .map(|bin| Bin {
+                    name: bin.name,
+                    // Blobs are base64 encoded in JSON
+                    value: bin.value.map(|value| match value {
+                        MessagePackValue::Value(value) => value,
+                        MessagePackValue::Blob(bytes) => {
+                            Value::String(BASE64_STANDARD.encode(bytes))
+                        }
+                    }),
+                    r#type: bin.r#type,
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,22 +1,47 @@
-use crate::connector::map_value_to_field;
+use crate::connector::{
+    add_columns, build_columns_map, deserialize_progress, is_older_than, map_error,
+    map_key_to_field, map_value_to_field, missing_required_bin, remove_missing_columns,
+    serialize_progress, split_set_name, validate_bins, AerospikeConnectorError, AerospikeEvent,
+    Bin, ErrorBody, ErrorKind, IngestionProgress, MissingBins,
+};
+use crate::msgpack::MessagePackAerospikeEvent;
+use crate::xdr_client::XdrRetryPolicy;
+use actix_web::http::StatusCode;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
+use dozer_ingestion_connector::dozer_types::models::connection::Base64Alphabet;
+use dozer_ingestion_connector::dozer_types::models::ingestion_types::IngestionMessage;
 use dozer_ingestion_connector::dozer_types::ordered_float::OrderedFloat;
 use dozer_ingestion_connector::dozer_types::rust_decimal::Decimal;
-use dozer_ingestion_connector::dozer_types::serde_json::{json, Value};
-use dozer_ingestion_connector::dozer_types::types::{Field, FieldType};
+use dozer_ingestion_connector::dozer_types::serde::Serialize;
+use dozer_ingestion_connector::dozer_types::serde_bytes;
+use dozer_ingestion_connector::dozer_types::serde_json::{self, json, Value};
+use dozer_ingestion_connector::dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, SourceDefinition,
+};
+use std::collections::HashMap;
+use std::time::Duration;
 
 #[macro_export]
 macro_rules! test_conversion {
     ($a:expr,$b:expr,$c:expr,$d:expr) => {
-        assert_eq!(map_value_to_field($a, $b, $c).unwrap(), $d)
+        assert_eq!(
+            map_value_to_field($a, $b, $c, Base64Alphabet::Standard).unwrap(),
+            $d
+        )
     };
 }
 
 #[test]
 pub fn test_type_conversion() {
     assert_eq!(
-        map_value_to_field("str", Value::Null, FieldType::UInt).unwrap(),
+        map_value_to_field(
+            "str",
+            Value::Null,
+            FieldType::UInt,
+            Base64Alphabet::Standard
+        )
+        .unwrap(),
         Field::Null
     );
 
@@ -134,3 +159,429 @@ pub fn test_type_conversion() {
         Field::Binary(vec![52, 57])
     );
 }
+
+#[test]
+pub fn test_opaque_bin_type_conversion() {
+    test_conversion!(
+        "hll",
+        json!(BASE64_STANDARD.encode(b"hll")),
+        FieldType::Binary,
+        Field::Binary(b"hll".to_vec())
+    );
+    assert!(map_value_to_field(
+        "hll",
+        json!("hll"),
+        FieldType::Int,
+        Base64Alphabet::Standard
+    )
+    .is_err());
+}
+
+#[test]
+pub fn test_validate_bins() {
+    let columns_map = HashMap::from([
+        ("PK".to_string(), (0, FieldType::String)),
+        ("name".to_string(), (1, FieldType::String)),
+        ("age".to_string(), (2, FieldType::Int)),
+    ]);
+    let bins = |names: &[&str]| -> Vec<Bin> {
+        names
+            .iter()
+            .map(|name| {
+                serde_json::from_value(json!({"name": name, "value": null, "type": "str"})).unwrap()
+            })
+            .collect()
+    };
+
+    assert!(validate_bins("users", &columns_map, &bins(&["name", "age"]), false).is_ok());
+
+    let Err(AerospikeConnectorError::SchemaMismatch {
+        unexpected,
+        missing,
+        ..
+    }) = validate_bins("users", &columns_map, &bins(&["name", "email"]), false)
+    else {
+        panic!("expected a schema mismatch");
+    };
+    assert_eq!(unexpected, vec!["email".to_string()]);
+    assert_eq!(missing, vec!["age".to_string()]);
+}
+
+#[test]
+pub fn test_missing_required_bin() {
+    let bins: Vec<Bin> = serde_json::from_value(json!([
+        {"name": "user_id", "value": 1, "type": "int"},
+        {"name": "Email", "value": null, "type": "str"},
+        {"name": "OrderId", "value": 2, "type": "int"},
+    ]))
+    .unwrap();
+    let required = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(missing_required_bin(&[], &bins, false), None);
+    assert_eq!(
+        missing_required_bin(&required(&["user_id"]), &bins, false),
+        None
+    );
+    // Null values don't count
+    assert_eq!(
+        missing_required_bin(&required(&["user_id", "Email"]), &bins, false),
+        Some("Email")
+    );
+    assert_eq!(
+        missing_required_bin(&required(&["user_id", "address"]), &bins, false),
+        Some("address")
+    );
+    // Required bins are stored as column keys, so lowercased in case insensitive mode
+    assert_eq!(
+        missing_required_bin(&required(&["orderid"]), &bins, false),
+        Some("orderid")
+    );
+    assert_eq!(
+        missing_required_bin(&required(&["orderid"]), &bins, true),
+        None
+    );
+}
+
+#[test]
+pub fn test_case_insensitive_bins() {
+    let field = |name: &str| {
+        FieldDefinition::new(
+            name.to_string(),
+            FieldType::String,
+            true,
+            SourceDefinition::Dynamic,
+        )
+    };
+    let fields = vec![field("PK"), field("MyBin")];
+    let bins: Vec<Bin> =
+        serde_json::from_value(json!([{"name": "mybin", "value": null, "type": "str"}])).unwrap();
+
+    let columns_map = build_columns_map("users", &fields, false).unwrap();
+    assert!(validate_bins("users", &columns_map, &bins, false).is_err());
+
+    let columns_map = build_columns_map("users", &fields, true).unwrap();
+    assert_eq!(columns_map.get("mybin"), Some(&(1, FieldType::String)));
+    assert!(validate_bins("users", &columns_map, &bins, true).is_ok());
+
+    assert!(matches!(
+        build_columns_map("users", &[field("MyBin"), field("mybin")], true),
+        Err(AerospikeConnectorError::CaseInsensitiveCollision { .. })
+    ));
+}
+
+#[test]
+pub fn test_add_columns() {
+    let columns_map = HashMap::from([("PK".to_string(), (0, FieldType::String))]);
+    let bins: Vec<Bin> = serde_json::from_value(json!([
+        {"name": "Email", "value": null, "type": "str"},
+        {"name": "PK", "value": null, "type": "str"},
+    ]))
+    .unwrap();
+
+    let mut added_columns = vec![];
+    let mut messages = vec![];
+    let added = add_columns(
+        0,
+        &columns_map,
+        &mut added_columns,
+        &bins,
+        true,
+        &mut messages,
+    );
+    assert_eq!(added, vec!["email".to_string()]);
+    let [IngestionMessage::ColumnAdded {
+        table_index: 0,
+        column,
+    }] = &messages[..]
+    else {
+        panic!("expected one added column, got {messages:?}");
+    };
+    assert_eq!(column.name, "Email");
+    assert!(column.nullable);
+
+    let mut messages = vec![];
+    add_columns(
+        0,
+        &columns_map,
+        &mut added_columns,
+        &bins,
+        true,
+        &mut messages,
+    );
+    assert!(messages.is_empty());
+}
+
+#[test]
+pub fn test_remove_missing_columns() {
+    let mut missing_bins = MissingBins::new(
+        vec!["PK".to_string(), "name".to_string(), "email".to_string()],
+        2,
+    );
+    let bins = |names: &[&str]| -> Vec<Bin> {
+        serde_json::from_value(Value::Array(
+            names
+                .iter()
+                .map(|name| json!({"name": name, "value": null, "type": "str"}))
+                .collect(),
+        ))
+        .unwrap()
+    };
+
+    let mut messages = vec![];
+    let mut remove = |names: &[&str]| {
+        remove_missing_columns(0, &mut missing_bins, &bins(names), false, &mut messages)
+    };
+    assert_eq!(remove(&["name"]), Vec::<usize>::new());
+    // A bin that comes back resets its count
+    assert_eq!(remove(&["email"]), Vec::<usize>::new());
+    assert_eq!(remove(&["email"]), vec![1]);
+    assert_eq!(remove(&["email", "name"]), vec![1]);
+
+    let [IngestionMessage::OperationEvent {
+        table_index: 0,
+        op: Operation::RemoveField {
+            table_index: 0,
+            field_name,
+        },
+        ..
+    }] = &messages[..]
+    else {
+        panic!("expected one removed column, got {messages:?}");
+    };
+    assert_eq!(field_name, "name");
+}
+
+#[test]
+pub fn test_map_key_to_field() {
+    assert_eq!(
+        map_key_to_field("1.5", FieldType::Float).unwrap(),
+        Field::Float(OrderedFloat(1.5))
+    );
+    assert_eq!(
+        map_key_to_field("-3", FieldType::Int).unwrap(),
+        Field::Int(-3)
+    );
+    assert_eq!(
+        map_key_to_field("3", FieldType::UInt).unwrap(),
+        Field::UInt(3)
+    );
+    assert_eq!(
+        map_key_to_field("1.5", FieldType::String).unwrap(),
+        Field::String("1.5".to_string())
+    );
+    assert!(matches!(
+        map_key_to_field("abc", FieldType::Float),
+        Err(AerospikeConnectorError::FloatParsingError(_))
+    ));
+    assert!(matches!(
+        map_key_to_field("abc", FieldType::Json),
+        Err(AerospikeConnectorError::UnsupportedType(FieldType::Json))
+    ));
+}
+
+#[test]
+fn test_is_older_than() {
+    let event = |gen: Option<u32>| -> AerospikeEvent {
+        serde_json::from_value(json!({
+            "msg": "write",
+            "key": ["test", "users", null, "1"],
+            "gen": gen,
+            "lut": 1000,
+            "bins": [],
+        }))
+        .unwrap()
+    };
+    assert!(!is_older_than(&event(Some(3)), None, None));
+    assert!(is_older_than(&event(Some(3)), Some(1001), None));
+    assert!(!is_older_than(&event(Some(3)), Some(1000), None));
+    assert!(is_older_than(&event(Some(3)), None, Some(4)));
+    assert!(!is_older_than(&event(Some(3)), None, Some(3)));
+    // Events without a generation aren't filtered by it
+    assert!(!is_older_than(&event(None), None, Some(4)));
+}
+
+#[test]
+pub fn test_split_set_name() {
+    assert_eq!(split_set_name("test", "users"), ("test", "users"));
+    assert_eq!(split_set_name("test", "other.users"), ("other", "users"));
+}
+
+#[test]
+fn test_xdr_retry_backoff() {
+    let policy = XdrRetryPolicy {
+        max_retries: 10,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(1),
+    };
+    assert_eq!(policy.backoff(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_secs(1));
+    assert_eq!(policy.backoff(40), Duration::from_secs(1));
+}
+
+#[test]
+fn test_msgpack_event() {
+    #[derive(Serialize)]
+    #[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+    struct Event {
+        msg: &'static str,
+        key: Vec<Option<&'static str>>,
+        lut: u64,
+        bins: (IntBin, BlobBin),
+    }
+
+    #[derive(Serialize)]
+    #[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+    struct IntBin {
+        name: &'static str,
+        value: i64,
+        r#type: &'static str,
+    }
+
+    #[derive(Serialize)]
+    #[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+    struct BlobBin {
+        name: &'static str,
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+        r#type: &'static str,
+    }
+
+    let event = Event {
+        msg: "write",
+        key: vec![Some("test"), Some("users"), None, Some("1")],
+        lut: 1_700_000_000_000,
+        bins: (
+            IntBin {
+                name: "id",
+                value: 1,
+                r#type: "int",
+            },
+            BlobBin {
+                name: "data",
+                value: vec![1, 2, 3],
+                r#type: "blob",
+            },
+        ),
+    };
+    let bytes = rmp_serde::to_vec_named(&event).unwrap();
+    let event: AerospikeEvent = rmp_serde::from_slice::<MessagePackAerospikeEvent>(&bytes)
+        .unwrap()
+        .into();
+    assert_eq!(event.msg, "write");
+    assert_eq!(event.key[1].as_deref(), Some("users"));
+    assert_eq!(event.lut, 1_700_000_000_000);
+    assert_eq!(event.bins[0].value, Some(json!(1)));
+    assert_eq!(
+        map_value_to_field(
+            &event.bins[1].r#type,
+            event.bins[1].value.clone().unwrap(),
+            FieldType::Binary,
+            Base64Alphabet::Standard
+        )
+        .unwrap(),
+        Field::Binary(vec![1, 2, 3])
+    );
+}
+
+#[test]
+fn test_base64_alphabet() {
+    // Only valid in the url-safe alphabet
+    let value = || json!("-_8=");
+    assert!(
+        map_value_to_field("blob", value(), FieldType::Binary, Base64Alphabet::Standard).is_err()
+    );
+    for alphabet in [Base64Alphabet::UrlSafe, Base64Alphabet::Auto] {
+        assert_eq!(
+            map_value_to_field("blob", value(), FieldType::Binary, alphabet).unwrap(),
+            Field::Binary(vec![0xfb, 0xff])
+        );
+    }
+    assert_eq!(
+        map_value_to_field(
+            "blob",
+            json!("+/8="),
+            FieldType::Binary,
+            Base64Alphabet::Auto
+        )
+        .unwrap(),
+        Field::Binary(vec![0xfb, 0xff])
+    );
+}
+
+#[test]
+fn test_map_error_status() {
+    assert_eq!(
+        map_error(
+            AerospikeConnectorError::InvalidKeyValue(vec![]),
+            vec![],
+            false
+        )
+        .status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        map_error(AerospikeConnectorError::ParsingIntFailed, vec![], false).status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        map_error(
+            AerospikeConnectorError::SchemaNotFound("set".to_string()),
+            vec![],
+            false
+        )
+        .status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[test]
+fn test_map_error_body() {
+    let key = vec![
+        Some("test".to_string()),
+        Some("users".to_string()),
+        None,
+        Some("1".to_string()),
+    ];
+    let body = |verbose_errors| {
+        let response = map_error(
+            AerospikeConnectorError::ParsingIntFailed,
+            key.clone(),
+            verbose_errors,
+        );
+        actix_web::rt::System::new()
+            .block_on(actix_web::body::to_bytes(response.into_body()))
+            .unwrap()
+    };
+
+    assert!(body(false).is_empty());
+    assert_eq!(
+        serde_json::from_slice::<ErrorBody>(&body(true)).unwrap(),
+        ErrorBody {
+            kind: ErrorKind::InvalidEvent,
+            message: AerospikeConnectorError::ParsingIntFailed.to_string(),
+            key: key.clone(),
+        }
+    );
+}
+
+#[test]
+fn test_progress_state() {
+    let progress = IngestionProgress {
+        max_lut: [("test.users".to_string(), 1_700_000_000_000)].into(),
+        total_events: 42,
+        last_event_at: Some(1_700_000_001_000),
+    };
+    let state = serialize_progress(&progress).unwrap();
+    assert_eq!(deserialize_progress(&state).unwrap(), Some(progress));
+
+    // Other versions are dropped, even if their format is different
+    let state = json!({"version": 2, "offsets": [1, 2]}).to_string();
+    assert_eq!(deserialize_progress(state.as_bytes()).unwrap(), None);
+    assert!(deserialize_progress(b"{}").is_err());
+}
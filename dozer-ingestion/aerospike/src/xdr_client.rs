@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use dozer_ingestion_connector::dozer_types::log::warn;
+use dozer_ingestion_connector::dozer_types::models::connection::ReplicationSettings;
+use dozer_ingestion_connector::dozer_types::thiserror::{self, Error};
+use dozer_ingestion_connector::tokio;
+use reqwest::{Client, StatusCode};
+
+use crate::connector::AerospikeEvent;
+
+#[derive(Debug, Error)]
+pub enum XdrHttpClientError {
+    #[error("Cannot build XDR HTTP client: {0}")]
+    CannotBuildClient(#[source] reqwest::Error),
+
+    #[error("XDR push failed: {0}")]
+    PushFailed(#[from] reqwest::Error),
+
+    #[error("XDR endpoint rejected the event with status {0}")]
+    Rejected(StatusCode),
+
+    #[error("XDR push failed after {attempts} attempts: {last_error}")]
+    RetriesExhausted {
+        attempts: u32,
+        last_error: Box<XdrHttpClientError>,
+    },
+}
+
+impl XdrHttpClientError {
+    /// Whether pushing the same event again may succeed.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::PushFailed(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            Self::Rejected(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::CannotBuildClient(_) | Self::RetriesExhausted { .. } => false,
+        }
+    }
+}
+
+/// How failed pushes are retried: `max_retries` times, doubling the backoff from
+/// `initial_backoff` up to `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XdrRetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for XdrRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl XdrRetryPolicy {
+    /// The time to wait before retry number `retry`, counting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// Pushes events to an XDR HTTP endpoint, for replicating back into Aerospike.
+///
+/// Connections are pooled and reused across pushes. A connection that was closed is
+/// re-established by the next push, and pushes that fail on the network or with a server
+/// error are retried according to the retry policy.
+#[derive(Debug, Clone)]
+pub struct XdrHttpClient {
+    client: Client,
+    url: String,
+    retry_policy: XdrRetryPolicy,
+}
+
+impl XdrHttpClient {
+    pub fn new(
+        url: String,
+        settings: &ReplicationSettings,
+        retry_policy: XdrRetryPolicy,
+    ) -> Result<Self, XdrHttpClientError> {
+        let keep_alive = settings.keep_alive.then_some(Duration::from_secs(60));
+        let client = Client::builder()
+            .tcp_keepalive(keep_alive)
+            .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+            .connect_timeout(Duration::from_millis(settings.connect_timeout_ms))
+            .build()
+            .map_err(XdrHttpClientError::CannotBuildClient)?;
+        Ok(Self {
+            client,
+            url,
+            retry_policy,
+        })
+    }
+
+    pub async fn push(&self, event: &AerospikeEvent) -> Result<(), XdrHttpClientError> {
+        let mut retry = 0;
+        loop {
+            let error = match self.try_push(event).await {
+                Ok(()) => return Ok(()),
+                Err(e) if !e.is_retryable() => return Err(e),
+                Err(e) => e,
+            };
+            if retry == self.retry_policy.max_retries {
+                return Err(XdrHttpClientError::RetriesExhausted {
+                    attempts: retry + 1,
+                    last_error: Box::new(error),
+                });
+            }
+            let backoff = self.retry_policy.backoff(retry);
+            warn!("{error}, retrying in {backoff:?}");
+            tokio::time::sleep(backoff).await;
+            retry += 1;
+        }
+    }
+
+    async fn try_push(&self, event: &AerospikeEvent) -> Result<(), XdrHttpClientError> {
+        let response = self.client.post(&self.url).json(event).send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(XdrHttpClientError::Rejected(status))
+        }
+    }
+}
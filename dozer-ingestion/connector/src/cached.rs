@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::models::connection::Connection;
+use dozer_types::node::OpIdentifier;
+use dozer_types::types::{FieldType, Record};
+
+use crate::{
+    async_trait, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+
+type CachedSchemas = HashMap<(Connection, Vec<TableInfo>), (Instant, Vec<SourceSchema>)>;
+
+/// `get_schemas` results shared by every `CachedConnector` created with a clone of this cache, keyed by
+/// connection and tables.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaCache {
+    /// How long cached schemas are used for. If `None`, they are used until `clear` is called.
+    ttl: Option<Duration>,
+    schemas: Arc<Mutex<CachedSchemas>>,
+}
+
+impl SchemaCache {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            schemas: Default::default(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.schemas.lock().unwrap().clear();
+    }
+
+    fn get(&self, connection: &Connection, table_infos: &[TableInfo]) -> Option<Vec<SourceSchema>> {
+        let schemas = self.schemas.lock().unwrap();
+        let (cached_at, schemas) = schemas.get(&(connection.clone(), table_infos.to_vec()))?;
+        match self.ttl {
+            Some(ttl) if cached_at.elapsed() >= ttl => None,
+            _ => Some(schemas.clone()),
+        }
+    }
+
+    fn insert(
+        &self,
+        connection: &Connection,
+        table_infos: &[TableInfo],
+        schemas: Vec<SourceSchema>,
+    ) {
+        self.schemas.lock().unwrap().insert(
+            (connection.clone(), table_infos.to_vec()),
+            (Instant::now(), schemas),
+        );
+    }
+
+    fn remove(&self, connection: &Connection, table_infos: &[TableInfo]) {
+        self.schemas
+            .lock()
+            .unwrap()
+            .remove(&(connection.clone(), table_infos.to_vec()));
+    }
+}
+
+/// A connector that memoizes the results of `get_schemas` in a `SchemaCache`, for when connectors of the same
+/// connection are asked for the same schemas more than once, like by the app UI and the pipeline builder.
+///
+/// Only calls where every table's schema was mapped successfully are cached.
+#[derive(Debug)]
+pub struct CachedConnector<C: ?Sized = dyn Connector> {
+    connection: Connection,
+    cache: SchemaCache,
+    connector: Box<C>,
+}
+
+impl<C: Connector + ?Sized> CachedConnector<C> {
+    pub fn new(connector: Box<C>, connection: Connection, cache: SchemaCache) -> Self {
+        Self {
+            connection,
+            cache,
+            connector,
+        }
+    }
+
+    pub fn into_inner(self) -> Box<C> {
+        self.connector
+    }
+}
+
+#[async_trait]
+impl<C: Connector + ?Sized> Connector for CachedConnector<C> {
+    /// The wrapped connector is usually a trait object, whose types mapping can't be asked for, so this
+    /// returns none. Ask the wrapped connector's type instead.
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    async fn validate_connection(&mut self) -> Result<(), BoxedError> {
+        self.connector.validate_connection().await
+    }
+
+    async fn list_tables(&mut self) -> Result<Vec<TableIdentifier>, BoxedError> {
+        self.connector.list_tables().await
+    }
+
+    async fn list_tables_paginated(
+        &mut self,
+        page_size: usize,
+        cursor: Option<String>,
+    ) -> Result<(Vec<TableIdentifier>, Option<String>), BoxedError> {
+        self.connector
+            .list_tables_paginated(page_size, cursor)
+            .await
+    }
+
+    async fn validate_tables(&mut self, tables: &[TableIdentifier]) -> Result<(), BoxedError> {
+        self.connector.validate_tables(tables).await
+    }
+
+    async fn list_columns(
+        &mut self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, BoxedError> {
+        self.connector.list_columns(tables).await
+    }
+
+    async fn get_schemas(
+        &mut self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, BoxedError> {
+        if let Some(schemas) = self.cache.get(&self.connection, table_infos) {
+            return Ok(schemas.into_iter().map(Ok).collect());
+        }
+
+        let results = self.connector.get_schemas(table_infos).await?;
+        if results.iter().all(Result::is_ok) {
+            let schemas = results
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .expect("all results are ok");
+            self.cache
+                .insert(&self.connection, table_infos, schemas.clone());
+            Ok(schemas.into_iter().map(Ok).collect())
+        } else {
+            self.cache.remove(&self.connection, table_infos);
+            Ok(results)
+        }
+    }
+
+    async fn test_query(&self, query: &str, limit: usize) -> Result<Vec<Record>, BoxedError> {
+        self.connector.test_query(query, limit).await
+    }
+
+    async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
+        self.connector.serialize_state().await
+    }
+
+    async fn start(
+        &mut self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+        last_checkpoint: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        self.connector
+            .start(ingestor, tables, last_checkpoint)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use dozer_types::errors::internal::BoxedError;
+    use dozer_types::models::connection::{Connection, ConnectionConfig};
+    use dozer_types::models::ingestion_types::KafkaConfig;
+    use dozer_types::node::OpIdentifier;
+    use dozer_types::types::{FieldType, Schema};
+
+    use super::{CachedConnector, SchemaCache};
+    use crate::{
+        async_trait, CdcType, Connector, Ingestor, SourceSchema, SourceSchemaResult,
+        TableIdentifier, TableInfo,
+    };
+
+    #[derive(Debug, Default)]
+    struct CountingConnector {
+        get_schemas_calls: usize,
+    }
+
+    #[async_trait]
+    impl Connector for CountingConnector {
+        fn types_mapping() -> Vec<(String, Option<FieldType>)> {
+            vec![]
+        }
+
+        async fn validate_connection(&mut self) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        async fn list_tables(&mut self) -> Result<Vec<TableIdentifier>, BoxedError> {
+            Ok(["a", "b", "c"]
+                .map(|name| TableIdentifier::from_table_name(name.to_string()))
+                .to_vec())
+        }
+
+        async fn validate_tables(&mut self, _tables: &[TableIdentifier]) -> Result<(), BoxedError> {
+            Ok(())
+        }
+
+        async fn list_columns(
+            &mut self,
+            _tables: Vec<TableIdentifier>,
+        ) -> Result<Vec<TableInfo>, BoxedError> {
+            Ok(vec![])
+        }
+
+        async fn get_schemas(
+            &mut self,
+            table_infos: &[TableInfo],
+        ) -> Result<Vec<SourceSchemaResult>, BoxedError> {
+            self.get_schemas_calls += 1;
+            Ok(table_infos
+                .iter()
+                .map(|table_info| {
+                    if table_info.name == "invalid" {
+                        Err("invalid table".into())
+                    } else {
+                        Ok(SourceSchema::new(Schema::default(), CdcType::Nothing))
+                    }
+                })
+                .collect())
+        }
+
+        async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
+            Ok(vec![])
+        }
+
+        async fn start(
+            &mut self,
+            _ingestor: &Ingestor,
+            _tables: Vec<TableInfo>,
+            _last_checkpoint: Option<OpIdentifier>,
+        ) -> Result<(), BoxedError> {
+            Ok(())
+        }
+    }
+
+    fn table_info(name: &str) -> TableInfo {
+        TableInfo {
+            schema: None,
+            name: name.to_string(),
+            column_names: vec![],
+        }
+    }
+
+    fn connection(name: &str) -> Connection {
+        Connection {
+            config: ConnectionConfig::Kafka(KafkaConfig {
+                broker: "localhost:9092".to_string(),
+                schema_registry_url: None,
+            }),
+            name: name.to_string(),
+        }
+    }
+
+    fn cached_connector(
+        connection_name: &str,
+        cache: SchemaCache,
+    ) -> CachedConnector<CountingConnector> {
+        CachedConnector::new(
+            Box::new(CountingConnector::default()),
+            connection(connection_name),
+            cache,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_schemas_is_cached() {
+        let mut connector = cached_connector("connection", SchemaCache::default());
+        let tables = [table_info("a"), table_info("b")];
+        for _ in 0..2 {
+            let schemas = connector.get_schemas(&tables).await.unwrap();
+            assert_eq!(schemas.len(), 2);
+        }
+        connector.get_schemas(&tables[..1]).await.unwrap();
+        assert_eq!(connector.into_inner().get_schemas_calls, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_shared_by_connection() {
+        let cache = SchemaCache::default();
+        let tables = [table_info("a")];
+        let mut first = cached_connector("connection", cache.clone());
+        first.get_schemas(&tables).await.unwrap();
+        let mut second = cached_connector("connection", cache.clone());
+        second.get_schemas(&tables).await.unwrap();
+        let mut other = cached_connector("other", cache.clone());
+        other.get_schemas(&tables).await.unwrap();
+        assert_eq!(first.into_inner().get_schemas_calls, 1);
+        assert_eq!(second.into_inner().get_schemas_calls, 0);
+        assert_eq!(other.into_inner().get_schemas_calls, 1);
+
+        cache.clear();
+        let mut cleared = cached_connector("connection", cache);
+        cleared.get_schemas(&tables).await.unwrap();
+        assert_eq!(cleared.into_inner().get_schemas_calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_schemas_are_not_cached() {
+        let mut connector = cached_connector("connection", SchemaCache::default());
+        let tables = [table_info("a"), table_info("invalid")];
+        for _ in 0..2 {
+            let schemas = connector.get_schemas(&tables).await.unwrap();
+            assert!(schemas[0].is_ok());
+            assert!(schemas[1].is_err());
+        }
+        assert_eq!(connector.into_inner().get_schemas_calls, 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_tables_paginated() {
+        let mut connector = cached_connector("connection", SchemaCache::default());
+        let (tables, cursor) = connector.list_tables_paginated(2, None).await.unwrap();
+        assert_eq!(tables.len(), 2);
+        let (tables, cursor) = connector.list_tables_paginated(2, cursor).await.unwrap();
+        assert_eq!(
+            tables[..],
+            [TableIdentifier::from_table_name("c".to_string())]
+        );
+        assert_eq!(cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl() {
+        let mut connector = cached_connector("connection", SchemaCache::new(Some(Duration::ZERO)));
+        let tables = [table_info("a")];
+        connector.get_schemas(&tables).await.unwrap();
+        connector.get_schemas(&tables).await.unwrap();
+        assert_eq!(connector.into_inner().get_schemas_calls, 2);
+    }
+}
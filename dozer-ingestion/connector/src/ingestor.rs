@@ -1,7 +1,18 @@
-use dozer_types::models::ingestion_types::IngestionMessage;
-use std::{error::Error, fmt::Display, time::Duration};
+use dozer_types::log::warn;
+use dozer_types::models::ingestion_types::{IngestionMessage, OverflowAction, OverflowPolicy};
+use metrics::{describe_counter, increment_counter};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::Display,
+    sync::{Arc, Condvar, Mutex, OnceLock},
+    time::Duration,
+};
 use tokio::{
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Notify,
+    },
     time::timeout,
 };
 
@@ -43,6 +54,154 @@ impl IngestionIterator {
 /// `IngestionMessage` is the message type that is sent over the channel.
 pub struct Ingestor {
     sender: Sender<IngestionMessage>,
+    /// Buffers the messages sent with a dropping `OverflowPolicy`. Created when first needed.
+    overflow_buffer: Arc<OnceLock<OverflowBuffer>>,
+}
+
+const DROPPED_MESSAGES_COUNTER_NAME: &str = "dozer_ingestor_dropped_messages_total";
+
+/// A bounded buffer in front of the channel to the pipeline. Unlike with the channel, operations
+/// in it can be dropped by the sender side. Other messages, such as commits and schema changes,
+/// are never dropped.
+#[derive(Debug)]
+struct OverflowBuffer {
+    shared: Arc<OverflowBufferShared>,
+}
+
+#[derive(Debug)]
+struct OverflowBufferShared {
+    capacity: usize,
+    state: Mutex<OverflowBufferState>,
+    /// Notified when a message is added or the buffer is dropped.
+    pushed: Condvar,
+    /// Notified when a message is taken or the channel to the pipeline is closed.
+    popped: Notify,
+}
+
+#[derive(Debug, Default)]
+struct OverflowBufferState {
+    messages: VecDeque<IngestionMessage>,
+    /// The buffer was dropped, so no more messages will be added.
+    dropped: bool,
+    /// The channel to the pipeline was closed, so no more messages will be taken.
+    closed: bool,
+}
+
+impl OverflowBufferState {
+    /// Adds `message` if there's room, or if an operation can be dropped as `on_full` says.
+    /// Returns whether an operation was dropped, or `message` back if it has to wait for room.
+    fn push(
+        &mut self,
+        message: IngestionMessage,
+        capacity: usize,
+        on_full: OverflowAction,
+    ) -> Result<bool, IngestionMessage> {
+        if self.messages.len() < capacity {
+            self.messages.push_back(message);
+            return Ok(false);
+        }
+        let buffered_operation = match on_full {
+            OverflowAction::DropOldest => self.messages.iter().position(is_operation),
+            OverflowAction::DropNewest | OverflowAction::Block => {
+                self.messages.iter().rposition(is_operation)
+            }
+        };
+        // The new operation is the newest one, and the oldest one if no operation is buffered
+        if is_operation(&message)
+            && (on_full != OverflowAction::DropOldest || buffered_operation.is_none())
+        {
+            return Ok(true);
+        }
+        match buffered_operation {
+            Some(index) => {
+                self.messages.remove(index);
+                self.messages.push_back(message);
+                Ok(true)
+            }
+            None => Err(message),
+        }
+    }
+}
+
+fn is_operation(message: &IngestionMessage) -> bool {
+    matches!(message, IngestionMessage::OperationEvent { .. })
+}
+
+impl OverflowBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(OverflowBufferShared {
+                capacity,
+                state: Default::default(),
+                pushed: Condvar::new(),
+                popped: Notify::new(),
+            }),
+        }
+    }
+
+    /// Moves the buffered messages to `target` on a thread of its own, until the buffer is
+    /// dropped and empty, or `target` is closed.
+    fn forward_to(&self, target: Sender<IngestionMessage>) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || loop {
+            let message = {
+                let mut state = shared.state.lock().unwrap();
+                loop {
+                    if let Some(message) = state.messages.pop_front() {
+                        break message;
+                    }
+                    if state.dropped {
+                        return;
+                    }
+                    state = shared.pushed.wait(state).unwrap();
+                }
+            };
+            shared.popped.notify_waiters();
+            if target.blocking_send(message).is_err() {
+                shared.state.lock().unwrap().closed = true;
+                shared.popped.notify_waiters();
+                return;
+            }
+        });
+    }
+
+    /// Adds `message` to the buffer. If it is full, an operation is dropped as `on_full` says.
+    /// If there's no operation to drop for a message that isn't one, waits until there's room.
+    /// Returns whether an operation was dropped.
+    async fn push(
+        &self,
+        mut message: IngestionMessage,
+        on_full: OverflowAction,
+    ) -> Result<bool, SendError> {
+        loop {
+            let popped = self.shared.popped.notified();
+            tokio::pin!(popped);
+            // Registers for the notification before checking for room, so it can't be missed
+            popped.as_mut().enable();
+            {
+                let mut state = self.shared.state.lock().unwrap();
+                if state.closed {
+                    return Err(SendError);
+                }
+                match state.push(message, self.shared.capacity, on_full) {
+                    Ok(dropped) => {
+                        drop(state);
+                        self.shared.pushed.notify_one();
+                        return Ok(dropped);
+                    }
+                    Err(returned) => message = returned,
+                }
+            }
+            popped.await;
+        }
+    }
+}
+
+impl Drop for OverflowBuffer {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().dropped = true;
+        self.shared.pushed.notify_one();
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -59,7 +218,10 @@ impl Error for SendError {}
 impl Ingestor {
     pub fn initialize_channel(config: IngestionConfig) -> (Ingestor, IngestionIterator) {
         let (sender, receiver) = channel(config.forwarder_channel_cap);
-        let ingestor = Self { sender };
+        let ingestor = Self {
+            sender,
+            overflow_buffer: Default::default(),
+        };
 
         let iterator = IngestionIterator { receiver };
         (ingestor, iterator)
@@ -69,6 +231,41 @@ impl Ingestor {
         self.sender.send(message).await.map_err(|_| SendError)
     }
 
+    /// Sends `message` like `handle_message` if `policy` blocks. Otherwise `message` goes through a
+    /// buffer as big as the channel, which drops operations as `policy` says when it is full
+    /// instead of waiting for the pipeline. Other messages are never dropped, and wait for room
+    /// if there's no operation to drop. Messages sent this way may be reordered relative to the
+    /// ones sent with `handle_message`.
+    pub async fn handle_message_with_policy(
+        &self,
+        message: IngestionMessage,
+        policy: OverflowPolicy,
+    ) -> Result<(), SendError> {
+        if policy.on_full == OverflowAction::Block {
+            return self.handle_message(message).await;
+        }
+        if self.sender.is_closed() {
+            return Err(SendError);
+        }
+        let buffer = self.overflow_buffer.get_or_init(|| {
+            describe_counter!(
+                DROPPED_MESSAGES_COUNTER_NAME,
+                "Number of messages dropped because the pipeline couldn't keep up"
+            );
+            let buffer = OverflowBuffer::new(self.sender.max_capacity());
+            buffer.forward_to(self.sender.clone());
+            buffer
+        });
+        if buffer.push(message, policy.on_full).await? {
+            warn!(
+                "Pipeline can't keep up with the connector, dropping operations ({:?})",
+                policy.on_full
+            );
+            increment_counter!(DROPPED_MESSAGES_COUNTER_NAME);
+        }
+        Ok(())
+    }
+
     pub fn blocking_handle_message(&self, message: IngestionMessage) -> Result<(), SendError> {
         self.sender.blocking_send(message).map_err(|_| SendError)
     }
@@ -80,14 +277,20 @@ impl Ingestor {
 
 #[cfg(test)]
 mod tests {
-    use super::Ingestor;
-    use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
-    use dozer_types::types::{Operation, Record};
+    use std::time::Duration;
+
+    use super::{Ingestor, OverflowBuffer};
+    use dozer_types::models::ingestion_types::{IngestionMessage, OverflowAction, TransactionInfo};
+    use dozer_types::types::{Field, Operation, Record};
+    use tokio::time::timeout;
 
     #[tokio::test]
     async fn test_message_handle() {
         let (sender, mut rx) = tokio::sync::mpsc::channel(10);
-        let ingestor = Ingestor { sender };
+        let ingestor = Ingestor {
+            sender,
+            overflow_buffer: Default::default(),
+        };
 
         // Expected seq no - 2
         let operation = Operation::Insert {
@@ -136,4 +339,79 @@ mod tests {
             );
         }
     }
+
+    fn commit(n: u64) -> IngestionMessage {
+        IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+            id: Some(dozer_types::node::OpIdentifier::new(n, 0)),
+        })
+    }
+
+    fn insert(n: u64) -> IngestionMessage {
+        IngestionMessage::OperationEvent {
+            table_index: 0,
+            op: Operation::Insert {
+                new: Record::new(vec![Field::UInt(n)]),
+            },
+            id: None,
+        }
+    }
+
+    fn pop(buffer: &OverflowBuffer) -> Option<IngestionMessage> {
+        let message = buffer.shared.state.lock().unwrap().messages.pop_front();
+        buffer.shared.popped.notify_waiters();
+        message
+    }
+
+    fn drain(buffer: &OverflowBuffer) -> Vec<IngestionMessage> {
+        std::iter::from_fn(|| pop(buffer)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_overflow_buffer() {
+        let buffer = OverflowBuffer::new(2);
+        let push = |message, on_full| buffer.push(message, on_full);
+        assert!(!push(insert(1), OverflowAction::DropNewest).await.unwrap());
+        assert!(!push(insert(2), OverflowAction::DropNewest).await.unwrap());
+        assert!(push(insert(3), OverflowAction::DropNewest).await.unwrap());
+        assert_eq!(drain(&buffer), vec![insert(1), insert(2)]);
+
+        assert!(!push(insert(1), OverflowAction::DropOldest).await.unwrap());
+        assert!(!push(insert(2), OverflowAction::DropOldest).await.unwrap());
+        assert!(push(insert(3), OverflowAction::DropOldest).await.unwrap());
+        assert_eq!(drain(&buffer), vec![insert(2), insert(3)]);
+
+        // Commits take the place of an operation
+        push(insert(1), OverflowAction::DropNewest).await.unwrap();
+        push(insert(2), OverflowAction::DropNewest).await.unwrap();
+        assert!(push(commit(1), OverflowAction::DropNewest).await.unwrap());
+        assert_eq!(drain(&buffer), vec![insert(1), commit(1)]);
+
+        push(insert(1), OverflowAction::DropOldest).await.unwrap();
+        push(insert(2), OverflowAction::DropOldest).await.unwrap();
+        assert!(push(commit(1), OverflowAction::DropOldest).await.unwrap());
+        assert_eq!(drain(&buffer), vec![insert(2), commit(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_buffer_full_of_control_messages() {
+        let buffer = OverflowBuffer::new(2);
+        for on_full in [OverflowAction::DropNewest, OverflowAction::DropOldest] {
+            assert!(!buffer.push(commit(1), on_full).await.unwrap());
+            assert!(!buffer.push(commit(2), on_full).await.unwrap());
+
+            // Operations are dropped instead of the buffered commits
+            assert!(buffer.push(insert(1), on_full).await.unwrap());
+            assert_eq!(buffer.shared.state.lock().unwrap().messages.len(), 2);
+
+            // Commits wait for room
+            let push = buffer.push(commit(3), on_full);
+            tokio::pin!(push);
+            assert!(timeout(Duration::from_millis(10), push.as_mut())
+                .await
+                .is_err());
+            assert_eq!(pop(&buffer), Some(commit(1)));
+            assert!(!push.await.unwrap());
+            assert_eq!(drain(&buffer), vec![commit(2), commit(3)]);
+        }
+    }
 }
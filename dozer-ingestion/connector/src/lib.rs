@@ -5,13 +5,15 @@ use dozer_types::node::OpIdentifier;
 use dozer_types::serde;
 use dozer_types::serde::{Deserialize, Serialize};
 pub use dozer_types::tonic::async_trait;
-use dozer_types::types::{FieldType, Schema};
+use dozer_types::types::{FieldType, Operation, Record, Schema};
 
+mod cached;
 mod ingestor;
 pub mod schema_parser;
 pub mod test_util;
 pub mod utils;
 
+pub use cached::{CachedConnector, SchemaCache};
 pub use ingestor::{IngestionConfig, IngestionIterator, Ingestor};
 
 pub use dozer_types;
@@ -27,8 +29,26 @@ pub enum CdcType {
     /// Connector only gets PK of old record on delete/update operations.
     OnlyPK,
     #[default]
-    /// Connector cannot get any info about old records. In other words, the table is append-only.
+    /// Connector cannot get any info about old records on delete/update operations.
     Nothing,
+    /// Connector only inserts records. Deletes, updates and upserts from it are dropped by the
+    /// source node.
+    AppendOnly,
+}
+
+impl CdcType {
+    /// Whether a table of this CDC type can produce `op`.
+    pub fn allows(&self, op: &Operation) -> bool {
+        match op {
+            Operation::Delete { .. } | Operation::Update { .. } | Operation::Upsert { .. } => {
+                *self != CdcType::AppendOnly
+            }
+            Operation::Insert { .. }
+            | Operation::BatchInsert { .. }
+            | Operation::AddField { .. }
+            | Operation::RemoveField { .. } => true,
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -38,7 +58,8 @@ pub struct SourceSchema {
     /// Dozer schema mapped from the source table. Columns are already filtered based on `TableInfo.column_names`.
     pub schema: Schema,
     #[serde(default)]
-    /// The source table's CDC type.
+    /// The source table's CDC type, which tells what the connector knows about the old record
+    /// of a delete or update, and whether it deletes or updates records at all.
     pub cdc_type: CdcType,
 }
 
@@ -65,6 +86,25 @@ pub trait Connector: Send + Sync + Debug {
     /// Lists all the table names in the connector.
     async fn list_tables(&mut self) -> Result<Vec<TableIdentifier>, BoxedError>;
 
+    /// Lists at most `page_size` table names, starting at `cursor`, and the cursor of the next page if there is one.
+    ///
+    /// The default implementation lists all the tables and slices the result. Connectors that can page on the server should override it.
+    async fn list_tables_paginated(
+        &mut self,
+        page_size: usize,
+        cursor: Option<String>,
+    ) -> Result<(Vec<TableIdentifier>, Option<String>), BoxedError> {
+        let start = cursor
+            .map(|cursor| cursor.parse::<usize>())
+            .transpose()?
+            .unwrap_or(0);
+        let mut tables = self.list_tables().await?;
+        let end = start.saturating_add(page_size.max(1)).min(tables.len());
+        let next_cursor = (end < tables.len()).then(|| end.to_string());
+        let page = tables.drain(start.min(end)..end).collect();
+        Ok((page, next_cursor))
+    }
+
     /// Validates the connector's table level properties for each table.
     async fn validate_tables(&mut self, tables: &[TableIdentifier]) -> Result<(), BoxedError>;
 
@@ -99,6 +139,13 @@ pub trait Connector: Send + Sync + Debug {
         Ok((table_infos, schemas))
     }
 
+    /// Runs `query` against the source and returns at most `limit` of the resulting records, for previewing data.
+    ///
+    /// The default implementation returns an error, for connectors that can't run ad hoc queries.
+    async fn test_query(&self, _query: &str, _limit: usize) -> Result<Vec<Record>, BoxedError> {
+        Err("This connector doesn't support test queries".into())
+    }
+
     /// Serializes any state that's required to re-instantiate this connector. Should not be confused with `last_checkpoint`.
     async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError>;
 
@@ -132,7 +179,7 @@ impl TableIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 #[serde(crate = "self::serde")]
 /// `TableIdentifier` with column names.
 pub struct TableInfo {
@@ -143,3 +190,22 @@ pub struct TableInfo {
     /// The column names to be mapped.
     pub column_names: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CdcType;
+    use dozer_types::types::{Operation, Record};
+
+    #[test]
+    fn test_cdc_type_allows() {
+        let insert = Operation::Insert {
+            new: Record::new(vec![]),
+        };
+        let delete = Operation::Delete {
+            old: Record::new(vec![]),
+        };
+        assert!(CdcType::AppendOnly.allows(&insert));
+        assert!(!CdcType::AppendOnly.allows(&delete));
+        assert!(CdcType::Nothing.allows(&delete));
+    }
+}
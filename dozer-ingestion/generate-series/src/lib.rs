@@ -0,0 +1,279 @@
+use dozer_ingestion_connector::{
+    async_trait,
+    dozer_types::{
+        chrono::{DateTime, Duration, FixedOffset, NaiveDate},
+        errors::internal::BoxedError,
+        models::ingestion_types::{GenerateSeriesConfig, IngestionMessage, TransactionInfo},
+        node::OpIdentifier,
+        thiserror::{self, Error},
+        types::{Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition},
+    },
+    futures::future::pending,
+    CdcType, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+
+#[cfg(test)]
+mod tests;
+
+const TABLE_NAME: &str = "generate_series";
+const COLUMN_NAME: &str = "value";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("the step of a series can't be zero")]
+    ZeroStep,
+    #[error("invalid date {0:?}, expected YYYY-MM-DD")]
+    InvalidDate(String),
+    #[error("invalid timestamp {0:?}, expected RFC 3339")]
+    InvalidTimestamp(String),
+}
+
+/// A parsed [`GenerateSeriesConfig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Series {
+    Integer {
+        start: i64,
+        stop: i64,
+        step: i64,
+    },
+    Date {
+        start: NaiveDate,
+        stop: NaiveDate,
+        step: Duration,
+    },
+    Timestamp {
+        start: DateTime<FixedOffset>,
+        stop: DateTime<FixedOffset>,
+        step: Duration,
+    },
+}
+
+impl Series {
+    fn new(config: &GenerateSeriesConfig) -> Result<Self, Error> {
+        let parse_date = |date: &str| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| Error::InvalidDate(date.to_string()))
+        };
+        let parse_timestamp = |timestamp: &str| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .map_err(|_| Error::InvalidTimestamp(timestamp.to_string()))
+        };
+
+        let series = match config {
+            GenerateSeriesConfig::Integer { start, stop, step } => Series::Integer {
+                start: *start,
+                stop: *stop,
+                step: *step,
+            },
+            GenerateSeriesConfig::Date {
+                start,
+                stop,
+                step_days,
+            } => Series::Date {
+                start: parse_date(start)?,
+                stop: parse_date(stop)?,
+                step: Duration::days(*step_days),
+            },
+            GenerateSeriesConfig::Timestamp {
+                start,
+                stop,
+                step_millis,
+            } => Series::Timestamp {
+                start: parse_timestamp(start)?,
+                stop: parse_timestamp(stop)?,
+                step: Duration::milliseconds(*step_millis),
+            },
+        };
+        if series.is_zero_step() {
+            return Err(Error::ZeroStep);
+        }
+        Ok(series)
+    }
+
+    fn is_zero_step(&self) -> bool {
+        match self {
+            Series::Integer { step, .. } => *step == 0,
+            Series::Date { step, .. } | Series::Timestamp { step, .. } => step.is_zero(),
+        }
+    }
+
+    fn field_type(&self) -> FieldType {
+        match self {
+            Series::Integer { .. } => FieldType::Int,
+            Series::Date { .. } => FieldType::Date,
+            Series::Timestamp { .. } => FieldType::Timestamp,
+        }
+    }
+
+    /// Values from `start` to `stop`, both inclusive. A series that steps away from `stop` is empty.
+    fn values(self) -> Box<dyn Iterator<Item = Field> + Send> {
+        fn take_until<T: PartialOrd + Copy + Send + 'static>(
+            start: T,
+            stop: T,
+            ascending: bool,
+            next: impl Fn(T) -> Option<T> + Send + 'static,
+            to_field: impl Fn(T) -> Field + Send + 'static,
+        ) -> Box<dyn Iterator<Item = Field> + Send> {
+            Box::new(
+                std::iter::successors(Some(start), move |value| next(*value))
+                    .take_while(move |value| {
+                        if ascending {
+                            *value <= stop
+                        } else {
+                            *value >= stop
+                        }
+                    })
+                    .map(to_field),
+            )
+        }
+
+        match self {
+            Series::Integer { start, stop, step } => take_until(
+                start,
+                stop,
+                step > 0,
+                move |value| value.checked_add(step),
+                Field::Int,
+            ),
+            Series::Date { start, stop, step } => take_until(
+                start,
+                stop,
+                step > Duration::zero(),
+                move |value| value.checked_add_signed(step),
+                Field::Date,
+            ),
+            Series::Timestamp { start, stop, step } => take_until(
+                start,
+                stop,
+                step > Duration::zero(),
+                move |value| value.checked_add_signed(step),
+                Field::Timestamp,
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GenerateSeriesConnector {
+    config: GenerateSeriesConfig,
+}
+
+impl GenerateSeriesConnector {
+    pub fn new(config: GenerateSeriesConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Connector for GenerateSeriesConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("integer".to_string(), Some(FieldType::Int)),
+            ("date".to_string(), Some(FieldType::Date)),
+            ("timestamp".to_string(), Some(FieldType::Timestamp)),
+        ]
+    }
+
+    async fn validate_connection(&mut self) -> Result<(), BoxedError> {
+        Series::new(&self.config)?;
+        Ok(())
+    }
+
+    async fn list_tables(&mut self) -> Result<Vec<TableIdentifier>, BoxedError> {
+        Ok(vec![TableIdentifier::from_table_name(
+            TABLE_NAME.to_string(),
+        )])
+    }
+
+    async fn validate_tables(&mut self, _tables: &[TableIdentifier]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn list_columns(
+        &mut self,
+        _tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, BoxedError> {
+        Ok(vec![TableInfo {
+            schema: None,
+            name: TABLE_NAME.to_string(),
+            column_names: vec![COLUMN_NAME.to_string()],
+        }])
+    }
+
+    async fn get_schemas(
+        &mut self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, BoxedError> {
+        let series = Series::new(&self.config)?;
+        Ok(table_infos
+            .iter()
+            .map(|_| {
+                Ok(SourceSchema {
+                    schema: Schema {
+                        fields: vec![FieldDefinition {
+                            name: COLUMN_NAME.to_string(),
+                            typ: series.field_type(),
+                            nullable: false,
+                            source: SourceDefinition::Dynamic,
+                        }],
+                        primary_index: vec![0],
+                    },
+                    cdc_type: CdcType::Nothing,
+                })
+            })
+            .collect())
+    }
+
+    async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
+        Ok(vec![])
+    }
+
+    async fn start(
+        &mut self,
+        ingestor: &Ingestor,
+        _tables: Vec<TableInfo>,
+        last_checkpoint: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        // The whole series is ingested as the snapshot, so there's nothing left to do after a restart.
+        if last_checkpoint.is_none() {
+            let series = Series::new(&self.config)?;
+            if ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingStarted,
+                ))
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+            for value in series.values() {
+                let message = IngestionMessage::OperationEvent {
+                    table_index: 0,
+                    op: Operation::Insert {
+                        new: Record::new(vec![value]),
+                    },
+                    id: None,
+                };
+                if ingestor.handle_message(message).await.is_err() {
+                    return Ok(());
+                }
+            }
+            if ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingDone {
+                        id: Some(OpIdentifier::new(0, 0)),
+                    },
+                ))
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+
+        pending().await
+    }
+}
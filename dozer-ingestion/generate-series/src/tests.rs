@@ -0,0 +1,97 @@
+use dozer_ingestion_connector::dozer_types::{
+    chrono::{DateTime, NaiveDate},
+    models::ingestion_types::GenerateSeriesConfig,
+    types::Field,
+};
+
+use crate::{Error, Series};
+
+fn values(config: GenerateSeriesConfig) -> Vec<Field> {
+    Series::new(&config).unwrap().values().collect()
+}
+
+#[test]
+fn test_integer_series() {
+    assert_eq!(
+        values(GenerateSeriesConfig::Integer {
+            start: 1,
+            stop: 10,
+            step: 4
+        }),
+        vec![Field::Int(1), Field::Int(5), Field::Int(9)]
+    );
+    assert_eq!(
+        values(GenerateSeriesConfig::Integer {
+            start: 3,
+            stop: 1,
+            step: -1
+        }),
+        vec![Field::Int(3), Field::Int(2), Field::Int(1)]
+    );
+    assert_eq!(
+        values(GenerateSeriesConfig::Integer {
+            start: 1,
+            stop: 3,
+            step: -1
+        }),
+        vec![]
+    );
+    assert_eq!(
+        values(GenerateSeriesConfig::Integer {
+            start: i64::MAX - 1,
+            stop: i64::MAX,
+            step: 2
+        }),
+        vec![Field::Int(i64::MAX - 1)]
+    );
+}
+
+#[test]
+fn test_date_series() {
+    let date = |day| Field::Date(NaiveDate::from_ymd_opt(2024, 1, day).unwrap());
+    assert_eq!(
+        values(GenerateSeriesConfig::Date {
+            start: "2024-01-01".to_string(),
+            stop: "2024-01-03".to_string(),
+            step_days: 1
+        }),
+        vec![date(1), date(2), date(3)]
+    );
+}
+
+#[test]
+fn test_timestamp_series() {
+    let timestamp = |timestamp| Field::Timestamp(DateTime::parse_from_rfc3339(timestamp).unwrap());
+    assert_eq!(
+        values(GenerateSeriesConfig::Timestamp {
+            start: "2024-01-01T00:00:00Z".to_string(),
+            stop: "2024-01-01T00:01:00Z".to_string(),
+            step_millis: 30_000
+        }),
+        vec![
+            timestamp("2024-01-01T00:00:00Z"),
+            timestamp("2024-01-01T00:00:30Z"),
+            timestamp("2024-01-01T00:01:00Z"),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_series() {
+    assert!(matches!(
+        Series::new(&GenerateSeriesConfig::Integer {
+            start: 1,
+            stop: 2,
+            step: 0
+        }),
+        Err(Error::ZeroStep)
+    ));
+    assert!(matches!(
+        Series::new(&GenerateSeriesConfig::Date {
+            start: "01/01/2024".to_string(),
+            stop: "2024-01-03".to_string(),
+            step_days: 1
+        }),
+        Err(Error::InvalidDate(_))
+    ));
+}
@@ -222,6 +222,9 @@ impl<'a> IntoField<'a> for Value {
                     from_value_opt::<Duration>(value)?,
                     TimeUnit::Microseconds,
                 )),
+                FieldType::Vector(_) => Err(MySQLConnectorError::UnsupportedFieldType(
+                    field_type.to_string(),
+                ))?,
             }
         };
 
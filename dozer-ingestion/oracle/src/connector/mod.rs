@@ -460,6 +460,8 @@ mod tests {
                     Operation::Insert { .. } => 1,
                     Operation::Delete { .. } => 1,
                     Operation::Update { .. } => 1,
+                    Operation::Upsert { .. } => 1,
+                    Operation::AddField { .. } | Operation::RemoveField { .. } => 0,
                 },
                 _ => 0,
             }
@@ -1,7 +1,11 @@
 use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
 use dozer_ingestion_connector::{
     async_trait,
-    dozer_types::{errors::internal::BoxedError, types::FieldType},
+    dozer_types::{
+        errors::internal::BoxedError,
+        serde_json,
+        types::{FieldType, Record},
+    },
     utils::ListOrFilterColumns,
     Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
 };
@@ -13,6 +17,7 @@ use tokio_postgres::Config;
 
 use crate::{
     connection::validator::validate_connection,
+    helper::{get_conversion_fn, map_row_to_record},
     iterator::PostgresIterator,
     schema::helper::{SchemaHelper, DEFAULT_SCHEMA_NAME},
     PostgresConnectorError,
@@ -103,6 +108,30 @@ impl Connector for PostgresConnector {
             .collect())
     }
 
+    async fn list_tables_paginated(
+        &mut self,
+        page_size: usize,
+        cursor: Option<String>,
+    ) -> Result<(Vec<TableIdentifier>, Option<String>), BoxedError> {
+        // The cursor is the schema and name of the last table of the previous page
+        let after = cursor
+            .map(|cursor| serde_json::from_str(&cursor))
+            .transpose()?;
+        let page_size = page_size.max(1);
+        let tables = self.schema_helper.get_tables_page(page_size, after).await?;
+        let next_cursor = match tables.last() {
+            Some(last) if tables.len() == page_size => Some(serde_json::to_string(last)?),
+            _ => None,
+        };
+        Ok((
+            tables
+                .into_iter()
+                .map(|(schema, name)| TableIdentifier::new(Some(schema), name))
+                .collect(),
+            next_cursor,
+        ))
+    }
+
     async fn validate_tables(&mut self, tables: &[TableIdentifier]) -> Result<(), BoxedError> {
         let tables = tables
             .iter()
@@ -163,6 +192,25 @@ impl Connector for PostgresConnector {
             .collect())
     }
 
+    async fn test_query(&self, query: &str, limit: usize) -> Result<Vec<Record>, BoxedError> {
+        let mut client = helper::connect(self.conn_config.clone()).await?;
+        let query = format!(
+            "SELECT * FROM ({}) AS test_query LIMIT {limit}",
+            query.trim().trim_end_matches(';')
+        );
+        let statement = client.prepare(&query).await?;
+        let conversions = statement
+            .columns()
+            .iter()
+            .map(|column| get_conversion_fn(column.type_()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = client.query(&statement, &[]).await?;
+        Ok(rows
+            .iter()
+            .map(|row| map_row_to_record(row, &conversions))
+            .collect::<Result<_, _>>()?)
+    }
+
     async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
         Ok(self.slot_name.as_bytes().to_vec())
     }
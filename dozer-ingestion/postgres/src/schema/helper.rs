@@ -153,6 +153,29 @@ impl SchemaHelper {
         })
     }
 
+    /// Lists at most `page_size` tables, in order, that come after `after`.
+    pub async fn get_tables_page(
+        &self,
+        page_size: usize,
+        after: Option<SchemaTableIdentifier>,
+    ) -> Result<Vec<SchemaTableIdentifier>, PostgresConnectorError> {
+        let mut client = helper::connect(self.conn_config.clone()).await?;
+        let (after_schema, after_name) = after.unwrap_or_default();
+        let rows = client
+            .query(
+                TABLES_PAGE_SQL,
+                &[
+                    &self.schema,
+                    &after_schema,
+                    &after_name,
+                    &(page_size as i64),
+                ],
+            )
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?;
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
     async fn get_columns(
         &self,
         tables: Option<&[ListOrFilterColumns]>,
@@ -378,6 +401,17 @@ fn find_table(
     }
 }
 
+const TABLES_PAGE_SQL: &str = "
+SELECT t.table_schema, t.table_name
+FROM information_schema.tables t
+WHERE t.table_type = 'BASE TABLE' AND ($1::text IS NULL OR t.table_schema = $1)
+      AND t.table_schema not in ('information_schema', 'pg_catalog')
+      and t.table_schema not like 'pg_toast%'
+      and t.table_schema not like 'pg_temp_%'
+      AND (t.table_schema, t.table_name) > ($2, $3)
+ORDER BY t.table_schema, t.table_name
+LIMIT $4;";
+
 const SQL: &str = "
 SELECT table_info.table_name,
        table_info.column_name,
@@ -14,6 +14,7 @@ use dozer_ingestion_connector::dozer_types::{
 use dozer_ingestion_deltalake::DeltaLakeConnector;
 #[cfg(feature = "ethereum")]
 use dozer_ingestion_ethereum::{EthLogConnector, EthTraceConnector};
+use dozer_ingestion_generate_series::GenerateSeriesConnector;
 use dozer_ingestion_grpc::{connector::GrpcConnector, ArrowAdapter, DefaultAdapter};
 use dozer_ingestion_javascript::JavaScriptConnector;
 #[cfg(feature = "kafka")]
@@ -138,11 +139,14 @@ pub fn get_connector(
             runtime,
             javascript_config,
         ))),
-        ConnectionConfig::Aerospike(config) => Ok(Box::new(AerospikeConnector::new(config))),
+        ConnectionConfig::Aerospike(config) => Ok(Box::new(AerospikeConnector::new(config, state))),
         ConnectionConfig::Oracle(oracle_config) => Ok(Box::new(OracleConnector::new(
             connection.name,
             oracle_config,
         ))),
+        ConnectionConfig::GenerateSeries(config) => {
+            Ok(Box::new(GenerateSeriesConnector::new(config)))
+        }
     }
 }
 
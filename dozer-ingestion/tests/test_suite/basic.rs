@@ -65,6 +65,13 @@ pub async fn run_test_suite_basic_data_ready<T: DataReadyConnectorTest>(runtime:
                         assert_record_matches_source_schema(op, &schemas[*table_index], true);
                     }
                 }
+                Operation::Upsert { .. } => panic!("Connectors must not emit upserts"),
+                Operation::AddField { .. } => {
+                    panic!("Connectors must report added columns with `ColumnAdded`")
+                }
+                Operation::RemoveField { .. } => {
+                    panic!("Connectors must not remove columns unless configured to")
+                }
             }
         }
     }
@@ -259,6 +266,13 @@ pub async fn run_test_suite_basic_cud<T: CudConnectorTest>(runtime: Arc<Runtime>
                     records.append_operation(RecordsOperation::Insert { new: new.values });
                 }
             }
+            Operation::Upsert { .. } => panic!("Connectors must not emit upserts"),
+            Operation::AddField { .. } => {
+                panic!("Connectors must report added columns with `ColumnAdded`")
+            }
+            Operation::RemoveField { .. } => {
+                panic!("Connectors must not remove columns unless configured to")
+            }
         }
     }
 
@@ -309,6 +323,7 @@ fn assert_record_matches_schema(record: &Record, schema: &Schema, only_match_pk:
             FieldType::Json => assert!(value.as_json().is_some()),
             FieldType::Point => assert!(value.as_point().is_some()),
             FieldType::Duration => assert!(value.as_duration().is_some()),
+            FieldType::Vector(_) => assert!(value.as_vector().is_some()),
         }
     }
 }
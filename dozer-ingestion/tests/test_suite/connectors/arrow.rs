@@ -292,6 +292,7 @@ fn field_type_to_arrow(field_type: FieldType) -> Option<arrow::datatypes::DataTy
         FieldType::Date => Some(arrow::datatypes::DataType::Date32),
         FieldType::Json => Some(arrow::datatypes::DataType::Utf8),
         FieldType::Point => None,
+        FieldType::Vector(_) => None,
         FieldType::Duration => Some(arrow::datatypes::DataType::Duration(
             arrow::datatypes::TimeUnit::Nanosecond,
         )),
@@ -443,6 +444,7 @@ fn fields_to_arrow<'a, F: IntoIterator<Item = &'a Field>>(
             Arc::new(builder.finish())
         }
         FieldType::Point => panic!("Point not supported"),
+        FieldType::Vector(_) => panic!("Vector not supported"),
         FieldType::Duration => {
             let mut builder = arrow::array::DurationNanosecondArray::builder(count);
             for field in fields {
@@ -170,6 +170,7 @@ fn field_type_to_sql(field_type: FieldType) -> Option<String> {
         FieldType::Json => Some("JSONB".to_string()),
         FieldType::Point => Some("POINT".to_string()),
         FieldType::Duration => Some("DURATION".to_string()),
+        FieldType::Vector(_) => None,
     }
 }
 
@@ -241,6 +242,7 @@ fn field_to_sql(field: &Field) -> String {
         Field::Json(b) => format!("'{}'::jsonb", json_to_string(b)),
         Field::Point(p) => format!("'({},{})'", p.0.x(), p.0.y()),
         Field::Duration(_) => field.to_string(),
+        Field::Vector(_) => format!("ARRAY{field}"),
         Field::Null => "NULL".to_string(),
     }
 }
@@ -126,6 +126,10 @@ pub fn map_record(
                 FieldType::Duration => {
                     values.push(Field::Null);
                 }
+                FieldType::Vector(_) => {
+                    let vector: Vec<f32> = serde_json::from_value(value.clone())?;
+                    values.push(vector.into());
+                }
             },
             None => {
                 let field = Field::Null;
@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use async_stream::try_stream;
 use camino::{Utf8Path, Utf8PathBuf};
 use dozer_types::{
@@ -102,6 +104,8 @@ pub struct RecordStoreSliceMeta {
     pub key: String,
     pub epoch_id: u64,
     pub processor_prefix: Utf8PathBuf,
+    pub last_modified: SystemTime,
+    pub size: u64,
 }
 
 pub fn list_record_store_slices<'a>(
@@ -129,6 +133,8 @@ pub fn list_record_store_slices<'a>(
                     key: object.key,
                     epoch_id,
                     processor_prefix,
+                    last_modified: object.last_modified,
+                    size: object.size,
                 };
             }
 
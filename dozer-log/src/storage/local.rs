@@ -223,7 +223,11 @@ fn list_objects_recursive(
                 let last_modified = metadata
                     .modified()
                     .map_err(|e| Error::FileSystem(path.to_string(), e))?;
-                objects.push(ListedObject { key, last_modified })
+                objects.push(ListedObject {
+                    key,
+                    last_modified,
+                    size: metadata.len(),
+                })
             }
         } else if metadata.is_dir() {
             list_objects_recursive(root, path.to_string(), prefix, objects)?;
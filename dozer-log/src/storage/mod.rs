@@ -21,6 +21,7 @@ pub use nonzero_ext::nonzero;
 pub struct ListedObject {
     pub key: String,
     pub last_modified: SystemTime,
+    pub size: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +78,8 @@ pub trait Storage: Debug + DynClone + Send + Sync + 'static {
     }
 }
 
+dyn_clone::clone_trait_object!(Storage);
+
 mod s3;
 
 #[derive(Debug, thiserror::Error)]
@@ -188,6 +188,7 @@ impl Storage for S3Storage {
                 (*object.last_modified().expect("must have last modified"))
                     .try_into()
                     .map(|last_modified| ListedObject {
+                        size: u64::try_from(object.size()).unwrap_or_default(),
                         key: object.key.expect("must have key"),
                         last_modified,
                     })
@@ -10,5 +10,6 @@
 #include <aerospike/as_map.h>
 #include <aerospike/as_orderedmap.h>
 #include <aerospike/as_arraylist.h>
+#include <aerospike/as_cdt_order.h>
 #include <aerospike/as_map.h>
 #include <aerospike/as_orderedmap.h>
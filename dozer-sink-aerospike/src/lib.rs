@@ -1,54 +1,92 @@
 use crossbeam_channel::{bounded, Receiver, Sender};
 use dozer_types::json_types::{DestructuredJsonRef, JsonValue};
 use dozer_types::models::connection::AerospikeConnection;
-use dozer_types::models::sink::DenormColumn;
+use dozer_types::models::sink::{
+    AerospikeBinConflictPolicy, AerospikeConflictPolicy, AerospikeConvertErrorPolicy,
+    AerospikeDurationUnit, AerospikeExistsPolicy, AerospikeKeyDigest, AerospikeKeyFormat,
+    AerospikeMapOrder, AerospikeOversizePolicy, DenormColumn,
+};
 use dozer_types::node::OpIdentifier;
 use std::alloc::{handle_alloc_error, Layout};
+use std::cell::Cell;
 use std::ffi::{c_char, c_void, CStr, CString, NulError};
-use std::fmt::Display;
+use std::fmt::{self, Display};
 use std::mem::{self, MaybeUninit};
-use std::num::NonZeroUsize;
-use std::ptr::{addr_of, null, NonNull};
-use std::sync::Arc;
-use std::thread::available_parallelism;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::ptr::{addr_of, null, null_mut, NonNull};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::{available_parallelism, JoinHandle};
 use std::time::{Duration, Instant};
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    iter,
+};
 
 use aerospike_client_sys::{
-    aerospike, aerospike_batch_write, aerospike_connect, aerospike_destroy, aerospike_key_put,
-    aerospike_key_remove, aerospike_key_select, aerospike_new, as_arraylist_append,
-    as_arraylist_destroy, as_arraylist_new, as_batch_record, as_batch_records,
-    as_batch_records_destroy, as_batch_write_record, as_bin_value, as_boolean_new, as_bytes_new,
-    as_bytes_new_wrap, as_bytes_set, as_bytes_type, as_bytes_type_e_AS_BYTES_STRING, as_config,
-    as_config_add_hosts, as_config_init, as_double_new, as_error, as_integer_new, as_key,
-    as_key_destroy, as_key_init_int64, as_key_init_rawp, as_key_init_value, as_key_value, as_nil,
-    as_operations, as_operations_add_write, as_operations_add_write_bool,
-    as_operations_add_write_double, as_operations_add_write_geojson_strp,
-    as_operations_add_write_int64, as_operations_add_write_rawp, as_operations_destroy,
-    as_operations_init, as_orderedmap, as_orderedmap_destroy, as_orderedmap_new, as_orderedmap_set,
-    as_policy_batch, as_policy_exists_e_AS_POLICY_EXISTS_CREATE,
+    aerospike, aerospike_batch_write, aerospike_connect, aerospike_destroy, aerospike_key_exists,
+    aerospike_key_get, aerospike_key_put, aerospike_key_remove, aerospike_key_select,
+    aerospike_new, as_arraylist_append, as_arraylist_destroy, as_arraylist_new, as_batch_record,
+    as_batch_records, as_batch_records_destroy, as_batch_write_record, as_bin, as_bin_value,
+    as_boolean, as_boolean_new, as_bytes_new, as_bytes_new_wrap, as_bytes_set, as_bytes_type,
+    as_bytes_type_e_AS_BYTES_STRING, as_config, as_config_add_hosts, as_config_init, as_double_new,
+    as_error, as_integer_new, as_key, as_key_destroy, as_key_init_int64, as_key_init_rawp,
+    as_key_init_value, as_key_set_digest, as_key_value, as_map, as_map_order,
+    as_map_order_AS_MAP_KEY_ORDERED, as_map_order_AS_MAP_KEY_VALUE_ORDERED, as_nil, as_operations,
+    as_operations_add_write, as_operations_add_write_bool, as_operations_add_write_double,
+    as_operations_add_write_geojson_strp, as_operations_add_write_int64,
+    as_operations_add_write_rawp, as_operations_destroy, as_operations_init, as_orderedmap,
+    as_orderedmap_destroy, as_orderedmap_new, as_orderedmap_set, as_policy_batch, as_policy_exists,
+    as_policy_exists_e_AS_POLICY_EXISTS_CREATE,
+    as_policy_exists_e_AS_POLICY_EXISTS_CREATE_OR_REPLACE,
+    as_policy_exists_e_AS_POLICY_EXISTS_IGNORE, as_policy_exists_e_AS_POLICY_EXISTS_REPLACE,
     as_policy_exists_e_AS_POLICY_EXISTS_UPDATE, as_policy_remove, as_policy_write, as_record,
     as_record_destroy, as_record_get, as_record_init, as_record_set, as_record_set_bool,
     as_record_set_double, as_record_set_geojson_strp, as_record_set_int64, as_record_set_nil,
-    as_record_set_raw_typep, as_record_set_rawp, as_status,
-    as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND, as_status_e_AEROSPIKE_OK, as_val,
-    as_val_val_reserve, as_vector, as_vector_increase_capacity, as_vector_init, AS_BATCH_WRITE,
-    AS_BIN_NAME_MAX_LEN,
+    as_record_set_raw_typep, as_record_set_rawp, as_status, as_status_e_AEROSPIKE_ERR_CLUSTER,
+    as_status_e_AEROSPIKE_ERR_CONNECTION, as_status_e_AEROSPIKE_ERR_INVALID_NODE,
+    as_status_e_AEROSPIKE_ERR_NO_MORE_CONNECTIONS, as_status_e_AEROSPIKE_ERR_RECORD_EXISTS,
+    as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND, as_status_e_AEROSPIKE_ERR_TIMEOUT,
+    as_status_e_AEROSPIKE_ERR_TLS_ERROR, as_status_e_AEROSPIKE_OK, as_val, as_val_type_e,
+    as_val_type_e_AS_BOOLEAN, as_val_type_e_AS_BYTES, as_val_type_e_AS_DOUBLE,
+    as_val_type_e_AS_INTEGER, as_val_type_e_AS_NIL, as_val_type_e_AS_STRING, as_val_val_reserve,
+    as_vector, as_vector_increase_capacity, as_vector_init, AS_BATCH_WRITE, AS_BIN_NAME_MAX_LEN,
 };
-use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::errors::MigrationError;
+use dozer_core::node::{CommitFailureAction, PortHandle, Sink, SinkFactory};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::geo::{Coord, Point};
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::tonic::async_trait;
 use dozer_types::{
+    chrono::{DateTime, NaiveDate},
     errors::types::TypeError,
     log::{error, info, warn},
     models::sink::AerospikeSinkConfig,
     thiserror::{self, Error},
+    tracing,
     types::{
-        DozerDuration, DozerPoint, Field, FieldType, Operation, Record, Schema, TableOperation,
+        DozerDuration, DozerPoint, Field, FieldType, Operation, Record, RecordDiff, Schema,
+        TableOperation,
     },
 };
+use lru::LruCache;
+use metrics::{counter, describe_counter, increment_counter};
+
+const CONVERT_ERROR_COUNTER_NAME: &str = "aerospike_sink_convert_errors";
+const ACTION_LABEL: &str = "action";
+const VERIFIED_WRITES_COUNTER_NAME: &str = "aerospike_sink_verified_writes";
+const SKIPPED_UNCHANGED_COUNTER_NAME: &str = "aerospike_sink_records_skipped_unchanged";
+const RESULT_LABEL: &str = "result";
+/// Aerospike derives a record's partition from the first two bytes of its digest.
+const PARTITION_DIGEST_BYTES: usize = 2;
+/// How many times a record is enriched again when a denormalization source keeps changing under it.
+const MAX_GENERATION_RETRIES: usize = 3;
+
+/// Overrides the number of CPUs the sink's threads default to, for when it can't be determined.
+const N_THREADS_ENV_VAR: &str = "DOZER_AEROSPIKE_SINK_THREADS";
 
 #[derive(Error, Debug)]
 enum AerospikeSinkError {
@@ -66,10 +104,89 @@ enum AerospikeSinkError {
     NulError(#[from] NulError),
     #[error("Could not create record")]
     CreateRecordError,
-    #[error("Column name \"{}\" exceeds aerospike's maximum bin name length ({})", .0, AS_BIN_NAME_MAX_LEN)]
+    #[error("Bin name \"{}\" exceeds aerospike's maximum bin name length ({})", .0, AS_BIN_NAME_MAX_LEN)]
     BinNameTooLong(String),
+    #[error("Bin \"{0}\" is written more than once")]
+    DuplicateBinName(String),
+    #[error("Bin names can't be empty")]
+    EmptyBinName,
+    #[error("Column \"{0}\" is not a Point, so it can't be used for a point region")]
+    NotAPointColumn(String),
+    #[error("Column \"{0}\" is not a Duration, so it can't be written as an integer duration")]
+    NotADurationColumn(String),
+    #[error("Column \"{0}\" is not a Json, so it can't be written as ordered maps")]
+    NotAJsonColumn(String),
+    #[error("Can't remove column \"{0}\", as the table has no bin for it")]
+    UnknownBin(String),
+    #[error("Can't remove column \"{0}\", as it is used for a key or a point region")]
+    RequiredBinRemoved(String),
+    #[error("Sample percentage {0} is larger than 100")]
+    InvalidSamplePercent(u8),
+    #[error("Column \"{0}\" is a vector without any dimensions")]
+    EmptyVector(String),
+    #[error("Can't colocate with set \"{0}\", as the table isn't denormalized from it")]
+    UnknownColocationSet(String),
+    #[error("Can't colocate with set \"{0}\", as it is in a different namespace")]
+    ColocationAcrossNamespaces(String),
+    #[error("Bin \"{0}\" copied from set \"{1}\" conflicts with a bin of the record")]
+    DenormalizedBinConflict(String, String),
+    #[error("Denormalization source in set \"{0}\" kept changing while the record was written")]
+    DenormalizationSourceChanged(String),
+    #[error("The last {misses} lookups of denormalization sources in set \"{namespace}.{set}\" found no record. Check the denormalization's `from_namespace` and `from_set`")]
+    DenormalizationSourceMissing {
+        namespace: String,
+        set: String,
+        misses: u32,
+    },
     #[error("Integer out of range. The supplied usigned integer was larger than the maximum representable value for an aerospike integer")]
     IntegerOutOfRange(u64),
+    #[error("The sink has been shut down")]
+    ShutDown,
+    #[error("Record skipped because a field failed type conversion: {0}")]
+    RecordSkipped(Box<AerospikeSinkError>),
+    #[error("The record's converted strings take {bytes} bytes, more than the maximum of {max}")]
+    RecordStringsTooLarge { bytes: usize, max: usize },
+    #[error("{} of {} records in a batch failed to write: {}", .failures.len(), .total, join_errors(.failures))]
+    BatchRecordsFailed {
+        failures: Vec<AerospikeSinkError>,
+        total: usize,
+    },
+}
+
+/// The bin a column is written to: its name in `mapping` if there's one, else the column name,
+/// truncated to Aerospike's maximum bin name length. Only truncated names are owned.
+fn bin_name<'a>(
+    column: &'a str,
+    mapping: Option<&'a HashMap<String, String>>,
+) -> Result<Cow<'a, str>, AerospikeSinkError> {
+    let max_len = AS_BIN_NAME_MAX_LEN as usize;
+    let Some(mapping) = mapping else {
+        if column.len() <= max_len {
+            return Ok(Cow::Borrowed(column));
+        }
+        let end = (0..=max_len)
+            .rev()
+            .find(|end| column.is_char_boundary(*end))
+            .unwrap_or_default();
+        return Ok(Cow::Owned(column[..end].to_owned()));
+    };
+    let bin = mapping.get(column).map_or(column, String::as_str);
+    if bin.len() > max_len {
+        return Err(AerospikeSinkError::BinNameTooLong(bin.to_owned()));
+    }
+    Ok(Cow::Borrowed(bin))
+}
+
+fn warn_truncated_bin_name(column: &str, bin: &str) {
+    warn!("Column \"{column}\" is written to Aerospike bin \"{bin}\", as its name exceeds the maximum bin name length ({AS_BIN_NAME_MAX_LEN}). Set `bin_name_mapping` to choose the bin name.");
+}
+
+fn join_errors(errors: &[AerospikeSinkError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[derive(Debug, Error)]
@@ -93,6 +210,23 @@ impl From<as_error> for AerospikeError {
     }
 }
 
+impl AerospikeError {
+    /// Whether the error is caused by the cluster or the connection to it, rather than by
+    /// the records that were written.
+    #[allow(non_upper_case_globals)]
+    fn is_connection_error(&self) -> bool {
+        matches!(
+            self.code,
+            as_status_e_AEROSPIKE_ERR_CONNECTION
+                | as_status_e_AEROSPIKE_ERR_TIMEOUT
+                | as_status_e_AEROSPIKE_ERR_CLUSTER
+                | as_status_e_AEROSPIKE_ERR_INVALID_NODE
+                | as_status_e_AEROSPIKE_ERR_NO_MORE_CONNECTIONS
+                | as_status_e_AEROSPIKE_ERR_TLS_ERROR
+        )
+    }
+}
+
 impl Display for AerospikeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} - {}", self.code, self.message)
@@ -103,6 +237,8 @@ impl Display for AerospikeError {
 #[derive(Debug)]
 struct Client {
     inner: NonNull<aerospike>,
+    /// Overrides the `exists` policy that inserts and updates are written with.
+    exists_policy: Option<as_policy_exists>,
 }
 
 // The aerospike client API is thread-safe.
@@ -127,13 +263,29 @@ unsafe fn as_try(mut f: impl FnMut(*mut as_error) -> as_status) -> Result<(), Ae
 }
 
 impl Client {
-    fn new(hosts: &CStr) -> Result<Self, AerospikeError> {
+    fn new(
+        hosts: &CStr,
+        max_conns_per_node: Option<u32>,
+        min_conns_per_node: Option<u32>,
+        exists_policy: Option<as_policy_exists>,
+    ) -> Result<Self, AerospikeError> {
         let mut config = unsafe {
             let mut config = MaybeUninit::uninit();
             as_config_init(config.as_mut_ptr());
             config.assume_init()
         };
         config.policies.batch.base.total_timeout = 10000;
+        if let Some(max_conns_per_node) = max_conns_per_node {
+            config.max_conns_per_node = max_conns_per_node;
+        }
+        if let Some(min_conns_per_node) = min_conns_per_node {
+            config.min_conns_per_node = min_conns_per_node;
+        }
+        // Upserts and batch writes use the default policies
+        if let Some(exists_policy) = exists_policy {
+            config.policies.write.exists = exists_policy;
+            config.policies.batch_write.exists = exists_policy;
+        }
         unsafe {
             // The hosts string will be copied, so pass it as `as_ptr` so the original
             // gets deallocated at the end of this block
@@ -148,6 +300,7 @@ impl Client {
             } else {
                 let this = Self {
                     inner: NonNull::new_unchecked(inner),
+                    exists_policy,
                 };
                 this.connect()?;
                 this
@@ -178,6 +331,15 @@ impl Client {
     }
 
     unsafe fn insert(&self, key: *const as_key, new: *mut as_record) -> Result<(), AerospikeError> {
+        let mut policy = self.inner.as_ref().config.policies.write;
+        policy.exists = self
+            .exists_policy
+            .unwrap_or(as_policy_exists_e_AS_POLICY_EXISTS_CREATE);
+        self.put(key, new, policy)
+    }
+
+    /// Writes the record only if it doesn't exist yet, whatever `exists_policy` is set to.
+    unsafe fn create(&self, key: *const as_key, new: *mut as_record) -> Result<(), AerospikeError> {
         let mut policy = self.inner.as_ref().config.policies.write;
         policy.exists = as_policy_exists_e_AS_POLICY_EXISTS_CREATE;
         self.put(key, new, policy)
@@ -185,10 +347,31 @@ impl Client {
 
     unsafe fn update(&self, key: *const as_key, new: *mut as_record) -> Result<(), AerospikeError> {
         let mut policy = self.inner.as_ref().config.policies.write;
-        policy.exists = as_policy_exists_e_AS_POLICY_EXISTS_UPDATE;
+        policy.exists = self
+            .exists_policy
+            .unwrap_or(as_policy_exists_e_AS_POLICY_EXISTS_UPDATE);
         self.put(key, new, policy)
     }
 
+    /// Writes the record with the default `exists` policy, which writes it whether or not it
+    /// exists unless `exists_policy` is set.
+    unsafe fn upsert(&self, key: *const as_key, new: *mut as_record) -> Result<(), AerospikeError> {
+        let policy = self.inner.as_ref().config.policies.write;
+        self.put(key, new, policy)
+    }
+
+    /// Whether writes replace the whole stored record, rather than only the bins written.
+    fn replaces_records(&self) -> bool {
+        #[allow(non_upper_case_globals)]
+        matches!(
+            self.exists_policy,
+            Some(
+                as_policy_exists_e_AS_POLICY_EXISTS_REPLACE
+                    | as_policy_exists_e_AS_POLICY_EXISTS_CREATE_OR_REPLACE
+            )
+        )
+    }
+
     unsafe fn delete(&self, key: *const as_key) -> Result<(), AerospikeError> {
         let policy = self.inner.as_ref().config.policies.remove;
         as_try(|err| {
@@ -231,6 +414,39 @@ impl Client {
             )
         })
     }
+
+    /// Reads only the metadata, such as the generation, of a record.
+    unsafe fn exists(
+        &self,
+        key: *const as_key,
+        record: &mut *mut as_record,
+    ) -> Result<(), AerospikeError> {
+        as_try(|err| {
+            aerospike_key_exists(
+                self.inner.as_ptr(),
+                err,
+                null(),
+                key,
+                record as *mut *mut as_record,
+            )
+        })
+    }
+
+    unsafe fn get(
+        &self,
+        key: *const as_key,
+        record: &mut *mut as_record,
+    ) -> Result<(), AerospikeError> {
+        as_try(|err| {
+            aerospike_key_get(
+                self.inner.as_ptr(),
+                err,
+                null(),
+                key,
+                record as *mut *mut as_record,
+            )
+        })
+    }
 }
 
 impl Drop for Client {
@@ -268,6 +484,31 @@ impl SinkFactory for AerospikeSinkFactory {
 
     fn prepare(&self, input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
         debug_assert!(input_schemas.len() == self.config.tables.len());
+        for (port, schema) in &input_schemas {
+            let mapping = self.config.tables[*port as usize].bin_name_mapping.as_ref();
+            if let Some(bin) = mapping
+                .into_iter()
+                .flat_map(|mapping| mapping.values())
+                .find(|bin| bin.len() > AS_BIN_NAME_MAX_LEN as usize)
+            {
+                return Err(AerospikeSinkError::BinNameTooLong(bin.clone()).into());
+            }
+
+            let mut bins = HashSet::new();
+            for field in &schema.fields {
+                if field.typ == FieldType::Vector(0) {
+                    return Err(AerospikeSinkError::EmptyVector(field.name.clone()).into());
+                }
+                let bin = bin_name(&field.name, mapping)?;
+                if let Cow::Owned(bin) = &bin {
+                    warn_truncated_bin_name(&field.name, bin);
+                }
+                if bins.contains(&bin) {
+                    return Err(AerospikeSinkError::DuplicateBinName(bin.into_owned()).into());
+                }
+                bins.insert(bin);
+            }
+        }
         Ok(())
     }
 
@@ -276,17 +517,57 @@ impl SinkFactory for AerospikeSinkFactory {
         mut input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn dozer_core::node::Sink>, BoxedError> {
         let hosts = CString::new(self.connection_config.hosts.as_str())?;
-        let client = Client::new(&hosts).map_err(AerospikeSinkError::from)?;
-        let n_threads = self
-            .config
-            .n_threads
-            .or_else(|| available_parallelism().ok())
-            .unwrap_or_else(|| {
-                warn!("Unable to automatically determine the correct amount of threads to use for Aerospike sink, so defaulting to 1.\nTo override, set `n_threads` in your Aerospike sink config");
-                NonZeroUsize::new(1).unwrap()
-            });
+        let exists_policy = match self.config.exists_policy {
+            AerospikeExistsPolicy::Operation => None,
+            AerospikeExistsPolicy::Ignore => Some(as_policy_exists_e_AS_POLICY_EXISTS_IGNORE),
+            AerospikeExistsPolicy::Replace => Some(as_policy_exists_e_AS_POLICY_EXISTS_REPLACE),
+            AerospikeExistsPolicy::CreateOrReplace => {
+                Some(as_policy_exists_e_AS_POLICY_EXISTS_CREATE_OR_REPLACE)
+            }
+        };
+        let client = Client::new(
+            &hosts,
+            self.connection_config.max_conns_per_node,
+            self.connection_config.min_conns_per_node,
+            exists_policy,
+        )
+        .map_err(AerospikeSinkError::from)?;
+        describe_counter!(
+            CONVERT_ERROR_COUNTER_NAME,
+            "Number of records skipped or fields nulled because of a failed type conversion"
+        );
+        let verify_sample_percent = if self.config.verify_writes {
+            if self.config.verify_sample_percent > 100 {
+                return Err(AerospikeSinkError::InvalidSamplePercent(
+                    self.config.verify_sample_percent,
+                )
+                .into());
+            }
+            describe_counter!(
+                VERIFIED_WRITES_COUNTER_NAME,
+                "Number of written records read back, by whether they were found"
+            );
+            self.config.verify_sample_percent
+        } else {
+            0
+        };
+        if self.config.checksum_cache_size.is_some() {
+            describe_counter!(
+                SKIPPED_UNCHANGED_COUNTER_NAME,
+                "Number of records not written because they didn't change since they were last written"
+            );
+        }
+        let n_threads = self.config.n_threads.unwrap_or_else(|| {
+            default_n_threads(self.config.default_threads.unwrap_or(NonZeroUsize::MIN))
+        });
+        if let Some(max_conns_per_node) = self.connection_config.max_conns_per_node {
+            if (max_conns_per_node as usize) < n_threads.get() {
+                warn!("Aerospike sink `max_conns_per_node` ({max_conns_per_node}) is lower than `n_threads` ({n_threads}), so writes may fail when the connection pool is exhausted");
+            }
+        }
 
         let mut tables = vec![];
+        let mut checksums = vec![];
         for (port, table) in self.config.tables.iter().enumerate() {
             let schema = input_schemas.remove(&(port as PortHandle)).unwrap();
             let primary_index = match schema.primary_index.len() {
@@ -311,30 +592,25 @@ impl SinkFactory for AerospikeSinkFactory {
                 dozer_types::types::FieldType::Timestamp |
                 dozer_types::types::FieldType::Date) => warn!("Using a {typ} column as a primary key for Aerospike sink. This is only allowed because this type is converted to a String. Cast to another type explicitly to silence this warning."),
 
-                // These are not OK as keys, so error out
+                // These are not OK as keys, so error out. The Aerospike source does read
+                // double keys as `Float`, but we don't write them back as keys.
                 typ @ (dozer_types::types::FieldType::Float|
                 dozer_types::types::FieldType::Boolean |
                 dozer_types::types::FieldType::Json |
-                dozer_types::types::FieldType::Point ) =>  {
+                dozer_types::types::FieldType::Point |
+                dozer_types::types::FieldType::Vector(_) ) =>  {
                         return Err(Box::new(AerospikeSinkError::UnsupportedPrimaryKeyType(typ)));
                     }
             }
-            for field in &schema.fields {
-                if field.name.len() > AS_BIN_NAME_MAX_LEN as usize {
-                    return Err(AerospikeSinkError::BinNameTooLong(field.name.to_owned()).into());
-                }
-            }
-            let bin_names = schema
+            let column_bins = schema
                 .fields
                 .iter()
-                .map(|field| {
-                    if field.name.len() <= AS_BIN_NAME_MAX_LEN as usize {
-                        CString::new(field.name.clone()).map_err(AerospikeSinkError::NulError)
-                    } else {
-                        Err(AerospikeSinkError::BinNameTooLong(field.name.to_owned()))
-                    }
-                })
-                .collect::<Result<_, _>>()?;
+                .map(|field| bin_name(&field.name, table.bin_name_mapping.as_ref()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let bin_names = column_bins
+                .iter()
+                .map(|bin| CString::new(bin.as_ref()).map_err(AerospikeSinkError::NulError))
+                .collect::<Result<Vec<_>, _>>()?;
 
             let denormalizations = table
                 .denormalize
@@ -357,33 +633,184 @@ impl SinkFactory for AerospikeSinkFactory {
                         &denorm.from_set,
                         schema.get_field_index(&denorm.key)?.0,
                         &columns,
+                        denorm.all_bins,
+                        denorm.on_bin_conflict,
+                        denorm.check_generation,
+                        denorm.max_consecutive_misses,
+                        denorm.nest_under.as_deref(),
                     )
                 })
                 .collect::<Result<Vec<_>, _>>()?;
-            let n_denormalization_cols = denormalizations
+            let colocate_with = match &table.key_digest {
+                AerospikeKeyDigest::Default => None,
+                AerospikeKeyDigest::ColocateWith { from_set } => {
+                    let index = table
+                        .denormalize
+                        .iter()
+                        .position(|denorm| &denorm.from_set == from_set)
+                        .ok_or_else(|| {
+                            AerospikeSinkError::UnknownColocationSet(from_set.clone())
+                        })?;
+                    if table.denormalize[index].from_namespace != table.namespace {
+                        return Err(AerospikeSinkError::ColocationAcrossNamespaces(
+                            from_set.clone(),
+                        )
+                        .into());
+                    }
+                    warn!("Aerospike sink set `{}` is colocated with set `{from_set}`. Its records use custom key digests, so they can't be read by key from other clients.", table.set_name);
+                    Some(index)
+                }
+            };
+
+            // Region bins are written next to the columns and denormalized bins, so they
+            // must not collide with either
+            let mut reserved_bins: HashSet<&str> = column_bins
+                .iter()
+                .map(|bin| bin.as_ref())
+                .chain(
+                    table
+                        .denormalize
+                        .iter()
+                        .filter(|denorm| denorm.nest_under.is_none())
+                        .flat_map(|denorm| {
+                            denorm.columns.iter().map(|col| match col {
+                                DenormColumn::Direct(name) => name.as_str(),
+                                DenormColumn::Renamed { destination, .. } => destination.as_str(),
+                            })
+                        }),
+                )
+                .collect();
+            for nest_under in table
+                .denormalize
+                .iter()
+                .filter_map(|denorm| denorm.nest_under.as_deref())
+            {
+                if !reserved_bins.insert(nest_under) {
+                    return Err(AerospikeSinkError::DuplicateBinName(nest_under.to_owned()));
+                }
+            }
+            let point_regions = table
+                .point_regions
                 .iter()
-                .map(|denorm| denorm.columns.len() as u16)
-                .sum();
+                .map(|region| {
+                    let (point_field, field) = schema.get_field_index(&region.column)?;
+                    if field.typ != FieldType::Point {
+                        return Err(AerospikeSinkError::NotAPointColumn(region.column.clone()));
+                    }
+                    if region.bin.len() > AS_BIN_NAME_MAX_LEN as usize {
+                        return Err(AerospikeSinkError::BinNameTooLong(region.bin.clone()));
+                    }
+                    if !reserved_bins.insert(&region.bin) {
+                        return Err(AerospikeSinkError::DuplicateBinName(region.bin.clone()));
+                    }
+                    Ok(PointRegion {
+                        point_field,
+                        bin: CString::new(region.bin.as_str())?,
+                        radius_meters: region.radius_meters,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut column_formats = vec![ColumnFormat::default(); schema.fields.len()];
+            for duration in &table.integer_durations {
+                let (index, field) = schema.get_field_index(&duration.column)?;
+                if field.typ != FieldType::Duration {
+                    return Err(AerospikeSinkError::NotADurationColumn(
+                        duration.column.clone(),
+                    ));
+                }
+                column_formats[index].duration_unit = Some(duration.unit);
+            }
+            for map in &table.ordered_maps {
+                let (index, field) = schema.get_field_index(&map.column)?;
+                if field.typ != FieldType::Json {
+                    return Err(AerospikeSinkError::NotAJsonColumn(map.column.clone()));
+                }
+                column_formats[index].map_order = Some(map.order);
+            }
+
+            let required = [primary_index]
+                .into_iter()
+                .chain(point_regions.iter().map(|region| region.point_field))
+                .chain(denormalizations.iter().map(|denorm| denorm.key_field))
+                .collect();
+            let bins = TableBins {
+                names: bin_names,
+                removed: vec![],
+                required,
+                mapping: table.bin_name_mapping.clone(),
+            };
+            // Denormalized bins can change while the record doesn't
+            checksums.push(
+                self.config
+                    .checksum_cache_size
+                    .filter(|_| table.denormalize.is_empty())
+                    .map(|size| WrittenChecksums {
+                        schema: schema.clone(),
+                        primary_index,
+                        checksums: LruCache::new(size),
+                    }),
+            );
 
             tables.push(AerospikeTable {
                 namespace: CString::new(table.namespace.clone())?,
                 set_name: CString::new(table.set_name.clone())?,
                 primary_index,
-                bin_names,
+                bins: Arc::new(RwLock::new(bins)),
                 denormalizations,
-                n_denormalization_cols,
+                colocate_with,
+                point_regions,
+                column_formats,
+                on_convert_error: table.on_convert_error,
+                on_conflict: table.on_conflict,
+                key_format: table.key_format,
+                schema: schema.clone(),
+                read_before_write: table.read_before_write,
+                string_capacity: schema
+                    .fields
+                    .iter()
+                    .map(|field| converted_string_len(field.typ))
+                    .sum(),
             });
         }
         Ok(Box::new(AerospikeSink::new(
             client,
             tables,
+            checksums,
             n_threads.into(),
+            self.config.max_batch_size,
+            verify_sample_percent,
+            self.config
+                .max_record_string_bytes
+                .map(|max_bytes| RecordStringLimit {
+                    max_bytes,
+                    on_oversize: self.config.on_oversize,
+                }),
         )))
     }
 
     fn type_name(&self) -> String {
         "aerospike".to_string()
     }
+
+    // Aerospike records have no schema, bins are written as they come
+    fn generate_migration_ddl(
+        &self,
+        _port: &PortHandle,
+        _old_schema: &Schema,
+        _new_schema: &Schema,
+    ) -> Result<String, MigrationError> {
+        Ok(String::new())
+    }
+
+    // A colocated record moves when its denormalization source changes, which can't be
+    // detected without the old record
+    fn supports_upsert(&self) -> bool {
+        self.config
+            .tables
+            .iter()
+            .all(|table| table.key_digest == AerospikeKeyDigest::Default)
+    }
 }
 
 // A wrapper type responsible for cleaning up a key. This doesn't own an as_key
@@ -395,6 +822,10 @@ impl Key<'_> {
     fn as_ptr(&self) -> *const as_key {
         (&*self.0) as *const as_key
     }
+
+    fn as_mut_ptr(&mut self) -> *mut as_key {
+        (&mut *self.0) as *mut as_key
+    }
 }
 
 impl Drop for Key<'_> {
@@ -422,10 +853,155 @@ impl Drop for AsRecord<'_> {
     }
 }
 
+/// A denormalization source record, which the client allocated when reading it.
+struct SourceRecord(NonNull<as_record>);
+
+impl SourceRecord {
+    fn as_ptr(&self) -> *mut as_record {
+        self.0.as_ptr()
+    }
+
+    fn generation(&self) -> u16 {
+        unsafe { (*self.0.as_ptr()).gen }
+    }
+
+    unsafe fn bins(&self) -> &[as_bin] {
+        let bins = &(*self.0.as_ptr()).bins;
+        if bins.size == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(bins.entries, bins.size as usize)
+        }
+    }
+}
+
+impl Drop for SourceRecord {
+    fn drop(&mut self) {
+        unsafe { as_record_destroy(self.0.as_ptr()) }
+    }
+}
+
+/// The number of threads to use when `n_threads` isn't set: the number of CPUs from
+/// `N_THREADS_ENV_VAR`, `available_parallelism` or the cgroup CPU quota, in that order.
+fn default_n_threads(fallback: NonZeroUsize) -> NonZeroUsize {
+    if let Ok(value) = std::env::var(N_THREADS_ENV_VAR) {
+        match value.parse() {
+            Ok(n_threads) => return n_threads,
+            Err(e) => warn!("Ignoring {N_THREADS_ENV_VAR}={value}: {e}"),
+        }
+    }
+    if let Ok(n_threads) = available_parallelism() {
+        return n_threads;
+    }
+    if let Some(n_threads) = cgroup_cpu_quota() {
+        return n_threads;
+    }
+    warn!("Unable to determine the number of CPUs for the Aerospike sink's threads, so defaulting to {fallback} (`default_threads`).\nSet `n_threads` in your Aerospike sink config to choose the number of threads explicitly");
+    fallback
+}
+
+/// The CPU quota of the cgroup the process runs in, rounded up, if it has one.
+fn cgroup_cpu_quota() -> Option<NonZeroUsize> {
+    if let Ok(cpu_max) = std::fs::read_to_string("/sys/fs/cgroup/cpu.max") {
+        let (quota, period) = cpu_max.trim().split_once(' ')?;
+        return cpus_from_quota(quota, period);
+    }
+    // cgroup v1
+    let quota = std::fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_quota_us").ok()?;
+    let period = std::fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_period_us").ok()?;
+    cpus_from_quota(quota.trim(), period.trim())
+}
+
+/// The number of CPUs a quota of `quota` microseconds every `period` microseconds amounts to.
+/// `None` if there's no quota, which is written as "max" or -1.
+fn cpus_from_quota(quota: &str, period: &str) -> Option<NonZeroUsize> {
+    let quota: u64 = quota.parse().ok()?;
+    let period: u64 = period.parse().ok()?;
+    if period == 0 {
+        return None;
+    }
+    NonZeroUsize::new(quota.div_ceil(period) as usize)
+}
+
 #[derive(Debug)]
 struct AerospikeSink {
-    sender: Sender<TableOperation>,
+    /// `None` once the sink has been shut down.
+    sender: Option<Sender<TableOperation>>,
+    workers: Vec<JoinHandle<()>>,
+    /// The bins of each table, by port.
+    bins: Vec<Arc<RwLock<TableBins>>>,
+    /// The checksums of the records written to each table, by port, if enabled for the table.
+    checksums: Vec<Option<WrittenChecksums>>,
     snapshotting_started_instant: HashMap<String, Instant>,
+    commit_retry_policy: RetryPolicy,
+    /// Number of times the commit of the current epoch failed.
+    failed_commits: u32,
+    lag: Arc<WriteAheadLag>,
+}
+
+/// Tracks how far the workers are behind the operations sent to them.
+#[derive(Debug)]
+struct WriteAheadLag {
+    /// The instant the `*_at` times count from.
+    start: Instant,
+    sent: AtomicU64,
+    processed: AtomicU64,
+    /// Milliseconds from `start` to when a worker last finished an operation, or to when the
+    /// workers were last given an operation while idle.
+    last_processed_at: AtomicU64,
+}
+
+impl WriteAheadLag {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            sent: AtomicU64::new(0),
+            processed: AtomicU64::new(0),
+            last_processed_at: AtomicU64::new(0),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    fn on_sent(&self) {
+        // The workers haven't been lagging while they had nothing to do
+        if self.sent.fetch_add(1, Ordering::AcqRel) == self.processed.load(Ordering::Acquire) {
+            self.last_processed_at.store(self.now(), Ordering::Release);
+        }
+    }
+
+    fn on_processed(&self) {
+        self.last_processed_at.store(self.now(), Ordering::Release);
+        self.processed.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Zero if every operation sent was processed, otherwise the time since a worker last
+    /// finished one.
+    fn lag(&self) -> Duration {
+        if self.processed.load(Ordering::Acquire) >= self.sent.load(Ordering::Acquire) {
+            return Duration::ZERO;
+        }
+        let last_processed_at = self.last_processed_at.load(Ordering::Acquire);
+        Duration::from_millis(self.now().saturating_sub(last_processed_at))
+    }
+}
+
+/// How failed commits are retried: up to `max_attempts` attempts in total, `backoff` apart.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -435,6 +1011,16 @@ struct Denormalization {
     key_field: usize,
     columns: Vec<(CString, CString)>,
     source_column_ptrs: Vec<*const c_char>,
+    /// Read the whole source record, and copy the bins that aren't in `columns` by name.
+    all_bins: bool,
+    on_bin_conflict: AerospikeBinConflictPolicy,
+    /// Enrich the record again if the source record's generation changed while it was written.
+    check_generation: bool,
+    max_consecutive_misses: Option<NonZeroU32>,
+    /// Lookups in a row that found no source record, across all workers.
+    consecutive_misses: Arc<AtomicU32>,
+    /// The bin the copied bins are written to as a map, if they aren't written as bins.
+    nest_under: Option<CString>,
 }
 
 // column ptrs
@@ -446,7 +1032,21 @@ impl Denormalization {
         set: &str,
         key_field: usize,
         columns: &[(String, String)],
+        all_bins: bool,
+        on_bin_conflict: AerospikeBinConflictPolicy,
+        check_generation: bool,
+        max_consecutive_misses: Option<NonZeroU32>,
+        nest_under: Option<&str>,
     ) -> Result<Self, AerospikeSinkError> {
+        if let Some(nest_under) = nest_under {
+            if nest_under.is_empty() {
+                return Err(AerospikeSinkError::EmptyBinName);
+            }
+            if nest_under.len() > AS_BIN_NAME_MAX_LEN as usize {
+                return Err(AerospikeSinkError::BinNameTooLong(nest_under.to_owned()));
+            }
+        }
+        let nest_under = nest_under.map(CString::new).transpose()?;
         let namespace = CString::new(namespace)?;
         let set = CString::new(set)?;
 
@@ -463,8 +1063,35 @@ impl Denormalization {
             key_field,
             columns,
             source_column_ptrs,
+            all_bins,
+            on_bin_conflict,
+            check_generation,
+            max_consecutive_misses,
+            consecutive_misses: Default::default(),
+            nest_under,
         })
     }
+
+    /// Counts a lookup that found no source record, failing once there were too many in a row.
+    fn record_miss(&self) -> Result<(), AerospikeSinkError> {
+        let misses = self.consecutive_misses.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.max_consecutive_misses {
+            Some(max) if misses >= max.get() => {
+                Err(AerospikeSinkError::DenormalizationSourceMissing {
+                    namespace: self.namespace.to_string_lossy().into_owned(),
+                    set: self.set.to_string_lossy().into_owned(),
+                    misses,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn record_hit(&self) {
+        if self.max_consecutive_misses.is_some() {
+            self.consecutive_misses.store(0, Ordering::Relaxed);
+        }
+    }
 }
 
 impl Clone for Denormalization {
@@ -479,63 +1106,428 @@ impl Clone for Denormalization {
             key_field: self.key_field,
             columns,
             source_column_ptrs,
+            all_bins: self.all_bins,
+            on_bin_conflict: self.on_bin_conflict,
+            check_generation: self.check_generation,
+            max_consecutive_misses: self.max_consecutive_misses,
+            consecutive_misses: self.consecutive_misses.clone(),
+            nest_under: self.nest_under.clone(),
         }
     }
 }
 
+/// A GeoJSON circle around a point column, written to its own bin.
+#[derive(Debug, Clone)]
+struct PointRegion {
+    point_field: usize,
+    bin: CString,
+    radius_meters: u32,
+}
+
+impl PointRegion {
+    /// The nul-terminated GeoJSON for the region, or `None` if the point is null.
+    fn geojson(&self, record: &Record) -> Option<String> {
+        let Field::Point(DozerPoint(Point(Coord { x, y }))) = &record.values[self.point_field]
+        else {
+            return None;
+        };
+        Some(format!(
+            r#"{{"type": "AeroCircle", "coordinates": [[{}, {}], {}]}}{}"#,
+            x.0, y.0, self.radius_meters, '\0'
+        ))
+    }
+}
+
 #[derive(Debug, Clone)]
 struct AerospikeTable {
     namespace: CString,
     set_name: CString,
     primary_index: usize,
-    bin_names: Vec<CString>,
+    /// Shared by the workers, so they all see the bins added and removed by `Operation::AddField`
+    /// and `Operation::RemoveField`.
+    bins: Arc<RwLock<TableBins>>,
     denormalizations: Vec<Denormalization>,
-    n_denormalization_cols: u16,
+    /// Index of the denormalization whose source records this table's records share a partition with.
+    colocate_with: Option<usize>,
+    point_regions: Vec<PointRegion>,
+    /// How each column is written. Columns added since the sink was built have the default format.
+    column_formats: Vec<ColumnFormat>,
+    on_convert_error: AerospikeConvertErrorPolicy,
+    on_conflict: AerospikeConflictPolicy,
+    key_format: AerospikeKeyFormat,
+    /// The schema the sink was built with. Columns added since aren't in it.
+    schema: Schema,
+    /// Whether updates compare the new record to the one stored in Aerospike, instead of the
+    /// old record of the operation, to find the bins to write.
+    read_before_write: bool,
+    /// Upper bound of the bytes of strings a record's fields are converted to.
+    string_capacity: usize,
+}
+
+/// How a column's values are written, where the table's config changes it.
+#[derive(Debug, Clone, Copy, Default)]
+struct ColumnFormat {
+    /// The unit a duration column is written in, if it's written as an integer.
+    duration_unit: Option<AerospikeDurationUnit>,
+    /// The order of the maps written for a json column, if they're ordered.
+    map_order: Option<AerospikeMapOrder>,
+}
+
+const DEFAULT_COLUMN_FORMAT: ColumnFormat = ColumnFormat {
+    duration_unit: None,
+    map_order: None,
+};
+
+#[derive(Debug)]
+struct TableBins {
+    /// The bin of each column.
+    names: Vec<CString>,
+    /// Indexes of the removed columns, in ascending order. Records don't have values for them
+    /// anymore, and their bins are written as nil.
+    removed: Vec<usize>,
+    /// Indexes of the columns that can't be removed, as they make up keys or point regions.
+    required: Vec<usize>,
+    /// The table's `bin_name_mapping`, for naming the bins of added columns.
+    mapping: Option<HashMap<String, String>>,
+}
+
+/// Checksums of the records last written to a table, by primary key.
+#[derive(Debug)]
+struct WrittenChecksums {
+    /// The table's schema, with the columns added since the sink was built. Removed columns stay,
+    /// as their values are restored as nulls before the checksums are computed.
+    schema: Schema,
+    primary_index: usize,
+    checksums: LruCache<Field, u64>,
+}
+
+impl WrittenChecksums {
+    /// Records the checksum of `record`, returning whether it's the one last written for its key.
+    fn is_unchanged(&mut self, record: &Record) -> bool {
+        let checksum = record.checksum(&self.schema);
+        let key = record.values[self.primary_index].clone();
+        self.checksums.put(key, checksum) == Some(checksum)
+    }
+
+    fn forget(&mut self, record: &Record) {
+        self.checksums.pop(&record.values[self.primary_index]);
+    }
+
+    /// Drops the records of `op` that didn't change, returning whether nothing is left to write.
+    fn skip_unchanged(&mut self, op: &mut Operation) -> bool {
+        let (skipped, nothing_left) = match op {
+            Operation::Insert { new } | Operation::Upsert { new } => {
+                let unchanged = self.is_unchanged(new);
+                (unchanged as u64, unchanged)
+            }
+            Operation::Update { old, new } => {
+                if old.values[self.primary_index] != new.values[self.primary_index] {
+                    self.forget(old);
+                }
+                let unchanged = self.is_unchanged(new);
+                (unchanged as u64, unchanged)
+            }
+            Operation::Delete { old } => {
+                self.forget(old);
+                (0, false)
+            }
+            Operation::BatchInsert { new } => {
+                let len = new.len();
+                new.retain(|record| !self.is_unchanged(record));
+                ((len - new.len()) as u64, new.is_empty())
+            }
+            Operation::AddField { .. } | Operation::RemoveField { .. } => (0, false),
+        };
+        if skipped > 0 {
+            counter!(SKIPPED_UNCHANGED_COUNTER_NAME, skipped);
+        }
+        nothing_left
+    }
+}
+
+impl TableBins {
+    /// Puts nulls back in place of the removed columns, in the records sent after they were removed.
+    fn restore_removed_columns(&self, op: &mut Operation) {
+        if self.removed.is_empty() {
+            return;
+        }
+        let restore = |record: &mut Record| {
+            if record.values.len() == self.names.len() - self.removed.len() {
+                for index in &self.removed {
+                    record.values.insert(*index, Field::Null);
+                }
+            }
+        };
+        match op {
+            Operation::Delete { old } => restore(old),
+            Operation::Insert { new } | Operation::Upsert { new } => restore(new),
+            Operation::Update { old, new } => {
+                restore(old);
+                restore(new);
+            }
+            Operation::BatchInsert { new } => new.iter_mut().for_each(restore),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {}
+        }
+    }
 }
 
 impl AerospikeSink {
-    fn new(client: Client, tables: Vec<AerospikeTable>, n_threads: usize) -> Self {
+    fn new(
+        client: Client,
+        tables: Vec<AerospikeTable>,
+        checksums: Vec<Option<WrittenChecksums>>,
+        n_threads: usize,
+        max_batch_size: NonZeroUsize,
+        verify_sample_percent: u8,
+        record_string_limit: Option<RecordStringLimit>,
+    ) -> Self {
         let client = Arc::new(client);
+        let bins = tables.iter().map(|table| table.bins.clone()).collect();
         let mut workers = Vec::with_capacity(n_threads);
         let (sender, receiver) = bounded(n_threads);
+        let lag = Arc::new(WriteAheadLag::new());
         for _ in 0..n_threads {
             workers.push(AerospikeSinkWorker {
                 client: client.clone(),
                 receiver: receiver.clone(),
                 tables: tables.clone(),
+                max_batch_size,
+                verify_sample_percent,
+                record_string_limit,
+                n_written: Cell::new(0),
+                lag: lag.clone(),
             });
         }
-        for mut worker in workers {
-            std::thread::spawn(move || worker.run());
-        }
+        let workers = workers
+            .into_iter()
+            .map(|mut worker| std::thread::spawn(move || worker.run()))
+            .collect();
 
         Self {
-            sender,
+            sender: Some(sender),
+            workers,
+            bins,
+            checksums,
             snapshotting_started_instant: Default::default(),
+            commit_retry_policy: Default::default(),
+            failed_commits: 0,
+            lag,
         }
     }
-}
 
-fn convert_json(value: &JsonValue) -> Result<*mut as_bin_value, AerospikeSinkError> {
-    unsafe {
-        Ok(match value.destructure_ref() {
-            DestructuredJsonRef::Null => addr_of!(as_nil) as *mut as_val as *mut as_bin_value,
-            DestructuredJsonRef::Bool(value) => {
-                check_alloc(as_boolean_new(value)) as *mut as_bin_value
-            }
-            DestructuredJsonRef::Number(value) => {
-                if let Some(float) = value.to_f64() {
-                    check_alloc(as_double_new(float)) as *mut as_bin_value
-                } else if let Some(integer) = value.to_i64() {
-                    check_alloc(as_integer_new(integer)) as *mut as_bin_value
-                } else {
-                    // If we can't represent as i64, we have a u64 that's larger than i64::MAX
-                    return Err(AerospikeSinkError::IntegerOutOfRange(
-                        value.to_u64().unwrap(),
-                    ));
-                }
+    /// Adds a bin for a column appended to the schema of the table on `port`. Records that
+    /// have the column write its value to the bin, and records that predate it don't write it.
+    fn add_bin(&self, port: PortHandle, name: &str) -> Result<(), AerospikeSinkError> {
+        let mut bins = self.bins[port as usize].write().unwrap();
+        let bin = bin_name(name, bins.mapping.as_ref())?;
+        if let Cow::Owned(bin) = &bin {
+            warn_truncated_bin_name(name, bin);
+        }
+        let bin = CString::new(bin.as_ref())?;
+        if bins.names.contains(&bin) {
+            return Err(AerospikeSinkError::DuplicateBinName(name.to_owned()));
+        }
+        bins.names.push(bin);
+        Ok(())
+    }
+
+    /// Stops writing the column removed from the schema of the table on `port`. Its bin is
+    /// written as nil from then on, so it is cleared from each record the next time it is written.
+    fn remove_bin(&self, port: PortHandle, name: &str) -> Result<(), AerospikeSinkError> {
+        let mut bins = self.bins[port as usize].write().unwrap();
+        let bin = bin_name(name, bins.mapping.as_ref())?;
+        let removed = bins
+            .names
+            .iter()
+            .enumerate()
+            .position(|(index, column_bin)| {
+                column_bin.as_bytes() == bin.as_bytes() && !bins.removed.contains(&index)
+            })
+            .ok_or_else(|| AerospikeSinkError::UnknownBin(name.to_owned()))?;
+        if bins.required.contains(&removed) {
+            return Err(AerospikeSinkError::RequiredBinRemoved(name.to_owned()));
+        }
+        let position = bins.removed.partition_point(|index| *index < removed);
+        bins.removed.insert(position, removed);
+        Ok(())
+    }
+
+    /// Stops accepting operations and waits for the workers to process the ones already queued.
+    fn shutdown(&mut self) {
+        let Some(sender) = self.sender.take() else {
+            return;
+        };
+        let pending = sender.len();
+        // Dropping the only sender disconnects the channel, so the workers exit once it is drained
+        drop(sender);
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                error!("Aerospike sink worker panicked");
             }
-            DestructuredJsonRef::String(value) => {
+        }
+        info!("Aerospike sink shut down after flushing {pending} queued operations");
+    }
+}
+
+/// The strings converted from an operation's fields. Aerospike only points to them, so they
+/// must outlive the write.
+///
+/// Strings are formatted into one buffer allocated up front. The buffer never grows, as that
+/// would move the strings already pointed to, so strings that don't fit are allocated on their own.
+#[derive(Debug, Default)]
+struct Arena {
+    buf: Vec<u8>,
+    /// Start and length of each string in `buf`. Only kept for debugging.
+    #[allow(dead_code)]
+    strings: Vec<(usize, usize)>,
+    /// Strings that didn't fit in `buf`, or were allocated by the caller. Only held, never read.
+    #[allow(dead_code)]
+    spilled: Vec<Vec<u8>>,
+    /// Total length of the strings.
+    bytes: usize,
+}
+
+impl Arena {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+            ..Default::default()
+        }
+    }
+
+    /// Keeps a string allocated by the caller alive.
+    fn push(&mut self, string: Vec<u8>) {
+        self.bytes += string.len();
+        self.spilled.push(string);
+    }
+
+    /// Formats `args` into the arena and returns the formatted string.
+    fn alloc_fmt(&mut self, args: fmt::Arguments) -> &mut [u8] {
+        let start = self.buf.len();
+        let mut writer = ArenaWriter {
+            buf: &mut self.buf,
+            start,
+            spilled: None,
+        };
+        fmt::write(&mut writer, args).expect("a Display implementation returned an error");
+        match writer.spilled {
+            None => {
+                let len = self.buf.len() - start;
+                self.bytes += len;
+                self.strings.push((start, len));
+                &mut self.buf[start..]
+            }
+            Some(string) => {
+                self.push(string);
+                self.spilled.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// Writes to the arena's buffer while it has room, and moves the string to an allocation of its
+/// own once it doesn't.
+struct ArenaWriter<'a> {
+    buf: &'a mut Vec<u8>,
+    /// Where the string starts in `buf`.
+    start: usize,
+    spilled: Option<Vec<u8>>,
+}
+
+impl fmt::Write for ArenaWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(spilled) = &mut self.spilled {
+            spilled.extend_from_slice(s.as_bytes());
+        } else if self.buf.capacity() - self.buf.len() >= s.len() {
+            self.buf.extend_from_slice(s.as_bytes());
+        } else {
+            let mut spilled = self.buf.split_off(self.start);
+            spilled.extend_from_slice(s.as_bytes());
+            self.spilled = Some(spilled);
+        }
+        Ok(())
+    }
+}
+
+/// An upper bound of the length of the string a field of type `typ` is converted to, for sizing
+/// the arena. 0 for types that aren't converted to strings, or whose strings are allocated
+/// separately anyway.
+fn converted_string_len(typ: FieldType) -> usize {
+    match typ {
+        // Sign, 39 digits
+        FieldType::U128 | FieldType::I128 => 40,
+        // Sign, point and 29 digits, or a leading zero and 28 decimal places
+        FieldType::Decimal => 31,
+        // Offset and nanoseconds included
+        FieldType::Timestamp => 35,
+        // Years beyond 9999 have a sign and more digits
+        FieldType::Date => 16,
+        // PT, u64 seconds, comma, 9 digits, S
+        FieldType::Duration => 33,
+        // Two floats and the GeoJSON around them
+        FieldType::Point => 96,
+        FieldType::UInt
+        | FieldType::Int
+        | FieldType::Float
+        | FieldType::Boolean
+        | FieldType::String
+        | FieldType::Text
+        | FieldType::Binary
+        | FieldType::Json
+        | FieldType::Vector(_) => 0,
+    }
+}
+
+/// How many bytes of converted strings a record may take, and what happens to it when it takes more.
+#[derive(Debug, Clone, Copy)]
+struct RecordStringLimit {
+    max_bytes: NonZeroUsize,
+    on_oversize: AerospikeOversizePolicy,
+}
+
+impl Drop for AerospikeSink {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn map_order_flags(order: AerospikeMapOrder) -> as_map_order {
+    match order {
+        AerospikeMapOrder::KeyOrdered => as_map_order_AS_MAP_KEY_ORDERED,
+        AerospikeMapOrder::KeyValueOrdered => as_map_order_AS_MAP_KEY_VALUE_ORDERED,
+    }
+}
+
+/// Converts a json value. Objects, including nested ones, are written as maps with `map_order`,
+/// or unordered maps if it's `None`.
+fn convert_json(
+    value: &JsonValue,
+    map_order: Option<AerospikeMapOrder>,
+) -> Result<*mut as_bin_value, AerospikeSinkError> {
+    unsafe {
+        Ok(match value.destructure_ref() {
+            DestructuredJsonRef::Null => addr_of!(as_nil) as *mut as_val as *mut as_bin_value,
+            DestructuredJsonRef::Bool(value) => {
+                check_alloc(as_boolean_new(value)) as *mut as_bin_value
+            }
+            DestructuredJsonRef::Number(value) => {
+                // Integral numbers are written as integers, even if they are stored as floats
+                // (e.g. `5.0`), so secondary indexes and arithmetic treat them as integers
+                if let Some(integer) = value
+                    .to_i64()
+                    .or_else(|| value.to_f64().and_then(integral_f64))
+                {
+                    check_alloc(as_integer_new(integer)) as *mut as_bin_value
+                } else if let Some(float) = value.to_f64() {
+                    check_alloc(as_double_new(float)) as *mut as_bin_value
+                } else {
+                    // If we can't represent as i64, we have a u64 that's larger than i64::MAX
+                    return Err(AerospikeSinkError::IntegerOutOfRange(
+                        value.to_u64().unwrap(),
+                    ));
+                }
+            }
+            DestructuredJsonRef::String(value) => {
                 let bytes = check_alloc(as_bytes_new(value.len() as u32));
                 as_bytes_set(bytes, 0, value.as_ptr(), value.len() as u32);
                 (*bytes).type_ = as_bytes_type_e_AS_BYTES_STRING;
@@ -544,7 +1536,7 @@ fn convert_json(value: &JsonValue) -> Result<*mut as_bin_value, AerospikeSinkErr
             DestructuredJsonRef::Array(value) => {
                 let list = check_alloc(as_arraylist_new(value.len() as u32, value.len() as u32));
                 for v in value.iter() {
-                    let as_value = convert_json(v)?;
+                    let as_value = convert_json(v, map_order)?;
                     if as_arraylist_append(list, as_value as *mut as_val)
                         != as_status_e_AEROSPIKE_OK
                     {
@@ -556,6 +1548,10 @@ fn convert_json(value: &JsonValue) -> Result<*mut as_bin_value, AerospikeSinkErr
             }
             DestructuredJsonRef::Object(value) => {
                 let map = check_alloc(as_orderedmap_new(value.len() as u32));
+                if let Some(order) = map_order {
+                    // `as_orderedmap` starts with its `as_map`
+                    (*(map as *mut as_map)).flags = map_order_flags(order);
+                }
                 struct Map(*mut as_orderedmap);
                 impl Drop for Map {
                     fn drop(&mut self) {
@@ -567,7 +1563,7 @@ fn convert_json(value: &JsonValue) -> Result<*mut as_bin_value, AerospikeSinkErr
                 // Make sure the map is deallocated if we encounter any error...
                 let _map_guard = Map(map);
                 for (k, v) in value.iter() {
-                    let as_value = convert_json(v)?;
+                    let as_value = convert_json(v, map_order)?;
                     let key = {
                         let bytes = check_alloc(as_bytes_new(k.len() as u32));
                         debug_assert!(as_bytes_set(bytes, 0, k.as_ptr(), k.len() as u32));
@@ -586,18 +1582,195 @@ fn convert_json(value: &JsonValue) -> Result<*mut as_bin_value, AerospikeSinkErr
     }
 }
 
+/// Converts a bin read from Aerospike back to the field of type `typ` it was written from. A
+/// missing bin is a null. Returns `None` for the bins that can't be told apart from other
+/// values, such as json, points, vectors and durations.
+unsafe fn bin_to_field(value: *const as_bin_value, typ: FieldType) -> Option<Field> {
+    if value.is_null() {
+        return Some(Field::Null);
+    }
+    #[allow(non_upper_case_globals)]
+    match ((*value).nil.type_ as as_val_type_e, typ) {
+        (as_val_type_e_AS_NIL, _) => Some(Field::Null),
+        (as_val_type_e_AS_INTEGER, FieldType::UInt) => {
+            Some(Field::UInt((*value).integer.value as u64))
+        }
+        (as_val_type_e_AS_INTEGER, FieldType::Int) => Some(Field::Int((*value).integer.value)),
+        (as_val_type_e_AS_DOUBLE, FieldType::Float) => {
+            Some(Field::Float(OrderedFloat((*value).dbl.value)))
+        }
+        (as_val_type_e_AS_BOOLEAN, FieldType::Boolean) => {
+            Some(Field::Boolean((*(value as *const as_boolean)).value))
+        }
+        (as_val_type_e_AS_STRING, typ) => {
+            let string = CStr::from_ptr((*value).string.value).to_str().ok()?;
+            match typ {
+                FieldType::String => Some(Field::String(string.to_owned())),
+                FieldType::Text => Some(Field::Text(string.to_owned())),
+                FieldType::U128 => string.parse().ok().map(Field::U128),
+                FieldType::I128 => string.parse().ok().map(Field::I128),
+                FieldType::Decimal => string.parse().ok().map(Field::Decimal),
+                FieldType::Timestamp => DateTime::parse_from_rfc3339(string)
+                    .ok()
+                    .map(Field::Timestamp),
+                FieldType::Date => string.parse::<NaiveDate>().ok().map(Field::Date),
+                _ => None,
+            }
+        }
+        (as_val_type_e_AS_BYTES, FieldType::Binary) => {
+            let bytes = &(*value).bytes;
+            Some(Field::Binary(
+                std::slice::from_raw_parts(bytes.value, bytes.size as usize).to_vec(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The value of a float without a fractional part, if it fits in an `i64`.
+fn integral_f64(float: f64) -> Option<i64> {
+    // `i64::MAX as f64` rounds up to 2^63, which is out of range
+    (float.fract() == 0.0 && float >= i64::MIN as f64 && float < i64::MAX as f64)
+        .then_some(float as i64)
+}
+
+/// Converts a json field, applying the table's `on_convert_error` policy if conversion fails.
+/// Returns `None` if the field should be written as nil.
+fn convert_json_field(
+    value: &JsonValue,
+    map_order: Option<AerospikeMapOrder>,
+    on_convert_error: AerospikeConvertErrorPolicy,
+) -> Result<Option<*mut as_bin_value>, AerospikeSinkError> {
+    apply_convert_error_policy(convert_json(value, map_order), on_convert_error)
+}
+
+/// Converts a duration written as an integer, applying the table's `on_convert_error` policy if
+/// its length doesn't fit. Returns `None` if the field should be written as nil.
+fn convert_duration_field(
+    duration: Duration,
+    unit: AerospikeDurationUnit,
+    on_convert_error: AerospikeConvertErrorPolicy,
+) -> Result<Option<i64>, AerospikeSinkError> {
+    apply_convert_error_policy(duration_integer(duration, unit), on_convert_error)
+}
+
+/// Converts an unsigned integer to the signed integer Aerospike stores, applying the table's
+/// `on_convert_error` policy if it's larger than `i64::MAX`. Returns `None` if the field should be
+/// written as nil.
+fn convert_uint_field(
+    value: u64,
+    on_convert_error: AerospikeConvertErrorPolicy,
+) -> Result<Option<i64>, AerospikeSinkError> {
+    apply_convert_error_policy(
+        i64::try_from(value).map_err(|_| AerospikeSinkError::IntegerOutOfRange(value)),
+        on_convert_error,
+    )
+}
+
+fn apply_convert_error_policy<T>(
+    result: Result<T, AerospikeSinkError>,
+    on_convert_error: AerospikeConvertErrorPolicy,
+) -> Result<Option<T>, AerospikeSinkError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => match on_convert_error {
+            AerospikeConvertErrorPolicy::Error => Err(e),
+            AerospikeConvertErrorPolicy::Skip => {
+                Err(AerospikeSinkError::RecordSkipped(Box::new(e)))
+            }
+            AerospikeConvertErrorPolicy::NullField => {
+                warn!("Writing nil for field that failed type conversion: {e}");
+                increment_counter!(CONVERT_ERROR_COUNTER_NAME, ACTION_LABEL => "null_field");
+                Ok(None)
+            }
+        },
+    }
+}
+
+/// Vectors are written as a blob of little-endian `f32`s, 4 bytes per element.
+fn vector_to_bytes(vector: &[OrderedFloat<f32>]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|element| element.0.to_le_bytes())
+        .collect()
+}
+
+/// Converts the length of a duration key to an integer key.
+fn duration_key(length: u128) -> Result<i64, AerospikeSinkError> {
+    let length = u64::try_from(length).unwrap_or(u64::MAX);
+    i64::try_from(length).map_err(|_| AerospikeSinkError::IntegerOutOfRange(length))
+}
+
+/// The length of a duration in `unit`, for writing it as an integer bin.
+fn duration_integer(
+    duration: Duration,
+    unit: AerospikeDurationUnit,
+) -> Result<i64, AerospikeSinkError> {
+    match unit {
+        AerospikeDurationUnit::Nanoseconds => duration_key(duration.as_nanos()),
+        AerospikeDurationUnit::Milliseconds => duration_key(duration.as_millis()),
+    }
+}
+
+/// The kind of an operation and the primary key it writes, for logging. Batches have no key.
+fn describe_operation(op: &Operation, primary_index: usize) -> (&'static str, Option<Field>) {
+    match op {
+        Operation::Insert { new } => ("insert", Some(new.values[primary_index].clone())),
+        Operation::Delete { old } => ("delete", Some(old.values[primary_index].clone())),
+        Operation::Update { new, .. } => ("update", Some(new.values[primary_index].clone())),
+        Operation::Upsert { new } => ("upsert", Some(new.values[primary_index].clone())),
+        Operation::BatchInsert { .. } => ("batch_insert", None),
+        Operation::AddField { .. } => ("add_field", None),
+        Operation::RemoveField { .. } => ("remove_field", None),
+    }
+}
+
+/// Whether the `n`th written record is read back, spreading `percent` out of every 100 records
+/// evenly.
+fn is_sampled(n: u64, percent: u8) -> bool {
+    let percent = percent as u64;
+    n * percent / 100 != (n + 1) * percent / 100
+}
+
+fn report_skipped_record(error: &AerospikeSinkError) {
+    warn!("{error}");
+    increment_counter!(CONVERT_ERROR_COUNTER_NAME, ACTION_LABEL => "skip");
+}
+
 struct AerospikeSinkWorker {
     client: Arc<Client>,
     receiver: Receiver<TableOperation>,
     tables: Vec<AerospikeTable>,
+    max_batch_size: NonZeroUsize,
+    /// Percentage of the records of written batches that are read back. 0 if writes aren't verified.
+    verify_sample_percent: u8,
+    record_string_limit: Option<RecordStringLimit>,
+    /// Number of records written in batches, for sampling the ones to read back.
+    n_written: Cell<u64>,
+    lag: Arc<WriteAheadLag>,
 }
 
 impl AerospikeSinkWorker {
     fn run(&mut self) {
         while let Ok(op) = self.receiver.recv() {
-            if let Err(e) = self.process_impl(op) {
-                error!("Error processing operation: {}", e);
+            let port = op.port as usize;
+            let (operation, key) = describe_operation(&op.op, self.tables[port].primary_index);
+            match self.process_impl(op) {
+                Ok(()) => {}
+                Err(e @ AerospikeSinkError::RecordSkipped(_)) => report_skipped_record(&e),
+                Err(e) => {
+                    let table = &self.tables[port];
+                    tracing::error!(
+                        namespace = %table.namespace.to_string_lossy(),
+                        set = %table.set_name.to_string_lossy(),
+                        operation,
+                        key = key.as_ref().map(tracing::field::display),
+                        error = %e,
+                        "Error processing operation",
+                    );
+                }
             }
+            self.lag.on_processed();
         }
     }
 
@@ -607,13 +1780,13 @@ impl AerospikeSinkWorker {
         key: *mut as_key,
         namespace: &CStr,
         set: &CStr,
-        mut string: String,
-        allocated_strings: &mut Vec<String>,
+        args: fmt::Arguments,
+        allocated_strings: &mut Arena,
     ) {
         unsafe {
+            let string = allocated_strings.alloc_fmt(args);
             let bytes = as_bytes_new_wrap(string.as_mut_ptr(), string.len() as u32, false);
             (*bytes).type_ = as_bytes_type_e_AS_BYTES_STRING;
-            allocated_strings.push(string);
             as_key_init_value(
                 key,
                 namespace.as_ptr(),
@@ -629,7 +1802,8 @@ impl AerospikeSinkWorker {
         namespace: &CStr,
         set: &CStr,
         key_field: &Field,
-        allocated_strings: &mut Vec<String>,
+        key_format: AerospikeKeyFormat,
+        allocated_strings: &mut Arena,
     ) -> Result<(), AerospikeSinkError> {
         unsafe {
             match key_field {
@@ -640,13 +1814,13 @@ impl AerospikeSinkWorker {
                     as_key_init_int64(key, namespace.as_ptr(), set.as_ptr(), *v);
                 }
                 Field::U128(v) => {
-                    self.set_str_key(key, namespace, set, v.to_string(), allocated_strings)
+                    self.set_str_key(key, namespace, set, format_args!("{v}"), allocated_strings)
                 }
                 Field::I128(v) => {
-                    self.set_str_key(key, namespace, set, v.to_string(), allocated_strings)
+                    self.set_str_key(key, namespace, set, format_args!("{v}"), allocated_strings)
                 }
                 Field::Decimal(v) => {
-                    self.set_str_key(key, namespace, set, v.to_string(), allocated_strings)
+                    self.set_str_key(key, namespace, set, format_args!("{v}"), allocated_strings)
                 }
                 // For keys, we need to allocate a new CString, because there is no
                 // API to set a key to a string that's not null-terminated. For bin
@@ -675,30 +1849,79 @@ impl AerospikeSinkWorker {
                     );
                 }
 
-                Field::Timestamp(v) => self.set_str_key(
-                    key,
-                    namespace,
-                    set,
-                    // Use a delayed formatting to RFC3339 so we don't have to allocate an
-                    // intermediate rust String
-                    v.to_rfc3339(),
-                    allocated_strings,
-                ),
-                // Date's display implementation is RFC3339 compatible
-                Field::Date(v) => {
-                    self.set_str_key(key, namespace, set, v.to_string(), allocated_strings)
-                }
-                // We can ignore the time unit, as we always output a
-                // full-resolution duration
-                Field::Duration(DozerDuration(duration, _)) => self.set_str_key(
-                    key,
-                    namespace,
-                    set,
-                    format!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
-                    allocated_strings,
-                ),
+                Field::Timestamp(v) => match key_format {
+                    AerospikeKeyFormat::Rfc3339 => self.set_str_key(
+                        key,
+                        namespace,
+                        set,
+                        format_args!("{}", v.to_rfc3339()),
+                        allocated_strings,
+                    ),
+                    AerospikeKeyFormat::EpochMillis => {
+                        as_key_init_int64(
+                            key,
+                            namespace.as_ptr(),
+                            set.as_ptr(),
+                            v.timestamp_millis(),
+                        );
+                    }
+                    AerospikeKeyFormat::EpochSeconds => {
+                        as_key_init_int64(key, namespace.as_ptr(), set.as_ptr(), v.timestamp());
+                    }
+                },
+                Field::Date(v) => match key_format {
+                    // Date's display implementation is RFC3339 compatible
+                    AerospikeKeyFormat::Rfc3339 => self.set_str_key(
+                        key,
+                        namespace,
+                        set,
+                        format_args!("{v}"),
+                        allocated_strings,
+                    ),
+                    AerospikeKeyFormat::EpochMillis => {
+                        let midnight = v.and_hms_opt(0, 0, 0).unwrap().and_utc();
+                        as_key_init_int64(
+                            key,
+                            namespace.as_ptr(),
+                            set.as_ptr(),
+                            midnight.timestamp_millis(),
+                        );
+                    }
+                    AerospikeKeyFormat::EpochSeconds => {
+                        let midnight = v.and_hms_opt(0, 0, 0).unwrap().and_utc();
+                        as_key_init_int64(
+                            key,
+                            namespace.as_ptr(),
+                            set.as_ptr(),
+                            midnight.timestamp(),
+                        );
+                    }
+                },
+                Field::Duration(DozerDuration(duration, _)) => match key_format {
+                    // We can ignore the time unit, as we always output a
+                    // full-resolution duration
+                    AerospikeKeyFormat::Rfc3339 => self.set_str_key(
+                        key,
+                        namespace,
+                        set,
+                        format_args!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
+                        allocated_strings,
+                    ),
+                    AerospikeKeyFormat::EpochMillis => {
+                        let millis = duration_key(duration.as_millis())?;
+                        as_key_init_int64(key, namespace.as_ptr(), set.as_ptr(), millis);
+                    }
+                    AerospikeKeyFormat::EpochSeconds => {
+                        let seconds = duration_key(duration.as_secs().into())?;
+                        as_key_init_int64(key, namespace.as_ptr(), set.as_ptr(), seconds);
+                    }
+                },
                 Field::Null => unreachable!("Primary key cannot be null"),
-                Field::Boolean(_) | Field::Json(_) | Field::Point(_) | Field::Float(_) => {
+                Field::Boolean(_)
+                | Field::Json(_)
+                | Field::Point(_)
+                | Field::Float(_)
+                | Field::Vector(_) => {
                     unreachable!("Unsupported primary key type. If this is reached, it means this record does not conform to the schema.")
                 }
             };
@@ -706,19 +1929,55 @@ impl AerospikeSinkWorker {
         Ok(())
     }
 
+    /// Moves `key` to the partition of the record `record` is denormalized from, by copying the
+    /// partition bytes of that record's digest into the key's digest.
+    unsafe fn colocate_key(
+        &self,
+        key: *mut as_key,
+        table: &AerospikeTable,
+        record: &Record,
+        allocated_strings: &mut Arena,
+    ) -> Result<(), AerospikeSinkError> {
+        let Some(index) = table.colocate_with else {
+            return Ok(());
+        };
+        let Denormalization {
+            namespace,
+            set,
+            key_field,
+            ..
+        } = &table.denormalizations[index];
+        unsafe {
+            let mut _source_key = MaybeUninit::uninit();
+            self.init_key(
+                _source_key.as_mut_ptr(),
+                namespace,
+                set,
+                &record.values[*key_field],
+                table.key_format,
+                allocated_strings,
+            )?;
+            let mut source_key = Key(_source_key.assume_init_mut());
+            as_try(|err| as_key_set_digest(err, source_key.as_mut_ptr()))?;
+            as_try(|err| as_key_set_digest(err, key))?;
+            (*key).digest.value[..PARTITION_DIGEST_BYTES]
+                .copy_from_slice(&(*source_key.as_ptr()).digest.value[..PARTITION_DIGEST_BYTES]);
+        }
+        Ok(())
+    }
+
     unsafe fn rec_set_str(
         record: *mut as_record,
         name: *const c_char,
-        string: String,
-        allocated_strings: &mut Vec<String>,
+        args: fmt::Arguments,
+        allocated_strings: &mut Arena,
     ) {
         Self::rec_set_bytes(
             record,
             name,
-            string.as_bytes(),
+            allocated_strings.alloc_fmt(args),
             as_bytes_type_e_AS_BYTES_STRING,
         );
-        allocated_strings.push(string);
     }
 
     unsafe fn rec_set_bytes(
@@ -732,29 +1991,58 @@ impl AerospikeSinkWorker {
         as_record_set_raw_typep(record, name, ptr, len as u32, type_, false);
     }
 
+    #[allow(clippy::too_many_arguments)]
     unsafe fn init_record(
         &self,
         record: *mut as_record,
         dozer_record: &Record,
         bin_names: &[CString],
+        point_regions: &[PointRegion],
+        column_formats: &[ColumnFormat],
+        written_columns: Option<&[bool]>,
         n_extra_cols: u16,
-        allocated_strings: &mut Vec<String>,
+        on_convert_error: AerospikeConvertErrorPolicy,
+        allocated_strings: &mut Arena,
     ) -> Result<(), AerospikeSinkError> {
-        as_record_init(record, dozer_record.values.len() as u16 + n_extra_cols);
-        for (def, field) in bin_names.iter().zip(&dozer_record.values) {
+        as_record_init(
+            record,
+            dozer_record.values.len() as u16 + point_regions.len() as u16 + n_extra_cols,
+        );
+        for (index, ((def, field), format)) in bin_names
+            .iter()
+            .zip(&dozer_record.values)
+            .zip(
+                column_formats
+                    .iter()
+                    .chain(iter::repeat(&DEFAULT_COLUMN_FORMAT)),
+            )
+            .enumerate()
+        {
+            if written_columns.is_some_and(|written| !written[index]) {
+                continue;
+            }
             let name = def.as_ptr();
             match field {
-                Field::UInt(v) => {
-                    as_record_set_int64(record, name, *v as i64);
-                }
+                Field::UInt(v) => match convert_uint_field(*v, on_convert_error) {
+                    Ok(Some(value)) => {
+                        as_record_set_int64(record, name, value);
+                    }
+                    Ok(None) => {
+                        as_record_set_nil(record, name);
+                    }
+                    Err(e) => {
+                        as_record_destroy(record);
+                        return Err(e);
+                    }
+                },
                 Field::U128(v) => {
-                    Self::rec_set_str(record, name, v.to_string(), allocated_strings);
+                    Self::rec_set_str(record, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Int(v) => {
                     as_record_set_int64(record, name, *v);
                 }
                 Field::I128(v) => {
-                    Self::rec_set_str(record, name, v.to_string(), allocated_strings);
+                    Self::rec_set_str(record, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Float(OrderedFloat(v)) => {
                     as_record_set_double(record, name, *v);
@@ -775,24 +2063,48 @@ impl AerospikeSinkWorker {
                 Field::Binary(v) => {
                     as_record_set_rawp(record, name, v.as_ptr(), v.len() as u32, false);
                 }
+                Field::Vector(v) => {
+                    let bytes = vector_to_bytes(v);
+                    as_record_set_rawp(record, name, bytes.as_ptr(), bytes.len() as u32, false);
+                    allocated_strings.push(bytes);
+                }
                 Field::Decimal(v) => {
-                    Self::rec_set_str(record, name, v.to_string(), allocated_strings);
+                    Self::rec_set_str(record, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Timestamp(v) => {
-                    Self::rec_set_str(record, name, v.to_rfc3339(), allocated_strings);
+                    Self::rec_set_str(
+                        record,
+                        name,
+                        format_args!("{}", v.to_rfc3339()),
+                        allocated_strings,
+                    );
                 }
                 // Date's display implementation is RFC3339 compatible
                 Field::Date(v) => {
-                    Self::rec_set_str(record, name, v.to_string(), allocated_strings);
+                    Self::rec_set_str(record, name, format_args!("{v}"), allocated_strings);
                 }
-                Field::Duration(DozerDuration(duration, _)) => {
-                    Self::rec_set_str(
+                Field::Duration(DozerDuration(duration, _)) => match format.duration_unit {
+                    Some(unit) => {
+                        match convert_duration_field(*duration, *unit, on_convert_error) {
+                            Ok(Some(value)) => {
+                                as_record_set_int64(record, name, value);
+                            }
+                            Ok(None) => {
+                                as_record_set_nil(record, name);
+                            }
+                            Err(e) => {
+                                as_record_destroy(record);
+                                return Err(e);
+                            }
+                        }
+                    }
+                    None => Self::rec_set_str(
                         record,
                         name,
-                        format!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
+                        format_args!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
                         allocated_strings,
-                    );
-                }
+                    ),
+                },
                 Field::Null => {
                     as_record_set_nil(record, name);
                 }
@@ -803,16 +2115,35 @@ impl AerospikeSinkWorker {
                     // a plain string format. Instead, we just make sure we include a nul-byte
                     // in our regular string, as that is easiest to integration with the other
                     // string allocations.
-                    let string = format!(
+                    let string = allocated_strings.alloc_fmt(format_args!(
                         r#"{{"type": "Point", "coordinates": [{}, {}]}}{}"#,
                         x.0, y.0, '\0'
-                    );
+                    ));
                     as_record_set_geojson_strp(record, name, string.as_ptr().cast(), false);
-                    allocated_strings.push(string);
                 }
-                Field::Json(v) => {
-                    let value = convert_json(v)?;
-                    as_record_set(record, name, value);
+                Field::Json(v) => match convert_json_field(v, format.map_order, on_convert_error) {
+                    Ok(Some(value)) => {
+                        as_record_set(record, name, value);
+                    }
+                    Ok(None) => {
+                        as_record_set_nil(record, name);
+                    }
+                    Err(e) => {
+                        as_record_destroy(record);
+                        return Err(e);
+                    }
+                },
+            }
+        }
+        for region in point_regions {
+            let name = region.bin.as_ptr();
+            match region.geojson(dozer_record) {
+                Some(string) => {
+                    as_record_set_geojson_strp(record, name, string.as_ptr().cast(), false);
+                    allocated_strings.push(string.into_bytes());
+                }
+                None => {
+                    as_record_set_nil(record, name);
                 }
             }
         }
@@ -822,12 +2153,12 @@ impl AerospikeSinkWorker {
     unsafe fn set_operation_str(
         ops: *mut as_operations,
         name: *const c_char,
-        mut string: String,
-        allocated_strings: &mut Vec<String>,
+        args: fmt::Arguments,
+        allocated_strings: &mut Arena,
     ) {
+        let string = allocated_strings.alloc_fmt(args);
         let ptr = string.as_mut_ptr();
         let len = string.len();
-        allocated_strings.push(string);
         // Unfortunately we need to do an allocation here for the bytes container.
         // This is because as_operations does not allow setting a bytes type in
         // its operations api. TODO: Add a raw_typep api like `as_record_set_raw_typep`
@@ -842,9 +2173,16 @@ impl AerospikeSinkWorker {
         ops: *mut as_operations,
         dozer_record: &Record,
         bin_names: &[CString],
-        allocated_strings: &mut Vec<String>,
+        point_regions: &[PointRegion],
+        column_formats: &[ColumnFormat],
+        on_convert_error: AerospikeConvertErrorPolicy,
+        allocated_strings: &mut Arena,
     ) -> Result<(), AerospikeSinkError> {
-        for (def, field) in bin_names.iter().zip(&dozer_record.values) {
+        for ((def, field), format) in bin_names.iter().zip(&dozer_record.values).zip(
+            column_formats
+                .iter()
+                .chain(iter::repeat(&DEFAULT_COLUMN_FORMAT)),
+        ) {
             let name = def.as_ptr();
             // This is almost the same as the implementation for keys,
             // the key difference being that we don't have to allocate a new
@@ -853,16 +2191,23 @@ impl AerospikeSinkWorker {
             // TODO: Unify the implementations
             match field {
                 Field::UInt(v) => {
-                    as_operations_add_write_int64(ops, name, *v as i64);
+                    match convert_uint_field(*v, on_convert_error)? {
+                        Some(value) => as_operations_add_write_int64(ops, name, value),
+                        None => as_operations_add_write(
+                            ops,
+                            name,
+                            addr_of!(as_nil) as *mut as_bin_value,
+                        ),
+                    };
                 }
                 Field::U128(v) => {
-                    Self::set_operation_str(ops, name, v.to_string(), allocated_strings);
+                    Self::set_operation_str(ops, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Int(v) => {
                     as_operations_add_write_int64(ops, name, *v);
                 }
                 Field::I128(v) => {
-                    Self::set_operation_str(ops, name, v.to_string(), allocated_strings);
+                    Self::set_operation_str(ops, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Float(v) => {
                     as_operations_add_write_double(ops, name, v.0);
@@ -882,24 +2227,50 @@ impl AerospikeSinkWorker {
                 Field::Binary(v) => {
                     as_operations_add_write_rawp(ops, name, v.as_ptr(), v.len() as u32, false);
                 }
+                Field::Vector(v) => {
+                    let bytes = vector_to_bytes(v);
+                    as_operations_add_write_rawp(
+                        ops,
+                        name,
+                        bytes.as_ptr(),
+                        bytes.len() as u32,
+                        false,
+                    );
+                    allocated_strings.push(bytes);
+                }
                 Field::Decimal(v) => {
-                    Self::set_operation_str(ops, name, v.to_string(), allocated_strings);
+                    Self::set_operation_str(ops, name, format_args!("{v}"), allocated_strings);
                 }
                 Field::Timestamp(v) => {
-                    Self::set_operation_str(ops, name, v.to_rfc3339(), allocated_strings);
+                    Self::set_operation_str(
+                        ops,
+                        name,
+                        format_args!("{}", v.to_rfc3339()),
+                        allocated_strings,
+                    );
                 }
                 // Date's display implementation is RFC3339 compatible
                 Field::Date(v) => {
-                    Self::set_operation_str(ops, name, v.to_string(), allocated_strings);
+                    Self::set_operation_str(ops, name, format_args!("{v}"), allocated_strings);
                 }
-                Field::Duration(DozerDuration(duration, _)) => {
-                    Self::set_operation_str(
+                Field::Duration(DozerDuration(duration, _)) => match format.duration_unit {
+                    Some(unit) => {
+                        match convert_duration_field(*duration, *unit, on_convert_error)? {
+                            Some(value) => as_operations_add_write_int64(ops, name, value),
+                            None => as_operations_add_write(
+                                ops,
+                                name,
+                                addr_of!(as_nil) as *mut as_bin_value,
+                            ),
+                        };
+                    }
+                    None => Self::set_operation_str(
                         ops,
                         name,
-                        format!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
+                        format_args!("PT{},{:09}S", duration.as_secs(), duration.subsec_nanos()),
                         allocated_strings,
-                    );
-                }
+                    ),
+                },
                 Field::Null => {
                     // as_bin_value is a union, with nil being an as_val. It is therefore
                     // valid to just cast a pointer to the as_nil constant (of type as_val),
@@ -912,42 +2283,69 @@ impl AerospikeSinkWorker {
                     // in our regular string, as that is easiest to integration with the other
                     // string allocations being `String` and not `CString`. We know we won't
                     // have any intermediate nul-bytes, as we control the string
-                    let string = format!(
+                    let string = allocated_strings.alloc_fmt(format_args!(
                         r#"{{"type": "Point", "coordinates": [{}, {}]}}{}"#,
                         x.0, y.0, '\0'
-                    );
+                    ));
                     as_operations_add_write_geojson_strp(ops, name, string.as_ptr().cast(), false);
-                    allocated_strings.push(string);
                 }
                 Field::Json(v) => {
-                    as_operations_add_write(ops, name, convert_json(v)?);
+                    let value = convert_json_field(v, format.map_order, on_convert_error)?
+                        .unwrap_or(addr_of!(as_nil) as *mut as_bin_value);
+                    as_operations_add_write(ops, name, value);
+                }
+            }
+        }
+        for region in point_regions {
+            let name = region.bin.as_ptr();
+            match region.geojson(dozer_record) {
+                Some(string) => {
+                    as_operations_add_write_geojson_strp(ops, name, string.as_ptr().cast(), false);
+                    allocated_strings.push(string.into_bytes());
+                }
+                None => {
+                    as_operations_add_write(ops, name, addr_of!(as_nil) as *mut as_bin_value);
                 }
             }
         }
         Ok(())
     }
 
-    fn process_impl(&mut self, op: TableOperation) -> Result<(), AerospikeSinkError> {
+    fn process_impl(&mut self, mut op: TableOperation) -> Result<(), AerospikeSinkError> {
         let table = &self.tables[op.port as usize];
+        table
+            .bins
+            .read()
+            .unwrap()
+            .restore_removed_columns(&mut op.op);
 
-        if !table.denormalizations.is_empty() {
+        // Batch writes can't skip the records that already exist, so those tables insert one
+        // record at a time
+        if !table.denormalizations.is_empty()
+            || table.on_conflict == AerospikeConflictPolicy::SkipOnExist
+        {
             if let Operation::BatchInsert { new } = op.op {
                 for rec in new.into_iter() {
-                    self.process_impl(TableOperation {
+                    match self.process_impl(TableOperation {
                         op: Operation::Insert { new: rec },
                         id: op.id,
                         port: op.port,
-                    })?;
+                    }) {
+                        Err(e @ AerospikeSinkError::RecordSkipped(_)) => report_skipped_record(&e),
+                        result => result?,
+                    }
                 }
                 return Ok(());
             }
         }
-        // XXX: We know from the schema how many strings we have to allocate,
-        // so we could optimize this to allocate the correct amount ahead
-        // of time. Furthermore, we also know (an upper bound of) the total size of the strings we
-        // have to allocate, so we could just allocate one large Vec<u8>, and
-        // use that for all string allocations, like an arena
-        let mut allocated_strings = Vec::new();
+        // Updates convert the key of the old record as well as the new record
+        let n_records = match &op.op {
+            Operation::BatchInsert { new } => new.len(),
+            Operation::Update { .. } => 2,
+            _ => 1,
+        };
+        let mut allocated_strings =
+            Arena::with_capacity(self.tables[op.port as usize].string_capacity * n_records);
         match op.op {
             Operation::Insert { new } => {
                 // We create the key and record on the stack, because we can
@@ -965,67 +2363,121 @@ impl AerospikeSinkWorker {
                         &table.namespace,
                         &table.set_name,
                         &new.values[table.primary_index],
+                        table.key_format,
                         &mut allocated_strings,
                     )?;
-                    let k = Key(key.assume_init_mut());
-                    self.init_record(
-                        _record.as_mut_ptr(),
-                        &new,
-                        &table.bin_names,
-                        table.n_denormalization_cols,
-                        &mut allocated_strings,
-                    )?;
-                    let mut record = AsRecord(_record.assume_init_mut());
-                    for Denormalization {
-                        key_field,
-                        source_column_ptrs,
-                        namespace,
-                        set,
-                        columns,
-                    } in &table.denormalizations
-                    {
-                        let mut _key = MaybeUninit::uninit();
-                        self.init_key(
-                            _key.as_mut_ptr(),
-                            namespace,
-                            set,
-                            &new.values[*key_field],
+                    let mut k = Key(key.assume_init_mut());
+                    self.colocate_key(k.as_mut_ptr(), table, &new, &mut allocated_strings)?;
+                    let mut attempt = 0;
+                    loop {
+                        let strings_start = allocated_strings.bytes;
+                        // The source records are read first, as the record must have room for
+                        // every bin copied from them when it is initialized
+                        let sources = table
+                            .denormalizations
+                            .iter()
+                            .map(|denorm| {
+                                self.read_denormalization_source(
+                                    denorm,
+                                    &new,
+                                    table.key_format,
+                                    &mut allocated_strings,
+                                )
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let n_copied_bins = table
+                            .denormalizations
+                            .iter()
+                            .zip(&sources)
+                            .map(|(denorm, source)| {
+                                if denorm.nest_under.is_some() {
+                                    return 1;
+                                }
+                                let n_all_bins = if denorm.all_bins {
+                                    source.bins().len()
+                                } else {
+                                    0
+                                };
+                                (denorm.columns.len() + n_all_bins) as u16
+                            })
+                            .sum();
+                        self.init_record(
+                            _record.as_mut_ptr(),
+                            &new,
+                            &table.bins.read().unwrap().names,
+                            &table.point_regions,
+                            &table.column_formats,
+                            None,
+                            n_copied_bins,
+                            table.on_convert_error,
                             &mut allocated_strings,
                         )?;
-                        let key = Key(_key.assume_init_mut());
-                        let mut _rec = MaybeUninit::uninit();
-                        as_record_init(_rec.as_mut_ptr(), columns.len() as u16);
-                        let mut denorm_rec = AsRecord(_rec.assume_init_mut());
-                        loop {
-                            #[allow(non_upper_case_globals)]
-                            match self.client.select(
-                                key.as_ptr(),
-                                source_column_ptrs,
-                                &mut denorm_rec.as_mut_ptr(),
-                            ) {
-                                Ok(()) => break,
-                                // If the record is not found, wait and try again,
-                                // we are probably behind the task responsible for writing it
-                                Err(AerospikeError {
-                                    code: as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND,
-                                    message: _,
-                                }) => std::thread::sleep(Duration::from_millis(100)),
-                                Err(e) => return Err(e.into()),
+                        let mut record = AsRecord(_record.assume_init_mut());
+                        self.check_record_strings(allocated_strings.bytes - strings_start)?;
+                        for (denorm, source) in table.denormalizations.iter().zip(&sources) {
+                            if let Some(nest_under) = &denorm.nest_under {
+                                let map = Self::nested_bins(denorm, source)?;
+                                as_record_set(
+                                    record.as_mut_ptr(),
+                                    nest_under.as_ptr(),
+                                    map as *mut as_bin_value,
+                                );
+                                continue;
+                            }
+                            for (src, dst) in &denorm.columns {
+                                let val = as_record_get(source.as_ptr(), src.as_ptr());
+
+                                // Increment ref count, so we can destroy the source record
+                                // without dropping the bin values
+                                as_val_val_reserve(val as *mut as_val);
+                                as_record_set(record.as_mut_ptr(), dst.as_ptr(), val);
+                            }
+                            if denorm.all_bins {
+                                Self::copy_all_bins(record.as_mut_ptr(), denorm, source)?;
                             }
                         }
-                        // The column_ptrs array needs to end with a null ptr, so use
-                        // `columns` for the bound instead
-                        for (src, dst) in columns {
-                            let val = as_record_get(denorm_rec.as_mut_ptr(), src.as_ptr());
-
-                            // Increment ref count, so we can destroy the denorm record
-                            // without dropping the bin values
-                            as_val_val_reserve(val as *mut as_val);
-                            as_record_set(record.as_mut_ptr(), dst.as_ptr(), val);
+                        // A retry overwrites the record written by the previous attempt
+                        if attempt > 0 {
+                            self.client.upsert(k.as_ptr(), record.as_mut_ptr())?;
+                        } else {
+                            match table.on_conflict {
+                                AerospikeConflictPolicy::FailOnExist => {
+                                    self.client.insert(k.as_ptr(), record.as_mut_ptr())?
+                                }
+                                AerospikeConflictPolicy::Upsert => {
+                                    self.client.upsert(k.as_ptr(), record.as_mut_ptr())?
+                                }
+                                AerospikeConflictPolicy::SkipOnExist => {
+                                    match self.client.create(k.as_ptr(), record.as_mut_ptr()) {
+                                        #[allow(non_upper_case_globals)]
+                                        Err(AerospikeError {
+                                            code: as_status_e_AEROSPIKE_ERR_RECORD_EXISTS,
+                                            message: _,
+                                        }) => break,
+                                        result => result?,
+                                    }
+                                }
+                            }
+                        }
+                        drop(record);
+
+                        let Some(denorm) = self.changed_denormalization_source(
+                            &table.denormalizations,
+                            &sources,
+                            &new,
+                            table.key_format,
+                            &mut allocated_strings,
+                        )?
+                        else {
+                            break;
+                        };
+                        if attempt == MAX_GENERATION_RETRIES {
+                            return Err(AerospikeSinkError::DenormalizationSourceChanged(
+                                denorm.set.to_string_lossy().into_owned(),
+                            ));
                         }
-                        as_record_destroy(denorm_rec.as_mut_ptr());
+                        attempt += 1;
                     }
-                    self.client.insert(k.as_ptr(), record.as_mut_ptr())?;
                 }
             }
             Operation::Delete { old } => {
@@ -1036,13 +2488,33 @@ impl AerospikeSinkWorker {
                         &table.namespace,
                         &table.set_name,
                         &old.values[table.primary_index],
+                        table.key_format,
                         &mut allocated_strings,
                     )?;
-                    let k = Key(key.assume_init_mut());
+                    let mut k = Key(key.assume_init_mut());
+                    self.colocate_key(k.as_mut_ptr(), table, &old, &mut allocated_strings)?;
                     self.client.delete(k.as_ptr())?;
                 }
             }
             Operation::Update { old, new } => {
+                // A colocated record moves to another partition when its denormalization
+                // source changes, so it has to be rewritten under its new digest
+                if let Some(index) = table.colocate_with {
+                    let key_field = table.denormalizations[index].key_field;
+                    if old.values[key_field] != new.values[key_field] {
+                        let (id, port) = (op.id, op.port);
+                        self.process_impl(TableOperation {
+                            op: Operation::Delete { old },
+                            id,
+                            port,
+                        })?;
+                        return self.process_impl(TableOperation {
+                            op: Operation::Insert { new },
+                            id,
+                            port,
+                        });
+                    }
+                }
                 let mut key = MaybeUninit::uninit();
                 let mut record = MaybeUninit::uninit();
                 unsafe {
@@ -1051,20 +2523,73 @@ impl AerospikeSinkWorker {
                         &table.namespace,
                         &table.set_name,
                         &old.values[table.primary_index],
+                        table.key_format,
                         &mut allocated_strings,
                     )?;
-                    let k = Key(key.assume_init_mut());
+                    let mut k = Key(key.assume_init_mut());
+                    self.colocate_key(k.as_mut_ptr(), table, &old, &mut allocated_strings)?;
+                    let bins = table.bins.read().unwrap();
+                    let Some(written_columns) =
+                        self.columns_to_update(table, k.as_ptr(), &bins.names, Some(&old), &new)?
+                    else {
+                        return Ok(());
+                    };
                     self.init_record(
                         record.as_mut_ptr(),
                         &new,
-                        &table.bin_names,
+                        &bins.names,
+                        &table.point_regions,
+                        &table.column_formats,
+                        Some(&written_columns),
                         0,
+                        table.on_convert_error,
                         &mut allocated_strings,
                     )?;
+                    drop(bins);
                     let mut r = AsRecord(record.assume_init_mut());
+                    self.check_record_strings(allocated_strings.bytes)?;
                     self.client.update(k.as_ptr(), r.as_mut_ptr())?;
                 }
             }
+            Operation::Upsert { new } => {
+                let mut key = MaybeUninit::uninit();
+                let mut record = MaybeUninit::uninit();
+                unsafe {
+                    self.init_key(
+                        key.as_mut_ptr(),
+                        &table.namespace,
+                        &table.set_name,
+                        &new.values[table.primary_index],
+                        table.key_format,
+                        &mut allocated_strings,
+                    )?;
+                    let k = Key(key.assume_init_mut());
+                    let bins = table.bins.read().unwrap();
+                    let Some(written_columns) =
+                        self.columns_to_update(table, k.as_ptr(), &bins.names, None, &new)?
+                    else {
+                        return Ok(());
+                    };
+                    self.init_record(
+                        record.as_mut_ptr(),
+                        &new,
+                        &bins.names,
+                        &table.point_regions,
+                        &table.column_formats,
+                        Some(&written_columns),
+                        0,
+                        table.on_convert_error,
+                        &mut allocated_strings,
+                    )?;
+                    drop(bins);
+                    let mut r = AsRecord(record.assume_init_mut());
+                    self.check_record_strings(allocated_strings.bytes)?;
+                    self.client.upsert(k.as_ptr(), r.as_mut_ptr())?;
+                }
+            }
+            Operation::BatchInsert { new } if new.len() > self.max_batch_size.get() => {
+                return self.write_in_batches(op.port, op.id, new);
+            }
             Operation::BatchInsert { new } => {
                 // Create an as_batch_write_record for each key
                 // Create an as_operations for each bin and assign them to the
@@ -1077,33 +2602,464 @@ impl AerospikeSinkWorker {
                 // Wrapper type here, so `as_operations_destroy` is called, even
                 // when an error occurs
                 let mut operations = Operations::new(new.len());
+                let mut written = Vec::with_capacity(new.len());
                 for dozer_record in new.iter() {
                     unsafe {
-                        let record = as_batch_write_reserve(batch.as_ptr());
-                        let ops = operations.next(dozer_record.values.len());
+                        let ops =
+                            operations.next(dozer_record.values.len() + table.point_regions.len());
                         if ops.is_null() {
                             return Err(AerospikeSinkError::CreateRecordError);
                         }
-                        self.init_ops(ops, dozer_record, &table.bin_names, &mut allocated_strings)?;
+                        let strings_start = allocated_strings.bytes;
+                        match self
+                            .init_ops(
+                                ops,
+                                dozer_record,
+                                &table.bins.read().unwrap().names,
+                                &table.point_regions,
+                                &table.column_formats,
+                                table.on_convert_error,
+                                &mut allocated_strings,
+                            )
+                            .and_then(|()| {
+                                self.check_record_strings(allocated_strings.bytes - strings_start)
+                            }) {
+                            Ok(()) => {}
+                            Err(e @ AerospikeSinkError::RecordSkipped(_)) => {
+                                report_skipped_record(&e);
+                                continue;
+                            }
+                            Err(e) => return Err(e),
+                        }
+                        // Only reserve the batch record once its operations are complete,
+                        // so skipped records don't leave empty entries in the batch
+                        let record = as_batch_write_reserve(batch.as_ptr());
                         (*record).ops = ops;
                         self.init_key(
                             &mut (*record).key as *mut as_key,
                             &table.namespace,
                             &table.set_name,
                             &dozer_record.values[table.primary_index],
+                            table.key_format,
                             &mut allocated_strings,
                         )?;
+                        written.push(dozer_record);
                     }
                 }
                 unsafe {
-                    self.client.write_batch(batch.as_ptr())?;
+                    if (*batch.as_ptr()).list.size == 0 {
+                        return Ok(());
+                    }
+                    match self.client.write_batch(batch.as_ptr()) {
+                        Ok(()) => {
+                            if self.verify_sample_percent > 0 {
+                                self.verify_writes(table, &written, &mut allocated_strings)?;
+                            }
+                        }
+                        Err(e) if e.is_connection_error() => return Err(e.into()),
+                        Err(e) => {
+                            warn!(
+                                "Batch write of {} records failed, retrying them one by one: {e}",
+                                new.len()
+                            );
+                            drop(operations);
+                            drop(batch);
+                            return self.write_individually(op.port, op.id, new);
+                        }
+                    }
                 }
             }
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                unreachable!("fields are added and removed by `AerospikeSink`")
+            }
         }
         Ok(())
     }
 }
 
+impl AerospikeSinkWorker {
+    /// Applies the record string limit to a record whose fields were converted to `bytes` bytes
+    /// of strings.
+    fn check_record_strings(&self, bytes: usize) -> Result<(), AerospikeSinkError> {
+        let Some(limit) = self.record_string_limit else {
+            return Ok(());
+        };
+        if bytes <= limit.max_bytes.get() {
+            return Ok(());
+        }
+        let error = AerospikeSinkError::RecordStringsTooLarge {
+            bytes,
+            max: limit.max_bytes.get(),
+        };
+        match limit.on_oversize {
+            AerospikeOversizePolicy::Error => Err(error),
+            AerospikeOversizePolicy::Skip => {
+                Err(AerospikeSinkError::RecordSkipped(Box::new(error)))
+            }
+        }
+    }
+
+    /// The columns of an updated or upserted record that have to be written, or `None` if none
+    /// changed.
+    ///
+    /// The new record is compared to the one stored under `key` if the table reads before
+    /// writing, else to the old record of the operation. Upserts have no old record, so they
+    /// write every column unless the table reads before writing. Stored bins that can't be
+    /// converted back to fields are written.
+    ///
+    /// Every column is written if anything changed and the `exists` policy replaces records,
+    /// as a partial write would delete the bins that aren't written.
+    unsafe fn columns_to_update(
+        &self,
+        table: &AerospikeTable,
+        key: *const as_key,
+        bin_names: &[CString],
+        old: Option<&Record>,
+        new: &Record,
+    ) -> Result<Option<Vec<bool>>, AerospikeSinkError> {
+        let diff = match old {
+            Some(old) => Record::diff(old, new, &table.schema),
+            None if table.read_before_write => RecordDiff::default(),
+            None => return Ok(Some(vec![true; new.values.len()])),
+        };
+        if diff.is_primary_key_change(&table.schema) {
+            return Ok(Some(vec![true; new.values.len()]));
+        }
+        let mut written = vec![false; new.values.len()];
+        let diff = if table.read_before_write {
+            let mut stored = null_mut();
+            #[allow(non_upper_case_globals)]
+            match self.client.get(key, &mut stored) {
+                Ok(()) => {}
+                // Writing the whole record reports the missing record as usual
+                Err(AerospikeError {
+                    code: as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND,
+                    message: _,
+                }) => return Ok(Some(vec![true; new.values.len()])),
+                Err(e) => return Err(e.into()),
+            }
+            let stored = NonNull::new(stored)
+                .map(SourceRecord)
+                .ok_or(AerospikeSinkError::CreateRecordError)?;
+            let mut current = old.unwrap_or(new).clone();
+            for (index, name) in bin_names.iter().enumerate().take(new.values.len()) {
+                let field = table.schema.fields.get(index).and_then(|field| {
+                    bin_to_field(as_record_get(stored.as_ptr(), name.as_ptr()), field.typ)
+                });
+                match field {
+                    Some(field) => current.values[index] = field,
+                    None => written[index] = true,
+                }
+            }
+            Record::diff(&current, new, &table.schema)
+        } else {
+            diff
+        };
+        for (index, _, _) in diff.changed_fields {
+            written[index] = true;
+        }
+        if !written.contains(&true) {
+            return Ok(None);
+        }
+        if self.client.replaces_records() {
+            return Ok(Some(vec![true; new.values.len()]));
+        }
+        Ok(Some(written))
+    }
+
+    /// Reads back a sample of the records of a written batch by key, and reports the ones
+    /// that aren't found.
+    unsafe fn verify_writes(
+        &self,
+        table: &AerospikeTable,
+        written: &[&Record],
+        allocated_strings: &mut Arena,
+    ) -> Result<(), AerospikeSinkError> {
+        let bins = table.bins.read().unwrap();
+        // The primary key bin can't be removed, so it is always there to be read
+        let bin_ptrs = [bins.names[table.primary_index].as_ptr(), null()];
+        for dozer_record in written {
+            let n = self.n_written.get();
+            self.n_written.set(n + 1);
+            if !is_sampled(n, self.verify_sample_percent) {
+                continue;
+            }
+            let primary_key = &dozer_record.values[table.primary_index];
+            let mut key = MaybeUninit::uninit();
+            self.init_key(
+                key.as_mut_ptr(),
+                &table.namespace,
+                &table.set_name,
+                primary_key,
+                table.key_format,
+                allocated_strings,
+            )?;
+            let key = Key(key.assume_init_mut());
+            let mut record = null_mut();
+            let result = self.client.select(key.as_ptr(), &bin_ptrs, &mut record);
+            drop(NonNull::new(record).map(SourceRecord));
+            #[allow(non_upper_case_globals)]
+            match result {
+                Ok(()) => {
+                    increment_counter!(VERIFIED_WRITES_COUNTER_NAME, RESULT_LABEL => "found");
+                }
+                Err(AerospikeError {
+                    code: as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND,
+                    message: _,
+                }) => {
+                    warn!(
+                        "Record with key {primary_key} written to {}.{} was not found when read back",
+                        table.namespace.to_string_lossy(),
+                        table.set_name.to_string_lossy()
+                    );
+                    increment_counter!(VERIFIED_WRITES_COUNTER_NAME, RESULT_LABEL => "missing");
+                }
+                Err(e) => warn!("Failed to read back a written record: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the record `dozer_record` is denormalized from. Only the bins in `columns` are
+    /// read, unless the denormalization copies all bins.
+    unsafe fn read_denormalization_source(
+        &self,
+        denorm: &Denormalization,
+        dozer_record: &Record,
+        key_format: AerospikeKeyFormat,
+        allocated_strings: &mut Arena,
+    ) -> Result<SourceRecord, AerospikeSinkError> {
+        let mut _key = MaybeUninit::uninit();
+        self.init_key(
+            _key.as_mut_ptr(),
+            &denorm.namespace,
+            &denorm.set,
+            &dozer_record.values[denorm.key_field],
+            key_format,
+            allocated_strings,
+        )?;
+        let key = Key(_key.assume_init_mut());
+        loop {
+            // A null record makes the client allocate one that fits all the bins read
+            let mut record = null_mut();
+            let result = if denorm.all_bins {
+                self.client.get(key.as_ptr(), &mut record)
+            } else {
+                self.client
+                    .select(key.as_ptr(), &denorm.source_column_ptrs, &mut record)
+            };
+            #[allow(non_upper_case_globals)]
+            match result {
+                Ok(()) => {
+                    denorm.record_hit();
+                    return NonNull::new(record)
+                        .map(SourceRecord)
+                        .ok_or(AerospikeSinkError::CreateRecordError);
+                }
+                // If the record is not found, wait and try again,
+                // we are probably behind the task responsible for writing it
+                Err(AerospikeError {
+                    code: as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND,
+                    message: _,
+                }) => {
+                    denorm.record_miss()?;
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Re-reads the generation of the sources whose denormalization checks it, and returns the
+    /// first one that changed since `sources` were read. A deleted source counts as changed.
+    unsafe fn changed_denormalization_source<'a>(
+        &self,
+        denormalizations: &'a [Denormalization],
+        sources: &[SourceRecord],
+        dozer_record: &Record,
+        key_format: AerospikeKeyFormat,
+        allocated_strings: &mut Arena,
+    ) -> Result<Option<&'a Denormalization>, AerospikeSinkError> {
+        for (denorm, source) in denormalizations.iter().zip(sources) {
+            if !denorm.check_generation {
+                continue;
+            }
+            let mut _key = MaybeUninit::uninit();
+            self.init_key(
+                _key.as_mut_ptr(),
+                &denorm.namespace,
+                &denorm.set,
+                &dozer_record.values[denorm.key_field],
+                key_format,
+                allocated_strings,
+            )?;
+            let key = Key(_key.assume_init_mut());
+            let mut record = null_mut();
+            #[allow(non_upper_case_globals)]
+            let changed = match self.client.exists(key.as_ptr(), &mut record) {
+                Ok(()) => {
+                    NonNull::new(record)
+                        .map(SourceRecord)
+                        .ok_or(AerospikeSinkError::CreateRecordError)?
+                        .generation()
+                        != source.generation()
+                }
+                Err(AerospikeError {
+                    code: as_status_e_AEROSPIKE_ERR_RECORD_NOT_FOUND,
+                    message: _,
+                }) => true,
+                Err(e) => return Err(e.into()),
+            };
+            if changed {
+                return Ok(Some(denorm));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Copies every bin of `source` to `record` under its own name.
+    unsafe fn copy_all_bins(
+        record: *mut as_record,
+        denorm: &Denormalization,
+        source: &SourceRecord,
+    ) -> Result<(), AerospikeSinkError> {
+        for bin in source.bins() {
+            let name = bin.name.as_ptr();
+            if !as_record_get(record, name).is_null() {
+                match denorm.on_bin_conflict {
+                    AerospikeBinConflictPolicy::Error => {
+                        return Err(AerospikeSinkError::DenormalizedBinConflict(
+                            CStr::from_ptr(name).to_string_lossy().into_owned(),
+                            denorm.set.to_string_lossy().into_owned(),
+                        ))
+                    }
+                    AerospikeBinConflictPolicy::KeepRecord => continue,
+                    AerospikeBinConflictPolicy::Overwrite => {}
+                }
+            }
+            as_val_val_reserve(bin.valuep as *mut as_val);
+            as_record_set(record, name, bin.valuep);
+        }
+        Ok(())
+    }
+
+    /// Builds the map a denormalization nests its copied bins under: every bin of `source` if it
+    /// copies all bins, then its columns, whose entries take precedence. Missing bins are left out.
+    unsafe fn nested_bins(
+        denorm: &Denormalization,
+        source: &SourceRecord,
+    ) -> Result<*mut as_orderedmap, AerospikeSinkError> {
+        let all_bins = if denorm.all_bins { source.bins() } else { &[] };
+        let map = check_alloc(as_orderedmap_new(
+            (all_bins.len() + denorm.columns.len()) as u32,
+        ));
+        let entries =
+            all_bins
+                .iter()
+                .map(|bin| (CStr::from_ptr(bin.name.as_ptr()), bin.valuep))
+                .chain(denorm.columns.iter().map(|(src, dst)| {
+                    (dst.as_c_str(), as_record_get(source.as_ptr(), src.as_ptr()))
+                }));
+        for (name, val) in entries {
+            if val.is_null() || (*val).nil.type_ as as_val_type_e == as_val_type_e_AS_NIL {
+                continue;
+            }
+            let name = name.to_bytes();
+            let key = check_alloc(as_bytes_new(name.len() as u32));
+            as_bytes_set(key, 0, name.as_ptr(), name.len() as u32);
+            (*key).type_ = as_bytes_type_e_AS_BYTES_STRING;
+            // The map takes a reference, so the source record can be destroyed
+            as_val_val_reserve(val as *mut as_val);
+            if as_orderedmap_set(map, key as *mut as_val, val as *mut as_val) != 0 {
+                as_orderedmap_destroy(map);
+                return Err(AerospikeSinkError::CreateRecordError);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Splits a batch insert that is larger than `max_batch_size` into several batch writes,
+    /// which are written in order.
+    fn write_in_batches(
+        &mut self,
+        port: PortHandle,
+        id: Option<OpIdentifier>,
+        mut records: Vec<Record>,
+    ) -> Result<(), AerospikeSinkError> {
+        let total = records.len();
+        let mut failures = Vec::new();
+        while !records.is_empty() {
+            let rest = records.split_off(records.len().min(self.max_batch_size.get()));
+            let batch = mem::replace(&mut records, rest);
+            match self.process_impl(TableOperation {
+                op: Operation::BatchInsert { new: batch },
+                id,
+                port,
+            }) {
+                Ok(()) => {}
+                Err(AerospikeSinkError::BatchRecordsFailed {
+                    failures: batch_failures,
+                    total: _,
+                }) => failures.extend(batch_failures),
+                Err(e) => return Err(e),
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(AerospikeSinkError::BatchRecordsFailed { failures, total })
+        }
+    }
+
+    /// Writes the records of a failed batch one at a time, so only the records that fail
+    /// on their own are lost. Like the batch write, records that already exist are updated.
+    fn write_individually(
+        &mut self,
+        port: PortHandle,
+        id: Option<OpIdentifier>,
+        records: Vec<Record>,
+    ) -> Result<(), AerospikeSinkError> {
+        let total = records.len();
+        let mut failures = Vec::new();
+        for record in records {
+            let result = match self.process_impl(TableOperation {
+                op: Operation::Insert {
+                    new: record.clone(),
+                },
+                id,
+                port,
+            }) {
+                #[allow(non_upper_case_globals)]
+                Err(AerospikeSinkError::Aerospike(AerospikeError {
+                    code: as_status_e_AEROSPIKE_ERR_RECORD_EXISTS,
+                    message: _,
+                })) => self.process_impl(TableOperation {
+                    op: Operation::Update {
+                        old: record.clone(),
+                        new: record,
+                    },
+                    id,
+                    port,
+                }),
+                result => result,
+            };
+            match result {
+                Ok(()) => {}
+                Err(e @ AerospikeSinkError::RecordSkipped(_)) => report_skipped_record(&e),
+                Err(AerospikeSinkError::Aerospike(e)) if e.is_connection_error() => {
+                    return Err(e.into())
+                }
+                Err(e) => failures.push(e),
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(AerospikeSinkError::BatchRecordsFailed { failures, total })
+        }
+    }
+}
+
 struct Operations(Vec<MaybeUninit<as_operations>>);
 impl Operations {
     fn new(size: usize) -> Self {
@@ -1181,13 +3137,63 @@ unsafe fn as_batch_records_init(records: *mut as_batch_records, capacity: u32) {
     );
 }
 
+#[async_trait]
 impl Sink for AerospikeSink {
     fn commit(&mut self, _epoch_details: &dozer_core::epoch::Epoch) -> Result<(), BoxedError> {
+        self.failed_commits = 0;
         Ok(())
     }
 
-    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
-        self.sender.send(op)?;
+    /// Retries commits that failed because of the cluster or the connection to it.
+    fn on_commit_failed(
+        &mut self,
+        error: &(dyn std::error::Error + 'static),
+        _epoch: &dozer_core::epoch::Epoch,
+    ) -> CommitFailureAction {
+        self.failed_commits += 1;
+        let is_transient = matches!(
+            error.downcast_ref::<AerospikeSinkError>(),
+            Some(AerospikeSinkError::Aerospike(e)) if e.is_connection_error()
+        );
+        if is_transient && self.failed_commits < self.commit_retry_policy.max_attempts {
+            CommitFailureAction::Retry(self.commit_retry_policy.backoff)
+        } else {
+            self.failed_commits = 0;
+            CommitFailureAction::Halt
+        }
+    }
+
+    fn process(&mut self, mut op: TableOperation) -> Result<(), BoxedError> {
+        // Every worker must see the change to the bins, so it is made here instead of by a worker
+        match &op.op {
+            Operation::AddField { field, .. } => {
+                self.add_bin(op.port, &field.name)?;
+                if let Some(checksums) = &mut self.checksums[op.port as usize] {
+                    checksums.schema.fields.push(field.clone());
+                }
+                return Ok(());
+            }
+            Operation::RemoveField { field_name, .. } => {
+                return Ok(self.remove_bin(op.port, field_name)?)
+            }
+            _ => {}
+        }
+        // Records and the schema must line up for the checksums, which they don't once columns
+        // are removed. Restoring is a no-op for the worker after that.
+        if let Some(checksums) = &mut self.checksums[op.port as usize] {
+            self.bins[op.port as usize]
+                .read()
+                .unwrap()
+                .restore_removed_columns(&mut op.op);
+            if checksums.skip_unchanged(&mut op.op) {
+                return Ok(());
+            }
+        }
+        self.sender
+            .as_ref()
+            .ok_or(AerospikeSinkError::ShutDown)?
+            .send(op)?;
+        self.lag.on_sent();
         Ok(())
     }
 
@@ -1239,6 +3245,17 @@ impl Sink for AerospikeSink {
     fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
         Ok(None)
     }
+
+    /// Writes the queued operations. The workers release the client when they exit, which
+    /// destroys it.
+    async fn on_pipeline_stop(&mut self) -> Result<(), BoxedError> {
+        self.shutdown();
+        Ok(())
+    }
+
+    fn get_write_ahead_lag(&self) -> Option<Duration> {
+        Some(self.lag.lag())
+    }
 }
 
 #[cfg(test)]
@@ -1250,12 +3267,18 @@ mod tests {
 
     use dozer_types::{
         chrono::{DateTime, NaiveDate},
-        models::sink::AerospikeSinkTable,
+        json_types::json_from_str,
+        models::sink::{
+            default_aerospike_max_batch_size, default_aerospike_verify_sample_percent,
+            AerospikePointRegion, AerospikeSinkTable,
+        },
         ordered_float::OrderedFloat,
         rust_decimal::Decimal,
         types::FieldDefinition,
     };
 
+    use aerospike_client_sys::as_val_val_destroy;
+
     use super::*;
 
     fn f(name: &str, typ: FieldType) -> FieldDefinition {
@@ -1285,6 +3308,302 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_duration_integer() {
+        let duration = Duration::new(3, 5_000_000);
+        assert_eq!(
+            duration_integer(duration, AerospikeDurationUnit::Nanoseconds).unwrap(),
+            3_005_000_000
+        );
+        assert_eq!(
+            duration_integer(duration, AerospikeDurationUnit::Milliseconds).unwrap(),
+            3_005
+        );
+        assert!(duration_integer(Duration::MAX, AerospikeDurationUnit::Nanoseconds).is_err());
+        assert!(matches!(
+            convert_duration_field(
+                Duration::MAX,
+                AerospikeDurationUnit::Nanoseconds,
+                AerospikeConvertErrorPolicy::NullField
+            ),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn test_convert_uint_field() {
+        assert!(matches!(
+            convert_uint_field(5, AerospikeConvertErrorPolicy::Error),
+            Ok(Some(5))
+        ));
+        assert!(matches!(
+            convert_uint_field(u64::MAX, AerospikeConvertErrorPolicy::Error),
+            Err(AerospikeSinkError::IntegerOutOfRange(u64::MAX))
+        ));
+        assert!(matches!(
+            convert_uint_field(u64::MAX, AerospikeConvertErrorPolicy::Skip),
+            Err(AerospikeSinkError::RecordSkipped(_))
+        ));
+        assert!(matches!(
+            convert_uint_field(u64::MAX, AerospikeConvertErrorPolicy::NullField),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn test_bin_to_field() {
+        unsafe {
+            assert_eq!(bin_to_field(null(), FieldType::String), Some(Field::Null));
+            let integer = as_integer_new(5) as *const as_bin_value;
+            assert_eq!(bin_to_field(integer, FieldType::Int), Some(Field::Int(5)));
+            assert_eq!(bin_to_field(integer, FieldType::UInt), Some(Field::UInt(5)));
+            assert_eq!(bin_to_field(integer, FieldType::Json), None);
+            as_val_val_destroy(integer as *mut as_val);
+        }
+    }
+
+    #[test]
+    fn test_convert_json_numbers() {
+        // Integers as `Ok`, doubles as `Err`
+        let convert = |json: &str| unsafe {
+            let value = convert_json(&json_from_str(json).unwrap(), None).unwrap();
+            #[allow(non_upper_case_globals)]
+            let number = match (*value).nil.type_ as as_val_type_e {
+                as_val_type_e_AS_INTEGER => Ok((*value).integer.value),
+                as_val_type_e_AS_DOUBLE => Err((*value).dbl.value),
+                typ => panic!("{json} was converted to type {typ}"),
+            };
+            as_val_val_destroy(value as *mut as_val);
+            number
+        };
+        assert_eq!(convert("5"), Ok(5));
+        assert_eq!(convert("5.0"), Ok(5));
+        assert_eq!(convert("5.5"), Err(5.5));
+        assert_eq!(convert("-5.0"), Ok(-5));
+        assert_eq!(convert("1e300"), Err(1e300));
+    }
+
+    #[test]
+    fn test_convert_json_map_order() {
+        let flags = |map_order| unsafe {
+            let value =
+                convert_json(&json_from_str(r#"{"b": 1, "a": 2}"#).unwrap(), map_order).unwrap();
+            let flags = (*(value as *mut as_map)).flags;
+            as_val_val_destroy(value as *mut as_val);
+            flags
+        };
+        assert_eq!(flags(None), 0);
+        assert_eq!(
+            flags(Some(AerospikeMapOrder::KeyOrdered)),
+            as_map_order_AS_MAP_KEY_ORDERED
+        );
+        assert_eq!(
+            flags(Some(AerospikeMapOrder::KeyValueOrdered)),
+            as_map_order_AS_MAP_KEY_VALUE_ORDERED
+        );
+    }
+
+    #[test]
+    fn test_bin_name() {
+        assert_eq!(bin_name("short", None).unwrap(), "short");
+        let truncated = bin_name("a_very_long_column_name", None).unwrap();
+        assert!(matches!(truncated, Cow::Owned(_)));
+        assert_eq!(truncated, "a_very_long_col");
+        // Truncated on a character boundary
+        assert_eq!(bin_name("aaaaaaaaaaaaaaé", None).unwrap(), "aaaaaaaaaaaaaa");
+
+        let mapping = HashMap::from([
+            ("a_very_long_column_name".to_owned(), "long".to_owned()),
+            ("b".to_owned(), "a_very_long_bin_name".to_owned()),
+        ]);
+        assert_eq!(
+            bin_name("a_very_long_column_name", Some(&mapping)).unwrap(),
+            "long"
+        );
+        assert_eq!(bin_name("short", Some(&mapping)).unwrap(), "short");
+        assert!(matches!(
+            bin_name("b", Some(&mapping)),
+            Err(AerospikeSinkError::BinNameTooLong(bin)) if bin == "a_very_long_bin_name"
+        ));
+        assert!(matches!(
+            bin_name("another_long_column", Some(&mapping)),
+            Err(AerospikeSinkError::BinNameTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn test_denormalization_nest_under() {
+        let new = |nest_under| {
+            Denormalization::new(
+                "test",
+                "users",
+                0,
+                &[],
+                false,
+                Default::default(),
+                false,
+                None,
+                Some(nest_under),
+            )
+        };
+        assert!(new("customer").unwrap().nest_under.is_some());
+        assert!(matches!(new(""), Err(AerospikeSinkError::EmptyBinName)));
+        assert!(matches!(
+            new("a_very_long_bin_name"),
+            Err(AerospikeSinkError::BinNameTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn test_denormalization_circuit_breaker() {
+        let denorm = Denormalization::new(
+            "test",
+            "users",
+            0,
+            &[],
+            false,
+            Default::default(),
+            false,
+            NonZeroU32::new(3),
+            None,
+        )
+        .unwrap();
+        // Misses are counted across the workers' clones
+        let clone = denorm.clone();
+        assert!(denorm.record_miss().is_ok());
+        assert!(clone.record_miss().is_ok());
+        clone.record_hit();
+        assert!(denorm.record_miss().is_ok());
+        assert!(denorm.record_miss().is_ok());
+        assert!(matches!(
+            clone.record_miss(),
+            Err(AerospikeSinkError::DenormalizationSourceMissing { set, misses: 3, .. }) if set == "users"
+        ));
+    }
+
+    #[test]
+    fn test_is_sampled() {
+        let n_sampled = |percent| (0..1000).filter(|&n| is_sampled(n, percent)).count();
+        assert_eq!(n_sampled(0), 0);
+        assert_eq!(n_sampled(1), 10);
+        assert_eq!(n_sampled(30), 300);
+        assert_eq!(n_sampled(100), 1000);
+        // The sampled records are spread out, rather than the first ones of every 100
+        assert!(!is_sampled(0, 50));
+        assert!(is_sampled(1, 50));
+        assert!(!is_sampled(2, 50));
+    }
+
+    #[test]
+    fn test_skip_unchanged() {
+        let mut schema = Schema::new();
+        schema
+            .field(f("id", FieldType::UInt), true)
+            .field(f("name", FieldType::String), false);
+        let mut checksums = WrittenChecksums {
+            schema,
+            primary_index: 0,
+            checksums: LruCache::new(NonZeroUsize::new(1).unwrap()),
+        };
+        let record =
+            |id, name: &str| Record::new(vec![Field::UInt(id), Field::String(name.into())]);
+
+        let mut op = Operation::Insert {
+            new: record(1, "a"),
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+        assert!(checksums.skip_unchanged(&mut op));
+        let mut op = Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b"),
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+
+        // Only the unchanged record is dropped from batches
+        let mut op = Operation::BatchInsert {
+            new: vec![record(1, "b"), record(2, "c")],
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+        assert_eq!(
+            op,
+            Operation::BatchInsert {
+                new: vec![record(2, "c")]
+            }
+        );
+
+        // The least recently written key is evicted
+        let mut op = Operation::Upsert {
+            new: record(1, "b"),
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+
+        let mut op = Operation::Delete {
+            old: record(1, "b"),
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+        let mut op = Operation::Insert {
+            new: record(1, "b"),
+        };
+        assert!(!checksums.skip_unchanged(&mut op));
+    }
+
+    #[test]
+    fn test_arena() {
+        let mut arena = Arena::with_capacity(8);
+        let first = arena.alloc_fmt(format_args!("{}", 1234)).as_ptr();
+        arena.alloc_fmt(format_args!("{}", "abcd"));
+        // Strings that don't fit are allocated on their own, without moving the buffer
+        assert_eq!(arena.alloc_fmt(format_args!("{}", "xyz")), b"xyz");
+        assert_eq!(arena.buf.as_ptr(), first);
+        assert_eq!(arena.buf, b"1234abcd");
+        assert_eq!(arena.strings, vec![(0, 4), (4, 4)]);
+        assert_eq!(arena.spilled, vec![b"xyz".to_vec()]);
+        assert_eq!(arena.bytes, 11);
+    }
+
+    #[test]
+    fn test_arena_allocations() {
+        // 16 decimal columns take one allocation per record, instead of one per column
+        let capacity = 16 * converted_string_len(FieldType::Decimal);
+        let decimal = Decimal::from_i128_with_scale(-79228162514264337593543950335, 28);
+        for _ in 0..10_000 {
+            let mut arena = Arena::with_capacity(capacity);
+            for _ in 0..16 {
+                arena.alloc_fmt(format_args!("{decimal}"));
+            }
+            assert!(arena.spilled.is_empty());
+            assert_eq!(arena.strings.len(), 16);
+        }
+    }
+
+    #[test]
+    fn test_cpus_from_quota() {
+        assert_eq!(cpus_from_quota("max", "100000"), None);
+        assert_eq!(cpus_from_quota("-1", "100000"), None);
+        assert_eq!(cpus_from_quota("200000", "100000"), NonZeroUsize::new(2));
+        // Fractions of a CPU round up
+        assert_eq!(cpus_from_quota("50000", "100000"), NonZeroUsize::new(1));
+        assert_eq!(cpus_from_quota("250000", "100000"), NonZeroUsize::new(3));
+        assert_eq!(cpus_from_quota("100000", "0"), None);
+    }
+
+    #[test]
+    fn test_write_ahead_lag() {
+        let lag = WriteAheadLag::new();
+        assert_eq!(lag.lag(), Duration::ZERO);
+
+        lag.on_sent();
+        lag.on_sent();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(lag.lag() >= Duration::from_millis(20));
+
+        // Finishing an operation resets the lag until the next one takes long
+        lag.on_processed();
+        assert!(lag.lag() < Duration::from_millis(20));
+        lag.on_processed();
+        assert_eq!(lag.lag(), Duration::ZERO);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_inserts_batch() {
@@ -1345,11 +3664,32 @@ mod tests {
             AerospikeSinkConfig {
                 connection: "".to_owned(),
                 n_threads: Some(1.try_into().unwrap()),
+                default_threads: None,
+                max_batch_size: default_aerospike_max_batch_size(),
+                verify_writes: false,
+                verify_sample_percent: default_aerospike_verify_sample_percent(),
+                exists_policy: Default::default(),
+                checksum_cache_size: None,
+                max_record_string_bytes: None,
+                on_oversize: Default::default(),
                 tables: vec![AerospikeSinkTable {
                     source_table_name: "test".into(),
                     namespace: "test".into(),
                     set_name: set.to_owned(),
                     denormalize: vec![],
+                    point_regions: vec![AerospikePointRegion {
+                        column: "point".into(),
+                        bin: "point_region".into(),
+                        radius_meters: 100,
+                    }],
+                    on_convert_error: Default::default(),
+                    key_format: Default::default(),
+                    key_digest: Default::default(),
+                    integer_durations: vec![],
+                    bin_name_mapping: None,
+                    ordered_maps: vec![],
+                    on_conflict: Default::default(),
+                    read_before_write: false,
                 }],
             },
         );
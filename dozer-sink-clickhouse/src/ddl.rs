@@ -1,3 +1,4 @@
+use dozer_core::errors::MigrationError;
 use dozer_types::log::warn;
 use dozer_types::models::sink::ClickhouseSinkTableOptions;
 use dozer_types::types::{FieldDefinition, FieldType, Schema};
@@ -75,6 +76,53 @@ impl ClickhouseDDL {
         )
     }
 
+    /// The `ALTER TABLE` statements that add the columns of `new_schema` missing from
+    /// `old_schema`, drop the ones that were removed, and change the type of the others when needed.
+    pub fn get_migration_query(
+        table_name: &str,
+        cluster: Option<&str>,
+        old_schema: &Schema,
+        new_schema: &Schema,
+    ) -> Result<String, MigrationError> {
+        let alter_table = match cluster {
+            Some(cluster) => format!("ALTER TABLE {table_name} ON CLUSTER {cluster}"),
+            None => format!("ALTER TABLE {table_name}"),
+        };
+        let column_type = |field: &FieldDefinition| {
+            if field.typ == FieldType::Duration {
+                return Err(MigrationError::UnsupportedType {
+                    column: field.name.clone(),
+                    typ: field.typ,
+                });
+            }
+            Ok(Self::map_field_to_type(field))
+        };
+
+        let mut statements = vec![];
+        for field in &new_schema.fields {
+            match old_schema.fields.iter().find(|old| old.name == field.name) {
+                None => statements.push(format!(
+                    "{alter_table} ADD COLUMN {} {};",
+                    field.name,
+                    column_type(field)?
+                )),
+                Some(old) if old.typ != field.typ || old.nullable != field.nullable => statements
+                    .push(format!(
+                        "{alter_table} MODIFY COLUMN {} {};",
+                        field.name,
+                        column_type(field)?
+                    )),
+                Some(_) => {}
+            }
+        }
+        for field in &old_schema.fields {
+            if !new_schema.fields.iter().any(|new| new.name == field.name) {
+                statements.push(format!("{alter_table} DROP COLUMN {};", field.name));
+            }
+        }
+        Ok(statements.join("\n"))
+    }
+
     pub fn map_field_to_type(field: &FieldDefinition) -> String {
         let typ = match field.typ {
             FieldType::UInt => "UInt64",
@@ -92,13 +140,17 @@ impl ClickhouseDDL {
             FieldType::Json => "JSON",
             FieldType::Point => "Point",
             FieldType::Duration => unimplemented!(),
+            FieldType::Vector(_) => "Array(Float32)",
         };
 
         if field.nullable {
-            if field.typ != FieldType::Binary {
+            if !matches!(field.typ, FieldType::Binary | FieldType::Vector(_)) {
                 format!("Nullable({})", typ)
             } else {
-                warn!("Binary field cannot be nullable, ignoring nullable flag");
+                warn!(
+                    "{} field cannot be nullable, ignoring nullable flag",
+                    field.typ
+                );
                 typ.to_string()
             }
         } else {
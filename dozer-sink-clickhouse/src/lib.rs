@@ -9,6 +9,7 @@ use crate::ClickhouseSinkError::{
 use clickhouse::inserter::Inserter;
 use clickhouse::Client;
 use dozer_core::epoch::Epoch;
+use dozer_core::errors::MigrationError;
 use dozer_core::node::{PortHandle, Sink, SinkFactory};
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_log::storage::Queue;
@@ -26,6 +27,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use crate::ddl::ClickhouseDDL;
 use crate::schema::{ClickhouseSchema, ClickhouseTable};
 use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate};
 use dozer_types::json_types::JsonValue;
@@ -90,6 +92,7 @@ pub enum FieldWrapper {
     Json(#[cfg_attr(feature= "arbitrary", arbitrary(with = arb_json::arbitrary_json))] JsonValue),
     Point(DozerPoint),
     Duration(DozerDuration),
+    Vector(Vec<OrderedFloat<f32>>),
     OptionalUInt(Option<u64>),
     OptionalU128(Option<u128>),
     OptionalInt(Option<i64>),
@@ -131,6 +134,7 @@ fn convert_field_to_ff(field: Field, nullable: bool) -> FieldWrapper {
             Field::Json(v) => FieldWrapper::OptionalJson(Some(v)),
             Field::Point(v) => FieldWrapper::OptionalPoint(Some(v)),
             Field::Duration(v) => FieldWrapper::OptionalDuration(Some(v)),
+            Field::Vector(v) => FieldWrapper::Vector(v),
             Field::Null => FieldWrapper::Null(None),
         }
     } else {
@@ -150,6 +154,7 @@ fn convert_field_to_ff(field: Field, nullable: bool) -> FieldWrapper {
             Field::Json(v) => FieldWrapper::Json(v),
             Field::Point(v) => FieldWrapper::Point(v),
             Field::Duration(v) => FieldWrapper::Duration(v),
+            Field::Vector(v) => FieldWrapper::Vector(v),
             Field::Null => FieldWrapper::Null(None),
         }
     }
@@ -179,6 +184,25 @@ impl SinkFactory for ClickhouseSinkFactory {
         Ok(())
     }
 
+    fn generate_migration_ddl(
+        &self,
+        _port: &PortHandle,
+        old_schema: &Schema,
+        new_schema: &Schema,
+    ) -> Result<String, MigrationError> {
+        let cluster = self
+            .config
+            .create_table_options
+            .as_ref()
+            .and_then(|options| options.cluster.as_deref());
+        ClickhouseDDL::get_migration_query(
+            &self.config.sink_table_name,
+            cluster,
+            old_schema,
+            new_schema,
+        )
+    }
+
     async fn build(
         &self,
         mut input_schemas: HashMap<PortHandle, Schema>,
@@ -432,6 +456,9 @@ impl Sink for ClickhouseSink {
 
                 self.commit_insert()?;
             }
+            Operation::Upsert { .. }
+            | Operation::AddField { .. }
+            | Operation::RemoveField { .. } => return Err(BoxedError::from(UnsupportedOperation)),
         }
 
         Ok(())
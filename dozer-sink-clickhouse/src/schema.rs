@@ -142,10 +142,11 @@ impl ClickhouseSchema {
                         "Duration".to_string(),
                     ))
                 }
+                FieldType::Vector(_) => "Array(Float32)",
             }
             .to_string();
 
-            if field.nullable {
+            if field.nullable && !matches!(field.typ, FieldType::Vector(_)) {
                 expected_type = format!("Nullable({expected_type})");
             }
 
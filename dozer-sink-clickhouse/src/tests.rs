@@ -1,3 +1,4 @@
+use crate::ddl::ClickhouseDDL;
 use crate::schema::ClickhouseSchema;
 use crate::ClickhouseSinkError;
 use clickhouse::Client;
@@ -92,3 +93,31 @@ async fn test_get_not_existing_clickhouse_table() {
         Err(ClickhouseSinkError::SinkTableDoesNotExist)
     ));
 }
+
+#[test]
+fn test_get_migration_query() {
+    let old_schema = get_dozer_schema();
+    let mut new_schema = old_schema.clone();
+    new_schema.fields.remove(1);
+    new_schema.fields[0].nullable = true;
+    new_schema.fields.push(FieldDefinition {
+        name: "created_at".to_string(),
+        typ: FieldType::Timestamp,
+        nullable: false,
+        source: Default::default(),
+    });
+
+    let query =
+        ClickhouseDDL::get_migration_query("sink_table", None, &old_schema, &new_schema).unwrap();
+    assert_eq!(
+        query,
+        "ALTER TABLE sink_table MODIFY COLUMN id Nullable(UInt64);\n\
+         ALTER TABLE sink_table ADD COLUMN created_at DateTime64(3);\n\
+         ALTER TABLE sink_table DROP COLUMN data;"
+    );
+
+    let query =
+        ClickhouseDDL::get_migration_query("sink_table", Some("cluster"), &old_schema, &old_schema)
+            .unwrap();
+    assert!(query.is_empty());
+}
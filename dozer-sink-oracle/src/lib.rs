@@ -61,6 +61,10 @@ enum Error {
     },
     #[error("Oracle database error: {0}")]
     Oracle(oracle::Error),
+    #[error(
+        "Can't add or remove column {0}, as the destination table can't change while the sink runs"
+    )]
+    UnsupportedSchemaChange(String),
 }
 
 impl From<oracle::Error> for Error {
@@ -261,6 +265,7 @@ impl OracleSinkFactory {
                 dozer_types::types::FieldType::Json => unimplemented!(),
                 dozer_types::types::FieldType::Point => unimplemented!("Oracle Point"),
                 dozer_types::types::FieldType::Duration => unimplemented!(),
+                dozer_types::types::FieldType::Vector(_) => unimplemented!("Oracle Vector"),
             };
             column_defs.push(format!(
                 "\"{name}\" {col_type}{}",
@@ -309,9 +314,8 @@ fn generate_merge_statement(table_name: &str, schema: &Schema) -> String {
         .join(", ");
 
     let mut pk_select = schema
-        .primary_index
-        .iter()
-        .map(|ix| &schema.fields[*ix].name)
+        .primary_key_field_names()
+        .into_iter()
         .map(|name| format!("D.\"{name}\" = S.\"{name}\""))
         .collect::<Vec<_>>()
         .join(" AND ");
@@ -570,6 +574,9 @@ impl Sink for OracleSink {
 
                 self.batch(op.id, OpKind::Update, new)?;
             }
+            dozer_types::types::Operation::Upsert { new } => {
+                self.batch(op.id, OpKind::Update, new)?;
+            }
             dozer_types::types::Operation::BatchInsert { mut new } => {
                 let mut batch = self
                     .conn
@@ -589,6 +596,12 @@ impl Sink for OracleSink {
                 }
                 batch.execute()?;
             }
+            dozer_types::types::Operation::AddField { field, .. } => {
+                return Err(Box::new(Error::UnsupportedSchemaChange(field.name)));
+            }
+            dozer_types::types::Operation::RemoveField { field_name, .. } => {
+                return Err(Box::new(Error::UnsupportedSchemaChange(field_name)));
+            }
         }
         Ok(())
     }
@@ -0,0 +1,412 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use dozer_core::epoch::Epoch;
+use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_log::storage::Queue;
+use dozer_log::tokio::runtime::Runtime;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::json_types::json_to_string;
+use dozer_types::log::{debug, warn};
+use dozer_types::models::sink::{QdrantPointsCountMismatchPolicy, QdrantSinkConfig};
+use dozer_types::node::OpIdentifier;
+use dozer_types::thiserror::{self, Error};
+use dozer_types::tonic::async_trait;
+use dozer_types::types::{Field, FieldType, Operation, Record, Schema, TableOperation};
+use qdrant_client::client::{Payload, QdrantClient};
+use qdrant_client::qdrant::points_selector::PointsSelectorOneOf;
+use qdrant_client::qdrant::value::Kind;
+use qdrant_client::qdrant::{
+    NamedVectors, NullValue, PointId, PointStruct, PointsIdsList, PointsSelector, Value,
+};
+
+#[derive(Error, Debug)]
+enum QdrantSinkError {
+    #[error("Qdrant error: {0}")]
+    Qdrant(#[from] anyhow::Error),
+
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
+
+    #[error("Column {0} has type {1}, but the vector column must be a vector")]
+    NotAVectorColumn(String, FieldType),
+
+    #[error("Qdrant points need a primary key of a single uint, int, string or text column")]
+    UnsupportedPrimaryKey,
+
+    #[error("{0} is not a valid point id. Point ids are unsigned integers or UUIDs")]
+    InvalidPointId(Field),
+
+    #[error("Record has no value in vector column {0}")]
+    MissingVector(String),
+
+    #[error("Can't add or remove column {0}, as the collection's payload can't change while the sink runs")]
+    UnsupportedSchemaChange(String),
+
+    #[error(
+        "Qdrant collection {collection_name} has {actual} points, but {expected} were expected"
+    )]
+    PointsCountMismatch {
+        collection_name: String,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+#[derive(Debug)]
+pub struct QdrantSinkFactory {
+    runtime: Arc<Runtime>,
+    config: QdrantSinkConfig,
+}
+
+impl QdrantSinkFactory {
+    pub fn new(config: QdrantSinkConfig, runtime: Arc<Runtime>) -> Self {
+        Self { config, runtime }
+    }
+}
+
+/// Where the parts of a point are found in a record.
+#[derive(Debug, Clone, PartialEq)]
+struct PointLayout {
+    id_index: usize,
+    vector_index: usize,
+    payload: Vec<(usize, String)>,
+}
+
+impl PointLayout {
+    fn new(config: &QdrantSinkConfig, schema: &Schema) -> Result<Self, QdrantSinkError> {
+        let find_column = |name: &str| {
+            schema
+                .fields
+                .iter()
+                .position(|field| field.name == name)
+                .ok_or_else(|| QdrantSinkError::ColumnNotFound(name.to_string()))
+        };
+
+        let id_index = match schema.primary_index[..] {
+            [id_index] => id_index,
+            _ => return Err(QdrantSinkError::UnsupportedPrimaryKey),
+        };
+        if !matches!(
+            schema.fields[id_index].typ,
+            FieldType::UInt | FieldType::Int | FieldType::String | FieldType::Text
+        ) {
+            return Err(QdrantSinkError::UnsupportedPrimaryKey);
+        }
+
+        let vector_index = find_column(&config.vector_column)?;
+        let vector_type = schema.fields[vector_index].typ;
+        if !matches!(vector_type, FieldType::Vector(_)) {
+            return Err(QdrantSinkError::NotAVectorColumn(
+                config.vector_column.clone(),
+                vector_type,
+            ));
+        }
+
+        let payload = config
+            .payload_columns
+            .iter()
+            .map(|name| Ok((find_column(name)?, name.clone())))
+            .collect::<Result<_, QdrantSinkError>>()?;
+
+        Ok(Self {
+            id_index,
+            vector_index,
+            payload,
+        })
+    }
+
+    fn point_id(&self, record: &Record) -> Result<PointId, QdrantSinkError> {
+        let id = &record.values[self.id_index];
+        match id {
+            Field::UInt(value) => Ok((*value).into()),
+            Field::Int(value) => u64::try_from(*value)
+                .map(Into::into)
+                .map_err(|_| QdrantSinkError::InvalidPointId(id.clone())),
+            Field::String(value) | Field::Text(value) => Ok(value.clone().into()),
+            _ => Err(QdrantSinkError::InvalidPointId(id.clone())),
+        }
+    }
+
+    fn point(&self, vector_name: &str, record: &Record) -> Result<PointStruct, QdrantSinkError> {
+        let Some(vector) = record.values[self.vector_index].as_vector() else {
+            return Err(QdrantSinkError::MissingVector(vector_name.to_string()));
+        };
+        let vectors = NamedVectors::default().add_vector(
+            vector_name,
+            vector.iter().map(|element| element.0).collect(),
+        );
+
+        let mut payload = Payload::new();
+        for (index, name) in &self.payload {
+            payload.insert(name, payload_value(&record.values[*index]));
+        }
+
+        Ok(PointStruct::new(self.point_id(record)?, vectors, payload))
+    }
+}
+
+/// Numbers, booleans and strings are written as such. Other fields are written as strings.
+fn payload_value(field: &Field) -> Value {
+    match field {
+        Field::UInt(value) => match i64::try_from(*value) {
+            Ok(value) => value.into(),
+            Err(_) => value.to_string().into(),
+        },
+        Field::Int(value) => (*value).into(),
+        Field::Float(value) => value.0.into(),
+        Field::Boolean(value) => (*value).into(),
+        Field::String(value) | Field::Text(value) => value.clone().into(),
+        Field::Null => Value {
+            kind: Some(Kind::NullValue(NullValue::NullValue.into())),
+        },
+        Field::Json(value) => json_to_string(value).into(),
+        _ => field.to_string().into(),
+    }
+}
+
+fn points_selector(ids: Vec<PointId>) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList { ids })),
+    }
+}
+
+#[async_trait]
+impl SinkFactory for QdrantSinkFactory {
+    fn type_name(&self) -> String {
+        "qdrant".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_input_port_name(&self, _port: &PortHandle) -> String {
+        self.config.source_table_name.clone()
+    }
+
+    fn prepare(&self, input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        debug_assert!(input_schemas.len() == 1);
+        let schema = &input_schemas[&DEFAULT_PORT_HANDLE];
+        PointLayout::new(&self.config, schema)?;
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        let schema = input_schemas.remove(&DEFAULT_PORT_HANDLE).unwrap();
+        let layout = PointLayout::new(&self.config, &schema)?;
+        let client = QdrantClient::from_url(&self.config.url)
+            .build()
+            .map_err(QdrantSinkError::Qdrant)?;
+        let points_count = get_points_count(&client, &self.config.collection_name).await?;
+
+        Ok(Box::new(QdrantSink {
+            client,
+            runtime: self.runtime.clone(),
+            config: self.config.clone(),
+            layout,
+            pending: Vec::new(),
+            expected_points_count: points_count,
+        }))
+    }
+}
+
+async fn get_points_count(
+    client: &QdrantClient,
+    collection_name: &str,
+) -> Result<Option<u64>, QdrantSinkError> {
+    let info = client.collection_info(collection_name).await?;
+    Ok(info.result.and_then(|info| info.points_count))
+}
+
+struct QdrantSink {
+    client: QdrantClient,
+    runtime: Arc<Runtime>,
+    config: QdrantSinkConfig,
+    layout: PointLayout,
+    /// Inserted points that haven't been upserted yet.
+    pending: Vec<PointStruct>,
+    /// The number of points the collection should have after everything written so far.
+    expected_points_count: Option<u64>,
+}
+
+impl Debug for QdrantSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QdrantSink")
+            .field("config", &self.config)
+            .field("layout", &self.layout)
+            .field("pending", &self.pending.len())
+            .field("expected_points_count", &self.expected_points_count)
+            .finish()
+    }
+}
+
+impl QdrantSink {
+    fn upsert(&self, points: Vec<PointStruct>) -> Result<(), QdrantSinkError> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        self.runtime
+            .block_on(self.client.upsert_points_batch_blocking(
+                &self.config.collection_name,
+                None,
+                points,
+                None,
+                self.config.batch_size.get(),
+            ))?;
+        Ok(())
+    }
+
+    fn delete(&self, ids: Vec<PointId>) -> Result<(), QdrantSinkError> {
+        self.runtime.block_on(self.client.delete_points_blocking(
+            &self.config.collection_name,
+            None,
+            &points_selector(ids),
+            None,
+        ))?;
+        Ok(())
+    }
+
+    fn flush_pending(&mut self) -> Result<(), QdrantSinkError> {
+        let pending = std::mem::take(&mut self.pending);
+        self.upsert(pending)
+    }
+
+    fn add_expected_points(&mut self, count: i64) {
+        if let Some(expected) = &mut self.expected_points_count {
+            *expected = expected.saturating_add_signed(count);
+        }
+    }
+
+    fn process_impl(&mut self, op: Operation) -> Result<(), QdrantSinkError> {
+        let vector_name = &self.config.vector_column;
+        match op {
+            Operation::Insert { new } => {
+                self.pending.push(self.layout.point(vector_name, &new)?);
+                self.add_expected_points(1);
+                if self.pending.len() >= self.config.batch_size.get() {
+                    self.flush_pending()?;
+                }
+            }
+            Operation::BatchInsert { new } => {
+                let points = new
+                    .iter()
+                    .map(|record| self.layout.point(vector_name, record))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.add_expected_points(points.len() as i64);
+                self.flush_pending()?;
+                self.upsert(points)?;
+            }
+            // Deletes and updates go after the pending inserts, which they may refer to.
+            Operation::Delete { old } => {
+                let id = self.layout.point_id(&old)?;
+                self.flush_pending()?;
+                self.delete(vec![id])?;
+                self.add_expected_points(-1);
+            }
+            Operation::Update { old, new } => {
+                let old_id = self.layout.point_id(&old)?;
+                let point = self.layout.point(vector_name, &new)?;
+                self.flush_pending()?;
+                if point.id.as_ref() != Some(&old_id) {
+                    self.delete(vec![old_id])?;
+                }
+                self.upsert(vec![point])?;
+            }
+            Operation::Upsert { new } => {
+                let point = self.layout.point(vector_name, &new)?;
+                self.flush_pending()?;
+                self.upsert(vec![point])?;
+            }
+            Operation::AddField { field, .. } => {
+                return Err(QdrantSinkError::UnsupportedSchemaChange(field.name));
+            }
+            Operation::RemoveField { field_name, .. } => {
+                return Err(QdrantSinkError::UnsupportedSchemaChange(field_name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Sink for QdrantSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        self.flush_pending()?;
+        Ok(())
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        self.process_impl(op.op)?;
+        Ok(())
+    }
+
+    /// Checks that the collection has as many points as expected. Upserts of points that
+    /// already exist, for example when a source is snapshotted again, make the count diverge,
+    /// so `on_points_count_mismatch` can choose to only report a mismatch and expect the
+    /// actual count from then on.
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        self.flush_pending()?;
+        let points_count = self
+            .runtime
+            .block_on(get_points_count(&self.client, &self.config.collection_name))?;
+        match (self.expected_points_count, points_count) {
+            (Some(expected), Some(actual)) if expected != actual => {
+                let error = QdrantSinkError::PointsCountMismatch {
+                    collection_name: self.config.collection_name.clone(),
+                    expected,
+                    actual,
+                };
+                match self.config.on_points_count_mismatch {
+                    QdrantPointsCountMismatchPolicy::Error => return Err(error.into()),
+                    QdrantPointsCountMismatchPolicy::Warn => warn!("{error}"),
+                }
+            }
+            _ => debug!(
+                "Qdrant collection {} has {points_count:?} points",
+                self.config.collection_name
+            ),
+        }
+        self.expected_points_count = points_count;
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<OpIdentifier>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+        Ok(None)
+    }
+
+    fn flush_batch(&mut self) -> Result<(), BoxedError> {
+        self.flush_pending()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{payload_value, PointLayout, QdrantSinkError};
+use dozer_types::models::sink::{default_qdrant_batch_size, QdrantSinkConfig};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema};
+use qdrant_client::qdrant::point_id::PointIdOptions;
+use qdrant_client::qdrant::value::Kind;
+
+fn get_sink_config() -> QdrantSinkConfig {
+    QdrantSinkConfig {
+        url: "http://localhost:6334".to_string(),
+        collection_name: "collection".to_string(),
+        source_table_name: "source_table".to_string(),
+        vector_column: "embedding".to_string(),
+        payload_columns: vec!["name".to_string()],
+        batch_size: default_qdrant_batch_size(),
+        on_points_count_mismatch: Default::default(),
+    }
+}
+
+fn get_dozer_schema(id_type: FieldType) -> Schema {
+    Schema {
+        fields: vec![
+            FieldDefinition {
+                name: "id".to_string(),
+                typ: id_type,
+                nullable: false,
+                source: Default::default(),
+            },
+            FieldDefinition {
+                name: "name".to_string(),
+                typ: FieldType::String,
+                nullable: true,
+                source: Default::default(),
+            },
+            FieldDefinition {
+                name: "embedding".to_string(),
+                typ: FieldType::Vector(2),
+                nullable: false,
+                source: Default::default(),
+            },
+        ],
+        primary_index: vec![0],
+    }
+}
+
+#[test]
+fn test_point_layout() {
+    let layout = PointLayout::new(&get_sink_config(), &get_dozer_schema(FieldType::UInt)).unwrap();
+    assert_eq!(
+        layout,
+        PointLayout {
+            id_index: 0,
+            vector_index: 2,
+            payload: vec![(1, "name".to_string())],
+        }
+    );
+
+    let record = Record::new(vec![
+        Field::UInt(7),
+        Field::String("seven".to_string()),
+        Field::Vector(vec![OrderedFloat(0.5), OrderedFloat(-1.0)]),
+    ]);
+    let point = layout.point("embedding", &record).unwrap();
+    assert_eq!(
+        point.id.unwrap().point_id_options,
+        Some(PointIdOptions::Num(7))
+    );
+    assert_eq!(
+        point.payload["name"].kind,
+        Some(Kind::StringValue("seven".to_string()))
+    );
+}
+
+#[test]
+fn test_point_layout_validation() {
+    let mut config = get_sink_config();
+    config.vector_column = "name".to_string();
+    assert!(matches!(
+        PointLayout::new(&config, &get_dozer_schema(FieldType::UInt)),
+        Err(QdrantSinkError::NotAVectorColumn(_, FieldType::String))
+    ));
+
+    let mut config = get_sink_config();
+    config.payload_columns.push("missing".to_string());
+    assert!(matches!(
+        PointLayout::new(&config, &get_dozer_schema(FieldType::UInt)),
+        Err(QdrantSinkError::ColumnNotFound(column)) if column == "missing"
+    ));
+
+    assert!(matches!(
+        PointLayout::new(&get_sink_config(), &get_dozer_schema(FieldType::Float)),
+        Err(QdrantSinkError::UnsupportedPrimaryKey)
+    ));
+}
+
+#[test]
+fn test_negative_point_id() {
+    let layout = PointLayout::new(&get_sink_config(), &get_dozer_schema(FieldType::Int)).unwrap();
+    let record = Record::new(vec![
+        Field::Int(-1),
+        Field::Null,
+        Field::Vector(vec![OrderedFloat(0.0), OrderedFloat(0.0)]),
+    ]);
+    assert!(matches!(
+        layout.point_id(&record),
+        Err(QdrantSinkError::InvalidPointId(Field::Int(-1)))
+    ));
+}
+
+#[test]
+fn test_payload_value() {
+    assert_eq!(
+        payload_value(&Field::Int(-3)).kind,
+        Some(Kind::IntegerValue(-3))
+    );
+    assert_eq!(
+        payload_value(&Field::UInt(u64::MAX)).kind,
+        Some(Kind::StringValue(u64::MAX.to_string()))
+    );
+    assert!(matches!(
+        payload_value(&Field::Null).kind,
+        Some(Kind::NullValue(_))
+    ));
+}
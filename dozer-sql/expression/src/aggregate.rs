@@ -1,7 +1,13 @@
+use dozer_types::ordered_float::OrderedFloat;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash, bincode::Encode, bincode::Decode)]
 pub enum AggregateFunctionType {
+    /// Collects the first argument into a JSON array. With `order_by`, the second argument is the sort key.
+    ArrayAgg {
+        distinct: bool,
+        order_by: Option<SortDirection>,
+    },
     Avg,
     Count,
     Max,
@@ -10,9 +16,20 @@ pub enum AggregateFunctionType {
     Min,
     MinAppendOnly,
     MinValue,
+    /// Continuous percentile, interpolated between neighbouring values and estimated as a float. Holds the fraction in `[0, 1]`.
+    PercentileCont(#[bincode(with_serde)] OrderedFloat<f64>),
+    /// Discrete percentile, the first value whose cumulative distribution reaches the fraction.
+    /// Estimated as a float, so it may not be one of the input values.
+    PercentileDisc(#[bincode(with_serde)] OrderedFloat<f64>),
     Sum,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash, bincode::Encode, bincode::Decode)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
 impl AggregateFunctionType {
     pub(crate) fn new(name: &str) -> Option<AggregateFunctionType> {
         match name {
@@ -28,11 +45,31 @@ impl AggregateFunctionType {
             _ => None,
         }
     }
+
+    pub(crate) fn is_percentile(name: &str) -> bool {
+        matches!(name, "percentile_cont" | "percentile_disc")
+    }
+
+    pub(crate) fn new_percentile(name: &str, fraction: f64) -> Option<AggregateFunctionType> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+        match name {
+            "percentile_cont" => Some(AggregateFunctionType::PercentileCont(OrderedFloat(
+                fraction,
+            ))),
+            "percentile_disc" => Some(AggregateFunctionType::PercentileDisc(OrderedFloat(
+                fraction,
+            ))),
+            _ => None,
+        }
+    }
 }
 
 impl Display for AggregateFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregateFunctionType::ArrayAgg { .. } => f.write_str("ARRAY_AGG"),
             AggregateFunctionType::Avg => f.write_str("AVG"),
             AggregateFunctionType::Count => f.write_str("COUNT"),
             AggregateFunctionType::Max => f.write_str("MAX"),
@@ -41,6 +78,8 @@ impl Display for AggregateFunctionType {
             AggregateFunctionType::Min => f.write_str("MIN"),
             AggregateFunctionType::MinAppendOnly => f.write_str("MIN_APPEND_ONLY"),
             AggregateFunctionType::MinValue => f.write_str("MIN_VALUE"),
+            AggregateFunctionType::PercentileCont(_) => f.write_str("PERCENTILE_CONT"),
+            AggregateFunctionType::PercentileDisc(_) => f.write_str("PERCENTILE_DISC"),
             AggregateFunctionType::Sum => f.write_str("SUM"),
         }
     }
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use crate::aggregate::AggregateFunctionType;
+use crate::aggregate::{AggregateFunctionType, SortDirection};
+use crate::arg_utils::validate_num_arguments;
 use crate::conditional::ConditionalExpressionType;
-use crate::datetime::DateTimeFunctionType;
+use crate::datetime::{
+    validate_format_date_format, validate_parse_date_format, DateTimeFunctionType,
+};
 use crate::error::Error;
 use dozer_types::models::udf_config::{UdfConfig, UdfType};
 use dozer_types::types::FieldType;
@@ -11,8 +14,8 @@ use dozer_types::{
     types::{Field, FieldDefinition, Schema, SourceDefinition},
 };
 use sqlparser::ast::{
-    BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr, Function,
-    FunctionArg, FunctionArgExpr, Ident, Interval, TrimWhereField,
+    ArrayAgg, BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr,
+    Function, FunctionArg, FunctionArgExpr, Ident, Interval, TrimWhereField,
     UnaryOperator as SqlUnaryOperator, Value as SqlValue,
 };
 use tokio::runtime::Runtime;
@@ -20,10 +23,13 @@ use tokio::runtime::Runtime;
 use crate::execution::Expression;
 use crate::execution::Expression::{ConditionalExpression, GeoFunction, Now, ScalarFunction};
 use crate::geo::common::GeoFunctionType;
+use crate::hash::{HashAlgorithm, Hasher};
 use crate::json_functions::JsonFunctionType;
 use crate::operator::{BinaryOperatorType, UnaryOperatorType};
+use crate::regexp::CompiledRegex;
 use crate::scalar::common::ScalarFunctionType;
 use crate::scalar::string::TrimType;
+use crate::vector::VectorFunctionType;
 
 use super::cast::CastOperatorType;
 
@@ -110,6 +116,9 @@ impl ExpressionBuilder {
                 self.parse_sql_function(parse_aggregations, sql_function, schema, udfs)
                     .await
             }
+            SqlExpr::ArrayAgg(array_agg) if parse_aggregations => {
+                self.parse_sql_array_agg(array_agg, schema, udfs).await
+            }
             SqlExpr::Like {
                 negated,
                 expr,
@@ -302,7 +311,10 @@ impl ExpressionBuilder {
             return None;
         }
 
-        let aggr = AggregateFunctionType::new(function_name.as_str())?;
+        let aggr = AggregateFunctionType::new(function_name.as_str());
+        if aggr.is_none() && !AggregateFunctionType::is_percentile(function_name.as_str()) {
+            return None;
+        }
 
         let mut arg_expr: Vec<Expression> = Vec::new();
         for arg in &sql_function.args {
@@ -312,10 +324,67 @@ impl ExpressionBuilder {
                 .ok()?;
             arg_expr.push(aggregation);
         }
+        let aggr = match aggr {
+            Some(aggr) => aggr,
+            // PERCENTILE_CONT(value, fraction): the fraction is a literal and is kept in the function type.
+            None => {
+                let fraction = match arg_expr.pop()? {
+                    Expression::Literal(field) => field.to_float()?,
+                    _ => return None,
+                };
+                AggregateFunctionType::new_percentile(function_name.as_str(), fraction)?
+            }
+        };
         let measure = Expression::AggregateFunction {
             fun: aggr,
             args: arg_expr,
         };
+        Some(self.push_aggregation(measure))
+    }
+
+    async fn parse_sql_array_agg(
+        &mut self,
+        array_agg: &ArrayAgg,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        if array_agg.limit.is_some() || array_agg.within_group {
+            return Err(Error::UnsupportedExpression(SqlExpr::ArrayAgg(
+                array_agg.clone(),
+            )));
+        }
+
+        let mut args = vec![
+            self.parse_sql_expression(true, &array_agg.expr, schema, udfs)
+                .await?,
+        ];
+        let order_by = match &array_agg.order_by {
+            Some(order_by) => {
+                args.push(
+                    self.parse_sql_expression(true, &order_by.expr, schema, udfs)
+                        .await?,
+                );
+                Some(if order_by.asc == Some(false) {
+                    SortDirection::Descending
+                } else {
+                    SortDirection::Ascending
+                })
+            }
+            None => None,
+        };
+
+        let measure = Expression::AggregateFunction {
+            fun: AggregateFunctionType::ArrayAgg {
+                distinct: array_agg.distinct,
+                order_by,
+            },
+            args,
+        };
+        Ok(self.push_aggregation(measure))
+    }
+
+    /// Registers the aggregation, reusing an identical one, and returns the column it is output to.
+    fn push_aggregation(&mut self, measure: Expression) -> Expression {
         let index = match self
             .aggregations
             .iter()
@@ -328,9 +397,9 @@ impl ExpressionBuilder {
                 self.aggregations.len() - 1
             }
         };
-        Some(Expression::Column {
+        Expression::Column {
             index: self.offset + index,
-        })
+        }
     }
 
     async fn scalar_function_check(
@@ -381,6 +450,30 @@ impl ExpressionBuilder {
         })
     }
 
+    async fn vector_expr_check(
+        &mut self,
+        function_name: String,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Option<Expression> {
+        let mut function_args: Vec<Expression> = Vec::new();
+        for arg in &sql_function.args {
+            function_args.push(
+                self.parse_sql_function_arg(parse_aggregations, arg, schema, udfs)
+                    .await
+                    .ok()?,
+            );
+        }
+
+        let vft = VectorFunctionType::new(function_name.as_str())?;
+        Some(Expression::VectorFunction {
+            fun: vft,
+            args: function_args,
+        })
+    }
+
     fn datetime_expr_check(&mut self, function_name: String) -> Option<Expression> {
         let dtf = DateTimeFunctionType::new(function_name.as_str())?;
         Some(Now { fun: dtf })
@@ -452,6 +545,50 @@ impl ExpressionBuilder {
                 .await;
         }
 
+        if function_name == "regexp_matches" || function_name == "regexp_replace" {
+            return self
+                .parse_sql_regexp_function(
+                    &function_name,
+                    parse_aggregations,
+                    sql_function,
+                    schema,
+                    udfs,
+                )
+                .await;
+        }
+
+        if function_name == "format_date" || function_name == "parse_date" {
+            return self
+                .parse_sql_date_format_function(
+                    &function_name,
+                    parse_aggregations,
+                    sql_function,
+                    schema,
+                    udfs,
+                )
+                .await;
+        }
+
+        if function_name == "struct" {
+            return self
+                .parse_sql_struct(parse_aggregations, sql_function, schema, udfs)
+                .await;
+        }
+
+        if let Some(algorithm) = HashAlgorithm::new(&function_name) {
+            let mut args = Vec::with_capacity(sql_function.args.len());
+            for arg in &sql_function.args {
+                args.push(
+                    self.parse_sql_function_arg(parse_aggregations, arg, schema, udfs)
+                        .await?,
+                );
+            }
+            return Ok(Expression::Hash {
+                hasher: Hasher::new(algorithm),
+                args,
+            });
+        }
+
         if let Some(aggr_check) = self
             .aggr_function_check(
                 function_name.clone(),
@@ -491,6 +628,19 @@ impl ExpressionBuilder {
             return Ok(geo_check);
         }
 
+        if let Some(vector_check) = self
+            .vector_expr_check(
+                function_name.clone(),
+                parse_aggregations,
+                sql_function,
+                schema,
+                udfs,
+            )
+            .await
+        {
+            return Ok(vector_check);
+        }
+
         if let Some(conditional_check) = self
             .conditional_expr_check(
                 function_name.clone(),
@@ -560,6 +710,102 @@ impl ExpressionBuilder {
         Err(Error::UnknownFunction(function_name.clone()))
     }
 
+    async fn parse_sql_regexp_function(
+        &mut self,
+        function_name: &str,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut args = Vec::with_capacity(sql_function.args.len());
+        for arg in &sql_function.args {
+            args.push(
+                self.parse_sql_function_arg(parse_aggregations, arg, schema, udfs)
+                    .await?,
+            );
+        }
+
+        let replace = function_name == "regexp_replace";
+        let expected_args = if replace { 3..4 } else { 2..3 };
+        validate_num_arguments(expected_args, args.len(), function_name.to_uppercase())?;
+
+        // The pattern is compiled once here, so it can't depend on the record.
+        let mut args = args.into_iter();
+        let arg = Box::new(args.next().unwrap());
+        let regex = match args.next() {
+            Some(Expression::Literal(Field::String(pattern))) => CompiledRegex::new(&pattern)?,
+            _ => return Err(Error::NonLiteralRegexPattern(function_name.to_uppercase())),
+        };
+
+        Ok(match args.next() {
+            Some(replacement) if replace => Expression::RegexpReplace {
+                arg,
+                regex,
+                replacement: Box::new(replacement),
+            },
+            _ => Expression::RegexpMatches { arg, regex },
+        })
+    }
+
+    async fn parse_sql_date_format_function(
+        &mut self,
+        function_name: &str,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut args = Vec::with_capacity(sql_function.args.len());
+        for arg in &sql_function.args {
+            args.push(
+                self.parse_sql_function_arg(parse_aggregations, arg, schema, udfs)
+                    .await?,
+            );
+        }
+        validate_num_arguments(2..3, args.len(), function_name.to_uppercase())?;
+
+        // The format is validated here, so it can't depend on the record.
+        let mut args = args.into_iter();
+        let arg = Box::new(args.next().unwrap());
+        let Some(Expression::Literal(Field::String(format))) = args.next() else {
+            return Err(Error::NonLiteralDateFormat(function_name.to_uppercase()));
+        };
+
+        if function_name == "format_date" {
+            validate_format_date_format(&format)?;
+            Ok(Expression::FormatDate { arg, format })
+        } else {
+            validate_parse_date_format(&format)?;
+            Ok(Expression::ParseDate { arg, format })
+        }
+    }
+
+    /// Parses `STRUCT(name => expr, ...)`. Every argument must be named.
+    async fn parse_sql_struct(
+        &mut self,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut fields = Vec::with_capacity(sql_function.args.len());
+        for arg in &sql_function.args {
+            let FunctionArg::Named {
+                name,
+                arg: FunctionArgExpr::Expr(expr),
+            } = arg
+            else {
+                return Err(Error::UnsupportedFunctionArg(arg.clone()));
+            };
+            let expr = self
+                .parse_sql_expression(parse_aggregations, expr, schema, udfs)
+                .await?;
+            fields.push((name.value.clone(), expr));
+        }
+        Ok(Expression::Struct(fields))
+    }
+
     async fn parse_sql_function_arg(
         &mut self,
         parse_aggregations: bool,
@@ -1,34 +1,105 @@
 use dozer_types::types::Record;
-use dozer_types::types::{Field, Schema};
+use dozer_types::types::{Field, FieldType, Schema, SourceDefinition};
 use std::iter::zip;
 
+use crate::cast::{cast_field, get_common_type};
+use crate::comparison::evaluate_eq;
 use crate::error::Error;
-use crate::execution::Expression;
+use crate::execution::{Expression, ExpressionType};
 
 pub fn evaluate_case(
     schema: &Schema,
-    _operand: &Option<Box<Expression>>,
+    operand: &mut Option<Box<Expression>>,
     conditions: &mut [Expression],
     results: &mut [Expression],
     else_result: &mut Option<Box<Expression>>,
     record: &Record,
 ) -> Result<Field, Error> {
+    // In a simple CASE, a NULL operand never matches any WHEN value.
+    let operand = match operand {
+        Some(operand) => Some(operand.evaluate(record, schema)?),
+        None => None,
+    };
     let iter = zip(conditions, results);
     for (cond, res) in iter {
         let field = cond.evaluate(record, schema)?;
-        if let Some(cond_match) = field.as_boolean() {
-            if cond_match {
-                let then_res = res.evaluate(record, schema)?;
-                return Ok(then_res);
-            } else {
-                continue;
+        let cond_match = match &operand {
+            // Compare with the same semantics as `=`, so e.g. an integer operand matches a float value.
+            Some(operand) => {
+                operand != &Field::Null
+                    && field != Field::Null
+                    && evaluate_eq(
+                        schema,
+                        &mut Expression::Literal(operand.clone()),
+                        &mut Expression::Literal(field),
+                        record,
+                    )? == Field::Boolean(true)
             }
+            // A NULL or non-boolean condition is treated as not matching.
+            None => field.as_boolean().unwrap_or(false),
+        };
+        if cond_match {
+            let then_res = res.evaluate(record, schema)?;
+            return coerce_case_result(schema, results, else_result, then_res);
         }
     }
     if let Some(else_res) = else_result {
         let else_return = else_res.evaluate(record, schema)?;
-        Ok(else_return)
+        coerce_case_result(schema, results, else_result, else_return)
     } else {
         Ok(Field::Null)
     }
 }
+
+/// Converts a branch's value to the type of the whole CASE, which may be wider than the branch's own.
+fn coerce_case_result(
+    schema: &Schema,
+    results: &[Expression],
+    else_result: &Option<Box<Expression>>,
+    field: Field,
+) -> Result<Field, Error> {
+    if field == Field::Null {
+        return Ok(field);
+    }
+    let return_type = get_case_type(schema, results, else_result)?.return_type;
+    if field.ty() == Some(return_type) {
+        Ok(field)
+    } else {
+        cast_field(&field, return_type)
+    }
+}
+
+pub fn get_case_type(
+    schema: &Schema,
+    results: &[Expression],
+    else_result: &Option<Box<Expression>>,
+) -> Result<ExpressionType, Error> {
+    // Without an ELSE, unmatched rows evaluate to NULL.
+    let mut nullable = else_result.is_none();
+    let mut return_type: Option<FieldType> = None;
+    for result in results.iter().chain(else_result.as_deref()) {
+        if let Expression::Literal(Field::Null) = result {
+            nullable = true;
+            continue;
+        }
+        let typ = result.get_type(schema)?;
+        nullable |= typ.nullable;
+        // Numeric branches are widened to a type all of them fit in, e.g. `THEN 1 ELSE 1.5` is a float.
+        return_type = match return_type {
+            None => Some(typ.return_type),
+            Some(expected) => Some(get_common_type(expected, typ.return_type).ok_or(
+                Error::IncompatibleCaseResultTypes {
+                    expected,
+                    actual: typ.return_type,
+                },
+            )?),
+        };
+    }
+    let return_type = return_type.ok_or(Error::LiteralExpressionIsNull)?;
+    Ok(ExpressionType::new(
+        return_type,
+        nullable,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
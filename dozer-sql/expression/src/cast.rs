@@ -33,6 +33,7 @@ impl Display for CastOperatorType {
             FieldType::Json => f.write_str("CAST AS JSON"),
             FieldType::Point => f.write_str("CAST AS POINT"),
             FieldType::Duration => f.write_str("CAST AS DURATION"),
+            FieldType::Vector(dimension) => write!(f, "CAST AS VECTOR({dimension})"),
         }
     }
 }
@@ -208,6 +209,10 @@ impl CastOperatorType {
                 ],
                 FieldType::Duration,
             ),
+            FieldType::Vector(dimension) => (
+                vec![FieldType::Vector(dimension)],
+                FieldType::Vector(dimension),
+            ),
         };
 
         let expression_type = validate_arg_type(arg, expected_input_type, schema, self, 0)?;
@@ -220,6 +225,41 @@ impl CastOperatorType {
     }
 }
 
+/// The type that values of both `left` and `right` can be converted to without losing their
+/// kind, used where several expressions produce a single column. Only numeric types are widened.
+pub(crate) fn get_common_type(left: FieldType, right: FieldType) -> Option<FieldType> {
+    match (left, right) {
+        (left, right) if left == right => Some(left),
+        (FieldType::Int, FieldType::UInt) | (FieldType::UInt, FieldType::Int) => {
+            Some(FieldType::Int)
+        }
+        (FieldType::UInt, FieldType::U128) | (FieldType::U128, FieldType::UInt) => {
+            Some(FieldType::U128)
+        }
+        (FieldType::I128, FieldType::UInt | FieldType::U128 | FieldType::Int)
+        | (FieldType::UInt | FieldType::U128 | FieldType::Int, FieldType::I128)
+        | (FieldType::Int, FieldType::U128)
+        | (FieldType::U128, FieldType::Int) => Some(FieldType::I128),
+        (
+            FieldType::Float,
+            FieldType::UInt | FieldType::U128 | FieldType::Int | FieldType::I128,
+        )
+        | (
+            FieldType::UInt | FieldType::U128 | FieldType::Int | FieldType::I128,
+            FieldType::Float,
+        ) => Some(FieldType::Float),
+        (
+            FieldType::Decimal,
+            FieldType::UInt | FieldType::U128 | FieldType::Int | FieldType::I128 | FieldType::Float,
+        )
+        | (
+            FieldType::UInt | FieldType::U128 | FieldType::Int | FieldType::I128 | FieldType::Float,
+            FieldType::Decimal,
+        ) => Some(FieldType::Decimal),
+        _ => None,
+    }
+}
+
 pub fn cast_field(input: &Field, output_type: FieldType) -> Result<Field, Error> {
     match output_type {
         FieldType::UInt => {
@@ -354,5 +394,12 @@ pub fn cast_field(input: &Field, output_type: FieldType) -> Result<Field, Error>
                 })
             }
         }
+        FieldType::Vector(dimension) => match input {
+            Field::Vector(value) if value.len() == dimension => Ok(input.clone()),
+            _ => Err(Error::InvalidCast {
+                from: input.clone(),
+                to: output_type,
+            }),
+        },
     }
 }
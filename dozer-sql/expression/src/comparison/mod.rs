@@ -103,6 +103,7 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_)
                     | Field::Null => Ok(Field::Null),
                 },
@@ -148,7 +149,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -196,7 +198,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -242,7 +245,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -292,7 +296,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -333,6 +338,7 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -405,6 +411,7 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -494,11 +501,9 @@ macro_rules! define_comparison {
                         })?;
                         Ok(Field::Boolean($function(left_val, right_v)))
                     }
-                    Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
+                    Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+                        PipelineError::InvalidTypeComparison(left_p, right_p, $op.to_string()),
+                    ),
                 },
                 Field::Timestamp(left_v) => match right_p {
                     Field::Timestamp(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
@@ -523,6 +528,7 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -553,6 +559,7 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -579,7 +586,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Date(_)
-                    | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Duration(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -609,17 +617,16 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Date(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
                     )),
                 },
-                Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                    left_p,
-                    right_p,
-                    $op.to_string(),
-                )),
+                Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+                    PipelineError::InvalidTypeComparison(left_p, right_p, $op.to_string()),
+                ),
             }
         }
     };
@@ -693,6 +700,7 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
         },
@@ -735,7 +743,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -780,7 +789,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -823,7 +833,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -867,7 +878,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -907,6 +919,7 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -967,6 +980,7 @@ pub fn evaluate_lt(
                 | Field::Date(_)
                 | Field::Json(_)
                 | Field::Point(_)
+                | Field::Vector(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -1044,11 +1058,9 @@ pub fn evaluate_lt(
                 })?;
                 Ok(Field::Boolean(left_val < right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, "<".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v < right_v)),
@@ -1070,6 +1082,7 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1096,6 +1109,7 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1122,7 +1136,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Duration(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -1148,17 +1163,16 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
             )),
         },
-        Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            "<".to_string(),
-        )),
+        Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+            PipelineError::InvalidTypeComparison(left_p, right_p, "<".to_string()),
+        ),
     }
 }
 
@@ -1227,6 +1241,7 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
         },
@@ -1269,7 +1284,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1314,7 +1330,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1357,7 +1374,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1401,7 +1419,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1441,6 +1460,7 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1501,6 +1521,7 @@ pub fn evaluate_gt(
                 | Field::Date(_)
                 | Field::Json(_)
                 | Field::Point(_)
+                | Field::Vector(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -1578,11 +1599,9 @@ pub fn evaluate_gt(
                 })?;
                 Ok(Field::Boolean(left_val > right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, ">".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v > right_v)),
@@ -1604,6 +1623,7 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1630,6 +1650,7 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1656,7 +1677,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Duration(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1682,17 +1704,16 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
             )),
         },
-        Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            ">".to_string(),
-        )),
+        Field::Binary(_) | Field::Json(_) | Field::Vector(_) => Err(
+            PipelineError::InvalidTypeComparison(left_p, right_p, ">".to_string()),
+        ),
     }
 }
 
@@ -1,3 +1,6 @@
+use crate::arg_utils::validate_two_arguments;
+use crate::cast::{cast_field, get_common_type};
+use crate::comparison::evaluate_eq;
 use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
 use dozer_types::types::Record;
@@ -17,7 +20,7 @@ pub(crate) fn get_conditional_expr_type(
 ) -> Result<ExpressionType, Error> {
     match function {
         ConditionalExpressionType::Coalesce => validate_coalesce(args, schema),
-        ConditionalExpressionType::NullIf => todo!(),
+        ConditionalExpressionType::NullIf => validate_nullif(args, schema),
     }
 }
 
@@ -38,7 +41,7 @@ impl ConditionalExpressionType {
     ) -> Result<Field, Error> {
         match self {
             ConditionalExpressionType::Coalesce => evaluate_coalesce(schema, args, record),
-            ConditionalExpressionType::NullIf => todo!(),
+            ConditionalExpressionType::NullIf => evaluate_nullif(schema, args, record),
         }
     }
 }
@@ -51,15 +54,36 @@ pub(crate) fn validate_coalesce(
         return Err(Error::EmptyCoalesceArguments);
     }
 
-    let return_types = args
-        .iter()
-        .map(|expr| expr.get_type(schema).unwrap().return_type)
-        .collect::<Vec<FieldType>>();
-    let return_type = return_types[0];
+    // NULL literals have no type of their own, so the result type comes from the other arguments.
+    // The result can only be null if every argument can be null.
+    let mut return_type: Option<FieldType> = None;
+    let mut nullable = true;
+    for (argument_index, expr) in args.iter().enumerate() {
+        if let Expression::Literal(Field::Null) = expr {
+            continue;
+        }
+        let typ = expr.get_type(schema)?;
+        nullable &= typ.nullable;
+        // Numeric arguments are widened to a type all of them fit in, e.g. `COALESCE(int_col, 0.0)` is a float.
+        return_type = match return_type {
+            None => Some(typ.return_type),
+            Some(expected) => {
+                Some(get_common_type(expected, typ.return_type).ok_or_else(|| {
+                    Error::InvalidFunctionArgumentType {
+                        function_name: ConditionalExpressionType::Coalesce.to_string(),
+                        argument_index,
+                        expected: vec![expected],
+                        actual: typ.return_type,
+                    }
+                })?)
+            }
+        };
+    }
+    let return_type = return_type.ok_or(Error::LiteralExpressionIsNull)?;
 
     Ok(ExpressionType::new(
         return_type,
-        false,
+        nullable,
         dozer_types::types::SourceDefinition::Dynamic,
         false,
     ))
@@ -71,14 +95,60 @@ pub(crate) fn evaluate_coalesce(
     record: &Record,
 ) -> Result<Field, Error> {
     // The COALESCE function returns the first of its arguments that is not null.
-    for expr in args {
-        let field = expr.evaluate(record, schema)?;
-        if field != Field::Null {
-            return Ok(field);
+    let mut result = Field::Null;
+    for expr in args.iter_mut() {
+        result = expr.evaluate(record, schema)?;
+        if result != Field::Null {
+            break;
         }
     }
     // Null is returned only if all arguments are null.
-    Ok(Field::Null)
+    if result == Field::Null {
+        return Ok(result);
+    }
+    // The value is converted to the type of the whole call, which may be wider than its argument's.
+    let return_type = validate_coalesce(args, schema)?.return_type;
+    if result.ty() == Some(return_type) {
+        Ok(result)
+    } else {
+        cast_field(&result, return_type)
+    }
+}
+
+pub(crate) fn validate_nullif(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let (arg1, _) = validate_two_arguments(args, schema, ConditionalExpressionType::NullIf)?;
+
+    Ok(ExpressionType::new(
+        arg1.return_type,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+pub(crate) fn evaluate_nullif(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    // The NULLIF function returns null if its arguments are equal, and the first argument otherwise.
+    let field = args[0].evaluate(record, schema)?;
+    let other = args[1].evaluate(record, schema)?;
+    // Compare with the same semantics as `=`, so e.g. a float column can be compared to an integer literal.
+    let equal = evaluate_eq(
+        schema,
+        &mut Expression::Literal(field.clone()),
+        &mut Expression::Literal(other),
+        record,
+    )?;
+    if equal == Field::Boolean(true) {
+        Ok(Field::Null)
+    } else {
+        Ok(field)
+    }
 }
 
 impl Display for ConditionalExpressionType {
@@ -308,6 +378,90 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_coalesce_nullable_args() {
+        proptest!(ProptestConfig::with_cases(1000), move |(
+            values in prop::collection::vec(prop::option::of(any::<i64>()), 1..8))| {
+            let schema = values
+                .iter()
+                .enumerate()
+                .fold(Schema::default(), |mut schema, (i, _)| {
+                    schema.field(
+                        FieldDefinition::new(
+                            format!("field{i}"),
+                            FieldType::Int,
+                            true,
+                            SourceDefinition::Dynamic,
+                        ),
+                        false,
+                    );
+                    schema
+                });
+            let row = Record::new(
+                values
+                    .iter()
+                    .map(|value| value.map_or(Field::Null, Field::Int))
+                    .collect(),
+            );
+            let mut args = (0..values.len())
+                .map(|index| Expression::Column { index })
+                .collect::<Vec<_>>();
+
+            let typ = validate_coalesce(&args, &schema).unwrap();
+            assert_eq!(typ.return_type, FieldType::Int);
+            assert!(typ.nullable);
+
+            let expected = values
+                .iter()
+                .find_map(|value| value.map(Field::Int))
+                .unwrap_or(Field::Null);
+            let res = evaluate_coalesce(&schema, &mut args, &row).unwrap();
+            assert_eq!(res, expected);
+            if values.iter().any(Option::is_some) {
+                assert_ne!(res, Field::Null);
+            }
+        });
+    }
+
+    #[test]
+    fn test_validate_coalesce_types() {
+        let schema = Schema::default()
+            .field(
+                FieldDefinition::new(
+                    String::from("nullable"),
+                    FieldType::Int,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .field(
+                FieldDefinition::new(
+                    String::from("not_nullable"),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        let nullable = Expression::Column { index: 0 };
+        let not_nullable = Expression::Column { index: 1 };
+        let null = Expression::Literal(Field::Null);
+
+        let typ = validate_coalesce(&[null.clone(), nullable.clone()], &schema).unwrap();
+        assert_eq!(typ.return_type, FieldType::Int);
+        assert!(typ.nullable);
+
+        let typ = validate_coalesce(&[nullable.clone(), not_nullable], &schema).unwrap();
+        assert_eq!(typ.return_type, FieldType::Int);
+        assert!(!typ.nullable);
+
+        let string = Expression::Literal(Field::String("X".to_string()));
+        assert!(validate_coalesce(&[nullable, string], &schema).is_err());
+        assert!(validate_coalesce(&[null], &schema).is_err());
+    }
+
     fn test_validate_coalesce(args: &[Expression], typ: FieldType) {
         let schema = Schema::default()
             .field(
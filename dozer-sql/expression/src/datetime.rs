@@ -2,12 +2,13 @@ use crate::arg_utils::{extract_timestamp, extract_uint, validate_arg_type};
 use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
 
-use dozer_types::chrono::{DateTime, Datelike, FixedOffset, Offset, Timelike, Utc};
+use dozer_types::chrono::format::{Item, StrftimeItems};
+use dozer_types::chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, Timelike, Utc};
 use dozer_types::types::Record;
 use dozer_types::types::{DozerDuration, Field, FieldType, Schema, TimeUnit};
 use num_traits::ToPrimitive;
 use sqlparser::ast::DateTimeField;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum DateTimeFunctionType {
@@ -254,3 +255,82 @@ mod tests {
         }
     }
 }
+
+/// Checks a `FORMAT_DATE` format when the expression is built. Formatting fails on unknown
+/// specifiers and on specifiers that need a time, which a date doesn't have.
+pub(crate) fn validate_format_date_format(format: &str) -> Result<(), Error> {
+    let mut formatted = String::new();
+    write!(formatted, "{}", NaiveDate::default().format(format))
+        .map_err(|_| Error::InvalidDateFormat(format.to_string()))
+}
+
+/// Checks a `PARSE_DATE` format when the expression is built.
+pub(crate) fn validate_parse_date_format(format: &str) -> Result<(), Error> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        Err(Error::InvalidDateFormat(format.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn get_format_date_type(
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let arg_type = validate_arg_type(arg, vec![FieldType::Date], schema, "FORMAT_DATE", 0)?;
+    Ok(ExpressionType {
+        return_type: FieldType::String,
+        ..arg_type
+    })
+}
+
+pub(crate) fn get_parse_date_type(
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        "PARSE_DATE",
+        0,
+    )?;
+    Ok(ExpressionType {
+        return_type: FieldType::Date,
+        nullable: true,
+        ..arg_type
+    })
+}
+
+pub(crate) fn evaluate_format_date(
+    schema: &Schema,
+    arg: &mut Expression,
+    format: &str,
+    record: &Record,
+) -> Result<Field, Error> {
+    match arg.evaluate(record, schema)? {
+        Field::Date(date) => Ok(Field::String(date.format(format).to_string())),
+        Field::Null => Ok(Field::Null),
+        other => Err(Error::InvalidFunctionArgument {
+            function_name: "FORMAT_DATE".to_string(),
+            argument_index: 0,
+            argument: other,
+        }),
+    }
+}
+
+/// Values that don't match the format evaluate to `NULL`.
+pub(crate) fn evaluate_parse_date(
+    schema: &Schema,
+    arg: &mut Expression,
+    format: &str,
+    record: &Record,
+) -> Result<Field, Error> {
+    let arg_field = arg.evaluate(record, schema)?;
+    let Some(value) = arg_field.as_string().or_else(|| arg_field.as_text()) else {
+        return Ok(Field::Null);
+    };
+    Ok(NaiveDate::parse_from_str(value, format)
+        .map(Field::Date)
+        .unwrap_or(Field::Null))
+}
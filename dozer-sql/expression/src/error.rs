@@ -49,6 +49,11 @@ pub enum Error {
         expected: Range<usize>,
         actual: usize,
     },
+    #[error("CASE results have incompatible types: expected {expected}, got {actual}")]
+    IncompatibleCaseResultTypes {
+        expected: FieldType,
+        actual: FieldType,
+    },
     #[error("Empty coalesce arguments")]
     EmptyCoalesceArguments,
     #[error(
@@ -80,6 +85,14 @@ pub enum Error {
     InvalidLikeEscape(#[from] like::InvalidEscapeError),
     #[error("Invalid like pattern: {0}")]
     InvalidLikePattern(#[from] like::InvalidPatternError),
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    #[error("The pattern of {0} must be a string literal")]
+    NonLiteralRegexPattern(String),
+    #[error("Invalid date format: {0}")]
+    InvalidDateFormat(String),
+    #[error("The format of {0} must be a string literal")]
+    NonLiteralDateFormat(String),
 
     #[error("Unsupported extract: {0}")]
     UnsupportedExtract(DateTimeField),
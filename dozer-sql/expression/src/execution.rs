@@ -1,13 +1,22 @@
-use crate::arg_utils::{validate_one_argument, validate_two_arguments};
-use crate::case::evaluate_case;
+use crate::arg_utils::{validate_num_arguments, validate_one_argument, validate_two_arguments};
+use crate::case::{evaluate_case, get_case_type};
 use crate::conditional::{get_conditional_expr_type, ConditionalExpressionType};
-use crate::datetime::{get_datetime_function_type, DateTimeFunctionType};
+use crate::datetime::{
+    evaluate_format_date, evaluate_parse_date, get_datetime_function_type, get_format_date_type,
+    get_parse_date_type, DateTimeFunctionType,
+};
 use crate::error::Error;
 use crate::geo::common::{get_geo_function_type, GeoFunctionType};
-use crate::json_functions::JsonFunctionType;
+use crate::hash::{evaluate_hash, validate_hash, Hasher};
+use crate::json_functions::{evaluate_struct, JsonFunctionType};
 use crate::operator::{BinaryOperatorType, UnaryOperatorType};
+use crate::regexp::{
+    evaluate_regexp_matches, evaluate_regexp_replace, get_regexp_matches_type,
+    get_regexp_replace_type, CompiledRegex,
+};
 use crate::scalar::common::{get_scalar_function_type, ScalarFunctionType};
 use crate::scalar::string::{evaluate_trim, validate_trim, TrimType};
+use crate::vector::{get_vector_function_type, VectorFunctionType};
 use std::iter::zip;
 
 use super::aggregate::AggregateFunctionType;
@@ -42,6 +51,10 @@ pub enum Expression {
         fun: GeoFunctionType,
         args: Vec<Expression>,
     },
+    VectorFunction {
+        fun: VectorFunctionType,
+        args: Vec<Expression>,
+    },
     ConditionalExpression {
         fun: ConditionalExpressionType,
         args: Vec<Expression>,
@@ -50,6 +63,14 @@ pub enum Expression {
         fun: DateTimeFunctionType,
         arg: Box<Expression>,
     },
+    FormatDate {
+        arg: Box<Expression>,
+        format: String,
+    },
+    ParseDate {
+        arg: Box<Expression>,
+        format: String,
+    },
     AggregateFunction {
         fun: AggregateFunctionType,
         args: Vec<Expression>,
@@ -68,6 +89,15 @@ pub enum Expression {
         pattern: Box<Expression>,
         escape: Option<char>,
     },
+    RegexpMatches {
+        arg: Box<Expression>,
+        regex: CompiledRegex,
+    },
+    RegexpReplace {
+        arg: Box<Expression>,
+        regex: CompiledRegex,
+        replacement: Box<Expression>,
+    },
     InList {
         expr: Box<Expression>,
         list: Vec<Expression>,
@@ -80,6 +110,12 @@ pub enum Expression {
         fun: JsonFunctionType,
         args: Vec<Expression>,
     },
+    Hash {
+        hasher: Hasher,
+        args: Vec<Expression>,
+    },
+    /// A JSON object with a key for each name, whose value is the expression's.
+    Struct(Vec<(String, Expression)>),
     Case {
         operand: Option<Box<Expression>>,
         conditions: Vec<Expression>,
@@ -235,6 +271,19 @@ impl Expression {
                 pattern,
                 escape: _,
             } => arg.to_string(schema) + " LIKE " + pattern.to_string(schema).as_str(),
+            Expression::RegexpMatches { arg, regex } => {
+                format!("REGEXP_MATCHES({},{})", arg.to_string(schema), regex)
+            }
+            Expression::RegexpReplace {
+                arg,
+                regex,
+                replacement,
+            } => format!(
+                "REGEXP_REPLACE({},{},{})",
+                arg.to_string(schema),
+                regex,
+                replacement.to_string(schema)
+            ),
             Expression::InList {
                 expr,
                 list,
@@ -262,9 +311,26 @@ impl Expression {
                         .as_str()
                     + ")"
             }
+            Expression::VectorFunction { fun, args } => {
+                fun.to_string()
+                    + "("
+                    + args
+                        .iter()
+                        .map(|e| e.to_string(schema))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
+            }
             Expression::DateTimeFunction { fun, arg } => {
                 fun.to_string() + "(" + arg.to_string(schema).as_str() + ")"
             }
+            Expression::FormatDate { arg, format } => {
+                format!("FORMAT_DATE({},'{}')", arg.to_string(schema), format)
+            }
+            Expression::ParseDate { arg, format } => {
+                format!("PARSE_DATE({},'{}')", arg.to_string(schema), format)
+            }
             Expression::Now { fun } => fun.to_string() + "()",
             Expression::Json { fun, args } => {
                 fun.to_string()
@@ -277,6 +343,27 @@ impl Expression {
                         .as_str()
                     + ")"
             }
+            Expression::Hash { hasher, args } => {
+                hasher.algorithm().to_string()
+                    + "("
+                    + args
+                        .iter()
+                        .map(|e| e.to_string(schema))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
+            }
+            Expression::Struct(fields) => {
+                "STRUCT(".to_string()
+                    + fields
+                        .iter()
+                        .map(|(name, e)| format!("{name} => {}", e.to_string(schema)))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
+            }
             Expression::JavaScriptUdf(udf) => udf.to_string(schema),
         }
     }
@@ -348,6 +435,14 @@ impl Expression {
                 pattern,
                 escape,
             } => evaluate_like(schema, arg, pattern, *escape, record),
+            Expression::RegexpMatches { arg, regex } => {
+                evaluate_regexp_matches(schema, arg, regex, record)
+            }
+            Expression::RegexpReplace {
+                arg,
+                regex,
+                replacement,
+            } => evaluate_regexp_replace(schema, arg, regex, replacement, record),
             Expression::InList {
                 expr,
                 list,
@@ -355,10 +450,19 @@ impl Expression {
             } => evaluate_in_list(schema, expr, list, *negated, record),
             Expression::Cast { arg, typ } => typ.evaluate(schema, arg, record),
             Expression::GeoFunction { fun, args } => fun.evaluate(schema, args, record),
+            Expression::VectorFunction { fun, args } => fun.evaluate(schema, args, record),
             Expression::ConditionalExpression { fun, args } => fun.evaluate(schema, args, record),
             Expression::DateTimeFunction { fun, arg } => fun.evaluate(schema, arg, record),
+            Expression::FormatDate { arg, format } => {
+                evaluate_format_date(schema, arg, format, record)
+            }
+            Expression::ParseDate { arg, format } => {
+                evaluate_parse_date(schema, arg, format, record)
+            }
             Expression::Now { fun } => fun.evaluate_now(),
             Expression::Json { fun, args } => fun.evaluate(schema, args, record),
+            Expression::Hash { hasher, args } => evaluate_hash(schema, hasher, args, record),
+            Expression::Struct(fields) => evaluate_struct(schema, fields, record),
             Expression::Case {
                 operand,
                 conditions,
@@ -418,6 +522,12 @@ impl Expression {
                 pattern,
                 escape: _,
             } => get_like_operator_type(arg, pattern, schema),
+            Expression::RegexpMatches { arg, regex } => get_regexp_matches_type(arg, regex, schema),
+            Expression::RegexpReplace {
+                arg,
+                regex: _,
+                replacement,
+            } => get_regexp_replace_type(arg, replacement, schema),
             Expression::InList {
                 expr: _,
                 list: _,
@@ -430,9 +540,12 @@ impl Expression {
             )),
             Expression::Cast { arg, typ } => typ.get_return_type(schema, arg),
             Expression::GeoFunction { fun, args } => get_geo_function_type(fun, args, schema),
+            Expression::VectorFunction { fun, args } => get_vector_function_type(fun, args, schema),
             Expression::DateTimeFunction { fun, arg } => {
                 get_datetime_function_type(fun, arg, schema)
             }
+            Expression::FormatDate { arg, format: _ } => get_format_date_type(arg, schema),
+            Expression::ParseDate { arg, format: _ } => get_parse_date_type(arg, schema),
             Expression::Now { fun: _ } => Ok(ExpressionType::new(
                 FieldType::Timestamp,
                 false,
@@ -445,20 +558,19 @@ impl Expression {
                 dozer_types::types::SourceDefinition::Dynamic,
                 false,
             )),
+            Expression::Hash { hasher, args } => validate_hash(hasher, args, schema),
+            Expression::Struct(_) => Ok(ExpressionType::new(
+                FieldType::Json,
+                false,
+                SourceDefinition::Dynamic,
+                false,
+            )),
             Expression::Case {
                 operand: _,
                 conditions: _,
                 results,
-                else_result: _,
-            } => {
-                let typ = results.first().unwrap().get_type(schema)?;
-                Ok(ExpressionType::new(
-                    typ.return_type,
-                    true,
-                    dozer_types::types::SourceDefinition::Dynamic,
-                    false,
-                ))
-            }
+                else_result,
+            } => get_case_type(schema, results, else_result),
             #[cfg(feature = "python")]
             Expression::PythonUDF { return_type, .. } => Ok(ExpressionType::new(
                 *return_type,
@@ -493,7 +605,7 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::GeoFunction { args, .. } => {
+            Expression::GeoFunction { args, .. } | Expression::VectorFunction { args, .. } => {
                 for arg in args {
                     arg.serialize_state(object)?;
                 }
@@ -505,7 +617,9 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::DateTimeFunction { arg, .. } => arg.serialize_state(object),
+            Expression::DateTimeFunction { arg, .. }
+            | Expression::FormatDate { arg, .. }
+            | Expression::ParseDate { arg, .. } => arg.serialize_state(object),
             Expression::AggregateFunction { args, .. } => {
                 for arg in args {
                     arg.serialize_state(object)?;
@@ -524,6 +638,13 @@ impl Expression {
                 arg.serialize_state(object)?;
                 pattern.serialize_state(object)
             }
+            Expression::RegexpMatches { arg, .. } => arg.serialize_state(object),
+            Expression::RegexpReplace {
+                arg, replacement, ..
+            } => {
+                arg.serialize_state(object)?;
+                replacement.serialize_state(object)
+            }
             Expression::InList { expr, list, .. } => {
                 expr.serialize_state(object)?;
                 for arg in list {
@@ -532,12 +653,18 @@ impl Expression {
                 Ok(())
             }
             Expression::Now { .. } => Ok(()),
-            Expression::Json { args, .. } => {
+            Expression::Json { args, .. } | Expression::Hash { args, .. } => {
                 for arg in args {
                     arg.serialize_state(object)?;
                 }
                 Ok(())
             }
+            Expression::Struct(fields) => {
+                for (_, arg) in fields {
+                    arg.serialize_state(object)?;
+                }
+                Ok(())
+            }
             Expression::Case {
                 operand,
                 conditions,
@@ -591,7 +718,7 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::GeoFunction { args, .. } => {
+            Expression::GeoFunction { args, .. } | Expression::VectorFunction { args, .. } => {
                 for arg in args {
                     arg.deserialize_state(cursor)?;
                 }
@@ -603,7 +730,9 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::DateTimeFunction { arg, .. } => arg.deserialize_state(cursor),
+            Expression::DateTimeFunction { arg, .. }
+            | Expression::FormatDate { arg, .. }
+            | Expression::ParseDate { arg, .. } => arg.deserialize_state(cursor),
             Expression::AggregateFunction { args, .. } => {
                 for arg in args {
                     arg.deserialize_state(cursor)?;
@@ -622,6 +751,13 @@ impl Expression {
                 arg.deserialize_state(cursor)?;
                 pattern.deserialize_state(cursor)
             }
+            Expression::RegexpMatches { arg, .. } => arg.deserialize_state(cursor),
+            Expression::RegexpReplace {
+                arg, replacement, ..
+            } => {
+                arg.deserialize_state(cursor)?;
+                replacement.deserialize_state(cursor)
+            }
             Expression::InList { expr, list, .. } => {
                 expr.deserialize_state(cursor)?;
                 for arg in list {
@@ -630,12 +766,18 @@ impl Expression {
                 Ok(())
             }
             Expression::Now { .. } => Ok(()),
-            Expression::Json { args, .. } => {
+            Expression::Json { args, .. } | Expression::Hash { args, .. } => {
                 for arg in args {
                     arg.deserialize_state(cursor)?;
                 }
                 Ok(())
             }
+            Expression::Struct(fields) => {
+                for (_, arg) in fields {
+                    arg.deserialize_state(cursor)?;
+                }
+                Ok(())
+            }
             Expression::Case {
                 operand,
                 conditions,
@@ -921,6 +1063,9 @@ fn get_aggregate_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, Error> {
     match function {
+        AggregateFunctionType::ArrayAgg { order_by, .. } => {
+            validate_array_agg(args, schema, order_by.is_some())
+        }
         AggregateFunctionType::Avg => validate_avg(args, schema),
         AggregateFunctionType::Count => validate_count(args, schema),
         AggregateFunctionType::Max => validate_max(args, schema),
@@ -929,10 +1074,32 @@ fn get_aggregate_function_type(
         AggregateFunctionType::Min => validate_min(args, schema),
         AggregateFunctionType::MinAppendOnly => validate_min_append_only(args, schema),
         AggregateFunctionType::MinValue => validate_min_value(args, schema),
+        AggregateFunctionType::PercentileCont(_) | AggregateFunctionType::PercentileDisc(_) => {
+            validate_percentile(function, args, schema)
+        }
         AggregateFunctionType::Sum => validate_sum(args, schema),
     }
 }
 
+fn validate_array_agg(
+    args: &[Expression],
+    schema: &Schema,
+    ordered: bool,
+) -> Result<ExpressionType, Error> {
+    let num_arguments = if ordered { 2 } else { 1 };
+    validate_num_arguments(num_arguments..num_arguments + 1, args.len(), "ARRAY_AGG")?;
+    for arg in args {
+        arg.get_type(schema)?;
+    }
+
+    Ok(ExpressionType::new(
+        FieldType::Json,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
 fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
     let arg = validate_one_argument(args, schema, AggregateFunctionType::Avg)?;
 
@@ -951,7 +1118,8 @@ fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Avg.to_string(),
                 argument_index: 0,
@@ -977,6 +1145,50 @@ fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
     ))
 }
 
+fn validate_percentile(
+    function: &AggregateFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let arg = validate_one_argument(args, schema, function)?;
+
+    match arg.return_type {
+        FieldType::UInt
+        | FieldType::U128
+        | FieldType::Int
+        | FieldType::I128
+        | FieldType::Float
+        | FieldType::Decimal => Ok(ExpressionType::new(
+            FieldType::Float,
+            true,
+            SourceDefinition::Dynamic,
+            false,
+        )),
+        FieldType::Boolean
+        | FieldType::String
+        | FieldType::Text
+        | FieldType::Date
+        | FieldType::Timestamp
+        | FieldType::Binary
+        | FieldType::Json
+        | FieldType::Point
+        | FieldType::Duration
+        | FieldType::Vector(_) => Err(Error::InvalidFunctionArgumentType {
+            function_name: function.to_string(),
+            argument_index: 0,
+            actual: arg.return_type,
+            expected: vec![
+                FieldType::UInt,
+                FieldType::U128,
+                FieldType::Int,
+                FieldType::I128,
+                FieldType::Float,
+                FieldType::Decimal,
+            ],
+        }),
+    }
+}
+
 fn validate_count(_args: &[Expression], _schema: &Schema) -> Result<ExpressionType, Error> {
     Ok(ExpressionType::new(
         FieldType::Int,
@@ -1004,7 +1216,8 @@ fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Max.to_string(),
                 argument_index: 0,
@@ -1049,7 +1262,8 @@ fn validate_min(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Min.to_string(),
                 argument_index: 0,
@@ -1095,7 +1309,8 @@ fn validate_max_append_only(args: &[Expression], schema: &Schema) -> Result<Expr
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MaxAppendOnly.to_string(),
                 argument_index: 0,
@@ -1140,7 +1355,8 @@ fn validate_min_append_only(args: &[Expression], schema: &Schema) -> Result<Expr
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MinAppendOnly.to_string(),
                 argument_index: 0,
@@ -1185,7 +1401,8 @@ fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Sum.to_string(),
                 argument_index: 0,
@@ -1228,7 +1445,8 @@ fn validate_max_value(args: &[Expression], schema: &Schema) -> Result<Expression
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MaxValue.to_string(),
                 argument_index: 0,
@@ -1274,7 +1492,8 @@ fn validate_min_value(args: &[Expression], schema: &Schema) -> Result<Expression
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Vector(_) => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MinValue.to_string(),
                 argument_index: 0,
@@ -0,0 +1,142 @@
+use crate::arg_utils::validate_num_arguments;
+use crate::error::Error;
+use crate::execution::{Expression, ExpressionType};
+
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+
+/// Written between the encoded arguments of a multi-argument hash.
+const SEPARATOR: u8 = 0x1f;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    Blake3,
+    Md5,
+    Sha256,
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashAlgorithm::Blake3 => f.write_str("HASH"),
+            HashAlgorithm::Md5 => f.write_str("MD5"),
+            HashAlgorithm::Sha256 => f.write_str("SHA256"),
+        }
+    }
+}
+
+impl HashAlgorithm {
+    pub(crate) fn new(name: &str) -> Option<HashAlgorithm> {
+        match name {
+            "hash" | "blake3" => Some(HashAlgorithm::Blake3),
+            "md5" => Some(HashAlgorithm::Md5),
+            "sha256" => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Hasher state kept in the expression so it's reset, not rebuilt, for every record.
+#[derive(Debug, Clone)]
+pub enum Hasher {
+    Blake3(Box<blake3::Hasher>),
+    Md5(Md5),
+    Sha256(Sha256),
+}
+
+impl PartialEq for Hasher {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm() == other.algorithm()
+    }
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+            HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        match self {
+            Hasher::Blake3(_) => HashAlgorithm::Blake3,
+            Hasher::Md5(_) => HashAlgorithm::Md5,
+            Hasher::Sha256(_) => HashAlgorithm::Sha256,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Hasher::Blake3(hasher) => {
+                hasher.reset();
+            }
+            Hasher::Md5(hasher) => Digest::reset(hasher),
+            Hasher::Sha256(hasher) => Digest::reset(hasher),
+        }
+    }
+
+    fn finalize_reset(&mut self) -> Vec<u8> {
+        match self {
+            Hasher::Blake3(hasher) => {
+                let hash = hasher.finalize();
+                hasher.reset();
+                hash.as_bytes().to_vec()
+            }
+            Hasher::Md5(hasher) => hasher.finalize_reset().to_vec(),
+            Hasher::Sha256(hasher) => hasher.finalize_reset().to_vec(),
+        }
+    }
+}
+
+pub(crate) fn validate_hash(
+    hasher: &Hasher,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_num_arguments(1..usize::MAX, args.len(), hasher.algorithm())?;
+    for arg in args {
+        arg.get_type(schema)?;
+    }
+    Ok(ExpressionType::new(
+        FieldType::Binary,
+        false,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// Hashes the type-prefixed encoding of every argument, so `1` and `'1'` hash differently.
+pub(crate) fn evaluate_hash(
+    schema: &Schema,
+    hasher: &mut Hasher,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    for (index, arg) in args.iter_mut().enumerate() {
+        if index > 0 {
+            hasher.update(&[SEPARATOR]);
+        }
+        match arg.evaluate(record, schema) {
+            Ok(value) => hasher.update(&value.encode()),
+            Err(e) => {
+                hasher.reset();
+                return Err(e);
+            }
+        }
+    }
+    Ok(Field::Binary(hasher.finalize_reset()))
+}
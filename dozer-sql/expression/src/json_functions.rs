@@ -2,17 +2,35 @@ use crate::arg_utils::validate_num_arguments;
 use crate::error::Error;
 use crate::execution::Expression;
 
-use dozer_types::json_types::JsonValue;
+use dozer_types::json_types::{field_to_json_value, JsonObject, JsonValue};
 use dozer_types::types::Record;
 use dozer_types::types::{Field, Schema};
 use jsonpath::{JsonPathFinder, JsonPathInst};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+/// Evaluates `STRUCT(name => expr, ...)` to a JSON object. Null values become JSON nulls.
+pub(crate) fn evaluate_struct(
+    schema: &Schema,
+    fields: &mut [(String, Expression)],
+    record: &Record,
+) -> Result<Field, Error> {
+    let mut object = JsonObject::new();
+    for (name, expr) in fields {
+        object.insert(
+            name.as_str(),
+            field_to_json_value(expr.evaluate(record, schema)?),
+        );
+    }
+    Ok(Field::Json(object.into()))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum JsonFunctionType {
     JsonValue,
     JsonQuery,
+    JsonExtract,
+    JsonSet,
 }
 
 impl Display for JsonFunctionType {
@@ -20,6 +38,8 @@ impl Display for JsonFunctionType {
         match self {
             JsonFunctionType::JsonValue => f.write_str("JSON_VALUE".to_string().as_str()),
             JsonFunctionType::JsonQuery => f.write_str("JSON_QUERY".to_string().as_str()),
+            JsonFunctionType::JsonExtract => f.write_str("JSON_EXTRACT"),
+            JsonFunctionType::JsonSet => f.write_str("JSON_SET"),
         }
     }
 }
@@ -29,6 +49,8 @@ impl JsonFunctionType {
         match name {
             "json_value" => Some(JsonFunctionType::JsonValue),
             "json_query" => Some(JsonFunctionType::JsonQuery),
+            "json_extract" => Some(JsonFunctionType::JsonExtract),
+            "json_set" => Some(JsonFunctionType::JsonSet),
             _ => None,
         }
     }
@@ -42,6 +64,8 @@ impl JsonFunctionType {
         match self {
             JsonFunctionType::JsonValue => self.evaluate_json_value(schema, args, record),
             JsonFunctionType::JsonQuery => self.evaluate_json_query(schema, args, record),
+            JsonFunctionType::JsonExtract => self.evaluate_json_extract(schema, args, record),
+            JsonFunctionType::JsonSet => self.evaluate_json_set(schema, args, record),
         }
     }
 
@@ -92,6 +116,49 @@ impl JsonFunctionType {
         }
     }
 
+    /// Returns the value at `path`, or `Field::Null` if nothing matches. Several matches are returned as an array.
+    pub(crate) fn evaluate_json_extract(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        validate_num_arguments(2..3, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        let path = args[1].evaluate(record, schema)?.to_string();
+
+        let json_value = self.evaluate_json(json_input, path)?;
+        if json_value.is_null() {
+            Ok(Field::Null)
+        } else {
+            Ok(Field::Json(json_value))
+        }
+    }
+
+    /// Returns a copy of the input with the value at `path` replaced. Missing object keys are created,
+    /// the input is returned unchanged if the path goes through a scalar or past the end of an array.
+    pub(crate) fn evaluate_json_set(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        validate_num_arguments(3..4, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        let path = args[1].evaluate(record, schema)?.to_string();
+        let value = field_to_json_value(args[2].evaluate(record, schema)?);
+
+        let Some(mut json_value) = json_input.to_json().filter(|json| !json.is_null()) else {
+            return Ok(Field::Null);
+        };
+        let segments = parse_json_set_path(&path)?;
+        let mut updated = json_value.clone();
+        if set_json_path(&mut updated, &segments, value).is_some() {
+            json_value = updated;
+        }
+        Ok(Field::Json(json_value))
+    }
+
     pub(crate) fn evaluate_json(
         &self,
         json_input: Field,
@@ -116,3 +183,71 @@ impl JsonFunctionType {
         Ok(found)
     }
 }
+
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses the subset of JSONPath that addresses a single value: `$`, `.key`, `['key']` and `[index]`.
+fn parse_json_set_path(path: &str) -> Result<Vec<JsonPathSegment>, Error> {
+    let invalid = || Error::InvalidJsonPath(path.to_string());
+    let mut rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = vec![];
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(JsonPathSegment::Key(after_dot[..end].to_string()));
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket.find(']').ok_or_else(invalid)?;
+            let inner = after_bracket[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+                .or_else(|| {
+                    inner
+                        .strip_prefix('"')
+                        .and_then(|key| key.strip_suffix('"'))
+                });
+            segments.push(match quoted {
+                Some(key) => JsonPathSegment::Key(key.to_string()),
+                None => JsonPathSegment::Index(inner.parse().map_err(|_| invalid())?),
+            });
+            rest = &after_bracket[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+/// Returns `None` if the path can't be followed in `target`.
+fn set_json_path(
+    target: &mut JsonValue,
+    segments: &[JsonPathSegment],
+    value: JsonValue,
+) -> Option<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        *target = value;
+        return Some(());
+    };
+    let child = match segment {
+        JsonPathSegment::Key(key) => {
+            if target.is_null() {
+                *target = JsonObject::new().into();
+            }
+            let object = target.as_object_mut()?;
+            if object.get(key.as_str()).is_none() {
+                object.insert(key.as_str(), JsonValue::NULL);
+            }
+            object.get_mut(key.as_str())?
+        }
+        JsonPathSegment::Index(index) => target.as_array_mut()?.get_mut(*index)?,
+    };
+    set_json_path(child, rest, value)
+}
@@ -2,19 +2,22 @@ pub mod aggregate;
 mod arg_utils;
 pub mod builder;
 mod case;
-mod cast;
+pub mod cast;
 mod comparison;
-mod conditional;
+pub mod conditional;
 mod datetime;
 pub mod error;
 pub mod execution;
 mod geo;
+mod hash;
 mod in_list;
 mod json_functions;
 mod logical;
 mod mathematical;
 pub mod operator;
+mod regexp;
 pub mod scalar;
+mod vector;
 
 mod javascript;
 #[cfg(feature = "onnx")]
@@ -30,6 +30,7 @@ pub fn evaluate_and(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "AND".to_string())),
         },
         Field::Boolean(false) => match r_field {
@@ -49,6 +50,7 @@ pub fn evaluate_and(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "AND".to_string())),
         },
         Field::Null => Ok(Field::Boolean(false)),
@@ -65,6 +67,7 @@ pub fn evaluate_and(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_) => Err(Error::InvalidType(l_field, "AND".to_string())),
     }
 }
@@ -95,6 +98,7 @@ pub fn evaluate_or(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "OR".to_string())),
         },
         Field::Boolean(false) | Field::Null => match right.evaluate(record, schema)? {
@@ -114,6 +118,7 @@ pub fn evaluate_or(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "OR".to_string())),
         },
         Field::UInt(_)
@@ -129,6 +134,7 @@ pub fn evaluate_or(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_) => Err(Error::InvalidType(l_field, "OR".to_string())),
     }
 }
@@ -156,6 +162,7 @@ pub fn evaluate_not(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_) => Err(Error::InvalidType(value_p, "NOT".to_string())),
     }
 }
@@ -83,6 +83,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Null => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -165,6 +166,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Null => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -370,6 +372,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -617,6 +620,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -842,6 +846,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1112,6 +1117,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1353,6 +1359,7 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Vector(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1484,6 +1491,7 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Vector(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1574,6 +1582,7 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Vector(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1664,6 +1673,7 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Vector(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1737,6 +1747,7 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Vector(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1759,7 +1770,8 @@ macro_rules! define_math_operator {
                 | Field::Binary(_)
                 | Field::Date(_)
                 | Field::Json(_)
-                | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                | Field::Point(_)
+                | Field::Vector(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
                     $op.to_string(),
@@ -1796,6 +1808,7 @@ pub fn evaluate_plus(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_)
         | Field::Null => Err(PipelineError::InvalidType(
             expression_result,
@@ -1826,6 +1839,7 @@ pub fn evaluate_minus(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_)
         | Field::Null => Err(PipelineError::InvalidType(
             expression_result,
@@ -73,6 +73,7 @@ pub fn evaluate_py_udf(
             | FieldType::Timestamp
             | FieldType::Point
             | FieldType::Duration
+            | FieldType::Vector(_)
             | FieldType::Json => return Err(Error::UnsupportedReturnType(*return_type)),
         })
     })
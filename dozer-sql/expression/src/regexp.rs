@@ -0,0 +1,142 @@
+use std::fmt::{Display, Formatter};
+
+use dozer_types::json_types::{JsonObject, JsonValue};
+use dozer_types::types::{Field, FieldType, Record, Schema};
+use regex::Regex;
+
+use crate::arg_utils::validate_arg_type;
+use crate::error::Error;
+use crate::execution::{Expression, ExpressionType};
+
+/// A regex compiled once when the expression is built.
+#[derive(Debug, Clone)]
+pub struct CompiledRegex(Regex);
+
+impl CompiledRegex {
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(Self(Regex::new(pattern)?))
+    }
+
+    fn has_named_groups(&self) -> bool {
+        self.0.capture_names().flatten().next().is_some()
+    }
+}
+
+impl PartialEq for CompiledRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Display for CompiledRegex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.0.as_str())
+    }
+}
+
+/// `REGEXP_MATCHES` returns whether the regex matches, or the named groups of the first match as a JSON object
+/// if the regex has any.
+pub(crate) fn get_regexp_matches_type(
+    arg: &Expression,
+    regex: &CompiledRegex,
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        "REGEXP_MATCHES",
+        0,
+    )?;
+    if regex.has_named_groups() {
+        Ok(ExpressionType {
+            return_type: FieldType::Json,
+            nullable: true,
+            ..arg_type
+        })
+    } else {
+        Ok(ExpressionType {
+            return_type: FieldType::Boolean,
+            ..arg_type
+        })
+    }
+}
+
+pub(crate) fn get_regexp_replace_type(
+    arg: &Expression,
+    replacement: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_arg_type(
+        replacement,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        "REGEXP_REPLACE",
+        2,
+    )?;
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        "REGEXP_REPLACE",
+        0,
+    )?;
+    Ok(ExpressionType {
+        return_type: FieldType::String,
+        ..arg_type
+    })
+}
+
+pub(crate) fn evaluate_regexp_matches(
+    schema: &Schema,
+    arg: &mut Expression,
+    regex: &CompiledRegex,
+    record: &Record,
+) -> Result<Field, Error> {
+    let arg_field = arg.evaluate(record, schema)?;
+    let Some(value) = arg_field.as_string().or_else(|| arg_field.as_text()) else {
+        return Ok(Field::Null);
+    };
+
+    if !regex.has_named_groups() {
+        return Ok(Field::Boolean(regex.0.is_match(value)));
+    }
+
+    let Some(captures) = regex.0.captures(value) else {
+        return Ok(Field::Null);
+    };
+    let mut object = JsonObject::new();
+    for name in regex.0.capture_names().flatten() {
+        let group: JsonValue = match captures.name(name) {
+            Some(group) => group.as_str().into(),
+            None => JsonValue::NULL,
+        };
+        object.insert(name, group);
+    }
+    Ok(Field::Json(object.into()))
+}
+
+/// Replaces every match. The replacement can refer to groups as `$1` or `${name}`.
+pub(crate) fn evaluate_regexp_replace(
+    schema: &Schema,
+    arg: &mut Expression,
+    regex: &CompiledRegex,
+    replacement: &mut Expression,
+    record: &Record,
+) -> Result<Field, Error> {
+    let arg_field = arg.evaluate(record, schema)?;
+    let Some(value) = arg_field.as_string().or_else(|| arg_field.as_text()) else {
+        return Ok(Field::Null);
+    };
+    let replacement_field = replacement.evaluate(record, schema)?;
+    let Some(replacement) = replacement_field
+        .as_string()
+        .or_else(|| replacement_field.as_text())
+    else {
+        return Ok(Field::Null);
+    };
+
+    Ok(Field::String(
+        regex.0.replace_all(value, replacement).into_owned(),
+    ))
+}
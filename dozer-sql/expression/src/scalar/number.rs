@@ -27,6 +27,7 @@ pub(crate) fn evaluate_abs(
         | Field::Binary(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_)
         | Field::Null => Err(Error::InvalidFunctionArgument {
             function_name: ScalarFunctionType::Abs.to_string(),
@@ -69,6 +70,7 @@ pub(crate) fn evaluate_round(
             | Field::Binary(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Vector(_)
             | Field::Duration(_)
             | Field::Null => {} // Truncate value to 0 decimals
         }
@@ -91,6 +93,7 @@ pub(crate) fn evaluate_round(
         | Field::Binary(_)
         | Field::Json(_)
         | Field::Point(_)
+        | Field::Vector(_)
         | Field::Duration(_) => Err(Error::InvalidFunctionArgument {
             function_name: ScalarFunctionType::Round.to_string(),
             argument_index: 0,
@@ -45,7 +45,8 @@ pub fn evaluate_ucase(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::Text(ret),
+        | FieldType::Duration
+        | FieldType::Vector(_) => Field::Text(ret),
     })
 }
 
@@ -104,7 +105,8 @@ pub fn evaluate_concat(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::String(res_str),
+        | FieldType::Duration
+        | FieldType::Vector(_) => Field::String(res_str),
     })
 }
 
@@ -185,7 +187,8 @@ pub fn evaluate_trim(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::Text(retval),
+        | FieldType::Duration
+        | FieldType::Vector(_) => Field::Text(retval),
     })
 }
 
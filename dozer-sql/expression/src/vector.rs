@@ -0,0 +1,110 @@
+use std::fmt::{Display, Formatter};
+
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use crate::arg_utils::validate_two_arguments;
+use crate::error::Error;
+use crate::execution::{Expression, ExpressionType};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum VectorFunctionType {
+    CosineSimilarity,
+    L2Distance,
+}
+
+impl Display for VectorFunctionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VectorFunctionType::CosineSimilarity => f.write_str("COSINE_SIMILARITY"),
+            VectorFunctionType::L2Distance => f.write_str("L2_DISTANCE"),
+        }
+    }
+}
+
+/// Both arguments must be vectors of the same dimension.
+pub(crate) fn get_vector_function_type(
+    function: &VectorFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let (left, right) = validate_two_arguments(args, schema, function)?;
+    let FieldType::Vector(dimension) = left.return_type else {
+        return Err(Error::InvalidFunctionArgumentType {
+            function_name: function.to_string(),
+            argument_index: 0,
+            actual: left.return_type,
+            expected: vec![FieldType::Vector(0)],
+        });
+    };
+    if right.return_type != FieldType::Vector(dimension) {
+        return Err(Error::InvalidFunctionArgumentType {
+            function_name: function.to_string(),
+            argument_index: 1,
+            actual: right.return_type,
+            expected: vec![FieldType::Vector(dimension)],
+        });
+    }
+
+    Ok(ExpressionType::new(
+        FieldType::Float,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+impl VectorFunctionType {
+    pub(crate) fn new(name: &str) -> Option<VectorFunctionType> {
+        match name {
+            "cosine_similarity" => Some(VectorFunctionType::CosineSimilarity),
+            "l2_distance" => Some(VectorFunctionType::L2Distance),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        let left = args[0].evaluate(record, schema)?;
+        let right = args[1].evaluate(record, schema)?;
+        let (Some(left_vector), Some(right_vector)) = (left.as_vector(), right.as_vector()) else {
+            return Ok(Field::Null);
+        };
+        if left_vector.len() != right_vector.len() {
+            return Err(Error::InvalidFunctionArgument {
+                function_name: self.to_string(),
+                argument_index: 1,
+                argument: right.clone(),
+            });
+        }
+
+        let pairs = left_vector
+            .iter()
+            .zip(right_vector)
+            .map(|(l, r)| (l.0 as f64, r.0 as f64));
+        match self {
+            VectorFunctionType::CosineSimilarity => {
+                let (mut dot, mut left_norm, mut right_norm) = (0.0, 0.0, 0.0);
+                for (l, r) in pairs {
+                    dot += l * r;
+                    left_norm += l * l;
+                    right_norm += r * r;
+                }
+                // The similarity to a zero vector is undefined.
+                if left_norm == 0.0 || right_norm == 0.0 {
+                    return Ok(Field::Null);
+                }
+                Ok(Field::Float(OrderedFloat(
+                    dot / (left_norm.sqrt() * right_norm.sqrt()),
+                )))
+            }
+            VectorFunctionType::L2Distance => Ok(Field::Float(OrderedFloat(
+                pairs.map(|(l, r)| (l - r) * (l - r)).sum::<f64>().sqrt(),
+            ))),
+        }
+    }
+}
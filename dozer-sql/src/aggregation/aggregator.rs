@@ -1,5 +1,6 @@
 #![allow(clippy::enum_variant_names)]
 
+use crate::aggregation::array_agg::ArrayAggAggregator;
 use crate::aggregation::avg::AvgAggregator;
 use crate::aggregation::count::CountAggregator;
 use crate::aggregation::max::MaxAggregator;
@@ -14,13 +15,14 @@ use dozer_types::rust_decimal::Decimal;
 use enum_dispatch::enum_dispatch;
 use std::collections::BTreeMap;
 
-use dozer_sql_expression::aggregate::AggregateFunctionType;
+use dozer_sql_expression::aggregate::{AggregateFunctionType, SortDirection};
 use dozer_sql_expression::execution::Expression;
 
 use crate::aggregation::max_append_only::MaxAppendOnlyAggregator;
 use crate::aggregation::max_value::MaxValueAggregator;
 use crate::aggregation::min_append_only::MinAppendOnlyAggregator;
 use crate::aggregation::min_value::MinValueAggregator;
+use crate::aggregation::percentile::PercentileAggregator;
 use crate::errors::PipelineError::{InvalidFunctionArgument, InvalidValue};
 use dozer_sql_expression::aggregate::AggregateFunctionType::MaxValue;
 use dozer_types::types::{DozerDuration, Field, FieldType, Schema};
@@ -37,6 +39,7 @@ pub trait Aggregator: Send + Sync + bincode::Encode + bincode::Decode {
 #[enum_dispatch(Aggregator)]
 #[derive(Debug, bincode::Encode, bincode::Decode)]
 pub enum AggregatorEnum {
+    ArrayAggAggregator,
     AvgAggregator,
     MinAggregator,
     MinAppendOnlyAggregator,
@@ -46,10 +49,15 @@ pub enum AggregatorEnum {
     MaxValueAggregator,
     SumAggregator,
     CountAggregator,
+    PercentileAggregator,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregatorType {
+    ArrayAgg {
+        distinct: bool,
+        order_by: Option<SortDirection>,
+    },
     Avg,
     Count,
     Max,
@@ -58,6 +66,8 @@ pub enum AggregatorType {
     Min,
     MinAppendOnly,
     MinValue,
+    PercentileCont(OrderedFloat<f64>),
+    PercentileDisc(OrderedFloat<f64>),
     Sum,
 }
 
@@ -241,6 +251,7 @@ impl OrderedAggregatorState {
 impl Display for AggregatorType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregatorType::ArrayAgg { .. } => f.write_str("array_agg"),
             AggregatorType::Avg => f.write_str("avg"),
             AggregatorType::Count => f.write_str("count"),
             AggregatorType::Max => f.write_str("max"),
@@ -249,6 +260,8 @@ impl Display for AggregatorType {
             AggregatorType::Min => f.write_str("min"),
             AggregatorType::MinAppendOnly => f.write_str("min_append_only"),
             AggregatorType::MinValue => f.write_str("min_value"),
+            AggregatorType::PercentileCont(_) => f.write_str("percentile_cont"),
+            AggregatorType::PercentileDisc(_) => f.write_str("percentile_disc"),
             AggregatorType::Sum => f.write_str("sum"),
         }
     }
@@ -256,6 +269,9 @@ impl Display for AggregatorType {
 
 pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnum {
     match typ {
+        AggregatorType::ArrayAgg { distinct, order_by } => {
+            ArrayAggAggregator::new(distinct, order_by).into()
+        }
         AggregatorType::Avg => AvgAggregator::new().into(),
         AggregatorType::Count => CountAggregator::new().into(),
         AggregatorType::Max => MaxAggregator::new().into(),
@@ -264,6 +280,12 @@ pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnu
         AggregatorType::Min => MinAggregator::new().into(),
         AggregatorType::MinAppendOnly => MinAppendOnlyAggregator::new().into(),
         AggregatorType::MinValue => MinValueAggregator::new().into(),
+        AggregatorType::PercentileCont(fraction) => {
+            PercentileAggregator::new(AggregateFunctionType::PercentileCont(fraction)).into()
+        }
+        AggregatorType::PercentileDisc(fraction) => {
+            PercentileAggregator::new(AggregateFunctionType::PercentileDisc(fraction)).into()
+        }
         AggregatorType::Sum => SumAggregator::new().into(),
     }
 }
@@ -273,6 +295,16 @@ pub fn get_aggregator_type_from_aggregation_expression(
     schema: &Schema,
 ) -> Result<(Vec<Expression>, AggregatorType), PipelineError> {
     match e {
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::ArrayAgg { distinct, order_by },
+            args,
+        } => Ok((
+            args.clone(),
+            AggregatorType::ArrayAgg {
+                distinct: *distinct,
+                order_by: *order_by,
+            },
+        )),
         Expression::AggregateFunction {
             fun: AggregateFunctionType::Sum,
             args,
@@ -405,6 +437,26 @@ pub fn get_aggregator_type_from_aggregation_expression(
                 .clone()],
             AggregatorType::Count,
         )),
+        Expression::AggregateFunction {
+            fun: fun @ AggregateFunctionType::PercentileCont(fraction),
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| PipelineError::NotEnoughArguments(fun.to_string()))?
+                .clone()],
+            AggregatorType::PercentileCont(*fraction),
+        )),
+        Expression::AggregateFunction {
+            fun: fun @ AggregateFunctionType::PercentileDisc(fraction),
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| PipelineError::NotEnoughArguments(fun.to_string()))?
+                .clone()],
+            AggregatorType::PercentileDisc(*fraction),
+        )),
         _ => Err(PipelineError::InvalidFunction(e.to_string(schema))),
     }
 }
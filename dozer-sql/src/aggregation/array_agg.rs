@@ -0,0 +1,98 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::errors::PipelineError;
+use crate::errors::PipelineError::InvalidValue;
+use dozer_sql_expression::aggregate::SortDirection;
+use dozer_types::json_types::{field_to_json_value, JsonArray};
+use dozer_types::types::{Field, FieldType};
+
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct ArrayAggAggregator {
+    distinct: bool,
+    order_by: Option<SortDirection>,
+    /// `(sort key, value)` pairs, kept sorted by key. Values with equal keys stay in insertion order.
+    /// The key is `Field::Null` when there's no `ORDER BY`.
+    values: Vec<(Field, Field)>,
+}
+
+impl ArrayAggAggregator {
+    pub fn new(distinct: bool, order_by: Option<SortDirection>) -> Self {
+        Self {
+            distinct,
+            order_by,
+            values: Vec::new(),
+        }
+    }
+
+    fn entry(&self, fields: &[Field]) -> Result<(Field, Field), PipelineError> {
+        let value = fields
+            .first()
+            .ok_or_else(|| InvalidValue("ARRAY_AGG is missing its argument".to_string()))?
+            .clone();
+        let key = match self.order_by {
+            Some(_) => fields
+                .get(1)
+                .ok_or_else(|| InvalidValue("ARRAY_AGG is missing its ORDER BY key".to_string()))?
+                .clone(),
+            None => Field::Null,
+        };
+        Ok((key, value))
+    }
+
+    fn get_array(&self) -> Field {
+        if self.values.is_empty() {
+            return Field::Null;
+        }
+        let mut seen: Vec<&Field> = Vec::new();
+        let array = self
+            .values
+            .iter()
+            .filter(|(_, value)| {
+                if !self.distinct {
+                    return true;
+                }
+                if seen.contains(&value) {
+                    return false;
+                }
+                seen.push(value);
+                true
+            })
+            .map(|(_, value)| field_to_json_value(value.clone()))
+            .collect::<JsonArray>();
+        Field::Json(array.into())
+    }
+}
+
+impl Aggregator for ArrayAggAggregator {
+    fn init(&mut self, _return_type: FieldType) {}
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        let entry = self.entry(old)?;
+        let index = self
+            .values
+            .iter()
+            .position(|existing| existing == &entry)
+            .ok_or_else(|| InvalidValue(format!("{:?}", entry.1)))?;
+        self.values.remove(index);
+        Ok(self.get_array())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let entry = self.entry(new)?;
+        let index = match self.order_by {
+            Some(SortDirection::Ascending) => {
+                self.values.partition_point(|(key, _)| key <= &entry.0)
+            }
+            Some(SortDirection::Descending) => {
+                self.values.partition_point(|(key, _)| key >= &entry.0)
+            }
+            None => self.values.len(),
+        };
+        self.values.insert(index, entry);
+        Ok(self.get_array())
+    }
+}
@@ -154,7 +154,8 @@ fn get_average(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Vector(_) => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Avg}"
             ))),
         },
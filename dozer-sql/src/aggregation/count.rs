@@ -64,7 +64,8 @@ fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, Pipeli
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Vector(_) => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Count}"
             ))),
         },
@@ -1,16 +1,29 @@
 use crate::planner::projection::CommonPlanner;
+use crate::projection::factory::{
+    apply_defaults, apply_output_order, apply_type_capabilities, parse_predicate,
+};
 use crate::projection::processor::ProjectionProcessor;
+use crate::projection::type_capabilities::TypeCapabilities;
+use crate::schema_evolution::{
+    schema_evolution_handler, MigrateOnSchemaChange, SchemaEvolutionHandler,
+};
+use crate::wire::{parse_expr, parse_select_item, AGGREGATION_TYPE_NAME};
 use crate::{aggregation::processor::AggregationProcessor, errors::PipelineError};
 use dozer_core::{
     node::{PortHandle, Processor, ProcessorFactory},
+    pipeline::WireFormatFactory,
     DEFAULT_PORT_HANDLE,
 };
+use dozer_sql_expression::execution::Expression;
 use dozer_sql_expression::sqlparser::ast::{Expr, SelectItem};
 use dozer_types::errors::internal::BoxedError;
+use dozer_types::models::flags::{ProjectionErrorPolicy, SchemaChangePolicy};
 use dozer_types::models::udf_config::UdfConfig;
 use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json;
 use dozer_types::tonic::async_trait;
-use dozer_types::types::Schema;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -24,6 +37,19 @@ pub struct AggregationProcessorFactory {
     enable_probabilistic_optimizations: bool,
     udfs: Vec<UdfConfig>,
     runtime: Arc<Runtime>,
+    /// Used when the query turns out to be a plain projection.
+    projection_error_policy: ProjectionErrorPolicy,
+    /// Used when the query turns out to be a plain projection.
+    schema_evolution: Arc<dyn SchemaEvolutionHandler>,
+    /// Values that replace nulls in the output columns of the same name, if the query is a plain projection.
+    projection_defaults: HashMap<String, Field>,
+    /// Records this isn't true for are dropped, if the query is a plain projection.
+    projection_predicate: Option<Expr>,
+    /// The order of the output columns by name, if the query is a plain projection and it differs from the select
+    /// order.
+    projection_output_order: Option<Vec<String>>,
+    /// The types the downstream sink can write, if the query is a plain projection and they're checked.
+    projection_type_capabilities: Option<TypeCapabilities>,
 
     /// Type name can only be determined after schema propagation.
     type_name: Mutex<Option<String>>,
@@ -47,10 +73,169 @@ impl AggregationProcessorFactory {
             enable_probabilistic_optimizations,
             udfs,
             runtime,
+            projection_error_policy: ProjectionErrorPolicy::default(),
+            schema_evolution: Arc::new(MigrateOnSchemaChange),
+            projection_defaults: HashMap::new(),
+            projection_predicate: None,
+            projection_output_order: None,
+            projection_type_capabilities: None,
             type_name: Mutex::new(None),
         }
     }
 
+    /// Sets what a plain projection does with records its expressions fail to evaluate on.
+    pub fn with_projection_error_policy(mut self, policy: ProjectionErrorPolicy) -> Self {
+        self.projection_error_policy = policy;
+        self
+    }
+
+    /// Sets what a plain projection does when columns are added to or removed from its input.
+    pub fn with_schema_evolution_handler(
+        mut self,
+        schema_evolution: Arc<dyn SchemaEvolutionHandler>,
+    ) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
+
+    /// Replaces nulls in the given output columns of a plain projection with a default. Aggregations fail to build
+    /// if any are given.
+    pub fn with_projection_defaults(mut self, defaults: HashMap<String, Field>) -> Self {
+        self.projection_defaults = defaults;
+        self
+    }
+
+    /// Drops the records of a plain projection that `predicate` evaluates to false or null on. Aggregations fail to
+    /// build if one is given.
+    pub fn with_projection_predicate(mut self, predicate: Expr) -> Self {
+        self.projection_predicate = Some(predicate);
+        self
+    }
+
+    /// Outputs the columns of a plain projection in `output_order`, which must name each selected column exactly
+    /// once. Aggregations fail to build if one is given.
+    pub fn with_projection_output_order(mut self, output_order: Vec<String>) -> Self {
+        self.projection_output_order = Some(output_order);
+        self
+    }
+
+    /// Casts the outputs of a plain projection of types the downstream sink can't write to the types
+    /// `type_capabilities` coerces them to. Aggregations fail to build if any are given.
+    pub fn with_projection_type_capabilities(
+        mut self,
+        type_capabilities: TypeCapabilities,
+    ) -> Self {
+        self.projection_type_capabilities = Some(type_capabilities);
+        self
+    }
+
+    /// Whether options that only apply to plain projections are set.
+    fn has_projection_options(&self) -> bool {
+        !self.projection_defaults.is_empty()
+            || self.projection_predicate.is_some()
+            || self.projection_output_order.is_some()
+            || self.projection_type_capabilities.is_some()
+    }
+
+    /// The expressions, output schema and predicate of a plain projection, with the projection options applied.
+    async fn plan_projection(
+        &self,
+        input_schema: &Schema,
+        planner: &CommonPlanner,
+    ) -> Result<(Vec<Expression>, Schema, Option<Expression>), PipelineError> {
+        let output_fields = &planner.post_projection_schema.fields;
+        let select_expr = output_fields
+            .iter()
+            .map(|field| field.name.clone())
+            .zip(planner.projection_output.iter().cloned())
+            .collect();
+        let select_expr = apply_defaults(&self.projection_defaults, select_expr, input_schema)?;
+        let select_expr = apply_type_capabilities(
+            self.projection_type_capabilities.as_ref(),
+            select_expr,
+            input_schema,
+        )?;
+        let select_expr = apply_output_order(self.projection_output_order.as_deref(), select_expr)?;
+
+        let mut output_schema = planner.post_projection_schema.clone();
+        output_schema.fields = select_expr
+            .iter()
+            .map(|(name, expr)| {
+                let field = output_fields
+                    .iter()
+                    .find(|field| &field.name == name)
+                    .expect("outputs keep their names");
+                let typ = expr.get_type(input_schema)?;
+                Ok(FieldDefinition {
+                    typ: typ.return_type,
+                    nullable: typ.nullable,
+                    ..field.clone()
+                })
+            })
+            .collect::<Result<_, PipelineError>>()?;
+        // The primary key columns keep their names, but may move
+        output_schema.primary_index = planner
+            .post_projection_schema
+            .primary_index
+            .iter()
+            .filter_map(|index| {
+                let name = &output_fields[*index].name;
+                output_schema
+                    .fields
+                    .iter()
+                    .position(|field| &field.name == name)
+            })
+            .collect();
+        let expressions = select_expr.into_iter().map(|(_, expr)| expr).collect();
+        let predicate = parse_predicate(
+            self.projection_predicate.as_ref(),
+            input_schema,
+            &self.udfs,
+            self.runtime.clone(),
+        )
+        .await?;
+        Ok((expressions, output_schema, predicate))
+    }
+
+    /// Rebuilds a factory from the configuration `to_wire` sent.
+    pub(crate) fn from_wire(config: &[u8], runtime: Arc<Runtime>) -> Result<Self, BoxedError> {
+        let config: AggregationWireConfig = serde_json::from_slice(config)?;
+        let mut factory = Self::new(
+            config.id,
+            config
+                .projection
+                .iter()
+                .map(|item| parse_select_item(item))
+                .collect::<Result<_, _>>()?,
+            config
+                .group_by
+                .iter()
+                .map(|expr| parse_expr(expr))
+                .collect::<Result<_, _>>()?,
+            config.having.as_deref().map(parse_expr).transpose()?,
+            config.enable_probabilistic_optimizations,
+            config.udfs,
+            runtime,
+        )
+        .with_projection_error_policy(config.projection_error_policy)
+        .with_schema_evolution_handler(schema_evolution_handler(config.on_schema_change))
+        .with_projection_defaults(config.projection_defaults);
+        factory.projection_predicate = config
+            .projection_predicate
+            .as_deref()
+            .map(parse_expr)
+            .transpose()?;
+        factory.projection_output_order = config.projection_output_order;
+        factory.projection_type_capabilities =
+            config
+                .projection_type_capabilities
+                .map(|(supported, coercions)| TypeCapabilities {
+                    supported: supported.into_iter().collect(),
+                    coercions: coercions.into_iter().collect(),
+                });
+        Ok(factory)
+    }
+
     async fn get_planner(&self, input_schema: Schema) -> Result<CommonPlanner, PipelineError> {
         let mut projection_planner =
             CommonPlanner::new(input_schema, self.udfs.as_slice(), self.runtime.clone());
@@ -102,7 +287,13 @@ impl ProcessorFactory for AggregationProcessorFactory {
             .to_string(),
         );
 
-        Ok(planner.post_projection_schema)
+        if !is_projection(&planner) {
+            if self.has_projection_options() {
+                return Err(PipelineError::ProjectionOptionsOnAggregation.into());
+            }
+            return Ok(planner.post_projection_schema);
+        }
+        Ok(self.plan_projection(input_schema, &planner).await?.1)
     }
 
     async fn build(
@@ -118,9 +309,15 @@ impl ProcessorFactory for AggregationProcessorFactory {
         let planner = self.get_planner(input_schema.clone()).await?;
 
         let processor: Box<dyn Processor> = if is_projection(&planner) {
+            let (expressions, output_schema, predicate) =
+                self.plan_projection(input_schema, &planner).await?;
             Box::new(ProjectionProcessor::new(
                 input_schema.clone(),
-                planner.projection_output,
+                output_schema,
+                expressions,
+                predicate,
+                self.projection_error_policy,
+                self.schema_evolution.clone(),
                 checkpoint_data,
             )?)
         } else {
@@ -142,6 +339,58 @@ impl ProcessorFactory for AggregationProcessorFactory {
     fn id(&self) -> String {
         self.id.clone()
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        let config = AggregationWireConfig {
+            id: self.id.clone(),
+            projection: self.projection.iter().map(ToString::to_string).collect(),
+            group_by: self.group_by.iter().map(ToString::to_string).collect(),
+            having: self.having.as_ref().map(ToString::to_string),
+            enable_probabilistic_optimizations: self.enable_probabilistic_optimizations,
+            udfs: self.udfs.clone(),
+            projection_error_policy: self.projection_error_policy,
+            // Custom handlers can't be rebuilt on the other node.
+            on_schema_change: self.schema_evolution.policy()?,
+            projection_defaults: self.projection_defaults.clone(),
+            projection_predicate: self.projection_predicate.as_ref().map(ToString::to_string),
+            projection_output_order: self.projection_output_order.clone(),
+            projection_type_capabilities: self.projection_type_capabilities.as_ref().map(
+                |type_capabilities| {
+                    (
+                        type_capabilities.supported.iter().copied().collect(),
+                        type_capabilities
+                            .coercions
+                            .iter()
+                            .map(|(from, to)| (*from, *to))
+                            .collect(),
+                    )
+                },
+            ),
+        };
+        Some(WireFormatFactory {
+            type_name: AGGREGATION_TYPE_NAME.to_string(),
+            config: serde_json::to_vec(&config).ok()?,
+        })
+    }
+}
+
+/// The configuration `AggregationProcessorFactory::to_wire` sends, with the parts of the query written as SQL.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct AggregationWireConfig {
+    id: String,
+    projection: Vec<String>,
+    group_by: Vec<String>,
+    having: Option<String>,
+    enable_probabilistic_optimizations: bool,
+    udfs: Vec<UdfConfig>,
+    projection_error_policy: ProjectionErrorPolicy,
+    on_schema_change: SchemaChangePolicy,
+    projection_defaults: HashMap<String, Field>,
+    projection_predicate: Option<String>,
+    projection_output_order: Option<Vec<String>>,
+    /// The supported types and the coercions, as pairs since JSON keys must be strings.
+    projection_type_capabilities: Option<(Vec<FieldType>, Vec<(FieldType, FieldType)>)>,
 }
 
 fn is_projection(planner: &CommonPlanner) -> bool {
@@ -60,7 +60,8 @@ impl MaxAggregator {
                 | FieldType::Text
                 | FieldType::Binary
                 | FieldType::Json
-                | FieldType::Point) => PipelineError::InvalidReturnType(format!(
+                | FieldType::Point
+                | FieldType::Vector(_)) => PipelineError::InvalidReturnType(format!(
                     "Not supported return type {typ} for {Max}"
                 )),
                 _ => panic!("MaxAggregator processor not correctly initialized"),
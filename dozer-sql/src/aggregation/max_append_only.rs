@@ -160,7 +160,8 @@ impl Aggregator for MaxAppendOnlyAggregator {
                     | FieldType::Text
                     | FieldType::Binary
                     | FieldType::Json
-                    | FieldType::Point => {
+                    | FieldType::Point
+                    | FieldType::Vector(_) => {
                         return Err(PipelineError::InvalidReturnType(format!(
                             "Not supported return type {typ} for {MaxAppendOnly}"
                         )));
@@ -61,7 +61,8 @@ impl MinAggregator {
                 | FieldType::Text
                 | FieldType::Binary
                 | FieldType::Json
-                | FieldType::Point) => PipelineError::InvalidReturnType(format!(
+                | FieldType::Point
+                | FieldType::Vector(_)) => PipelineError::InvalidReturnType(format!(
                     "Not supported return type {typ} for {Min}"
                 )),
                 _ => panic!("MinAggregator processor not correctly initialized"),
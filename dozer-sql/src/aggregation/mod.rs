@@ -1,4 +1,5 @@
 pub mod aggregator;
+pub mod array_agg;
 pub mod avg;
 pub mod count;
 pub mod factory;
@@ -6,6 +7,7 @@ pub mod max;
 pub mod max_value;
 pub mod min;
 pub mod min_value;
+pub mod percentile;
 pub mod processor;
 pub mod sum;
 mod tests;
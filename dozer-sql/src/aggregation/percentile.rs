@@ -0,0 +1,96 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::calculate_err_field;
+use crate::errors::{PipelineError, UnsupportedSqlError};
+use dozer_sql_expression::aggregate::AggregateFunctionType;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use tdigest::TDigest;
+
+/// Number of centroids kept by the digest. Higher values trade memory for accuracy.
+const DIGEST_SIZE: usize = 100;
+
+/// Approximates `PERCENTILE_CONT` and `PERCENTILE_DISC` with a t-digest.
+///
+/// Both return a float. The digest only keeps centroids, so once it has merged values
+/// `PERCENTILE_DISC` is an estimate too, and may not be one of the observed values.
+///
+/// A t-digest cannot forget values, so like the `*_APPEND_ONLY` aggregators this one only supports inserts.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct PercentileAggregator {
+    function_type: AggregateFunctionType,
+    #[bincode(with_serde)]
+    digest: TDigest,
+    count: u64,
+}
+
+impl PercentileAggregator {
+    pub fn new(function_type: AggregateFunctionType) -> Self {
+        Self {
+            function_type,
+            digest: TDigest::new_with_size(DIGEST_SIZE),
+            count: 0,
+        }
+    }
+
+    /// Folds the values seen by `other` into this aggregator, e.g. when restoring partial states from a checkpoint.
+    pub fn merge(&mut self, other: &PercentileAggregator) {
+        self.digest = TDigest::merge_digests(vec![self.digest.clone(), other.digest.clone()]);
+        self.count += other.count;
+    }
+
+    pub fn get_percentile(&self) -> Field {
+        if self.count == 0 {
+            return Field::Null;
+        }
+        let value = match self.function_type {
+            AggregateFunctionType::PercentileCont(OrderedFloat(fraction)) => {
+                self.digest.estimate_quantile(fraction)
+            }
+            // Estimates the value at rank `ceil(fraction * count)`, asked for at the centre of its
+            // centroid so the digest doesn't interpolate towards the neighbouring values. This is
+            // exact while every value still has its own centroid.
+            AggregateFunctionType::PercentileDisc(OrderedFloat(fraction)) => {
+                let count = self.count as f64;
+                let rank = (fraction * count).ceil().max(1.0);
+                self.digest.estimate_quantile((rank - 0.5) / count)
+            }
+            _ => unreachable!("percentile aggregator created for {}", self.function_type),
+        };
+        Field::Float(OrderedFloat(value))
+    }
+}
+
+impl Aggregator for PercentileAggregator {
+    fn init(&mut self, _return_type: FieldType) {}
+
+    fn update(&mut self, _old: &[Field], _new: &[Field]) -> Result<Field, PipelineError> {
+        Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::GenericError("Append only".to_string()),
+        ))
+    }
+
+    fn delete(&mut self, _old: &[Field]) -> Result<Field, PipelineError> {
+        Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::GenericError("Append only".to_string()),
+        ))
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let mut values = Vec::with_capacity(new.len());
+        for val in new {
+            if val == &Field::Null {
+                continue;
+            }
+            values.push(calculate_err_field!(
+                val.to_float(),
+                self.function_type,
+                val
+            ));
+        }
+        if !values.is_empty() {
+            self.count += values.len() as u64;
+            self.digest = self.digest.merge_unsorted(values);
+        }
+        Ok(self.get_percentile())
+    }
+}
@@ -570,6 +570,10 @@ impl AggregationProcessor {
                     Ok(r)
                 }
             }
+            Operation::Upsert { .. } => Err(PipelineError::UnexpectedUpsert),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                Err(PipelineError::UnsupportedSchemaChange)
+            }
             Operation::BatchInsert { new } => {
                 let mut result = vec![];
                 for record in new {
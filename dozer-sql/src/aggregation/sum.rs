@@ -178,7 +178,8 @@ pub fn get_sum(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Vector(_) => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Sum}"
             ))),
         },
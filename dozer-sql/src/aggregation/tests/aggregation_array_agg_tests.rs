@@ -0,0 +1,98 @@
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor, insert_exp, insert_field, update_exp, ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::json_types::{JsonArray, JsonValue};
+use dozer_types::types::Field;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+fn array(values: &[i64]) -> Field {
+    Field::Json(
+        values
+            .iter()
+            .map(|value| JsonValue::from(*value))
+            .collect::<JsonArray>()
+            .into(),
+    )
+}
+
+#[test]
+fn test_array_agg_order_by() {
+    let schema = init_input_schema(Int, "ARRAY_AGG");
+    let mut processor = init_processor(
+        "SELECT Country, ARRAY_AGG(Salary ORDER BY Salary DESC) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = output!(processor, insert_field(ITALY, &Field::Int(50)));
+    assert_eq!(out, vec![insert_exp(ITALY, &array(&[50]))]);
+
+    out = output!(processor, insert_field(ITALY, &Field::Int(100)));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, &array(&[50]), &array(&[100, 50]))]
+    );
+
+    out = output!(processor, insert_field(ITALY, &Field::Int(50)));
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &array(&[100, 50]),
+            &array(&[100, 50, 50])
+        )]
+    );
+
+    out = output!(processor, delete_field(ITALY, &Field::Int(100)));
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &array(&[100, 50, 50]),
+            &array(&[50, 50])
+        )]
+    );
+}
+
+#[test]
+fn test_array_agg_distinct() {
+    let schema = init_input_schema(Int, "ARRAY_AGG");
+    let mut processor = init_processor(
+        "SELECT Country, ARRAY_AGG(DISTINCT Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    output!(processor, insert_field(ITALY, &Field::Int(100)));
+    output!(processor, insert_field(ITALY, &Field::Int(50)));
+    let mut out = output!(processor, insert_field(ITALY, &Field::Int(100)));
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &array(&[100, 50]),
+            &array(&[100, 50])
+        )]
+    );
+
+    out = output!(processor, delete_field(ITALY, &Field::Int(100)));
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &array(&[100, 50]),
+            &array(&[50, 100])
+        )]
+    );
+}
@@ -0,0 +1,103 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::aggregation::percentile::PercentileAggregator;
+use crate::aggregation::tests::aggregation_tests_utils::{
+    init_input_schema, init_processor, insert_field, update_exp, ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::aggregate::AggregateFunctionType;
+use dozer_types::bincode;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::collections::HashMap;
+
+fn float(value: f64) -> Field {
+    Field::Float(OrderedFloat(value))
+}
+
+fn aggregator_with(function_type: AggregateFunctionType, values: &[i64]) -> PercentileAggregator {
+    let mut aggregator = PercentileAggregator::new(function_type);
+    aggregator.init(FieldType::Float);
+    for value in values {
+        aggregator.insert(&[Field::Int(*value)]).unwrap();
+    }
+    aggregator
+}
+
+#[test]
+fn test_percentile_median() {
+    let cont = aggregator_with(
+        AggregateFunctionType::PercentileCont(OrderedFloat(0.5)),
+        &[1, 2, 3, 4, 5],
+    );
+    assert_eq!(cont.get_percentile(), float(3.0));
+
+    let disc = aggregator_with(
+        AggregateFunctionType::PercentileDisc(OrderedFloat(0.5)),
+        &[5, 4, 3, 2, 1],
+    );
+    assert_eq!(disc.get_percentile(), float(3.0));
+
+    let disc = aggregator_with(
+        AggregateFunctionType::PercentileDisc(OrderedFloat(0.4)),
+        &[1, 2, 3, 4, 5],
+    );
+    assert_eq!(disc.get_percentile(), float(2.0));
+}
+
+#[test]
+fn test_percentile_many_values() {
+    let values = (1..=10_000).collect::<Vec<_>>();
+    for (fraction, expected) in [(0.5, 5000.5), (0.99, 9900.0), (0.01, 100.0)] {
+        let aggregator = aggregator_with(
+            AggregateFunctionType::PercentileCont(OrderedFloat(fraction)),
+            &values,
+        );
+        let Field::Float(OrderedFloat(value)) = aggregator.get_percentile() else {
+            panic!("percentile must be a float");
+        };
+        assert!((value - expected).abs() < 50.0, "{fraction}: {value}");
+    }
+}
+
+#[test]
+fn test_percentile_empty_and_null() {
+    let mut aggregator =
+        PercentileAggregator::new(AggregateFunctionType::PercentileCont(OrderedFloat(0.5)));
+    assert_eq!(aggregator.get_percentile(), Field::Null);
+    assert_eq!(aggregator.insert(&[Field::Null]).unwrap(), Field::Null);
+    assert!(aggregator.delete(&[Field::Int(1)]).is_err());
+}
+
+#[test]
+fn test_percentile_merge_and_serialize() {
+    let function_type = AggregateFunctionType::PercentileCont(OrderedFloat(0.5));
+    let mut left = aggregator_with(function_type.clone(), &[1, 2]);
+    let right = aggregator_with(function_type, &[3, 4, 5]);
+    left.merge(&right);
+    assert_eq!(left.get_percentile(), float(3.0));
+
+    let state = bincode::encode_to_vec(&left, bincode::config::legacy()).unwrap();
+    let (restored, _): (PercentileAggregator, _) =
+        bincode::decode_from_slice(&state, bincode::config::legacy()).unwrap();
+    assert_eq!(restored.get_percentile(), float(3.0));
+}
+
+#[test]
+fn test_percentile_cont_aggregation() {
+    let schema = init_input_schema(FieldType::Int, "PERCENTILE_CONT");
+    let mut processor = init_processor(
+        "SELECT Country, PERCENTILE_CONT(Salary, 0.5) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    for salary in 1..=5 {
+        out = output!(processor, insert_field(ITALY, &Field::Int(salary)));
+    }
+    let exp = vec![update_exp(ITALY, ITALY, &float(2.5), &float(3.0))];
+    assert_eq!(out, exp);
+}
@@ -1,4 +1,6 @@
 #[cfg(test)]
+mod aggregation_array_agg_tests;
+#[cfg(test)]
 mod aggregation_avg_tests;
 #[cfg(test)]
 mod aggregation_count_tests;
@@ -15,6 +17,8 @@ mod aggregation_min_value_tests;
 #[cfg(test)]
 mod aggregation_null;
 #[cfg(test)]
+mod aggregation_percentile_tests;
+#[cfg(test)]
 mod aggregation_sum_tests;
 #[cfg(test)]
 mod aggregation_test_planner;
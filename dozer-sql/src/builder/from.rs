@@ -12,6 +12,7 @@ use crate::{
 
 use super::{
     common::{get_name_or_alias, is_an_entry_point},
+    generate_series::{get_generate_series, insert_generate_series_to_pipeline},
     join::insert_join_to_pipeline,
     table_operator::{insert_table_operator_processor_to_pipeline, is_table_operator},
     ConnectionInfo,
@@ -36,6 +37,10 @@ fn insert_table_to_pipeline(
     pipeline_idx: usize,
     query_context: &mut QueryContext,
 ) -> Result<ConnectionInfo, PipelineError> {
+    if let Some(series) = get_generate_series(&relation)? {
+        return insert_generate_series_to_pipeline(relation, series, pipeline, query_context);
+    }
+
     if let Some(operator) = is_table_operator(&relation)? {
         let product_processor_name =
             insert_from_processor_to_pipeline(query_context, relation, pipeline)?;
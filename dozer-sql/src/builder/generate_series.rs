@@ -0,0 +1,202 @@
+use dozer_core::{
+    app::{AppPipeline, PipelineEntryPoint},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql_expression::sqlparser::ast::{
+    DataType, Expr, FunctionArg, FunctionArgExpr, Interval, TableFactor, UnaryOperator, Value,
+};
+use dozer_types::{
+    chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime},
+    models::ingestion_types::GenerateSeriesConfig,
+};
+
+use crate::{errors::PipelineError, product::table::factory::TableProcessorFactory};
+
+use super::{
+    common::{get_name_or_alias, string_from_sql_object_name},
+    ConnectionInfo, QueryContext,
+};
+
+const GENERATE_SERIES: &str = "generate_series";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SeriesArg {
+    Integer(i64),
+    Date(NaiveDate),
+    Timestamp(DateTime<FixedOffset>),
+    Interval(Duration),
+}
+
+/// Returns the series described by `FROM generate_series(start, stop[, step])`, if `relation` is one.
+pub fn get_generate_series(
+    relation: &TableFactor,
+) -> Result<Option<GenerateSeriesConfig>, PipelineError> {
+    let TableFactor::Table {
+        name,
+        args: Some(args),
+        ..
+    } = relation
+    else {
+        return Ok(None);
+    };
+    if string_from_sql_object_name(name).to_lowercase() != GENERATE_SERIES {
+        return Ok(None);
+    }
+
+    let args = args
+        .iter()
+        .map(parse_series_arg)
+        .collect::<Result<Vec<_>, _>>()?;
+    let config = match args[..] {
+        [SeriesArg::Integer(start), SeriesArg::Integer(stop)] => GenerateSeriesConfig::Integer {
+            start,
+            stop,
+            step: 1,
+        },
+        [SeriesArg::Integer(start), SeriesArg::Integer(stop), SeriesArg::Integer(step)] => {
+            GenerateSeriesConfig::Integer { start, stop, step }
+        }
+        [SeriesArg::Date(start), SeriesArg::Date(stop), SeriesArg::Interval(step)] => {
+            if step.num_days() == 0 || step != Duration::days(step.num_days()) {
+                return Err(invalid_series("the step of a DATE series must be whole days"));
+            }
+            GenerateSeriesConfig::Date {
+                start: start.format("%Y-%m-%d").to_string(),
+                stop: stop.format("%Y-%m-%d").to_string(),
+                step_days: step.num_days(),
+            }
+        }
+        [SeriesArg::Timestamp(start), SeriesArg::Timestamp(stop), SeriesArg::Interval(step)] => {
+            GenerateSeriesConfig::Timestamp {
+                start: start.to_rfc3339(),
+                stop: stop.to_rfc3339(),
+                step_millis: step.num_milliseconds(),
+            }
+        }
+        _ => {
+            return Err(invalid_series(
+                "expected (INT, INT[, INT]), (DATE, DATE, INTERVAL) or (TIMESTAMP, TIMESTAMP, INTERVAL)",
+            ))
+        }
+    };
+    Ok(Some(config))
+}
+
+/// Adds a table processor reading from the series. The series is a source of its own, named after the call,
+/// and is recorded in `query_context.generated_series` so a connection can be created for it.
+pub fn insert_generate_series_to_pipeline(
+    relation: TableFactor,
+    config: GenerateSeriesConfig,
+    pipeline: &mut AppPipeline,
+    query_context: &mut QueryContext,
+) -> Result<ConnectionInfo, PipelineError> {
+    let TableFactor::Table {
+        args: Some(args), ..
+    } = &relation
+    else {
+        return Err(invalid_series("expected arguments"));
+    };
+    let source_name = format!(
+        "{GENERATE_SERIES}({})",
+        args.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let processor_name = format!(
+        "from:{}--{}",
+        source_name,
+        query_context.get_next_processor_id()
+    );
+    if !query_context.processors_list.insert(processor_name.clone()) {
+        return Err(PipelineError::ProcessorAlreadyExists(processor_name));
+    }
+    let processor_factory =
+        TableProcessorFactory::new(processor_name.clone(), get_name_or_alias(&relation)?);
+    pipeline.add_processor(Box::new(processor_factory), processor_name.clone());
+    pipeline.add_entry_point(
+        processor_name.clone(),
+        PipelineEntryPoint::new(source_name.clone(), DEFAULT_PORT_HANDLE),
+    );
+
+    query_context.used_sources.push(source_name.clone());
+    query_context.generated_series.insert(source_name, config);
+
+    Ok(ConnectionInfo {
+        input_nodes: vec![],
+        output_node: (processor_name, DEFAULT_PORT_HANDLE),
+    })
+}
+
+fn invalid_series(reason: &str) -> PipelineError {
+    PipelineError::InvalidGenerateSeries(reason.to_string())
+}
+
+fn parse_series_arg(arg: &FunctionArg) -> Result<SeriesArg, PipelineError> {
+    let FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) = arg else {
+        return Err(invalid_series(&format!("unsupported argument {arg}")));
+    };
+    match expr {
+        Expr::Value(Value::Number(n, _)) => parse_integer(&n.to_string()),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match expr.as_ref() {
+            Expr::Value(Value::Number(n, _)) => parse_integer(&format!("-{n}")),
+            _ => Err(invalid_series(&format!("unsupported argument {arg}"))),
+        },
+        Expr::TypedString {
+            data_type: DataType::Date,
+            value,
+        } => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(SeriesArg::Date)
+            .map_err(|_| invalid_series(&format!("invalid date {value:?}"))),
+        Expr::TypedString {
+            data_type: DataType::Timestamp(..),
+            value,
+        } => DateTime::parse_from_rfc3339(value)
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                    .map(|timestamp| timestamp.and_utc().fixed_offset())
+            })
+            .map(SeriesArg::Timestamp)
+            .map_err(|_| invalid_series(&format!("invalid timestamp {value:?}"))),
+        Expr::Interval(interval) => parse_interval(interval).map(SeriesArg::Interval),
+        _ => Err(invalid_series(&format!("unsupported argument {arg}"))),
+    }
+}
+
+fn parse_integer(n: &str) -> Result<SeriesArg, PipelineError> {
+    n.parse()
+        .map(SeriesArg::Integer)
+        .map_err(|_| invalid_series(&format!("{n} is not an integer")))
+}
+
+/// Parses `INTERVAL '1 day'` and `INTERVAL '1' DAY`.
+fn parse_interval(interval: &Interval) -> Result<Duration, PipelineError> {
+    let Expr::Value(Value::SingleQuotedString(value)) = interval.value.as_ref() else {
+        return Err(invalid_series(&format!("unsupported interval {interval}")));
+    };
+    let (amount, unit) = match interval.leading_field {
+        Some(field) => (value.trim(), field.to_string()),
+        None => value
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(amount, unit)| (amount, unit.trim().to_string()))
+            .ok_or_else(|| invalid_series(&format!("unsupported interval {interval}")))?,
+    };
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| invalid_series(&format!("unsupported interval {interval}")))?;
+    let unit = unit.to_lowercase();
+    match unit.strip_suffix('s').unwrap_or(&unit) {
+        "week" => Ok(Duration::weeks(amount)),
+        "day" => Ok(Duration::days(amount)),
+        "hour" => Ok(Duration::hours(amount)),
+        "minute" => Ok(Duration::minutes(amount)),
+        "second" => Ok(Duration::seconds(amount)),
+        "millisecond" => Ok(Duration::milliseconds(amount)),
+        _ => Err(invalid_series(&format!("unsupported interval unit {unit}"))),
+    }
+}
@@ -1,12 +1,16 @@
 use crate::aggregation::factory::AggregationProcessorFactory;
 use crate::builder::PipelineError::InvalidQuery;
 use crate::errors::PipelineError;
+use crate::lag_lead::builder::extract_lag_lead_calls;
+use crate::lag_lead::factory::LagLeadProcessorFactory;
+use crate::schema_evolution::schema_evolution_handler;
 use crate::selection::factory::SelectionProcessorFactory;
 use dozer_core::app::AppPipeline;
 use dozer_core::node::PortHandle;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_sql_expression::builder::{ExpressionBuilder, NameOrAlias};
 use dozer_sql_expression::sqlparser::ast::{SetOperator, SetQuantifier, TableFactor};
+use dozer_types::models::ingestion_types::GenerateSeriesConfig;
 use dozer_types::models::udf_config::UdfConfig;
 
 use dozer_sql_expression::sqlparser::{
@@ -44,6 +48,9 @@ pub struct QueryContext {
     // Used Sources
     pub used_sources: Vec<String>,
 
+    // Sources created by `FROM generate_series(...)`, keyed by their name in `used_sources`
+    pub generated_series: HashMap<String, GenerateSeriesConfig>,
+
     // Internal tables map, used to store the tables that are created by the queries
     processors_list: HashSet<String>,
 
@@ -68,6 +75,7 @@ impl QueryContext {
             pipeline_map: Default::default(),
             output_tables_map: Default::default(),
             used_sources: Default::default(),
+            generated_series: Default::default(),
             processors_list: Default::default(),
             processor_counter: Default::default(),
             udfs,
@@ -236,7 +244,7 @@ fn query_to_pipeline(
 
 fn select_to_pipeline(
     table_info: TableInfo,
-    select: Select,
+    mut select: Select,
     pipeline: &mut AppPipeline,
     query_ctx: &mut QueryContext,
     pipeline_idx: usize,
@@ -259,6 +267,8 @@ fn select_to_pipeline(
     let gen_selection_name = format!("select--{}", query_ctx.get_next_processor_id());
     let (gen_product_name, product_output_port) = output_node;
 
+    let lag_lead_calls = extract_lag_lead_calls(&mut select.projection)?;
+
     for (source_name, processor_name, processor_port) in input_nodes {
         if let Some(table_info) = query_ctx
             .pipeline_map
@@ -276,7 +286,20 @@ fn select_to_pipeline(
         }
     }
 
-    let aggregation = AggregationProcessorFactory::new(
+    let output_table_name = if let Some(into) = select.into {
+        Some(into.name.to_string())
+    } else {
+        table_info.override_name.clone()
+    };
+    let projection_options = output_table_name.as_ref().and_then(|table_name| {
+        pipeline
+            .flags()
+            .projections
+            .iter()
+            .find(|options| &options.table_name == table_name)
+    });
+
+    let mut aggregation = AggregationProcessorFactory::new(
         gen_agg_name.clone(),
         select.projection,
         select.group_by,
@@ -288,11 +311,30 @@ fn select_to_pipeline(
             .unwrap_or(false),
         query_ctx.udfs.clone(),
         query_ctx.runtime.clone(),
-    );
+    )
+    .with_projection_error_policy(pipeline.flags().on_projection_error)
+    .with_schema_evolution_handler(schema_evolution_handler(pipeline.flags().on_schema_change));
+    if let Some(options) = projection_options {
+        aggregation = aggregation.with_projection_defaults(options.defaults.clone());
+        if let Some(predicate) = &options.predicate {
+            let predicate = Parser::new(&DozerDialect {})
+                .try_with_sql(predicate)
+                .and_then(|mut parser| parser.parse_expr())
+                .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
+            aggregation = aggregation.with_projection_predicate(predicate);
+        }
+        if !options.output_order.is_empty() {
+            aggregation = aggregation.with_projection_output_order(options.output_order.clone());
+        }
+        if let Some(type_capabilities) = &options.type_capabilities {
+            aggregation = aggregation.with_projection_type_capabilities(type_capabilities.into());
+        }
+    }
 
     pipeline.add_processor(Box::new(aggregation), gen_agg_name.clone());
 
     // Where clause
+    let (mut input_name, mut input_port) = (gen_product_name, product_output_port);
     if let Some(selection) = select.selection {
         let selection = SelectionProcessorFactory::new(
             gen_selection_name.clone(),
@@ -304,27 +346,44 @@ fn select_to_pipeline(
         pipeline.add_processor(Box::new(selection), gen_selection_name.clone());
 
         pipeline.connect_nodes(
-            gen_product_name,
-            product_output_port,
+            input_name,
+            input_port,
             gen_selection_name.clone(),
             DEFAULT_PORT_HANDLE,
         );
 
-        pipeline.connect_nodes(
-            gen_selection_name,
-            DEFAULT_PORT_HANDLE,
-            gen_agg_name.clone(),
-            DEFAULT_PORT_HANDLE,
+        (input_name, input_port) = (gen_selection_name, DEFAULT_PORT_HANDLE);
+    }
+
+    // LAG and LEAD are computed after the WHERE clause, and read by the projection as columns
+    if !lag_lead_calls.is_empty() {
+        let gen_lag_lead_name = format!("lag_lead--{}", query_ctx.get_next_processor_id());
+        let lag_lead = LagLeadProcessorFactory::new(
+            gen_lag_lead_name.clone(),
+            lag_lead_calls,
+            query_ctx.udfs.clone(),
+            query_ctx.runtime.clone(),
         );
-    } else {
+
+        pipeline.add_processor(Box::new(lag_lead), gen_lag_lead_name.clone());
+
         pipeline.connect_nodes(
-            gen_product_name,
-            product_output_port,
-            gen_agg_name.clone(),
+            input_name,
+            input_port,
+            gen_lag_lead_name.clone(),
             DEFAULT_PORT_HANDLE,
         );
+
+        (input_name, input_port) = (gen_lag_lead_name, DEFAULT_PORT_HANDLE);
     }
 
+    pipeline.connect_nodes(
+        input_name,
+        input_port,
+        gen_agg_name.clone(),
+        DEFAULT_PORT_HANDLE,
+    );
+
     query_ctx.pipeline_map.insert(
         (pipeline_idx, table_info.name.0.to_string()),
         OutputNodeInfo {
@@ -333,12 +392,6 @@ fn select_to_pipeline(
         },
     );
 
-    let output_table_name = if let Some(into) = select.into {
-        Some(into.name.to_string())
-    } else {
-        table_info.override_name.clone()
-    };
-
     if is_top_select && output_table_name.is_none() {
         return Err(PipelineError::MissingIntoClause);
     }
@@ -560,6 +613,7 @@ struct ConnectionInfo {
 
 mod common;
 mod from;
+mod generate_series;
 mod join;
 mod table_operator;
 
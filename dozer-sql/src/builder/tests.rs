@@ -1,6 +1,7 @@
 use super::statement_to_pipeline;
 use crate::{errors::PipelineError, tests::utils::create_test_runtime};
 use dozer_core::app::AppPipeline;
+use dozer_types::models::ingestion_types::GenerateSeriesConfig;
 #[test]
 #[should_panic]
 fn disallow_zero_outgoing_ndes() {
@@ -209,3 +210,44 @@ from tbl;"#;
     //check if the result is ok
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_generate_series() {
+    let sql = "SELECT value INTO days FROM generate_series(DATE '2024-01-01', DATE '2024-12-31', INTERVAL '1 day')";
+    let runtime = create_test_runtime();
+    let context = statement_to_pipeline(
+        sql,
+        &mut AppPipeline::new_with_default_flags(),
+        None,
+        vec![],
+        runtime,
+    )
+    .unwrap();
+
+    assert_eq!(context.used_sources.len(), 1);
+    assert_eq!(
+        context.generated_series.get(&context.used_sources[0]),
+        Some(&GenerateSeriesConfig::Date {
+            start: "2024-01-01".to_string(),
+            stop: "2024-12-31".to_string(),
+            step_days: 1
+        })
+    );
+}
+
+#[test]
+fn test_generate_series_invalid_step() {
+    let sql = "SELECT value INTO days FROM generate_series(DATE '2024-01-01', DATE '2024-12-31', INTERVAL '1 hour')";
+    let runtime = create_test_runtime();
+    let result = statement_to_pipeline(
+        sql,
+        &mut AppPipeline::new_with_default_flags(),
+        None,
+        vec![],
+        runtime,
+    );
+    assert!(matches!(
+        result,
+        Err(PipelineError::InvalidGenerateSeries(_))
+    ));
+}
@@ -8,7 +8,7 @@ use dozer_types::errors::types::TypeError;
 
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
-use dozer_types::types::{Field, FieldType};
+use dozer_types::types::{Field, FieldType, Schema};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone)]
@@ -49,6 +49,14 @@ pub enum PipelineError {
     NotEnoughArguments(String),
     #[error("Missing INTO clause for top-level SELECT statement")]
     MissingIntoClause,
+    #[error("Upsert operations are only sent to sinks")]
+    UnexpectedUpsert,
+    #[error("Fields can only be added to or removed from the input of a projection")]
+    UnsupportedSchemaChange,
+    #[error("Can't remove field {0}, as the input doesn't have it")]
+    RemovedFieldNotFound(String),
+    #[error("Record has {0} fields, but no version of the input schema has as many")]
+    UnexpectedRecordLength(usize),
     #[error("Duplicate INTO table name found: {0:?}")]
     DuplicateIntoClause(String),
 
@@ -76,6 +84,9 @@ pub enum PipelineError {
     #[error("Window: {0}")]
     WindowError(#[from] WindowError),
 
+    #[error("LAG/LEAD: {0}")]
+    LagLeadError(#[from] LagLeadError),
+
     #[error("Table Function is not supported")]
     UnsupportedTableFunction,
 
@@ -124,6 +135,48 @@ pub enum PipelineError {
 
     #[error("Duplicated Processor name: {0}")]
     ProcessorAlreadyExists(String),
+
+    #[error("Invalid GENERATE_SERIES: {0}")]
+    InvalidGenerateSeries(String),
+
+    #[error("Default given for column {0}, which isn't selected")]
+    UnknownProjectionDefault(String),
+
+    #[error("Projection predicate must be a Boolean, but is a {0}")]
+    InvalidProjectionPredicate(FieldType),
+
+    #[error("Column {column} has type {typ}, which the sink can't write or have it coerced to")]
+    UnsupportedProjectionOutputType { column: String, typ: FieldType },
+
+    #[error("Column {column} of type {from} can't be coerced to {to} for the sink")]
+    InvalidProjectionCoercion {
+        column: String,
+        from: FieldType,
+        to: FieldType,
+    },
+
+    #[error("Output order {order:?} isn't an ordering of the projection's outputs {outputs:?}")]
+    InvalidProjectionOutputOrder {
+        order: Vec<String>,
+        outputs: Vec<String>,
+    },
+
+    #[error("Default for column {column} must be a {expected}, but got {default:?}")]
+    InvalidProjectionDefault {
+        column: String,
+        expected: FieldType,
+        default: Field,
+    },
+
+    #[error("Projection options are only supported on queries without aggregation")]
+    ProjectionOptionsOnAggregation,
+
+    #[error("Schema of table {table} changed from {old:?} to {new:?}")]
+    SchemaEvolution {
+        table: String,
+        old: Schema,
+        new: Schema,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -290,6 +343,27 @@ pub enum WindowError {
     NoAlias,
 }
 
+#[derive(Error, Debug)]
+pub enum LagLeadError {
+    #[error("{0} requires an OVER clause")]
+    MissingOver(String),
+
+    #[error("Value not specified in {0}")]
+    MissingValueArgument(String),
+
+    #[error("Too many arguments in {0}")]
+    TooManyArguments(String),
+
+    #[error("Invalid argument '{0}' in {1}")]
+    InvalidArgument(String, String),
+
+    #[error("Invalid offset '{0}' in {1}. The offset must be a non-negative integer")]
+    InvalidOffset(String, String),
+
+    #[error("All LAG and LEAD functions in a SELECT must use the same PARTITION BY")]
+    MismatchedPartitions,
+}
+
 #[derive(Error, Debug)]
 pub enum TableOperatorError {
     #[error("Internal error: {0}")]
@@ -1,4 +1,6 @@
 use crate::expression::tests::test_common::run_fct;
+use dozer_sql_expression::execution::Expression;
+use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
 
 #[test]
@@ -104,3 +106,173 @@ fn test_case_no_else() {
     );
     assert_eq!(f, Field::Null);
 }
+
+#[test]
+fn test_case_operand() {
+    let f = run_fct(
+        "SELECT \
+                CASE age \
+                    WHEN 10 THEN 'ten' \
+                    WHEN 11 THEN 'eleven' \
+                    ELSE 'other' \
+                END AS age_text \
+            FROM users",
+        Schema::default()
+            .field(
+                FieldDefinition::new(
+                    String::from("age"),
+                    FieldType::Int,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone(),
+        vec![Field::Int(11)],
+    );
+    assert_eq!(f, Field::String("eleven".to_string()));
+}
+
+#[test]
+fn test_case_operand_numeric() {
+    let f = run_fct(
+        "SELECT \
+                CASE score \
+                    WHEN 1 THEN 'one' \
+                    ELSE 'other' \
+                END AS score_text \
+            FROM users",
+        Schema::default()
+            .field(
+                FieldDefinition::new(
+                    String::from("score"),
+                    FieldType::Float,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone(),
+        vec![Field::Float(OrderedFloat(1.0))],
+    );
+    assert_eq!(f, Field::String("one".to_string()));
+}
+
+#[test]
+fn test_case_numeric_results() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("age"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_fct(
+        "SELECT CASE WHEN age > 11 THEN 1 ELSE 1.5 END AS ratio FROM users",
+        schema.clone(),
+        vec![Field::Int(12)],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(1.0)));
+
+    let f = run_fct(
+        "SELECT CASE WHEN age > 11 THEN 1 ELSE 1.5 END AS ratio FROM users",
+        schema,
+        vec![Field::Int(10)],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(1.5)));
+}
+
+#[test]
+fn test_case_null_condition() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("age"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_fct(
+        "SELECT \
+                CASE \
+                    WHEN age > 11 THEN 'The age is greater than 11' \
+                    ELSE 'unknown' \
+                END AS age_text \
+            FROM users",
+        schema.clone(),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::String("unknown".to_string()));
+
+    let f = run_fct(
+        "SELECT \
+                CASE age \
+                    WHEN 11 THEN 'eleven' \
+                    ELSE 'unknown' \
+                END AS age_text \
+            FROM users",
+        schema,
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::String("unknown".to_string()));
+}
+
+#[test]
+fn test_case_type() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("age"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let condition = Expression::Literal(Field::Boolean(true));
+
+    let case = Expression::Case {
+        operand: None,
+        conditions: vec![condition.clone(), condition.clone()],
+        results: vec![
+            Expression::Column { index: 0 },
+            Expression::Literal(Field::Null),
+        ],
+        else_result: Some(Box::new(Expression::Literal(Field::Int(0)))),
+    };
+    let typ = case.get_type(&schema).unwrap();
+    assert_eq!(typ.return_type, FieldType::Int);
+    assert!(typ.nullable);
+
+    let case = Expression::Case {
+        operand: None,
+        conditions: vec![condition.clone()],
+        results: vec![Expression::Column { index: 0 }],
+        else_result: Some(Box::new(Expression::Literal(Field::Float(OrderedFloat(
+            1.5,
+        ))))),
+    };
+    let typ = case.get_type(&schema).unwrap();
+    assert_eq!(typ.return_type, FieldType::Float);
+    assert!(!typ.nullable);
+
+    let case = Expression::Case {
+        operand: None,
+        conditions: vec![condition],
+        results: vec![Expression::Column { index: 0 }],
+        else_result: Some(Box::new(Expression::Literal(Field::String(
+            "none".to_string(),
+        )))),
+    };
+    assert!(case.get_type(&schema).is_err());
+}
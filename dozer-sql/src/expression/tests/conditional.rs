@@ -75,6 +75,35 @@ fn test_coalesce_logic() {
     assert_eq!(f, Field::String("X".to_string()));
 }
 
+#[test]
+fn test_coalesce_numeric_arguments() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("field"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_fct(
+        "SELECT COALESCE(field, 0.0) FROM users",
+        schema.clone(),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(0.0)));
+
+    let f = run_fct(
+        "SELECT COALESCE(field, 0.0) FROM users",
+        schema,
+        vec![Field::Int(3)],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(3.0)));
+}
+
 #[test]
 fn test_coalesce_logic_null() {
     let f = run_fct(
@@ -94,3 +123,48 @@ fn test_coalesce_logic_null() {
     );
     assert_eq!(f, Field::Null);
 }
+
+#[test]
+fn test_nullif_logic() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("x"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("y"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_fct(
+        "SELECT NULLIF(x, 0) FROM t",
+        schema.clone(),
+        vec![Field::Int(5), Field::Int(2)],
+    );
+    assert_eq!(f, Field::Int(5));
+
+    let f = run_fct(
+        "SELECT NULLIF(x, 0) / y FROM t",
+        schema.clone(),
+        vec![Field::Int(0), Field::Int(2)],
+    );
+    assert_eq!(f, Field::Null);
+
+    let f = run_fct(
+        "SELECT y / NULLIF(x, 0) FROM t",
+        schema,
+        vec![Field::Int(0), Field::Int(2)],
+    );
+    assert_eq!(f, Field::Null);
+}
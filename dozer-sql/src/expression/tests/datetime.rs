@@ -1,4 +1,7 @@
 use crate::expression::tests::test_common::*;
+use crate::tests::utils::{create_test_runtime, get_select};
+use dozer_sql_expression::error::Error;
+use dozer_sql_expression::{builder::ExpressionBuilder, sqlparser::ast::SelectItem};
 use dozer_types::chrono::{DateTime, NaiveDate};
 use dozer_types::types::{
     DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition, TimeUnit,
@@ -180,3 +183,66 @@ fn test_now() {
     );
     assert!(f.to_timestamp().is_some())
 }
+
+fn date_schema(name: &str, typ: FieldType) -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(name.to_string(), typ, true, SourceDefinition::Dynamic),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_format_date() {
+    let f = run_fct(
+        "SELECT FORMAT_DATE(birthday, '%Y/%m/%d') FROM users",
+        date_schema("birthday", FieldType::Date),
+        vec![Field::Date(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap())],
+    );
+    assert_eq!(f, Field::String("2023/01/02".to_string()));
+
+    let f = run_fct(
+        "SELECT FORMAT_DATE(birthday, '%Y/%m/%d') FROM users",
+        date_schema("birthday", FieldType::Date),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_parse_date() {
+    let f = run_fct(
+        "SELECT PARSE_DATE(birthday, '%d.%m.%Y') FROM users",
+        date_schema("birthday", FieldType::String),
+        vec![Field::String("02.01.2023".to_string())],
+    );
+    assert_eq!(f, Field::Date(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()));
+
+    let f = run_fct(
+        "SELECT PARSE_DATE(birthday, '%d.%m.%Y') FROM users",
+        date_schema("birthday", FieldType::String),
+        vec![Field::String("2023-01-02".to_string())],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_date_format_is_validated() {
+    let schema = date_schema("birthday", FieldType::Date);
+    let runtime = create_test_runtime();
+    for sql in [
+        "SELECT FORMAT_DATE(birthday, '%Q') FROM users",
+        "SELECT FORMAT_DATE(birthday, '%H:%M') FROM users",
+        "SELECT PARSE_DATE(birthday, '%Q') FROM users",
+    ] {
+        let mut builder = ExpressionBuilder::new(schema.fields.len(), runtime.clone());
+        let SelectItem::UnnamedExpr(e) = &get_select(sql).unwrap().projection[0] else {
+            panic!("Invalid expr");
+        };
+        assert!(matches!(
+            runtime.block_on(builder.build(false, e, &schema, &[])),
+            Err(Error::InvalidDateFormat(_))
+        ));
+    }
+}
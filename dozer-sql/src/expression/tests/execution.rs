@@ -1,4 +1,6 @@
+use crate::aggregation::factory::AggregationProcessorFactory;
 use crate::projection::factory::ProjectionProcessorFactory;
+use crate::projection::type_capabilities::TypeCapabilities;
 use crate::tests::utils::{create_test_runtime, get_select};
 use dozer_core::node::ProcessorFactory;
 use dozer_core::DEFAULT_PORT_HANDLE;
@@ -238,3 +240,382 @@ fn test_wildcard() {
             .clone()
     );
 }
+
+#[test]
+fn test_projection_defaults() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("fn"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        get_select("SELECT fn FROM t1").unwrap().projection,
+        vec![],
+        runtime.clone(),
+    )
+    .with_defaults([("fn".to_string(), Field::String("unknown".to_string()))].into());
+    let r = runtime
+        .block_on(processor_factory.get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas))
+        .unwrap();
+    assert_eq!(r.fields[0].typ, FieldType::String);
+    assert!(!r.fields[0].nullable);
+
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        get_select("SELECT fn FROM t1").unwrap().projection,
+        vec![],
+        runtime.clone(),
+    )
+    .with_defaults([("fn".to_string(), Field::Int(0))].into());
+    assert!(runtime
+        .block_on(processor_factory.get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas))
+        .is_err());
+
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        get_select("SELECT fn FROM t1").unwrap().projection,
+        vec![],
+        runtime.clone(),
+    )
+    .with_defaults([("ln".to_string(), Field::String("unknown".to_string()))].into());
+    assert!(runtime
+        .block_on(processor_factory.get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas))
+        .is_err());
+}
+
+#[test]
+fn test_aggregation_projection_defaults() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("fn"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |sql: &str| {
+        let select = get_select(sql).unwrap();
+        AggregationProcessorFactory::new(
+            "aggregation_id".to_owned(),
+            select.projection,
+            select.group_by,
+            select.having,
+            false,
+            vec![],
+            runtime.clone(),
+        )
+        .with_projection_defaults([("fn".to_string(), Field::String("unknown".to_string()))].into())
+    };
+    let r = runtime
+        .block_on(
+            processor_factory("SELECT fn FROM t1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas),
+        )
+        .unwrap();
+    assert_eq!(r.fields[0].typ, FieldType::String);
+    assert!(!r.fields[0].nullable);
+
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT fn, COUNT(fn) FROM t1 GROUP BY fn")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+}
+
+#[test]
+fn test_projection_predicate() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |sql: &str| {
+        let select = get_select(sql).unwrap();
+        ProjectionProcessorFactory::_new(
+            "projection_id".to_owned(),
+            select.projection,
+            vec![],
+            runtime.clone(),
+        )
+        .with_predicate(select.selection.unwrap())
+    };
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT id FROM t1 WHERE id > 1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_ok());
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT id FROM t1 WHERE id + 1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+}
+
+#[test]
+fn test_aggregation_projection_predicate() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |sql: &str, predicate: &str| {
+        let select = get_select(sql).unwrap();
+        AggregationProcessorFactory::new(
+            "aggregation_id".to_owned(),
+            select.projection,
+            select.group_by,
+            select.having,
+            false,
+            vec![],
+            runtime.clone(),
+        )
+        .with_projection_predicate(get_select(predicate).unwrap().selection.unwrap())
+    };
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT id FROM t1", "SELECT id FROM t1 WHERE id > 1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_ok());
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT id FROM t1", "SELECT id FROM t1 WHERE id + 1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+    assert!(runtime
+        .block_on(
+            processor_factory("SELECT COUNT(id) FROM t1", "SELECT id FROM t1 WHERE id > 1")
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+}
+
+#[test]
+fn test_projection_output_order() {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    let schema = Schema::default()
+        .field(field("name", FieldType::String), false)
+        .field(field("id", FieldType::Int), true)
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |output_order: &[&str]| {
+        ProjectionProcessorFactory::_new(
+            "projection_id".to_owned(),
+            get_select("SELECT name, id + 1 AS next_id FROM t1")
+                .unwrap()
+                .projection,
+            vec![],
+            runtime.clone(),
+        )
+        .with_output_order(output_order.iter().map(|name| name.to_string()).collect())
+    };
+    let r = runtime
+        .block_on(
+            processor_factory(&["next_id", "name"])
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas),
+        )
+        .unwrap();
+    assert_eq!(r.fields[0].name, "next_id");
+    assert_eq!(r.fields[0].typ, FieldType::Int);
+    assert_eq!(r.fields[1].name, "name");
+
+    // Missing, unknown and repeated outputs are rejected.
+    for output_order in [
+        &["next_id"][..],
+        &["next_id", "name", "id"],
+        &["next_id", "next_id"],
+    ] {
+        assert!(runtime
+            .block_on(
+                processor_factory(output_order)
+                    .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+            )
+            .is_err());
+    }
+}
+
+#[test]
+fn test_aggregation_projection_output_order() {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    let schema = Schema::default()
+        .field(field("name", FieldType::String), false)
+        .field(field("id", FieldType::Int), true)
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |output_order: &[&str]| {
+        let select = get_select("SELECT name, id + 1 AS next_id FROM t1").unwrap();
+        AggregationProcessorFactory::new(
+            "aggregation_id".to_owned(),
+            select.projection,
+            select.group_by,
+            select.having,
+            false,
+            vec![],
+            runtime.clone(),
+        )
+        .with_projection_output_order(output_order.iter().map(|name| name.to_string()).collect())
+    };
+    let r = runtime
+        .block_on(
+            processor_factory(&["next_id", "name"])
+                .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas),
+        )
+        .unwrap();
+    assert_eq!(r.fields[0].name, "next_id");
+    assert_eq!(r.fields[0].typ, FieldType::Int);
+    assert_eq!(r.fields[1].name, "name");
+
+    assert!(runtime
+        .block_on(
+            processor_factory(&["next_id"]).get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+}
+
+#[test]
+fn test_projection_type_capabilities() {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    let schema = Schema::default()
+        .field(field("id", FieldType::Int), true)
+        .field(field("score", FieldType::Float), false)
+        .field(field("location", FieldType::Point), false)
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |sql: &str, capabilities: TypeCapabilities| {
+        ProjectionProcessorFactory::_new(
+            "projection_id".to_owned(),
+            get_select(sql).unwrap().projection,
+            vec![],
+            runtime.clone(),
+        )
+        .with_type_capabilities(capabilities)
+    };
+    let capabilities = TypeCapabilities::new([FieldType::Int, FieldType::String]);
+
+    let r = runtime
+        .block_on(
+            processor_factory(
+                "SELECT id, score FROM t1",
+                capabilities
+                    .clone()
+                    .with_coercion(FieldType::Float, FieldType::String),
+            )
+            .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas),
+        )
+        .unwrap();
+    assert_eq!(r.fields[0].typ, FieldType::Int);
+    assert_eq!(r.fields[1].typ, FieldType::String);
+
+    // Types that aren't coerced, or can't be cast to their coercion, are rejected.
+    for (sql, capabilities) in [
+        ("SELECT id, score FROM t1", capabilities.clone()),
+        (
+            "SELECT location FROM t1",
+            capabilities.with_coercion(FieldType::Point, FieldType::String),
+        ),
+    ] {
+        assert!(runtime
+            .block_on(
+                processor_factory(sql, capabilities)
+                    .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+            )
+            .is_err());
+    }
+}
+
+#[test]
+fn test_aggregation_projection_type_capabilities() {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    let schema = Schema::default()
+        .field(field("id", FieldType::Int), true)
+        .field(field("score", FieldType::Float), false)
+        .clone();
+    let input_schemas = [(DEFAULT_PORT_HANDLE, schema)].into_iter().collect();
+
+    let runtime = create_test_runtime();
+    let processor_factory = |capabilities: TypeCapabilities| {
+        let select = get_select("SELECT id, score FROM t1").unwrap();
+        AggregationProcessorFactory::new(
+            "aggregation_id".to_owned(),
+            select.projection,
+            select.group_by,
+            select.having,
+            false,
+            vec![],
+            runtime.clone(),
+        )
+        .with_projection_type_capabilities(capabilities)
+    };
+    let capabilities = TypeCapabilities::new([FieldType::Int, FieldType::String]);
+
+    let r = runtime
+        .block_on(
+            processor_factory(
+                capabilities
+                    .clone()
+                    .with_coercion(FieldType::Float, FieldType::String),
+            )
+            .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas),
+        )
+        .unwrap();
+    assert_eq!(r.fields[0].typ, FieldType::Int);
+    assert_eq!(r.fields[1].typ, FieldType::String);
+
+    assert!(runtime
+        .block_on(
+            processor_factory(capabilities).get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        )
+        .is_err());
+}
@@ -0,0 +1,59 @@
+use crate::expression::tests::test_common::run_fct;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn users_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn hash(sql: &str, id: i64, name: Field) -> Vec<u8> {
+    match run_fct(sql, users_schema(), vec![Field::Int(id), name]) {
+        Field::Binary(hash) => hash,
+        other => panic!("Expected a binary hash, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_hash() {
+    let sql = "SELECT HASH(id, name) FROM users";
+    let first = hash(sql, 1, Field::String("John".to_string()));
+    assert_eq!(first.len(), 32);
+    assert_eq!(first, hash(sql, 1, Field::String("John".to_string())));
+    assert_ne!(first, hash(sql, 2, Field::String("John".to_string())));
+    assert_ne!(first, hash(sql, 1, Field::Null));
+}
+
+#[test]
+fn test_hash_algorithms() {
+    let name = Field::String("John".to_string());
+    assert_eq!(
+        hash("SELECT MD5(id, name) FROM users", 1, name.clone()).len(),
+        16
+    );
+    assert_eq!(
+        hash("SELECT SHA256(id, name) FROM users", 1, name.clone()).len(),
+        32
+    );
+    assert_ne!(
+        hash("SELECT SHA256(id, name) FROM users", 1, name.clone()),
+        hash("SELECT HASH(id, name) FROM users", 1, name)
+    );
+}
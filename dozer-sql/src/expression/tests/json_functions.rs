@@ -820,3 +820,96 @@ fn test_json_value_diff_5() {
 
     assert_eq!(f, Field::Json(0.into()));
 }
+
+fn json_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("jsonInfo"),
+                FieldType::Json,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_json_extract() {
+    let json_val = json!({ "info": { "town": "Bristol", "tags": ["Sport", "Water polo"] } });
+
+    let f = run_fct(
+        "SELECT JSON_EXTRACT(jsonInfo,'$.info.tags') FROM users",
+        json_schema(),
+        vec![Field::Json(json_val.clone())],
+    );
+    assert_eq!(f, Field::Json(json!(["Sport", "Water polo"])));
+
+    let f = run_fct(
+        "SELECT JSON_EXTRACT(jsonInfo,'$.info.county') FROM users",
+        json_schema(),
+        vec![Field::Json(json_val)],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_json_set() {
+    let json_val = json!({ "info": { "town": "Bristol", "tags": ["Sport", "Water polo"] } });
+
+    let f = run_fct(
+        "SELECT JSON_SET(jsonInfo,'$.info.town','Bath') FROM users",
+        json_schema(),
+        vec![Field::Json(json_val.clone())],
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({ "info": { "town": "Bath", "tags": ["Sport", "Water polo"] } }))
+    );
+
+    let f = run_fct(
+        "SELECT JSON_SET(jsonInfo,'$.info.address.county',1) FROM users",
+        json_schema(),
+        vec![Field::Json(json_val.clone())],
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({
+            "info": { "town": "Bristol", "tags": ["Sport", "Water polo"], "address": { "county": 1 } }
+        }))
+    );
+
+    let f = run_fct(
+        "SELECT JSON_SET(jsonInfo,'$.info.tags[1]','Swimming') FROM users",
+        json_schema(),
+        vec![Field::Json(json_val.clone())],
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({ "info": { "town": "Bristol", "tags": ["Sport", "Swimming"] } }))
+    );
+
+    // Indexing past the end of an array leaves the value unchanged.
+    let f = run_fct(
+        "SELECT JSON_SET(jsonInfo,'$.info.tags[5]','Swimming') FROM users",
+        json_schema(),
+        vec![Field::Json(json_val.clone())],
+    );
+    assert_eq!(f, Field::Json(json_val));
+}
+
+#[test]
+fn test_struct() {
+    let json_val = json!({ "town": "Bristol" });
+
+    let f = run_fct(
+        "SELECT STRUCT(info => jsonInfo, town => JSON_VALUE(jsonInfo,'$.town'), county => NULL) FROM users",
+        json_schema(),
+        vec![Field::Json(json_val)],
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({ "info": { "town": "Bristol" }, "town": "Bristol", "county": null }))
+    );
+}
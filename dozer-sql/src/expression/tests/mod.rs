@@ -6,9 +6,11 @@ mod datetime;
 mod distance;
 mod execution;
 mod expression_builder_test;
+mod hash;
 mod in_list;
 mod json_functions;
 mod number;
 mod point;
 mod string;
 mod test_common;
+mod vector;
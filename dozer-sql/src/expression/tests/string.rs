@@ -1,5 +1,6 @@
 use crate::expression::tests::test_common::*;
 use dozer_types::chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use dozer_types::json_types::json;
 use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
 
 #[test]
@@ -479,3 +480,75 @@ fn test_to_char() {
     );
     assert_eq!(f, Field::String("%H:%M".to_string()));
 }
+
+fn name_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_regexp_matches() {
+    let f = run_fct(
+        "SELECT REGEXP_MATCHES(name, '^J[a-z]+$') FROM users",
+        name_schema(),
+        vec![Field::String("John".to_string())],
+    );
+    assert_eq!(f, Field::Boolean(true));
+
+    let f = run_fct(
+        "SELECT REGEXP_MATCHES(name, '^J[a-z]+$') FROM users",
+        name_schema(),
+        vec![Field::String("Mary".to_string())],
+    );
+    assert_eq!(f, Field::Boolean(false));
+
+    let f = run_fct(
+        "SELECT REGEXP_MATCHES(name, '^J') FROM users",
+        name_schema(),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_regexp_matches_named_groups() {
+    let f = run_fct(
+        "SELECT REGEXP_MATCHES(name, '(?P<first>[A-Za-z]+) (?P<last>[A-Za-z]+)') FROM users",
+        name_schema(),
+        vec![Field::String("John Doe".to_string())],
+    );
+    assert_eq!(f, Field::Json(json!({ "first": "John", "last": "Doe" })));
+
+    let f = run_fct(
+        "SELECT REGEXP_MATCHES(name, '(?P<first>[A-Za-z]+) (?P<last>[A-Za-z]+)') FROM users",
+        name_schema(),
+        vec![Field::String("John".to_string())],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_regexp_replace() {
+    let f = run_fct(
+        "SELECT REGEXP_REPLACE(name, '([A-Za-z]+) ([A-Za-z]+)', '$2, $1') FROM users",
+        name_schema(),
+        vec![Field::String("John Doe".to_string())],
+    );
+    assert_eq!(f, Field::String("Doe, John".to_string()));
+
+    let f = run_fct(
+        "SELECT REGEXP_REPLACE(name, '[aeiou]', '') FROM users",
+        name_schema(),
+        vec![Field::String("Mary Jane".to_string())],
+    );
+    assert_eq!(f, Field::String("Mry Jn".to_string()));
+}
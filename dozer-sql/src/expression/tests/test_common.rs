@@ -26,7 +26,7 @@ pub(crate) fn run_fct(sql: &str, schema: Schema, input: Vec<Field>) -> Field {
         vec![],
         runtime.clone(),
     );
-    runtime
+    let output_schema = runtime
         .block_on(
             processor_factory.get_output_schema(
                 &DEFAULT_PORT_HANDLE,
@@ -40,7 +40,7 @@ pub(crate) fn run_fct(sql: &str, schema: Schema, input: Vec<Field>) -> Field {
     let mut processor = runtime
         .block_on(processor_factory.build(
             HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
-            HashMap::new(),
+            HashMap::from([(DEFAULT_PORT_HANDLE, output_schema)]),
             None,
         ))
         .unwrap();
@@ -0,0 +1,90 @@
+use crate::expression::tests::test_common::run_fct;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn vectors_schema(left_dimension: usize, right_dimension: usize) -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("a"),
+                FieldType::Vector(left_dimension),
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("b"),
+                FieldType::Vector(right_dimension),
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn vector(values: &[f32]) -> Field {
+    Field::Vector(values.iter().copied().map(OrderedFloat).collect())
+}
+
+#[test]
+fn test_cosine_similarity() {
+    let f = run_fct(
+        "SELECT COSINE_SIMILARITY(a, b) FROM vectors",
+        vectors_schema(2, 2),
+        vec![vector(&[1.0, 0.0]), vector(&[1.0, 1.0])],
+    );
+    let Field::Float(OrderedFloat(similarity)) = f else {
+        panic!("Expected a float, got {f:?}");
+    };
+    assert!((similarity - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+
+    let f = run_fct(
+        "SELECT COSINE_SIMILARITY(a, b) FROM vectors",
+        vectors_schema(2, 2),
+        vec![vector(&[1.0, 2.0]), vector(&[-1.0, -2.0])],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(-1.0)));
+}
+
+#[test]
+fn test_cosine_similarity_zero_vector() {
+    let f = run_fct(
+        "SELECT COSINE_SIMILARITY(a, b) FROM vectors",
+        vectors_schema(2, 2),
+        vec![vector(&[0.0, 0.0]), vector(&[1.0, 1.0])],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_l2_distance() {
+    let f = run_fct(
+        "SELECT L2_DISTANCE(a, b) FROM vectors",
+        vectors_schema(3, 3),
+        vec![vector(&[1.0, 2.0, 3.0]), vector(&[4.0, 6.0, 3.0])],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(5.0)));
+}
+
+#[test]
+fn test_vector_function_null() {
+    let f = run_fct(
+        "SELECT L2_DISTANCE(a, b) FROM vectors",
+        vectors_schema(2, 2),
+        vec![vector(&[1.0, 2.0]), Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+#[should_panic]
+fn test_vector_function_dimension_mismatch() {
+    run_fct(
+        "SELECT L2_DISTANCE(a, b) FROM vectors",
+        vectors_schema(2, 3),
+        vec![vector(&[1.0, 2.0]), vector(&[1.0, 2.0, 3.0])],
+    );
+}
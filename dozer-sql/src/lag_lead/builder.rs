@@ -0,0 +1,181 @@
+use dozer_sql_expression::sqlparser::ast::{
+    Expr, Function, FunctionArg, FunctionArgExpr, Ident, SelectItem, Value,
+};
+
+use crate::errors::LagLeadError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LagLeadKind {
+    Lag,
+    Lead,
+}
+
+impl LagLeadKind {
+    fn new(name: &str) -> Option<Self> {
+        match name {
+            "lag" => Some(LagLeadKind::Lag),
+            "lead" => Some(LagLeadKind::Lead),
+            _ => None,
+        }
+    }
+}
+
+/// A `LAG` or `LEAD` call found in a SELECT projection.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LagLeadCall {
+    pub kind: LagLeadKind,
+    pub args: Vec<FunctionArg>,
+    pub partition_by: Vec<Expr>,
+    /// Name of the column the LAG/LEAD processor appends for this call.
+    pub column_name: String,
+}
+
+/// Replaces every `LAG(...) OVER (...)` and `LEAD(...) OVER (...)` call in the projection
+/// with a reference to the column that will hold its value, and returns the calls.
+///
+/// All calls must share the same `PARTITION BY`. `ORDER BY` is accepted, but records are
+/// always processed in arrival order.
+pub(crate) fn extract_lag_lead_calls(
+    projection: &mut [SelectItem],
+) -> Result<Vec<LagLeadCall>, LagLeadError> {
+    let mut calls = vec![];
+    for item in projection.iter_mut() {
+        match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                rewrite_expr(expr, &mut calls)?
+            }
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {}
+        }
+    }
+
+    if let Some(first) = calls.first() {
+        if calls
+            .iter()
+            .any(|call| call.partition_by != first.partition_by)
+        {
+            return Err(LagLeadError::MismatchedPartitions);
+        }
+    }
+    Ok(calls)
+}
+
+fn rewrite_expr(expr: &mut Expr, calls: &mut Vec<LagLeadCall>) -> Result<(), LagLeadError> {
+    match expr {
+        Expr::Function(function) => {
+            let name = function.name.to_string().to_lowercase();
+            if let Some(kind) = LagLeadKind::new(&name) {
+                let call = lag_lead_call(kind, function)?;
+                *expr = Expr::Identifier(Ident::new(call.column_name.clone()));
+                // The same call may appear more than once, but only gets one column.
+                if !calls.contains(&call) {
+                    calls.push(call);
+                }
+            } else {
+                for arg in function.args.iter_mut() {
+                    if let FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(arg),
+                        ..
+                    }
+                    | FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) = arg
+                    {
+                        rewrite_expr(arg, calls)?;
+                    }
+                }
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            rewrite_expr(left, calls)?;
+            rewrite_expr(right, calls)?;
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => rewrite_expr(expr, calls)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn lag_lead_call(kind: LagLeadKind, function: &Function) -> Result<LagLeadCall, LagLeadError> {
+    let Some(over) = &function.over else {
+        return Err(LagLeadError::MissingOver(function.name.to_string()));
+    };
+    Ok(LagLeadCall {
+        kind,
+        args: function.args.clone(),
+        partition_by: over.partition_by.clone(),
+        column_name: function.to_string(),
+    })
+}
+
+/// The arguments of `LAG(value, offset, default, ignore_nulls)` and
+/// `LEAD(value, offset, default, ignore_nulls)`. Only `value` is required.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LagLeadArgs {
+    pub value: Expr,
+    pub offset: usize,
+    pub default: Expr,
+    pub ignore_nulls: bool,
+}
+
+const ARG_VALUE: usize = 0;
+const ARG_OFFSET: usize = 1;
+const ARG_DEFAULT: usize = 2;
+const ARG_IGNORE_NULLS: usize = 3;
+
+impl LagLeadCall {
+    pub(crate) fn parse_args(&self) -> Result<LagLeadArgs, LagLeadError> {
+        let name = self.column_name.as_str();
+        if self.args.len() > ARG_IGNORE_NULLS + 1 {
+            return Err(LagLeadError::TooManyArguments(name.to_string()));
+        }
+        let args = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Ok(expr),
+                _ => Err(LagLeadError::InvalidArgument(
+                    arg.to_string(),
+                    name.to_string(),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let value = args
+            .get(ARG_VALUE)
+            .ok_or_else(|| LagLeadError::MissingValueArgument(name.to_string()))?;
+        let offset = match args.get(ARG_OFFSET) {
+            Some(Expr::Value(Value::Number(n, _))) => n
+                .parse()
+                .map_err(|_| LagLeadError::InvalidOffset(n.to_string(), name.to_string()))?,
+            Some(expr) => {
+                return Err(LagLeadError::InvalidOffset(
+                    expr.to_string(),
+                    name.to_string(),
+                ))
+            }
+            None => 1,
+        };
+        let default = args
+            .get(ARG_DEFAULT)
+            .map_or(Expr::Value(Value::Null), |expr| (*expr).clone());
+        let ignore_nulls = match args.get(ARG_IGNORE_NULLS) {
+            Some(Expr::Value(Value::Boolean(ignore_nulls))) => *ignore_nulls,
+            Some(expr) => {
+                return Err(LagLeadError::InvalidArgument(
+                    expr.to_string(),
+                    name.to_string(),
+                ))
+            }
+            None => false,
+        };
+
+        Ok(LagLeadArgs {
+            value: (*value).clone(),
+            offset,
+            default,
+            ignore_nulls,
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use std::{collections::HashMap, sync::Arc};
+
+use dozer_core::{
+    node::{PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql_expression::{
+    builder::ExpressionBuilder, execution::Expression, sqlparser::ast::Expr as SqlExpr,
+};
+use dozer_types::{
+    errors::internal::BoxedError,
+    models::udf_config::UdfConfig,
+    tonic::async_trait,
+    types::{FieldDefinition, Schema, SourceDefinition},
+};
+use tokio::runtime::Runtime;
+
+use crate::errors::PipelineError;
+
+use super::{
+    builder::LagLeadCall,
+    processor::{LagLeadFunction, LagLeadProcessor},
+};
+
+#[derive(Debug)]
+pub struct LagLeadProcessorFactory {
+    id: String,
+    calls: Vec<LagLeadCall>,
+    udfs: Vec<UdfConfig>,
+    runtime: Arc<Runtime>,
+}
+
+impl LagLeadProcessorFactory {
+    pub(crate) fn new(
+        id: String,
+        calls: Vec<LagLeadCall>,
+        udfs: Vec<UdfConfig>,
+        runtime: Arc<Runtime>,
+    ) -> Self {
+        Self {
+            id,
+            calls,
+            udfs,
+            runtime,
+        }
+    }
+
+    async fn build_expression(
+        &self,
+        expr: &SqlExpr,
+        schema: &Schema,
+    ) -> Result<Expression, PipelineError> {
+        Ok(
+            ExpressionBuilder::new(schema.fields.len(), self.runtime.clone())
+                .build(false, expr, schema, &self.udfs)
+                .await?,
+        )
+    }
+
+    async fn build_functions(
+        &self,
+        schema: &Schema,
+    ) -> Result<Vec<LagLeadFunction>, PipelineError> {
+        let mut functions = vec![];
+        for call in &self.calls {
+            let args = call.parse_args()?;
+            functions.push(LagLeadFunction {
+                kind: call.kind,
+                value: self.build_expression(&args.value, schema).await?,
+                offset: args.offset,
+                default: self.build_expression(&args.default, schema).await?,
+                ignore_nulls: args.ignore_nulls,
+            });
+        }
+        Ok(functions)
+    }
+}
+
+#[async_trait]
+impl ProcessorFactory for LagLeadProcessorFactory {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn type_name(&self) -> String {
+        "LagLead".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let mut output_schema = input_schema.clone();
+        let functions = self.build_functions(input_schema).await?;
+        for (call, function) in self.calls.iter().zip(functions) {
+            let value_type = function
+                .value
+                .get_type(input_schema)
+                .map_err(PipelineError::from)?;
+            output_schema.field(
+                FieldDefinition::new(
+                    call.column_name.clone(),
+                    value_type.return_type,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        }
+        Ok(output_schema)
+    }
+
+    async fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?
+            .clone();
+
+        let mut partition_by = vec![];
+        for expr in self
+            .calls
+            .first()
+            .map_or(&[][..], |call| &call.partition_by)
+        {
+            partition_by.push(self.build_expression(expr, &input_schema).await?);
+        }
+        let functions = self.build_functions(&input_schema).await?;
+
+        Ok(Box::new(LagLeadProcessor::new(
+            input_schema,
+            partition_by,
+            functions,
+        )))
+    }
+}
@@ -0,0 +1,6 @@
+pub(crate) mod builder;
+pub(crate) mod factory;
+mod processor;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,259 @@
+use std::collections::{HashMap, VecDeque};
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::dozer_log::storage::Object;
+use dozer_core::epoch::Epoch;
+use dozer_core::node::Processor;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::execution::Expression;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Field, Operation, Record, Schema, TableOperation};
+
+use crate::errors::PipelineError;
+
+use super::builder::LagLeadKind;
+
+#[derive(Debug)]
+pub(crate) struct LagLeadFunction {
+    pub kind: LagLeadKind,
+    pub value: Expression,
+    pub offset: usize,
+    pub default: Expression,
+    pub ignore_nulls: bool,
+}
+
+impl LagLeadFunction {
+    /// Whether `value` counts towards the offset.
+    fn counts(&self, value: &Field) -> bool {
+        !(self.ignore_nulls && value == &Field::Null)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    input: Record,
+    /// The value of each function for `input`.
+    values: Vec<Field>,
+    output: Record,
+    /// For each function, how many of the following records have counted towards its LEAD offset.
+    following: Vec<usize>,
+}
+
+/// Appends a column per `LAG`/`LEAD` function to each record.
+///
+/// Records are buffered per partition for as long as a function may still need them:
+/// until enough later records have arrived for `LAG` to look past them, or for `LEAD` to
+/// resolve them. When a `LEAD` value becomes known, the earlier record is updated downstream.
+#[derive(Debug)]
+pub struct LagLeadProcessor {
+    input_schema: Schema,
+    partition_by: Vec<Expression>,
+    functions: Vec<LagLeadFunction>,
+    partitions: HashMap<Vec<Field>, VecDeque<Entry>>,
+}
+
+impl LagLeadProcessor {
+    pub(crate) fn new(
+        input_schema: Schema,
+        partition_by: Vec<Expression>,
+        functions: Vec<LagLeadFunction>,
+    ) -> Self {
+        Self {
+            input_schema,
+            partition_by,
+            functions,
+            partitions: HashMap::new(),
+        }
+    }
+
+    fn partition_key(&mut self, record: &Record) -> Result<Vec<Field>, PipelineError> {
+        self.partition_by
+            .iter_mut()
+            .map(|expr| Ok(expr.evaluate(record, &self.input_schema)?))
+            .collect()
+    }
+
+    fn with_defaults(&mut self, record: &Record) -> Result<Record, PipelineError> {
+        let defaults = self
+            .functions
+            .iter_mut()
+            .map(|function| Ok(function.default.evaluate(record, &self.input_schema)?))
+            .collect::<Result<Vec<_>, PipelineError>>()?;
+        Ok(Record::appended(record, &defaults))
+    }
+
+    fn insert(
+        &mut self,
+        record: Record,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), PipelineError> {
+        let key = self.partition_key(&record)?;
+        let values = self
+            .functions
+            .iter_mut()
+            .map(|function| Ok(function.value.evaluate(&record, &self.input_schema)?))
+            .collect::<Result<Vec<_>, PipelineError>>()?;
+        let mut output = self.with_defaults(&record)?;
+
+        let n_input_fields = record.values.len();
+        let history = self.partitions.entry(key.clone()).or_default();
+        for (i, function) in self.functions.iter().enumerate() {
+            if function.offset == 0 {
+                output.values[n_input_fields + i] = values[i].clone();
+            } else if function.kind == LagLeadKind::Lag {
+                let lagged = history
+                    .iter()
+                    .rev()
+                    .map(|entry| &entry.values[i])
+                    .filter(|value| function.counts(value))
+                    .nth(function.offset - 1);
+                if let Some(lagged) = lagged {
+                    output.values[n_input_fields + i] = lagged.clone();
+                }
+            }
+        }
+        fw.send(TableOperation::without_id(
+            Operation::Insert {
+                new: output.clone(),
+            },
+            DEFAULT_PORT_HANDLE,
+        ));
+
+        // This record may be the one an earlier record was waiting for.
+        for entry in history.iter_mut() {
+            let mut old = None;
+            for (i, function) in self.functions.iter().enumerate() {
+                if function.kind != LagLeadKind::Lead
+                    || entry.following[i] >= function.offset
+                    || !function.counts(&values[i])
+                {
+                    continue;
+                }
+                entry.following[i] += 1;
+                if entry.following[i] == function.offset {
+                    old.get_or_insert_with(|| entry.output.clone());
+                    entry.output.values[n_input_fields + i] = values[i].clone();
+                }
+            }
+            if let Some(old) = old {
+                fw.send(TableOperation::without_id(
+                    Operation::Update {
+                        old,
+                        new: entry.output.clone(),
+                    },
+                    DEFAULT_PORT_HANDLE,
+                ));
+            }
+        }
+
+        history.push_back(Entry {
+            input: record,
+            values,
+            output,
+            following: vec![0; self.functions.len()],
+        });
+        prune(history, &self.functions);
+        if history.is_empty() {
+            self.partitions.remove(&key);
+        }
+        Ok(())
+    }
+
+    fn delete(
+        &mut self,
+        record: Record,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), PipelineError> {
+        let key = self.partition_key(&record)?;
+        // Removing the record shifts the later records in the buffer, so they are
+        // compared against the records before it from now on.
+        let entry = match self.partitions.get_mut(&key) {
+            Some(history) => {
+                let entry = history
+                    .iter()
+                    .rposition(|entry| entry.input == record)
+                    .and_then(|position| history.remove(position));
+                if history.is_empty() {
+                    self.partitions.remove(&key);
+                }
+                entry
+            }
+            None => None,
+        };
+
+        // Records that are no longer buffered are deleted with the default values, as the
+        // values they were emitted with are not known anymore.
+        let output = match entry {
+            Some(entry) => entry.output,
+            None => self.with_defaults(&record)?,
+        };
+        fw.send(TableOperation::without_id(
+            Operation::Delete { old: output },
+            DEFAULT_PORT_HANDLE,
+        ));
+        Ok(())
+    }
+}
+
+/// Records kept beyond what `LAG` needs, so that a deleted record can be replaced by the one before it.
+const EXTRA_LAG_RECORDS: usize = 1;
+
+/// Drops the oldest records once no function needs them anymore.
+fn prune(history: &mut VecDeque<Entry>, functions: &[LagLeadFunction]) {
+    while let Some(front) = history.front() {
+        let needed = functions
+            .iter()
+            .enumerate()
+            .any(|(i, function)| match function.kind {
+                LagLeadKind::Lag => {
+                    function.counts(&front.values[i])
+                        && history
+                            .iter()
+                            .skip(1)
+                            .filter(|entry| function.counts(&entry.values[i]))
+                            .count()
+                            < function.offset + EXTRA_LAG_RECORDS
+                }
+                LagLeadKind::Lead => front.following[i] < function.offset,
+            });
+        if needed {
+            break;
+        }
+        history.pop_front();
+    }
+}
+
+impl Processor for LagLeadProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        op: TableOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        match op.op {
+            Operation::Delete { old } => self.delete(old, fw)?,
+            Operation::Insert { new } => self.insert(new, fw)?,
+            Operation::Update { old, new } => {
+                self.delete(old, fw)?;
+                self.insert(new, fw)?;
+            }
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                return Err(PipelineError::UnsupportedSchemaChange.into())
+            }
+            Operation::BatchInsert { new } => {
+                for record in new {
+                    self.insert(record, fw)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize(&mut self, _object: Object) -> Result<(), BoxedError> {
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+mod processor_test;
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::node::{Processor, ProcessorFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::sqlparser::ast::{Expr, Ident, SelectItem};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition, TableOperation,
+};
+
+use crate::errors::LagLeadError;
+use crate::lag_lead::builder::extract_lag_lead_calls;
+use crate::lag_lead::factory::LagLeadProcessorFactory;
+use crate::tests::utils::{create_test_runtime, get_select};
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: TableOperation) {
+        self.operations.push(op.op);
+    }
+}
+
+fn schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("sym"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("price"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn build_processor(sql: &str) -> (Box<dyn Processor>, Schema) {
+    let mut select = get_select(sql).unwrap();
+    let calls = extract_lag_lead_calls(&mut select.projection).unwrap();
+    let runtime = create_test_runtime();
+    let factory =
+        LagLeadProcessorFactory::new("lag_lead".to_string(), calls, vec![], runtime.clone());
+    let input_schemas = HashMap::from([(DEFAULT_PORT_HANDLE, schema())]);
+    let output_schema = runtime
+        .block_on(factory.get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas))
+        .unwrap();
+    let processor = runtime
+        .block_on(factory.build(input_schemas, HashMap::new(), None))
+        .unwrap();
+    (processor, output_schema)
+}
+
+fn record(id: i64, sym: &str, price: Option<i64>) -> Record {
+    Record::new(vec![
+        Field::Int(id),
+        Field::String(sym.to_string()),
+        price.map_or(Field::Null, Field::Int),
+    ])
+}
+
+fn process(processor: &mut Box<dyn Processor>, op: Operation) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor
+        .process(TableOperation::without_id(op, DEFAULT_PORT_HANDLE), &mut fw)
+        .unwrap();
+    fw.operations
+}
+
+fn insert(processor: &mut Box<dyn Processor>, new: Record) -> Vec<Operation> {
+    process(processor, Operation::Insert { new })
+}
+
+fn with(record: Record, field: Field) -> Record {
+    Record::appended(&record, &[field])
+}
+
+#[test]
+fn test_lag() {
+    let (mut processor, schema) =
+        build_processor("SELECT id, LAG(price, 1, 0) OVER (PARTITION BY sym) FROM t");
+    let field = schema.fields.last().unwrap();
+    assert_eq!(field.name, "LAG(price, 1, 0) OVER (PARTITION BY sym)");
+    assert_eq!(field.typ, FieldType::Int);
+    assert!(field.nullable);
+
+    let ops = insert(&mut processor, record(1, "a", Some(10)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(1, "a", Some(10)), Field::Int(0))
+        }]
+    );
+    let ops = insert(&mut processor, record(2, "b", Some(20)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(2, "b", Some(20)), Field::Int(0))
+        }]
+    );
+    let ops = insert(&mut processor, record(3, "a", Some(30)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(3, "a", Some(30)), Field::Int(10))
+        }]
+    );
+}
+
+#[test]
+fn test_lead() {
+    let (mut processor, _) =
+        build_processor("SELECT id, LEAD(price) OVER (PARTITION BY sym) FROM t");
+
+    let ops = insert(&mut processor, record(1, "a", Some(10)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(1, "a", Some(10)), Field::Null)
+        }]
+    );
+    let ops = insert(&mut processor, record(2, "a", Some(20)));
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Insert {
+                new: with(record(2, "a", Some(20)), Field::Null)
+            },
+            Operation::Update {
+                old: with(record(1, "a", Some(10)), Field::Null),
+                new: with(record(1, "a", Some(10)), Field::Int(20)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_lag_ignore_nulls() {
+    let (mut processor, _) =
+        build_processor("SELECT id, LAG(price, 1, -1, true) OVER (PARTITION BY sym) FROM t");
+
+    insert(&mut processor, record(1, "a", Some(10)));
+    let ops = insert(&mut processor, record(2, "a", None));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(2, "a", None), Field::Int(10))
+        }]
+    );
+    let ops = insert(&mut processor, record(3, "a", Some(30)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(3, "a", Some(30)), Field::Int(10))
+        }]
+    );
+}
+
+#[test]
+fn test_lag_delete_shifts_buffer() {
+    let (mut processor, _) =
+        build_processor("SELECT id, LAG(price) OVER (PARTITION BY sym) FROM t");
+
+    insert(&mut processor, record(1, "a", Some(10)));
+    insert(&mut processor, record(2, "a", Some(20)));
+    let ops = process(
+        &mut processor,
+        Operation::Delete {
+            old: record(2, "a", Some(20)),
+        },
+    );
+    assert_eq!(
+        ops,
+        vec![Operation::Delete {
+            old: with(record(2, "a", Some(20)), Field::Int(10))
+        }]
+    );
+    let ops = insert(&mut processor, record(3, "a", Some(30)));
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: with(record(3, "a", Some(30)), Field::Int(10))
+        }]
+    );
+}
+
+#[test]
+fn test_extract_lag_lead_calls() {
+    let mut select =
+        get_select("SELECT price - LAG(price) OVER (PARTITION BY sym) AS diff FROM t").unwrap();
+    let calls = extract_lag_lead_calls(&mut select.projection).unwrap();
+    assert_eq!(calls.len(), 1);
+    let SelectItem::ExprWithAlias {
+        expr: Expr::BinaryOp { right, .. },
+        ..
+    } = &select.projection[0]
+    else {
+        panic!("unexpected projection {:?}", select.projection);
+    };
+    assert_eq!(
+        **right,
+        Expr::Identifier(Ident::new(calls[0].column_name.clone()))
+    );
+
+    let mut select = get_select(
+        "SELECT LAG(price) OVER (PARTITION BY sym), LEAD(price) OVER (PARTITION BY id) FROM t",
+    )
+    .unwrap();
+    assert!(matches!(
+        extract_lag_lead_calls(&mut select.projection),
+        Err(LagLeadError::MismatchedPartitions)
+    ));
+}
@@ -2,13 +2,16 @@ mod aggregation;
 pub mod builder;
 pub mod errors;
 mod expression;
+mod lag_lead;
 mod planner;
 mod product;
 mod projection;
+pub mod schema_evolution;
 mod selection;
 mod table_operator;
 mod utils;
 mod window;
+pub mod wire;
 
 pub use dozer_sql_expression::sqlparser;
 
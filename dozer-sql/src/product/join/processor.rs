@@ -115,6 +115,10 @@ impl Processor for ProductProcessor {
                 old_records.extend(new_records);
                 old_records
             }
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                return Err(PipelineError::UnsupportedSchemaChange.into())
+            }
             Operation::BatchInsert { new } => {
                 for record in &new {
                     self.process(
@@ -177,6 +177,10 @@ impl Processor for SetProcessor {
                     }
                 }
             }
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                return Err(PipelineError::UnsupportedSchemaChange.into())
+            }
             Operation::BatchInsert { new } => {
                 for record in new {
                     self.process(
@@ -2,12 +2,15 @@ use std::collections::HashMap;
 
 use dozer_core::{
     node::{PortHandle, Processor, ProcessorFactory},
+    pipeline::WireFormatFactory,
     DEFAULT_PORT_HANDLE,
 };
 use dozer_sql_expression::builder::{extend_schema_source_def, NameOrAlias};
-use dozer_types::{errors::internal::BoxedError, tonic::async_trait, types::Schema};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::{errors::internal::BoxedError, serde_json, tonic::async_trait, types::Schema};
 
 use crate::errors::PipelineError;
+use crate::wire::TABLE_TYPE_NAME;
 
 use super::processor::TableProcessor;
 
@@ -21,6 +24,20 @@ impl TableProcessorFactory {
     pub fn new(id: String, table: NameOrAlias) -> Self {
         Self { id, table }
     }
+
+    /// Rebuilds a factory from the configuration `to_wire` sent.
+    pub(crate) fn from_wire(config: &[u8]) -> Result<Self, BoxedError> {
+        let config: TableWireConfig = serde_json::from_slice(config)?;
+        Ok(Self::new(config.id, NameOrAlias(config.name, config.alias)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct TableWireConfig {
+    id: String,
+    name: String,
+    alias: Option<String>,
 }
 
 #[async_trait]
@@ -65,4 +82,16 @@ impl ProcessorFactory for TableProcessorFactory {
             checkpoint_data,
         )))
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        let config = TableWireConfig {
+            id: self.id.clone(),
+            name: self.table.0.clone(),
+            alias: self.table.1.clone(),
+        };
+        Some(WireFormatFactory {
+            type_name: TABLE_TYPE_NAME.to_string(),
+            config: serde_json::to_vec(&config).ok()?,
+        })
+    }
 }
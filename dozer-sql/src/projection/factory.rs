@@ -6,19 +6,25 @@ use dozer_core::{
 };
 use dozer_sql_expression::{
     builder::ExpressionBuilder,
+    cast::CastOperatorType,
+    conditional::ConditionalExpressionType,
     execution::Expression,
     sqlparser::ast::{Expr, Ident, SelectItem},
 };
 use dozer_types::{
     errors::internal::BoxedError,
-    types::{FieldDefinition, Schema},
+    types::{Field, FieldDefinition, FieldType, Schema},
+};
+use dozer_types::{
+    models::{flags::ProjectionErrorPolicy, udf_config::UdfConfig},
+    tonic::async_trait,
 };
-use dozer_types::{models::udf_config::UdfConfig, tonic::async_trait};
 use tokio::runtime::Runtime;
 
 use crate::errors::PipelineError;
+use crate::schema_evolution::{MigrateOnSchemaChange, SchemaEvolutionHandler};
 
-use super::processor::ProjectionProcessor;
+use super::{processor::ProjectionProcessor, type_capabilities::TypeCapabilities};
 
 #[derive(Debug)]
 pub struct ProjectionProcessorFactory {
@@ -26,6 +32,18 @@ pub struct ProjectionProcessorFactory {
     id: String,
     udfs: Vec<UdfConfig>,
     runtime: Arc<Runtime>,
+    /// Values that replace nulls in the output columns of the same name.
+    defaults: HashMap<String, Field>,
+    /// What the processor does with records its expressions fail to evaluate on.
+    error_policy: ProjectionErrorPolicy,
+    /// Records the predicate isn't true for are dropped, like with a `WHERE` clause.
+    predicate: Option<Expr>,
+    /// The order of the output columns by name, if it differs from the select order.
+    output_order: Option<Vec<String>>,
+    /// The types the downstream sink can write, if they're checked.
+    type_capabilities: Option<TypeCapabilities>,
+    /// Decides whether columns added to or removed from the input are migrated in place.
+    schema_evolution: Arc<dyn SchemaEvolutionHandler>,
 }
 
 impl ProjectionProcessorFactory {
@@ -41,8 +59,56 @@ impl ProjectionProcessorFactory {
             id,
             udfs,
             runtime,
+            defaults: HashMap::new(),
+            error_policy: ProjectionErrorPolicy::default(),
+            predicate: None,
+            output_order: None,
+            type_capabilities: None,
+            schema_evolution: Arc::new(MigrateOnSchemaChange),
         }
     }
+
+    /// Replaces nulls in the given output columns with a default, like wrapping each of them in
+    /// `COALESCE(column, default)`.
+    pub fn with_defaults(mut self, defaults: HashMap<String, Field>) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Sets what the processor does with records its expressions fail to evaluate on.
+    pub fn with_error_policy(mut self, error_policy: ProjectionErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Drops the records `predicate` evaluates to false or null on, like a `WHERE` clause.
+    pub fn with_predicate(mut self, predicate: Expr) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Outputs the columns in `output_order`, which must name each selected column exactly once,
+    /// instead of in select order.
+    pub fn with_output_order(mut self, output_order: Vec<String>) -> Self {
+        self.output_order = Some(output_order);
+        self
+    }
+
+    /// Casts the outputs of types the downstream sink can't write to the types `type_capabilities`
+    /// coerces them to, and fails on outputs it doesn't coerce.
+    pub fn with_type_capabilities(mut self, type_capabilities: TypeCapabilities) -> Self {
+        self.type_capabilities = Some(type_capabilities);
+        self
+    }
+
+    /// Sets what the processor does when columns are added to or removed from its input.
+    pub fn with_schema_evolution_handler(
+        mut self,
+        schema_evolution: Arc<dyn SchemaEvolutionHandler>,
+    ) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
 }
 
 #[async_trait]
@@ -106,6 +172,18 @@ impl ProcessorFactory for ProjectionProcessorFactory {
             }
         }
 
+        let select_expr = apply_defaults(&self.defaults, select_expr, input_schema)?;
+        let select_expr =
+            apply_type_capabilities(self.type_capabilities.as_ref(), select_expr, input_schema)?;
+        let select_expr = apply_output_order(self.output_order.as_deref(), select_expr)?;
+        parse_predicate(
+            self.predicate.as_ref(),
+            input_schema,
+            &self.udfs,
+            self.runtime.clone(),
+        )
+        .await?;
+
         let mut output_schema = input_schema.clone();
         let mut fields = vec![];
         for e in select_expr.iter() {
@@ -126,9 +204,12 @@ impl ProcessorFactory for ProjectionProcessorFactory {
     async fn build(
         &self,
         input_schemas: HashMap<PortHandle, Schema>,
-        _output_schemas: HashMap<PortHandle, Schema>,
+        mut output_schemas: HashMap<PortHandle, Schema>,
         checkpoint_data: Option<Vec<u8>>,
     ) -> Result<Box<dyn Processor>, BoxedError> {
+        let output_schema = output_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
         let schema = match input_schemas.get(&DEFAULT_PORT_HANDLE) {
             Some(schema) => Ok(schema),
             None => Err(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE)),
@@ -140,14 +221,157 @@ impl ProcessorFactory for ProjectionProcessorFactory {
                 parse_sql_select_item(select, schema, &self.udfs, self.runtime.clone()).await?,
             );
         }
+        let expressions = apply_defaults(&self.defaults, expressions, schema)?;
+        let expressions =
+            apply_type_capabilities(self.type_capabilities.as_ref(), expressions, schema)?;
+        let expressions = apply_output_order(self.output_order.as_deref(), expressions)?;
+        let predicate = parse_predicate(
+            self.predicate.as_ref(),
+            schema,
+            &self.udfs,
+            self.runtime.clone(),
+        )
+        .await?;
         Ok(Box::new(ProjectionProcessor::new(
             schema.clone(),
+            output_schema,
             expressions.into_iter().map(|e| e.1).collect(),
+            predicate,
+            self.error_policy,
+            self.schema_evolution.clone(),
             checkpoint_data,
         )?))
     }
 }
 
+/// Wraps the expressions of the columns that have a default in `COALESCE(expression, default)`, checking that each
+/// default has the column's type.
+pub(crate) fn apply_defaults(
+    defaults: &HashMap<String, Field>,
+    select_expr: Vec<(String, Expression)>,
+    schema: &Schema,
+) -> Result<Vec<(String, Expression)>, PipelineError> {
+    if let Some(column) = defaults
+        .keys()
+        .find(|column| !select_expr.iter().any(|(name, _)| name == *column))
+    {
+        return Err(PipelineError::UnknownProjectionDefault(column.clone()));
+    }
+
+    select_expr
+        .into_iter()
+        .map(|(name, expr)| {
+            let Some(default) = defaults.get(&name) else {
+                return Ok((name, expr));
+            };
+            let expected = expr.get_type(schema)?.return_type;
+            if default.ty() != Some(expected) {
+                return Err(PipelineError::InvalidProjectionDefault {
+                    column: name,
+                    expected,
+                    default: default.clone(),
+                });
+            }
+            let expr = Expression::ConditionalExpression {
+                fun: ConditionalExpressionType::Coalesce,
+                args: vec![expr, Expression::Literal(default.clone())],
+            };
+            Ok((name, expr))
+        })
+        .collect()
+}
+
+/// Casts the outputs of types the sink can't write to the types `type_capabilities` coerces them to, and fails on
+/// outputs it doesn't coerce.
+pub(crate) fn apply_type_capabilities(
+    type_capabilities: Option<&TypeCapabilities>,
+    select_expr: Vec<(String, Expression)>,
+    schema: &Schema,
+) -> Result<Vec<(String, Expression)>, PipelineError> {
+    let Some(capabilities) = type_capabilities else {
+        return Ok(select_expr);
+    };
+    select_expr
+        .into_iter()
+        .map(|(name, expr)| {
+            let typ = expr.get_type(schema)?.return_type;
+            let Some(to) = capabilities.output_type(typ) else {
+                return Err(PipelineError::UnsupportedProjectionOutputType { column: name, typ });
+            };
+            if to == typ {
+                return Ok((name, expr));
+            }
+            let expr = Expression::Cast {
+                arg: Box::new(expr),
+                typ: CastOperatorType(to),
+            };
+            // Not every type can be cast to every other
+            if expr.get_type(schema).is_err() {
+                return Err(PipelineError::InvalidProjectionCoercion {
+                    column: name,
+                    from: typ,
+                    to,
+                });
+            }
+            Ok((name, expr))
+        })
+        .collect()
+}
+
+/// Reorders the outputs as `output_order`, which must name each of them exactly once.
+pub(crate) fn apply_output_order(
+    output_order: Option<&[String]>,
+    select_expr: Vec<(String, Expression)>,
+) -> Result<Vec<(String, Expression)>, PipelineError> {
+    let Some(order) = output_order else {
+        return Ok(select_expr);
+    };
+    let outputs = select_expr
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let invalid = || PipelineError::InvalidProjectionOutputOrder {
+        order: order.to_vec(),
+        outputs: outputs.clone(),
+    };
+
+    let mut select_expr = select_expr.into_iter().map(Some).collect::<Vec<_>>();
+    let mut ordered = Vec::with_capacity(select_expr.len());
+    for name in order {
+        let Some(position) = select_expr
+            .iter()
+            .position(|expr| matches!(expr, Some((expr_name, _)) if expr_name == name))
+        else {
+            return Err(invalid());
+        };
+        ordered.push(select_expr[position].take().unwrap());
+    }
+    if select_expr.iter().any(Option::is_some) {
+        return Err(invalid());
+    }
+    Ok(ordered)
+}
+
+/// Parses a projection's predicate, checking that it evaluates to a boolean.
+pub(crate) async fn parse_predicate(
+    predicate: Option<&Expr>,
+    schema: &Schema,
+    udfs: &[UdfConfig],
+    runtime: Arc<Runtime>,
+) -> Result<Option<Expression>, PipelineError> {
+    let Some(predicate) = predicate else {
+        return Ok(None);
+    };
+    let predicate = ExpressionBuilder::new(0, runtime)
+        .parse_sql_expression(true, predicate, schema, udfs)
+        .await?;
+    let return_type = predicate.get_type(schema)?.return_type;
+    if return_type != FieldType::Boolean {
+        return Err(PipelineError::InvalidProjectionPredicate(return_type));
+    }
+    Ok(Some(predicate))
+}
+
 pub(crate) async fn parse_sql_select_item(
     sql: &SelectItem,
     schema: &Schema,
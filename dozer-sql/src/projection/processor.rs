@@ -1,84 +1,309 @@
+use std::sync::Arc;
+
 use crate::errors::PipelineError;
+use crate::schema_evolution::{SchemaEvolutionAction, SchemaEvolutionHandler, SchemaMigration};
 use dozer_core::checkpoint::serialize::Cursor;
 use dozer_sql_expression::execution::Expression;
 
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::dozer_log::storage::Object;
 use dozer_core::epoch::Epoch;
-use dozer_core::node::Processor;
+use dozer_core::node::{Processor, ProcessorExplanation};
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::types::{Operation, Record, Schema, TableOperation};
+use dozer_types::models::flags::ProjectionErrorPolicy;
+use dozer_types::types::{
+    Field, FieldDefinition, Operation, Record, Schema, SourceDefinition, TableOperation,
+};
+use metrics::{describe_counter, increment_counter};
+
+const EVALUATION_ERRORS: &str = "projection.evaluation_errors";
+const COLUMN_LABEL: &str = "column";
+/// The `column` label of the predicate's evaluation errors.
+const PREDICATE_LABEL_VALUE: &str = "WHERE";
 
 #[derive(Debug)]
 pub struct ProjectionProcessor {
     expressions: Vec<Expression>,
     input_schema: Schema,
+    output_schema: Schema,
+    /// Default values of the columns added to the input schema after the processor was built, in schema order.
+    added_column_defaults: Vec<Field>,
+    /// Indexes of the input columns removed after the processor was built, in ascending order. They stay in the input
+    /// schema, and read as null.
+    removed_columns: Vec<usize>,
+    /// The input columns records have in each version of the input schema, in record order. Version 0 is the schema
+    /// the processor was built with, and every column added or removed since makes a new version.
+    schema_versions: Vec<Vec<usize>>,
+    /// `Some` if every expression is a bare column reference, i.e. the projection only renames or reorders columns.
+    /// Each entry is the input column index and whether this is its last use, so the value can be moved instead of cloned.
+    column_remap: Option<Vec<(usize, bool)>>,
+    /// Records the predicate doesn't evaluate to true on are dropped.
+    predicate: Option<Expression>,
+    /// What to do with a record when one of the expressions fails to evaluate on it.
+    error_policy: ProjectionErrorPolicy,
+    /// Decides whether columns added to or removed from the input are migrated in place.
+    schema_evolution: Arc<dyn SchemaEvolutionHandler>,
 }
 
 impl ProjectionProcessor {
     pub fn new(
         input_schema: Schema,
+        output_schema: Schema,
         mut expressions: Vec<Expression>,
+        mut predicate: Option<Expression>,
+        error_policy: ProjectionErrorPolicy,
+        schema_evolution: Arc<dyn SchemaEvolutionHandler>,
         checkpoint_data: Option<Vec<u8>>,
     ) -> Result<Self, PipelineError> {
+        describe_counter!(
+            EVALUATION_ERRORS,
+            "Number of projected expressions that failed to evaluate, by output column"
+        );
         if let Some(data) = checkpoint_data {
             let mut cursor = Cursor::new(&data);
-            for expr in &mut expressions {
+            for expr in expressions.iter_mut().chain(&mut predicate) {
                 expr.deserialize_state(&mut cursor)?;
             }
         }
+        let column_remap = column_remap(&expressions);
+        let schema_versions = vec![(0..input_schema.fields.len()).collect()];
         Ok(Self {
             input_schema,
+            output_schema,
             expressions,
+            added_column_defaults: vec![],
+            removed_columns: vec![],
+            schema_versions,
+            column_remap,
+            predicate,
+            error_policy,
+            schema_evolution,
         })
     }
 
-    fn delete(&mut self, record: &Record) -> Result<Operation, PipelineError> {
-        let mut results = vec![];
+    /// The input schema without the columns removed after the processor was built.
+    fn current_input_schema(&self) -> Schema {
+        let mut schema = self.input_schema.clone();
+        schema.fields = self
+            .input_schema
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.removed_columns.contains(index))
+            .map(|(_, field)| field.clone())
+            .collect();
+        schema
+    }
 
-        for expr in &mut self.expressions {
-            results.push(expr.evaluate(record, &self.input_schema)?);
+    /// Appends a column to the input schema if the schema evolution handler migrates it. Records that don't have it
+    /// yet read it as the handler's default value.
+    fn add_column(
+        &mut self,
+        field: FieldDefinition,
+        source_default: Field,
+    ) -> Result<(), PipelineError> {
+        let old = self.current_input_schema();
+        let mut new = old.clone();
+        new.field(field.clone(), false);
+        match self
+            .schema_evolution
+            .on_add_field(&old, &new, &source_default)
+        {
+            SchemaEvolutionAction::Migrate(SchemaMigration::AddColumn { default_value }) => {
+                self.input_schema.field(field, false);
+                self.added_column_defaults.push(default_value);
+                let mut columns = self.current_columns().to_vec();
+                columns.push(self.input_schema.fields.len() - 1);
+                self.schema_versions.push(columns);
+                Ok(())
+            }
+            _ => Err(PipelineError::SchemaEvolution {
+                table: table_name(&field),
+                old,
+                new,
+            }),
         }
+    }
 
-        let mut output_record = Record::new(results);
-        output_record.set_lifetime(record.lifetime.to_owned());
+    /// Removes a column from the input if the schema evolution handler migrates it. Output columns that only copy it
+    /// are removed too, and their names returned.
+    fn remove_column(&mut self, name: &str) -> Result<Vec<String>, PipelineError> {
+        let removed = self
+            .input_schema
+            .fields
+            .iter()
+            .enumerate()
+            .position(|(index, field)| field.name == name && !self.removed_columns.contains(&index))
+            .ok_or_else(|| PipelineError::RemovedFieldNotFound(name.to_string()))?;
+        let old = self.current_input_schema();
+        let mut new = old.clone();
+        let position = new
+            .fields
+            .iter()
+            .position(|field| field.name == name)
+            .unwrap();
+        new.fields.remove(position);
+        match self.schema_evolution.on_schema_change(&old, &new) {
+            SchemaEvolutionAction::Migrate(SchemaMigration::RemoveColumn) => {}
+            _ => {
+                return Err(PipelineError::SchemaEvolution {
+                    table: table_name(&self.input_schema.fields[removed]),
+                    old,
+                    new,
+                })
+            }
+        }
+        let position = self
+            .removed_columns
+            .partition_point(|index| *index < removed);
+        self.removed_columns.insert(position, removed);
+        let mut columns = self.current_columns().to_vec();
+        columns.retain(|index| *index != removed);
+        self.schema_versions.push(columns);
 
-        Ok(Operation::Delete { old: output_record })
+        let mut removed_outputs = vec![];
+        for position in (0..self.expressions.len()).rev() {
+            if matches!(self.expressions[position], Expression::Column { index } if index == removed)
+            {
+                self.expressions.remove(position);
+                removed_outputs.push(self.output_schema.fields.remove(position).name);
+                self.output_schema
+                    .primary_index
+                    .retain(|index| *index != position);
+                for index in &mut self.output_schema.primary_index {
+                    if *index > position {
+                        *index -= 1;
+                    }
+                }
+            }
+        }
+        removed_outputs.reverse();
+        self.column_remap = column_remap(&self.expressions);
+        Ok(removed_outputs)
     }
 
-    fn insert(&mut self, record: &Record) -> Result<Record, PipelineError> {
-        let mut results = vec![];
+    /// The input columns records have in the latest version of the input schema.
+    fn current_columns(&self) -> &[usize] {
+        self.schema_versions
+            .last()
+            .expect("There's always the version the processor was built with")
+    }
 
-        for expr in &mut self.expressions {
-            results.push(expr.evaluate(record, &self.input_schema)?);
+    /// Lays `record` out like the input schema. The record is read in the latest version of the input schema with as
+    /// many columns as it has. Removed columns it doesn't have read as null, and added columns it doesn't have yet as
+    /// their default values.
+    fn add_missing_columns(&self, record: &mut Record) -> Result<(), PipelineError> {
+        let num_fields = self.input_schema.fields.len();
+        if record.values.len() == num_fields {
+            return Ok(());
         }
+        let columns = self
+            .schema_versions
+            .iter()
+            .rev()
+            .find(|columns| columns.len() == record.values.len())
+            .ok_or(PipelineError::UnexpectedRecordLength(record.values.len()))?;
 
-        let mut output_record = Record::new(results);
-        output_record.set_lifetime(record.lifetime.to_owned());
-        Ok(output_record)
+        let mut values = vec![None; num_fields];
+        for (index, value) in columns.iter().zip(record.values.drain(..)) {
+            values[*index] = Some(value);
+        }
+        let first_added_column = num_fields - self.added_column_defaults.len();
+        record.values = values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| match value {
+                Some(value) => value,
+                None if self.removed_columns.contains(&index) => Field::Null,
+                // Only columns added since version 0 can be missing without being removed
+                None => self.added_column_defaults[index - first_added_column].clone(),
+            })
+            .collect();
+        Ok(())
     }
 
-    fn update(&mut self, old: &Record, new: &Record) -> Result<Operation, PipelineError> {
-        let mut old_results = vec![];
-        let mut new_results = vec![];
+    /// Evaluates the expressions on `record`. Returns `None` if the record is dropped by the predicate or skipped by
+    /// the error policy.
+    fn project(&mut self, mut record: Record) -> Result<Option<Record>, PipelineError> {
+        self.add_missing_columns(&mut record)?;
 
-        for expr in &mut self.expressions {
-            old_results.push(expr.evaluate(old, &self.input_schema)?);
-            new_results.push(expr.evaluate(new, &self.input_schema)?);
+        if let Some(predicate) = &mut self.predicate {
+            match predicate.evaluate(&record, &self.input_schema) {
+                Ok(Field::Boolean(true)) => {}
+                Ok(_) => return Ok(None),
+                Err(e) => {
+                    increment_counter!(EVALUATION_ERRORS, COLUMN_LABEL => PREDICATE_LABEL_VALUE);
+                    match self.error_policy {
+                        ProjectionErrorPolicy::Error => return Err(e.into()),
+                        // A null predicate drops the record too
+                        ProjectionErrorPolicy::Null | ProjectionErrorPolicy::SkipRecord => {
+                            return Ok(None)
+                        }
+                    }
+                }
+            }
         }
 
-        let mut old_output_record = Record::new(old_results);
-        old_output_record.set_lifetime(old.lifetime.to_owned());
-        let mut new_output_record = Record::new(new_results);
-        new_output_record.set_lifetime(new.lifetime.to_owned());
-        Ok(Operation::Update {
-            old: old_output_record,
-            new: new_output_record,
-        })
+        if let Some(column_remap) = &self.column_remap {
+            let mut values = Vec::with_capacity(column_remap.len());
+            for (index, is_last_use) in column_remap {
+                values.push(if *is_last_use {
+                    std::mem::replace(&mut record.values[*index], Field::Null)
+                } else {
+                    record.values[*index].clone()
+                });
+            }
+            record.values = values;
+            return Ok(Some(record));
+        }
+
+        let mut results = Vec::with_capacity(self.expressions.len());
+        for (expr, field) in self.expressions.iter_mut().zip(&self.output_schema.fields) {
+            match expr.evaluate(&record, &self.input_schema) {
+                Ok(value) => results.push(value),
+                Err(e) => {
+                    increment_counter!(EVALUATION_ERRORS, COLUMN_LABEL => field.name.clone());
+                    match self.error_policy {
+                        ProjectionErrorPolicy::Error => return Err(e.into()),
+                        ProjectionErrorPolicy::Null => results.push(Field::Null),
+                        ProjectionErrorPolicy::SkipRecord => return Ok(None),
+                    }
+                }
+            }
+        }
+
+        let mut output_record = Record::new(results);
+        output_record.set_lifetime(record.lifetime);
+        Ok(Some(output_record))
+    }
+}
+
+/// The name of the table `field` comes from, for errors.
+fn table_name(field: &FieldDefinition) -> String {
+    match &field.source {
+        SourceDefinition::Table { name, .. } | SourceDefinition::Alias { name } => name.clone(),
+        SourceDefinition::Dynamic => String::new(),
     }
 }
 
+fn column_remap(expressions: &[Expression]) -> Option<Vec<(usize, bool)>> {
+    let indexes = expressions
+        .iter()
+        .map(|expr| match expr {
+            Expression::Column { index } => Some(*index),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        indexes
+            .iter()
+            .enumerate()
+            .map(|(position, index)| (*index, !indexes[position + 1..].contains(index)))
+            .collect(),
+    )
+}
+
 impl Processor for ProjectionProcessor {
     fn process(
         &mut self,
@@ -86,16 +311,55 @@ impl Processor for ProjectionProcessor {
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), BoxedError> {
         let output_op = match op.op {
-            Operation::Delete { ref old } => self.delete(old)?,
-            Operation::Insert { ref new } => Operation::Insert {
-                new: self.insert(new)?,
+            Operation::Delete { old } => match self.project(old)? {
+                Some(old) => Operation::Delete { old },
+                None => return Ok(()),
+            },
+            Operation::Insert { new } => match self.project(new)? {
+                Some(new) => Operation::Insert { new },
+                None => return Ok(()),
+            },
+            // A skipped side turns the update into an insert or a delete of the other side
+            Operation::Update { old, new } => match (self.project(old)?, self.project(new)?) {
+                (Some(old), Some(new)) => Operation::Update { old, new },
+                (Some(old), None) => Operation::Delete { old },
+                (None, Some(new)) => Operation::Insert { new },
+                (None, None) => return Ok(()),
             },
-            Operation::Update { ref old, ref new } => self.update(old, new)?,
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            // The projection's output doesn't change, so the new field isn't forwarded
+            Operation::AddField {
+                field,
+                default_value,
+                ..
+            } => {
+                self.add_column(field, default_value)?;
+                return Ok(());
+            }
+            Operation::RemoveField {
+                table_index,
+                field_name,
+            } => {
+                for field_name in self.remove_column(&field_name)? {
+                    fw.send(TableOperation {
+                        id: op.id,
+                        op: Operation::RemoveField {
+                            table_index,
+                            field_name,
+                        },
+                        port: DEFAULT_PORT_HANDLE,
+                    });
+                }
+                return Ok(());
+            }
             Operation::BatchInsert { new } => {
-                let records = new
-                    .iter()
-                    .map(|record| self.insert(record))
-                    .collect::<Result<Vec<_>, _>>()?;
+                let mut records = Vec::with_capacity(new.len());
+                for record in new {
+                    records.extend(self.project(record)?);
+                }
+                if records.is_empty() {
+                    return Ok(());
+                }
                 Operation::BatchInsert { new: records }
             }
         };
@@ -112,9 +376,481 @@ impl Processor for ProjectionProcessor {
     }
 
     fn serialize(&mut self, mut object: Object) -> Result<(), BoxedError> {
-        for expr in &self.expressions {
+        for expr in self.expressions.iter().chain(&self.predicate) {
             expr.serialize_state(&mut object)?;
         }
         Ok(())
     }
+
+    fn explain(&self) -> ProcessorExplanation {
+        let expressions = self
+            .expressions
+            .iter()
+            .zip(&self.output_schema.fields)
+            .map(|(expr, field)| {
+                format!("{} AS {}", expr.to_string(&self.input_schema), field.name)
+            })
+            .collect::<Vec<_>>();
+        let mut description = format!("Evaluates {}", expressions.join(", "));
+        if let Some(predicate) = &self.predicate {
+            description += &format!(" where {}", predicate.to_string(&self.input_schema));
+        }
+        ProcessorExplanation {
+            name: "Projection".to_string(),
+            description,
+            input_schema: self.input_schema.clone(),
+            output_schema: self.output_schema.clone(),
+            state_size_bytes: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use crate::schema_evolution::{MigrateOnSchemaChange, StopOnSchemaChange};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct AddColumnHandler;
+
+    impl SchemaEvolutionHandler for AddColumnHandler {
+        fn on_schema_change(&self, _old: &Schema, _new: &Schema) -> SchemaEvolutionAction {
+            SchemaEvolutionAction::Migrate(SchemaMigration::AddColumn {
+                default_value: Field::Int(0),
+            })
+        }
+    }
+
+    fn field(name: &str) -> FieldDefinition {
+        FieldDefinition::new(
+            name.into(),
+            FieldType::Int,
+            false,
+            SourceDefinition::Dynamic,
+        )
+    }
+
+    #[test]
+    fn test_schema_evolution_add_column() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false);
+        let processor = |handler: Arc<dyn SchemaEvolutionHandler>| {
+            ProjectionProcessor::new(
+                schema.clone(),
+                schema.clone(),
+                vec![Expression::Column { index: 0 }],
+                None,
+                ProjectionErrorPolicy::Error,
+                handler,
+                None,
+            )
+            .unwrap()
+        };
+
+        let mut stopping = processor(Arc::new(StopOnSchemaChange));
+        assert!(matches!(
+            stopping.add_column(field("b"), Field::Int(7)),
+            Err(PipelineError::SchemaEvolution { .. })
+        ));
+        assert!(matches!(
+            stopping.remove_column("a"),
+            Err(PipelineError::SchemaEvolution { .. })
+        ));
+
+        let mut processor = processor(Arc::new(AddColumnHandler));
+        processor.add_column(field("b"), Field::Int(7)).unwrap();
+        let mut record = Record::new(vec![Field::Int(1)]);
+        processor.add_missing_columns(&mut record).unwrap();
+        assert_eq!(record.values, vec![Field::Int(1), Field::Int(0)]);
+        assert_eq!(
+            processor
+                .project(Record::new(vec![Field::Int(1)]))
+                .unwrap()
+                .unwrap()
+                .values,
+            vec![Field::Int(1)]
+        );
+    }
+
+    #[test]
+    fn test_remove_then_add_column() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false).field(field("b"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema.clone(),
+            schema,
+            vec![
+                Expression::Column { index: 0 },
+                Expression::Column { index: 1 },
+            ],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+        processor.remove_column("b").unwrap();
+        processor.add_column(field("c"), Field::Int(7)).unwrap();
+
+        let layout = |values| {
+            let mut record = Record::new(values);
+            processor
+                .add_missing_columns(&mut record)
+                .map(|()| record.values)
+        };
+        // Sent after both changes, without b
+        assert_eq!(
+            layout(vec![Field::Int(1), Field::Int(3)]).unwrap(),
+            vec![Field::Int(1), Field::Null, Field::Int(3)]
+        );
+        // Sent between them, without b and c
+        assert_eq!(
+            layout(vec![Field::Int(1)]).unwrap(),
+            vec![Field::Int(1), Field::Null, Field::Int(7)]
+        );
+        // With every column
+        assert_eq!(
+            layout(vec![Field::Int(1), Field::Int(2), Field::Int(3)]).unwrap(),
+            vec![Field::Int(1), Field::Int(2), Field::Int(3)]
+        );
+        assert!(matches!(
+            layout(vec![]),
+            Err(PipelineError::UnexpectedRecordLength(0))
+        ));
+    }
+
+    #[test]
+    fn test_removed_columns_without_added_columns() {
+        let mut schema = Schema::new();
+        schema
+            .field(field("a"), false)
+            .field(field("b"), false)
+            .field(field("c"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema.clone(),
+            schema,
+            vec![Expression::Column { index: 0 }],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+        processor.remove_column("b").unwrap();
+
+        let mut record = Record::new(vec![Field::Int(1), Field::Int(3)]);
+        processor.add_missing_columns(&mut record).unwrap();
+        assert_eq!(
+            record.values,
+            vec![Field::Int(1), Field::Null, Field::Int(3)]
+        );
+
+        // Shorter than any version of the schema
+        assert!(matches!(
+            processor.add_missing_columns(&mut Record::new(vec![Field::Int(1)])),
+            Err(PipelineError::UnexpectedRecordLength(1))
+        ));
+    }
+
+    #[derive(Debug, Default)]
+    struct TestChannelForwarder {
+        operations: Vec<TableOperation>,
+    }
+
+    impl ProcessorChannelForwarder for TestChannelForwarder {
+        fn send(&mut self, op: TableOperation) {
+            self.operations.push(op);
+        }
+    }
+
+    #[test]
+    fn test_add_field_operation() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema.clone(),
+            schema,
+            vec![Expression::Column { index: 1 }],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+
+        let mut fw = TestChannelForwarder::default();
+        let op = |op| TableOperation::without_id(op, DEFAULT_PORT_HANDLE);
+        processor
+            .process(
+                op(Operation::AddField {
+                    table_index: 0,
+                    field: field("b"),
+                    default_value: Field::Int(7),
+                }),
+                &mut fw,
+            )
+            .unwrap();
+        assert!(fw.operations.is_empty());
+        assert_eq!(processor.input_schema.fields.len(), 2);
+
+        let insert = |values| {
+            op(Operation::Insert {
+                new: Record::new(values),
+            })
+        };
+        processor
+            .process(insert(vec![Field::Int(1)]), &mut fw)
+            .unwrap();
+        processor
+            .process(insert(vec![Field::Int(1), Field::Int(2)]), &mut fw)
+            .unwrap();
+        assert_eq!(
+            fw.operations
+                .into_iter()
+                .map(|op| op.op)
+                .collect::<Vec<_>>(),
+            vec![
+                Operation::Insert {
+                    new: Record::new(vec![Field::Int(7)])
+                },
+                Operation::Insert {
+                    new: Record::new(vec![Field::Int(2)])
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_field_operation() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false).field(field("b"), false);
+        let mut output_schema = Schema::new();
+        output_schema
+            .field(field("b"), false)
+            .field(field("a_plus_b"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema,
+            output_schema,
+            vec![
+                Expression::Column { index: 1 },
+                Expression::BinaryOperator {
+                    left: Box::new(Expression::Column { index: 0 }),
+                    operator: dozer_sql_expression::operator::BinaryOperatorType::Add,
+                    right: Box::new(Expression::Column { index: 1 }),
+                },
+            ],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+
+        let mut fw = TestChannelForwarder::default();
+        let op = |op| TableOperation::without_id(op, DEFAULT_PORT_HANDLE);
+        let remove = |name: &str| {
+            op(Operation::RemoveField {
+                table_index: 0,
+                field_name: name.to_string(),
+            })
+        };
+        processor.process(remove("b"), &mut fw).unwrap();
+        assert!(processor.process(remove("b"), &mut fw).is_err());
+        processor
+            .process(
+                op(Operation::Insert {
+                    new: Record::new(vec![Field::Int(1)]),
+                }),
+                &mut fw,
+            )
+            .unwrap();
+        assert_eq!(
+            fw.operations
+                .into_iter()
+                .map(|op| op.op)
+                .collect::<Vec<_>>(),
+            vec![
+                Operation::RemoveField {
+                    table_index: 0,
+                    field_name: "b".to_string()
+                },
+                Operation::Insert {
+                    new: Record::new(vec![Field::Null])
+                },
+            ]
+        );
+        assert_eq!(processor.output_schema.fields, vec![field("a_plus_b")]);
+    }
+
+    #[test]
+    fn test_column_remap() {
+        let column = |index| Expression::Column { index };
+        assert_eq!(
+            column_remap(&[column(1), column(0), column(1)]),
+            Some(vec![(1, false), (0, true), (1, true)])
+        );
+        assert_eq!(
+            column_remap(&[column(0), Expression::Literal(Field::Int(1))]),
+            None
+        );
+
+        let mut schema = Schema::new();
+        schema.field(field("a"), false).field(field("b"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema.clone(),
+            schema,
+            vec![column(1), column(0), column(1)],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            processor
+                .project(Record::new(vec![Field::Int(1), Field::Int(2)]))
+                .unwrap()
+                .unwrap()
+                .values,
+            vec![Field::Int(2), Field::Int(1), Field::Int(2)]
+        );
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false).field(field("b"), false);
+        let divide = || Expression::BinaryOperator {
+            left: Box::new(Expression::Column { index: 0 }),
+            operator: dozer_sql_expression::operator::BinaryOperatorType::Div,
+            right: Box::new(Expression::Column { index: 1 }),
+        };
+        let processor = |error_policy| {
+            ProjectionProcessor::new(
+                schema.clone(),
+                schema.clone(),
+                vec![divide(), Expression::Column { index: 0 }],
+                None,
+                error_policy,
+                Arc::new(MigrateOnSchemaChange),
+                None,
+            )
+            .unwrap()
+        };
+        let record = || Record::new(vec![Field::Int(1), Field::Int(0)]);
+
+        assert!(processor(ProjectionErrorPolicy::Error)
+            .project(record())
+            .is_err());
+        assert_eq!(
+            processor(ProjectionErrorPolicy::Null)
+                .project(record())
+                .unwrap()
+                .unwrap()
+                .values,
+            vec![Field::Null, Field::Int(1)]
+        );
+        assert_eq!(
+            processor(ProjectionErrorPolicy::SkipRecord)
+                .project(record())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_predicate() {
+        let mut schema = Schema::new();
+        schema.field(field("a"), false);
+        let mut processor = ProjectionProcessor::new(
+            schema.clone(),
+            schema,
+            vec![Expression::Column { index: 0 }],
+            Some(Expression::BinaryOperator {
+                left: Box::new(Expression::Column { index: 0 }),
+                operator: dozer_sql_expression::operator::BinaryOperatorType::Gt,
+                right: Box::new(Expression::Literal(Field::Int(1))),
+            }),
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+
+        let mut fw = TestChannelForwarder::default();
+        let record = |value| Record::new(vec![Field::Int(value)]);
+        let update = |old, new| {
+            TableOperation::without_id(
+                Operation::Update {
+                    old: record(old),
+                    new: record(new),
+                },
+                DEFAULT_PORT_HANDLE,
+            )
+        };
+        for op in [
+            TableOperation::without_id(
+                Operation::BatchInsert {
+                    new: vec![record(1), record(2)],
+                },
+                DEFAULT_PORT_HANDLE,
+            ),
+            update(2, 3),
+            update(3, 0),
+            update(0, 4),
+            update(0, 1),
+        ] {
+            processor.process(op, &mut fw).unwrap();
+        }
+        assert_eq!(
+            fw.operations
+                .into_iter()
+                .map(|op| op.op)
+                .collect::<Vec<_>>(),
+            vec![
+                Operation::BatchInsert {
+                    new: vec![record(2)]
+                },
+                Operation::Update {
+                    old: record(2),
+                    new: record(3)
+                },
+                Operation::Delete { old: record(3) },
+                Operation::Insert { new: record(4) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut input_schema = Schema::new();
+        input_schema
+            .field(field("a"), false)
+            .field(field("b"), false);
+        let mut output_schema = Schema::new();
+        output_schema.field(field("total"), false);
+        let processor = ProjectionProcessor::new(
+            input_schema.clone(),
+            output_schema.clone(),
+            vec![Expression::BinaryOperator {
+                left: Box::new(Expression::Column { index: 0 }),
+                operator: dozer_sql_expression::operator::BinaryOperatorType::Add,
+                right: Box::new(Expression::Column { index: 1 }),
+            }],
+            None,
+            ProjectionErrorPolicy::Error,
+            Arc::new(MigrateOnSchemaChange),
+            None,
+        )
+        .unwrap();
+
+        let explanation = processor.explain();
+        assert_eq!(explanation.name, "Projection");
+        assert_eq!(explanation.description, "Evaluates a+b AS total");
+        assert_eq!(explanation.input_schema, input_schema);
+        assert_eq!(explanation.output_schema, output_schema);
+    }
 }
@@ -0,0 +1,53 @@
+use std::collections::{HashMap, HashSet};
+
+use dozer_types::models::flags::ProjectionTypeCapabilities;
+use dozer_types::types::FieldType;
+
+/// The field types the sink fed by a projection can write, and the types the others are
+/// coerced to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeCapabilities {
+    /// Types written as they are.
+    pub supported: HashSet<FieldType>,
+    /// The supported type each unsupported type is cast to.
+    pub coercions: HashMap<FieldType, FieldType>,
+}
+
+impl From<&ProjectionTypeCapabilities> for TypeCapabilities {
+    fn from(config: &ProjectionTypeCapabilities) -> Self {
+        Self {
+            supported: config.supported.iter().copied().collect(),
+            coercions: config
+                .coercions
+                .iter()
+                .map(|coercion| (coercion.from, coercion.to))
+                .collect(),
+        }
+    }
+}
+
+impl TypeCapabilities {
+    pub fn new(supported: impl IntoIterator<Item = FieldType>) -> Self {
+        Self {
+            supported: supported.into_iter().collect(),
+            coercions: HashMap::new(),
+        }
+    }
+
+    /// Casts outputs of type `from` to `to`, which should be supported.
+    pub fn with_coercion(mut self, from: FieldType, to: FieldType) -> Self {
+        self.coercions.insert(from, to);
+        self
+    }
+
+    /// The type outputs of type `typ` are written as, if the sink can write them at all.
+    pub fn output_type(&self, typ: FieldType) -> Option<FieldType> {
+        if self.supported.contains(&typ) {
+            return Some(typ);
+        }
+        self.coercions
+            .get(&typ)
+            .copied()
+            .filter(|to| self.supported.contains(to))
+    }
+}
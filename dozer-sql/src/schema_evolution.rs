@@ -0,0 +1,101 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use dozer_types::models::flags::SchemaChangePolicy;
+use dozer_types::types::{Field, Schema};
+
+/// How a processor should migrate its state when its input schema changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaMigration {
+    /// A column was appended to the input schema. Records that don't have it yet get `default_value` until backfill completes.
+    AddColumn { default_value: Field },
+    /// A column was removed from the input schema. Records read it as null.
+    RemoveColumn,
+}
+
+/// What the pipeline should do when a source table's schema changes mid-run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaEvolutionAction {
+    /// Stop the pipeline with `PipelineError::SchemaEvolution`.
+    Stop,
+    /// Drop the processor state and restart the pipeline with the new schema.
+    DropAndRestart,
+    /// Migrate the processor in place.
+    Migrate(SchemaMigration),
+}
+
+pub trait SchemaEvolutionHandler: Send + Sync + Debug {
+    fn on_schema_change(&self, old: &Schema, new: &Schema) -> SchemaEvolutionAction;
+
+    /// Called instead of `on_schema_change` when the source sent the default value of the column it added.
+    fn on_add_field(
+        &self,
+        old: &Schema,
+        new: &Schema,
+        _source_default: &Field,
+    ) -> SchemaEvolutionAction {
+        self.on_schema_change(old, new)
+    }
+
+    /// The configurable policy this handler implements, if any. Processors with other handlers can't be sent to
+    /// another node.
+    fn policy(&self) -> Option<SchemaChangePolicy> {
+        None
+    }
+}
+
+/// The handler that implements `policy`.
+pub fn schema_evolution_handler(policy: SchemaChangePolicy) -> Arc<dyn SchemaEvolutionHandler> {
+    match policy {
+        SchemaChangePolicy::Migrate => Arc::new(MigrateOnSchemaChange),
+        SchemaChangePolicy::Stop => Arc::new(StopOnSchemaChange),
+    }
+}
+
+/// Migrates in place when a column is appended to or removed from the schema, and stops on other changes. Added
+/// columns default to the value the source sent, or null. This is the behavior when no handler is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MigrateOnSchemaChange;
+
+impl SchemaEvolutionHandler for MigrateOnSchemaChange {
+    fn on_schema_change(&self, old: &Schema, new: &Schema) -> SchemaEvolutionAction {
+        self.on_add_field(old, new, &Field::Null)
+    }
+
+    fn on_add_field(
+        &self,
+        old: &Schema,
+        new: &Schema,
+        source_default: &Field,
+    ) -> SchemaEvolutionAction {
+        if new.fields.len() == old.fields.len() + 1 && new.fields.starts_with(&old.fields) {
+            SchemaEvolutionAction::Migrate(SchemaMigration::AddColumn {
+                default_value: source_default.clone(),
+            })
+        } else if new.fields.len() + 1 == old.fields.len()
+            && new.fields.iter().all(|field| old.fields.contains(field))
+        {
+            SchemaEvolutionAction::Migrate(SchemaMigration::RemoveColumn)
+        } else {
+            SchemaEvolutionAction::Stop
+        }
+    }
+
+    fn policy(&self) -> Option<SchemaChangePolicy> {
+        Some(SchemaChangePolicy::Migrate)
+    }
+}
+
+/// Stops the pipeline on any schema change.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StopOnSchemaChange;
+
+impl SchemaEvolutionHandler for StopOnSchemaChange {
+    fn on_schema_change(&self, _old: &Schema, _new: &Schema) -> SchemaEvolutionAction {
+        SchemaEvolutionAction::Stop
+    }
+
+    fn policy(&self) -> Option<SchemaChangePolicy> {
+        Some(SchemaChangePolicy::Stop)
+    }
+}
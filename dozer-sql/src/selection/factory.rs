@@ -1,13 +1,16 @@
 use std::{collections::HashMap, sync::Arc};
 
 use crate::errors::PipelineError;
+use crate::wire::{parse_expr, SELECTION_TYPE_NAME};
 use dozer_core::{
     node::{PortHandle, Processor, ProcessorFactory},
+    pipeline::WireFormatFactory,
     DEFAULT_PORT_HANDLE,
 };
 use dozer_sql_expression::builder::ExpressionBuilder;
 use dozer_sql_expression::sqlparser::ast::Expr as SqlExpr;
-use dozer_types::{errors::internal::BoxedError, types::Schema};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::{errors::internal::BoxedError, serde_json, types::Schema};
 use dozer_types::{models::udf_config::UdfConfig, tonic::async_trait};
 use tokio::runtime::Runtime;
 
@@ -36,6 +39,25 @@ impl SelectionProcessorFactory {
             runtime,
         }
     }
+
+    /// Rebuilds a factory from the configuration `to_wire` sent.
+    pub(crate) fn from_wire(config: &[u8], runtime: Arc<Runtime>) -> Result<Self, BoxedError> {
+        let config: SelectionWireConfig = serde_json::from_slice(config)?;
+        Ok(Self::new(
+            config.id,
+            parse_expr(&config.statement)?,
+            config.udfs,
+            runtime,
+        ))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct SelectionWireConfig {
+    id: String,
+    statement: String,
+    udfs: Vec<UdfConfig>,
 }
 
 #[async_trait]
@@ -87,4 +109,16 @@ impl ProcessorFactory for SelectionProcessorFactory {
             Err(e) => Err(e.into()),
         }
     }
+
+    fn to_wire(&self) -> Option<WireFormatFactory> {
+        let config = SelectionWireConfig {
+            id: self.id.clone(),
+            statement: self.statement.to_string(),
+            udfs: self.udfs.clone(),
+        };
+        Some(WireFormatFactory {
+            type_name: SELECTION_TYPE_NAME.to_string(),
+            config: serde_json::to_vec(&config).ok()?,
+        })
+    }
 }
@@ -94,6 +94,10 @@ impl Processor for SelectionProcessor {
                     }
                 }
             }
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                return Err(PipelineError::UnsupportedSchemaChange.into())
+            }
             Operation::BatchInsert { new } => {
                 let records = new
                     .into_iter()
@@ -1,2 +1,3 @@
 mod builder_test;
 pub mod utils;
+mod wire_test;
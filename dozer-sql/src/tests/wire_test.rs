@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dozer_core::node::ProcessorFactory;
+use dozer_types::types::{Field, Schema};
+
+use crate::aggregation::factory::AggregationProcessorFactory;
+use crate::schema_evolution::{SchemaEvolutionAction, SchemaEvolutionHandler};
+use crate::selection::factory::SelectionProcessorFactory;
+use crate::tests::utils::{create_test_runtime, get_select};
+use crate::wire::{AGGREGATION_TYPE_NAME, SELECTION_TYPE_NAME};
+
+#[test]
+fn test_aggregation_wire_round_trip() {
+    let runtime = create_test_runtime();
+    let select = get_select(
+        "SELECT country, SUM(amount) AS total FROM users GROUP BY country HAVING SUM(amount) > 10",
+    )
+    .unwrap();
+    let factory = AggregationProcessorFactory::new(
+        "aggregation".to_string(),
+        select.projection,
+        select.group_by,
+        select.having,
+        false,
+        vec![],
+        runtime.clone(),
+    )
+    .with_projection_defaults(HashMap::from([("total".to_string(), Field::Int(0))]));
+
+    let wire = factory.to_wire().unwrap();
+    assert_eq!(wire.type_name, AGGREGATION_TYPE_NAME);
+    let rebuilt = AggregationProcessorFactory::from_wire(&wire.config, runtime).unwrap();
+    assert_eq!(rebuilt.to_wire(), Some(wire));
+}
+
+#[test]
+fn test_selection_wire_round_trip() {
+    let runtime = create_test_runtime();
+    let select = get_select("SELECT * FROM users WHERE amount > 1 AND country = 'SG'").unwrap();
+    let factory = SelectionProcessorFactory::new(
+        "selection".to_string(),
+        select.selection.unwrap(),
+        vec![],
+        runtime.clone(),
+    );
+
+    let wire = factory.to_wire().unwrap();
+    assert_eq!(wire.type_name, SELECTION_TYPE_NAME);
+    let rebuilt = SelectionProcessorFactory::from_wire(&wire.config, runtime).unwrap();
+    assert_eq!(rebuilt.to_wire(), Some(wire));
+}
+
+#[derive(Debug)]
+struct CustomSchemaEvolution;
+
+impl SchemaEvolutionHandler for CustomSchemaEvolution {
+    fn on_schema_change(&self, _old: &Schema, _new: &Schema) -> SchemaEvolutionAction {
+        SchemaEvolutionAction::DropAndRestart
+    }
+}
+
+#[test]
+fn test_custom_schema_evolution_is_not_sent() {
+    let select = get_select("SELECT country FROM users").unwrap();
+    let factory = AggregationProcessorFactory::new(
+        "aggregation".to_string(),
+        select.projection,
+        select.group_by,
+        select.having,
+        false,
+        vec![],
+        create_test_runtime(),
+    )
+    .with_schema_evolution_handler(Arc::new(CustomSchemaEvolution));
+    assert!(factory.to_wire().is_none());
+}
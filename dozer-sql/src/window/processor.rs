@@ -67,6 +67,10 @@ impl Processor for WindowProcessor {
                     fw,
                 )?;
             }
+            Operation::Upsert { .. } => return Err(PipelineError::UnexpectedUpsert.into()),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                return Err(PipelineError::UnsupportedSchemaChange.into())
+            }
             Operation::BatchInsert { new } => {
                 let mut records = vec![];
                 for record in new {
@@ -0,0 +1,71 @@
+//! Constructors that rebuild the SQL processor factories sent to another node with
+//! `dozer_core::pipeline::Pipeline::to_wire_format`.
+//!
+//! The factories' configurations are JSON, with the parts of the query they hold written as SQL.
+
+use std::sync::Arc;
+
+use dozer_core::node::ProcessorFactory;
+use dozer_core::pipeline::FactoryRegistry;
+use dozer_sql_expression::sqlparser::{
+    ast::{Expr, SelectItem},
+    dialect::DozerDialect,
+    parser::{Parser, ParserError},
+};
+use dozer_types::errors::internal::BoxedError;
+use tokio::runtime::Runtime;
+
+use crate::aggregation::factory::AggregationProcessorFactory;
+use crate::product::table::factory::TableProcessorFactory;
+use crate::selection::factory::SelectionProcessorFactory;
+
+pub const AGGREGATION_TYPE_NAME: &str = "Aggregation";
+pub const SELECTION_TYPE_NAME: &str = "Selection";
+pub const TABLE_TYPE_NAME: &str = "Table";
+
+/// Registers the SQL processor factories that can be sent to another node. Their processors run on `runtime`.
+pub fn register_factories(registry: &FactoryRegistry, runtime: Arc<Runtime>) {
+    let aggregation_runtime = runtime.clone();
+    registry.register_processor(
+        AGGREGATION_TYPE_NAME,
+        Box::new(
+            move |config: &[u8]| -> Result<Box<dyn ProcessorFactory>, BoxedError> {
+                Ok(Box::new(AggregationProcessorFactory::from_wire(
+                    config,
+                    aggregation_runtime.clone(),
+                )?))
+            },
+        ),
+    );
+    registry.register_processor(
+        SELECTION_TYPE_NAME,
+        Box::new(
+            move |config: &[u8]| -> Result<Box<dyn ProcessorFactory>, BoxedError> {
+                Ok(Box::new(SelectionProcessorFactory::from_wire(
+                    config,
+                    runtime.clone(),
+                )?))
+            },
+        ),
+    );
+    registry.register_processor(
+        TABLE_TYPE_NAME,
+        Box::new(
+            |config: &[u8]| -> Result<Box<dyn ProcessorFactory>, BoxedError> {
+                Ok(Box::new(TableProcessorFactory::from_wire(config)?))
+            },
+        ),
+    );
+}
+
+pub(crate) fn parse_expr(sql: &str) -> Result<Expr, ParserError> {
+    Parser::new(&DozerDialect {})
+        .try_with_sql(sql)
+        .and_then(|mut parser| parser.parse_expr())
+}
+
+pub(crate) fn parse_select_item(sql: &str) -> Result<SelectItem, ParserError> {
+    Parser::new(&DozerDialect {})
+        .try_with_sql(sql)
+        .and_then(|mut parser| parser.parse_select_item())
+}
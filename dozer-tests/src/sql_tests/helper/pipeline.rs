@@ -247,6 +247,10 @@ impl TestSink {
                     self.update_result(Operation::Insert { new: record })?;
                 }
             }
+            Operation::Upsert { .. } => unreachable!("TestSink doesn't support upserts"),
+            Operation::AddField { .. } | Operation::RemoveField { .. } => {
+                unreachable!("TestSink doesn't support schema changes")
+            }
         }
         Ok(())
     }
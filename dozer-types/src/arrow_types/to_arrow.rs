@@ -134,6 +134,23 @@ pub fn map_record_to_arrow(
                     None as Option<&[u8]>,
                 ])) as ArrayRef
             }
+            (Field::Vector(v), FieldType::Vector(dimension)) => {
+                Arc::new(arrow_array::FixedSizeListArray::from_iter_primitive::<
+                    arrow_types::Float32Type,
+                    _,
+                    _,
+                >(
+                    [Some(v.iter().map(|element| Some(element.0)))],
+                    dimension as i32,
+                )) as ArrayRef
+            }
+            (Field::Null, FieldType::Vector(dimension)) => {
+                Arc::new(arrow_array::FixedSizeListArray::from_iter_primitive::<
+                    arrow_types::Float32Type,
+                    Vec<Option<f32>>,
+                    _,
+                >([None], dimension as i32)) as ArrayRef
+            }
             (a, b) => Err(arrow::error::ArrowError::InvalidArgumentError(format!(
                 "Invalid field type {b:?} for the field: {a:?}",
             )))?,
@@ -165,6 +182,10 @@ pub fn map_field_type(typ: FieldType) -> DataType {
         FieldType::Json => DataType::Utf8,
         FieldType::Point => DataType::Binary,
         FieldType::Duration => DataType::Duration(TimeUnit::Nanosecond),
+        FieldType::Vector(dimension) => DataType::FixedSizeList(
+            Arc::new(arrow_types::Field::new("item", DataType::Float32, true)),
+            dimension as i32,
+        ),
     }
 }
 
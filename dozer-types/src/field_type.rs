@@ -82,6 +82,12 @@ impl From<f64> for Field {
     }
 }
 
+impl From<Vec<f32>> for Field {
+    fn from(value: Vec<f32>) -> Self {
+        Field::Vector(value.into_iter().map(OrderedFloat).collect())
+    }
+}
+
 impl From<Decimal> for Field {
     fn from(value: Decimal) -> Self {
         Field::Decimal(value)
@@ -84,7 +84,7 @@ pub mod generated {
 
 pub mod conversions {
     use super::types::{value, DurationType, PointType, RustDecimal, Type, Value};
-    use crate::json_types::json_value_to_prost;
+    use crate::json_types::{field_to_json_value, json_value_to_prost};
     use crate::ordered_float::OrderedFloat;
     use crate::rust_decimal::Decimal;
     use crate::types::{DozerDuration, Field, FieldType, DATE_FORMAT};
@@ -164,6 +164,11 @@ pub mod conversions {
             },
             Field::Point(point) => map_x_y_to_prost_coord_map(point.0.x_y()),
             Field::Duration(d) => map_duration_to_prost_coord_map(d),
+            Field::Vector(v) => Value {
+                value: Some(value::Value::JsonValue(json_value_to_prost(
+                    field_to_json_value(Field::Vector(v)),
+                ))),
+            },
         }
     }
 
@@ -197,6 +202,7 @@ pub mod conversions {
             FieldType::Date => Type::String,
             FieldType::Point => Type::Point,
             FieldType::Duration => Type::Duration,
+            FieldType::Vector(_) => Type::Json,
         }
     }
     pub fn map_schema(schema: crate::types::Schema) -> crate::grpc_types::types::Schema {
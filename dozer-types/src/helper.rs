@@ -135,6 +135,21 @@ pub fn json_value_to_field(
                     .into(),
             )),
         },
+        FieldType::Vector(dimension) => serde_json::from_value::<Vec<OrderedFloat<f32>>>(value)
+            .map_err(DeserializationError::Json)
+            .and_then(|vector| {
+                if vector.len() == dimension {
+                    Ok(Field::Vector(vector))
+                } else {
+                    Err(DeserializationError::Custom(
+                        format!(
+                            "Expected a vector of {dimension} elements, got {}",
+                            vector.len()
+                        )
+                        .into(),
+                    ))
+                }
+            }),
     }
     .map_err(TypeError::DeserializationError)
 }
@@ -301,6 +316,21 @@ impl Field {
                     value.parse::<DozerDuration>().map(Field::Duration)
                 }
             }
+            FieldType::Vector(dimension) => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    serde_json::from_str::<Vec<OrderedFloat<f32>>>(value)
+                        .ok()
+                        .filter(|vector| vector.len() == dimension)
+                        .map(Field::Vector)
+                        .ok_or_else(|| TypeError::InvalidFieldValue {
+                            field_type: typ,
+                            nullable,
+                            value: value.to_string(),
+                        })
+                }
+            }
         }
     }
 }
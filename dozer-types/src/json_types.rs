@@ -126,6 +126,11 @@ pub fn field_to_json_value(field: Field) -> JsonValue {
         Field::Json(b) => b,
         Field::Point(point) => convert_x_y_to_object(point.0.x_y()),
         Field::Duration(d) => convert_duration_to_object(d),
+        Field::Vector(v) => v
+            .into_iter()
+            .map(|element| f64::from(element.0))
+            .collect::<IArray>()
+            .into(),
         Field::Null => JsonValue::NULL,
     }
 }
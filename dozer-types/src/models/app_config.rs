@@ -41,6 +41,14 @@ pub struct AppConfig {
     #[serde(default, skip_serializing_if = "equal_default")]
     /// The record store to use for the processors.
     pub record_store: RecordStore,
+
+    /// Sinks that fall further behind than this many seconds are reported with a warning.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sink_lag_alert_threshold_secs: Option<u64>,
+
+    /// How long, in seconds, source schemas fetched from a connection are reused for before the connection is asked again. Reused until the process exits if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_cache_ttl_secs: Option<u64>,
 }
 
 #[derive(Debug, JsonSchema, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
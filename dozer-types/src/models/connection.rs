@@ -1,9 +1,11 @@
 use crate::models::ingestion_types::{
-    ConfigSchemas, DeltaLakeConfig, EthConfig, GrpcConfig, JavaScriptConfig, KafkaConfig,
-    LocalStorage, MongodbConfig, MySQLConfig, S3Storage, SnowflakeConfig, WebhookConfig, SECRET,
+    ConfigSchemas, DeltaLakeConfig, EthConfig, GenerateSeriesConfig, GrpcConfig, JavaScriptConfig,
+    KafkaConfig, LocalStorage, MongodbConfig, MySQLConfig, OverflowPolicy, S3Storage,
+    SnowflakeConfig, WebhookConfig, SECRET,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use crate::errors::types::DeserializationError;
@@ -211,6 +213,8 @@ fn get_sslmode(mode: String) -> Result<SslMode, DeserializationError> {
 pub struct AerospikeConnection {
     pub hosts: String,
     pub namespace: String,
+    /// Sets to ingest. A set can be written as `namespace.set` to read it from a namespace
+    /// other than `namespace`.
     pub sets: Vec<String>,
     #[serde(default)]
     pub batching: bool,
@@ -218,6 +222,64 @@ pub struct AerospikeConnection {
     pub replication: ReplicationSettings,
     #[serde(default)]
     pub schemas: Option<ConfigSchemas>,
+    /// Compare the bins of the first event received for each set against the table's columns,
+    /// and reject the event if bins are unexpected or missing.
+    #[serde(default)]
+    pub strict_schema: bool,
+    /// Match bin names against columns ignoring case, so that `MyBin` fills the `mybin` column.
+    /// Columns of a set that only differ by case are rejected in this mode.
+    #[serde(default)]
+    pub case_insensitive_bins: bool,
+    /// Add a nullable column to a set's table when an event has a bin that isn't a column, instead
+    /// of dropping the bin. `strict_schema` is ignored in this mode.
+    #[serde(default)]
+    pub dynamic_schema: bool,
+    /// Remove a column from a set's table once this many consecutive events didn't have its bin.
+    /// Removed columns stay removed, even if their bin comes back. Columns are never removed if
+    /// unset.
+    #[serde(default)]
+    pub remove_missing_bins_after: Option<u32>,
+    /// Maximum number of connections the sink client keeps open to each node. Defaults to the
+    /// client library's 100. Each sink thread holds at most one connection to a node at a time,
+    /// so this should be at least the sink's `n_threads`.
+    #[serde(default)]
+    pub max_conns_per_node: Option<u32>,
+    /// Number of connections the sink client opens to each node up front and keeps open while
+    /// idle. Defaults to 0. Setting it to `n_threads` avoids connecting on the first writes.
+    #[serde(default)]
+    pub min_conns_per_node: Option<u32>,
+    /// Alphabet of the base64 encoded blob bins.
+    #[serde(default)]
+    pub base64_alphabet: Base64Alphabet,
+    /// Bins that events must have, by set, written as in `sets`. Events that lack one of their
+    /// set's required bins, or have it set to null, are skipped.
+    #[serde(default)]
+    pub required_bins: BTreeMap<String, Vec<String>>,
+    /// Don't send the snapshotting started and done markers when the connector starts. No
+    /// snapshot is taken, so the markers don't bracket any records and can be skipped when
+    /// only running CDC.
+    #[serde(default)]
+    pub skip_snapshotting_markers: bool,
+    /// Skip events whose last update time, in milliseconds since the unix epoch, is lower than
+    /// this. Bounds replays without filtering on the XDR side.
+    #[serde(default)]
+    pub min_lut: Option<u64>,
+    /// Skip events whose record generation is lower than this. Events without a generation are
+    /// always ingested.
+    #[serde(default)]
+    pub min_generation: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum Base64Alphabet {
+    /// `+` and `/`, as in RFC 4648 section 4.
+    #[default]
+    Standard,
+    /// `-` and `_`, as in RFC 4648 section 5.
+    UrlSafe,
+    /// Try the standard alphabet, then the URL-safe one.
+    Auto,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone, Hash)]
@@ -226,6 +288,45 @@ pub struct ReplicationSettings {
     pub server_address: String,
     #[serde(default = "default_server_port")]
     pub server_port: u32,
+    /// Serve the replication endpoint over HTTPS. Plain HTTP is used if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<ReplicationTlsConfig>,
+    /// Keep the connections of the XDR push client alive with TCP keepalives.
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive: bool,
+    /// Maximum number of idle connections the XDR push client keeps open to each host.
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// How long the XDR push client waits for a connection to be established.
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// How the bodies of the requests to the replication endpoint are encoded.
+    #[serde(default)]
+    pub request_format: RequestFormat,
+    /// Answer failed requests to the replication endpoint with a JSON body describing the error,
+    /// including the key of the event. The body is empty if not set, so internals aren't exposed.
+    #[serde(default)]
+    pub verbose_errors: bool,
+    /// What to do with events when the pipeline can't keep up with them. Blocks the request by default.
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum RequestFormat {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicationTlsConfig {
+    /// Path to the PEM encoded certificate chain.
+    pub cert_path: String,
+    /// Path to the PEM encoded private key.
+    pub key_path: String,
 }
 
 fn default_server_address() -> String {
@@ -236,11 +337,30 @@ fn default_server_port() -> u32 {
     5929
 }
 
+fn default_keep_alive() -> bool {
+    true
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    16
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    5000
+}
+
 impl Default for ReplicationSettings {
     fn default() -> Self {
         ReplicationSettings {
             server_address: default_server_address(),
             server_port: default_server_port(),
+            tls: None,
+            keep_alive: default_keep_alive(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            connect_timeout_ms: default_connect_timeout_ms(),
+            request_format: Default::default(),
+            verbose_errors: false,
+            overflow_policy: Default::default(),
         }
     }
 }
@@ -286,6 +406,9 @@ pub enum ConnectionConfig {
 
     Oracle(OracleConfig),
     Aerospike(AerospikeConnection),
+
+    /// In yaml, present as tag: `!GenerateSeries`
+    GenerateSeries(GenerateSeriesConfig),
 }
 
 impl ConnectionConfig {
@@ -305,6 +428,7 @@ impl ConnectionConfig {
             ConnectionConfig::Webhook(_) => "webhook".to_string(),
             ConnectionConfig::Oracle(_) => "oracle".to_string(),
             ConnectionConfig::Aerospike(_) => "aerospike".to_string(),
+            ConnectionConfig::GenerateSeries(_) => "generate_series".to_string(),
         }
     }
 }
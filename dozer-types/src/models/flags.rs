@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::types::{Field, FieldType};
+
 use super::equal_default;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Clone, Default)]
@@ -28,6 +32,18 @@ pub struct Flags {
 
     /// app checkpoints can be used to resume execution of a query.; Default: false
     pub enable_app_checkpoints: Option<bool>,
+
+    /// what to do with a record when a projected expression fails to evaluate on it.; Default: error
+    #[serde(default, skip_serializing_if = "equal_default")]
+    pub on_projection_error: ProjectionErrorPolicy,
+
+    /// what projections do when a source adds or removes a column mid-run.; Default: migrate
+    #[serde(default, skip_serializing_if = "equal_default")]
+    pub on_schema_change: SchemaChangePolicy,
+
+    /// options of the projections that write output tables.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projections: Vec<ProjectionOptions>,
 }
 
 pub fn default_dynamic() -> bool {
@@ -50,6 +66,80 @@ pub struct EnableProbabilisticOptimizations {
     pub in_aggregations: Option<bool>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ProjectionErrorPolicy {
+    /// Fail the pipeline.
+    #[default]
+    Error,
+    /// Output null for the column whose expression failed.
+    Null,
+    /// Drop the record.
+    SkipRecord,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum SchemaChangePolicy {
+    /// Migrate in place. Older records read an added column as the default the source sent for it, and a removed
+    /// column as null.
+    #[default]
+    Migrate,
+    /// Fail the pipeline.
+    Stop,
+}
+
+/// Options of the plain projection (a query without aggregation) that writes an output table.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectionOptions {
+    /// the output table written by the projection.
+    pub table_name: String,
+
+    /// values that replace nulls in the output columns of the same name, like `{ Int: 0 }`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, serde_json::Value>")]
+    pub defaults: HashMap<String, Field>,
+
+    /// a SQL expression the records must be true for to be written, like a `WHERE` clause.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<String>,
+
+    /// the names of the output columns in the order they are written, if it isn't the select order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_order: Vec<String>,
+
+    /// the types the sink can write. Outputs of other types are cast or fail the pipeline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_capabilities: Option<ProjectionTypeCapabilities>,
+}
+
+/// The field types the sink fed by a projection can write, and the types the others are cast to.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectionTypeCapabilities {
+    /// types written as they are, like `Int` or `String`.
+    #[schemars(with = "Vec<String>")]
+    pub supported: Vec<FieldType>,
+
+    /// the supported type each unsupported type is cast to, like `{ from: Decimal, to: String }`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coercions: Vec<ProjectionTypeCoercion>,
+}
+
+/// A cast of outputs of an unsupported type to a supported one.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectionTypeCoercion {
+    /// the unsupported type.
+    #[schemars(with = "String")]
+    pub from: FieldType,
+
+    /// the supported type it's cast to.
+    #[schemars(with = "String")]
+    pub to: FieldType,
+}
+
 pub fn default_push_events() -> bool {
     true
 }
@@ -8,7 +8,7 @@ use crate::{
     helper::{deserialize_duration_secs_f64, f64_schema, serialize_duration_secs_f64},
     models::connection::SchemaExample,
     node::OpIdentifier,
-    types::Operation,
+    types::{FieldDefinition, Operation},
 };
 
 use super::equal_default;
@@ -28,6 +28,12 @@ pub enum IngestionMessage {
         id: Option<OpIdentifier>,
     },
     TransactionInfo(TransactionInfo),
+    /// A nullable column was appended to a table's schema. Records of the table sent after this message include it.
+    ColumnAdded {
+        /// Index of the table that the column was added to.
+        table_index: usize,
+        column: FieldDefinition,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -43,6 +49,28 @@ pub enum TransactionInfo {
     SnapshottingDone { id: Option<OpIdentifier> },
 }
 
+/// What a connector does with a message when the pipeline is too far behind to take it.
+///
+/// Only operations are dropped. Commits, schema changes and other messages always wait for room.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Hash, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OverflowPolicy {
+    #[serde(default)]
+    pub on_full: OverflowAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Hash, JsonSchema, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum OverflowAction {
+    /// Wait until the pipeline takes the message.
+    #[default]
+    Block,
+    /// Drop the oldest operation waiting for the pipeline to make room for the new message.
+    DropOldest,
+    /// Drop the newest operation, which is the new message if it is an operation.
+    DropNewest,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
 pub struct EthFilter {
     // Starting block
@@ -553,6 +581,30 @@ pub fn default_bootstrap_path() -> String {
     String::from("src/js/bootstrap.js")
 }
 
+/// A series of values from `start` to `stop`, both inclusive, ingested as a `generate_series`
+/// table with a single `value` column.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum GenerateSeriesConfig {
+    Integer {
+        start: i64,
+        stop: i64,
+        step: i64,
+    },
+    /// Dates in `YYYY-MM-DD` format.
+    Date {
+        start: String,
+        stop: String,
+        step_days: i64,
+    },
+    /// Timestamps in RFC 3339 format.
+    Timestamp {
+        start: String,
+        stop: String,
+        step_millis: i64,
+    },
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
 #[schemars(example = "Self::example")]
 pub struct WebhookConfig {
@@ -1,4 +1,5 @@
-use std::num::NonZeroUsize;
+use std::collections::HashMap;
+use std::num::{NonZeroU32, NonZeroUsize};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -109,6 +110,7 @@ pub enum SinkConfig {
     Aerospike(AerospikeSinkConfig),
     Clickhouse(ClickhouseSinkConfig),
     Oracle(OracleSinkConfig),
+    Qdrant(QdrantSinkConfig),
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
@@ -131,6 +133,49 @@ pub struct AerospikeDenormalizations {
     pub from_set: String,
     pub key: String,
     pub columns: Vec<DenormColumn>,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// Also copy every bin of the source record, under its own name.
+    pub all_bins: bool,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What to do when a bin copied by `all_bins` has the name of a bin the record already has.
+    pub on_bin_conflict: AerospikeBinConflictPolicy,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// Check that the source record didn't change while the record was being written, and
+    /// enrich the record again if it did. Costs an extra read of the source record per write.
+    pub check_generation: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Fail the sink once this many lookups in a row found no source record, which happens when
+    /// `from_namespace` or `from_set` are wrong. Lookups that miss are retried every 100ms, and each
+    /// retry counts, so this should cover how far the source set can lag behind. Unset means
+    /// lookups are retried forever.
+    pub max_consecutive_misses: Option<NonZeroU32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Write the copied bins as the entries of a map in this bin, instead of as bins of their own.
+    /// `on_bin_conflict` doesn't apply then, as the copied bins can't conflict with the record's.
+    pub nest_under: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeBinConflictPolicy {
+    /// Fail the record.
+    #[default]
+    Error,
+    /// Keep the record's own bin.
+    KeepRecord,
+    /// Replace the record's bin with the copied one.
+    Overwrite,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AerospikePointRegion {
+    /// The `Point` column the region is centered on.
+    pub column: String,
+    /// The bin the region is written to.
+    pub bin: String,
+    /// Radius of the region, in meters.
+    pub radius_meters: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
@@ -141,6 +186,127 @@ pub struct AerospikeSinkTable {
     pub set_name: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub denormalize: Vec<AerospikeDenormalizations>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Write a GeoJSON circle around a point column to a companion bin, so it can be used in geospatial `within` queries.
+    pub point_regions: Vec<AerospikePointRegion>,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What an insert does when the record already exists.
+    pub on_conflict: AerospikeConflictPolicy,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What to do with a record when one of its fields can't be converted to an Aerospike value.
+    pub on_convert_error: AerospikeConvertErrorPolicy,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// How Timestamp, Date and Duration primary keys are written as Aerospike keys.
+    pub key_format: AerospikeKeyFormat,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// How the key digests, which decide the partition a record is stored in, are computed.
+    pub key_digest: AerospikeKeyDigest,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Duration columns written as an integer bin instead of an ISO 8601 string.
+    pub integer_durations: Vec<AerospikeDurationBin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Bin names of columns, by column name. Bin names can be at most 15 characters long. Without a mapping, longer column names are truncated.
+    pub bin_name_mapping: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Json columns whose objects are written as ordered maps, for efficient map range operations. Other maps are unordered.
+    pub ordered_maps: Vec<AerospikeOrderedMapBin>,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// Compare updated records to the ones stored in Aerospike instead of the old records of the updates, so that only the bins that differ are written. Costs an extra read per update.
+    pub read_before_write: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AerospikeDurationBin {
+    /// The `Duration` column.
+    pub column: String,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// The unit the duration's length is written in.
+    pub unit: AerospikeDurationUnit,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeDurationUnit {
+    /// Total nanoseconds. Durations longer than about 292 years don't fit.
+    #[default]
+    Nanoseconds,
+    /// Total milliseconds, truncated.
+    Milliseconds,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AerospikeOrderedMapBin {
+    /// The `Json` column. Every object in its values is written as a map with this order.
+    pub column: String,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What the maps are ordered by.
+    pub order: AerospikeMapOrder,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeMapOrder {
+    /// Ordered by key.
+    #[default]
+    KeyOrdered,
+    /// Ordered by key, with an index ordered by value.
+    KeyValueOrdered,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeConflictPolicy {
+    /// Fail the insert, as decided by the sink's `exists_policy`.
+    #[default]
+    FailOnExist,
+    /// Write the record whether or not it exists.
+    Upsert,
+    /// Leave the existing record as it is. Batch inserts into the table are written one record at a time.
+    SkipOnExist,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeConvertErrorPolicy {
+    /// Fail the record.
+    #[default]
+    Error,
+    /// Drop the record and continue.
+    Skip,
+    /// Write nil for the offending field and keep the rest of the record.
+    NullField,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeKeyFormat {
+    /// RFC 3339 strings. Durations are written as `PT{seconds},{nanoseconds}S`.
+    #[default]
+    Rfc3339,
+    /// Integer milliseconds since the Unix epoch. Dates are taken at midnight UTC and durations
+    /// are written as their length.
+    EpochMillis,
+    /// Integer seconds since the Unix epoch. Dates are taken at midnight UTC and durations
+    /// are written as their length.
+    EpochSeconds,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeKeyDigest {
+    /// Aerospike's digest of the set name and key.
+    #[default]
+    Default,
+    /// Advanced. Store each record in the same partition as the record it is denormalized
+    /// from, so the lookup for that denormalization doesn't have to leave the node. `from_set`
+    /// names the denormalization, which must be from the table's own namespace.
+    ///
+    /// The digest is no longer the one Aerospike computes from the key, so other clients can't
+    /// read these records by key, and all records sharing a denormalization source end up in
+    /// the same partition, which can unbalance the cluster.
+    ColocateWith { from_set: String },
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone)]
@@ -148,8 +314,56 @@ pub struct AerospikeSinkTable {
 pub struct AerospikeSinkConfig {
     pub connection: String,
     pub n_threads: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Number of threads used when `n_threads` is unset and the number of CPUs available can't be determined; Default: 1
+    pub default_threads: Option<NonZeroUsize>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tables: Vec<AerospikeSinkTable>,
+    #[serde(default = "default_aerospike_max_batch_size")]
+    /// Batch inserts with more records are written in several batches; Default: 5000
+    pub max_batch_size: NonZeroUsize,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// Read back a sample of the records of every written batch, and report the ones that are missing
+    pub verify_writes: bool,
+    #[serde(default = "default_aerospike_verify_sample_percent")]
+    /// Percentage of the written records that are read back when `verify_writes` is set; Default: 1
+    pub verify_sample_percent: u8,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// How writes treat records that do or don't exist, for all tables. Deletes aren't affected.
+    pub exists_policy: AerospikeExistsPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Number of keys per table whose last written record's checksum is kept, so that records that didn't change aren't written again. Tables with denormalizations always write every record. Disabled if unset
+    pub checksum_cache_size: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Maximum number of bytes of strings converted from a record's fields, like decimals and timestamps, before `on_oversize` applies to it. Unlimited if unset
+    pub max_record_string_bytes: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What to do with a record whose converted strings exceed `max_record_string_bytes`.
+    pub on_oversize: AerospikeOversizePolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeOversizePolicy {
+    /// Fail the record.
+    #[default]
+    Error,
+    /// Drop the record and continue.
+    Skip,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum AerospikeExistsPolicy {
+    /// Create records on insert and update existing records on update, failing if they respectively exist or don't.
+    #[default]
+    Operation,
+    /// Write records whether or not they exist, keeping the bins that aren't written.
+    Ignore,
+    /// Replace existing records, removing the bins that aren't written. Fails if a record doesn't exist.
+    Replace,
+    /// Create records or replace existing ones, removing the bins that aren't written.
+    CreateOrReplace,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone)]
@@ -183,6 +397,47 @@ pub struct OracleSinkConfig {
     pub table_name: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct QdrantSinkConfig {
+    pub url: String,
+    pub collection_name: String,
+    pub source_table_name: String,
+    /// The vector column. It's written as a named vector of the same name.
+    pub vector_column: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Columns written to the payload of each point.
+    pub payload_columns: Vec<String>,
+    #[serde(default = "default_qdrant_batch_size")]
+    /// Number of inserted points to buffer before they're upserted; Default: 100
+    pub batch_size: NonZeroUsize,
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// What to do when the collection doesn't have as many points as expected on persist.
+    pub on_points_count_mismatch: QdrantPointsCountMismatchPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum QdrantPointsCountMismatchPolicy {
+    /// Fail the persist.
+    #[default]
+    Error,
+    /// Log a warning and expect the actual count from then on. Use this when points may be upserted again, for example when a source is snapshotted again.
+    Warn,
+}
+
+pub fn default_aerospike_max_batch_size() -> NonZeroUsize {
+    NonZeroUsize::new(5000).unwrap()
+}
+
+pub fn default_aerospike_verify_sample_percent() -> u8 {
+    1
+}
+
+pub fn default_qdrant_batch_size() -> NonZeroUsize {
+    NonZeroUsize::new(100).unwrap()
+}
+
 pub fn default_log_reader_batch_size() -> u32 {
     1000
 }
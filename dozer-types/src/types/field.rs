@@ -36,6 +36,10 @@ pub enum Field {
     Json(#[cfg_attr(feature= "arbitrary", arbitrary(with = arb_json::arbitrary_json))] JsonValue),
     Point(DozerPoint),
     Duration(DozerDuration),
+    Vector(
+        #[cfg_attr(feature= "arbitrary", arbitrary(with = arbitrary_vector))]
+        Vec<OrderedFloat<f32>>,
+    ),
     Null,
 }
 
@@ -75,9 +79,15 @@ impl bincode::Decode for Field {
             13 => Ok(Field::Point(DozerPoint::decode(decoder)?)),
             14 => Ok(Field::Duration(DozerDuration::decode(decoder)?)),
             15 => Ok(Field::Null),
+            16 => Ok(Field::Vector(
+                Vec::<f32>::decode(decoder)?
+                    .into_iter()
+                    .map(OrderedFloat)
+                    .collect(),
+            )),
             other => Err(bincode::error::DecodeError::UnexpectedVariant {
                 type_name: "Field",
-                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 15 },
+                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 16 },
                 found: other,
             }),
         }
@@ -120,9 +130,15 @@ impl<'de> bincode::BorrowDecode<'de> for Field {
             13 => Ok(Field::Point(DozerPoint::borrow_decode(decoder)?)),
             14 => Ok(Field::Duration(DozerDuration::borrow_decode(decoder)?)),
             15 => Ok(Field::Null),
+            16 => Ok(Field::Vector(
+                Vec::<f32>::borrow_decode(decoder)?
+                    .into_iter()
+                    .map(OrderedFloat)
+                    .collect(),
+            )),
             other => Err(bincode::error::DecodeError::UnexpectedVariant {
                 type_name: "Field",
-                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 15 },
+                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 16 },
                 found: other,
             }),
         }
@@ -155,6 +171,11 @@ impl bincode::Encode for Field {
             }
             Field::Point(v) => v.encode(encoder),
             Field::Duration(v) => v.encode(encoder),
+            // Same layout as a `Vec<f32>`
+            Field::Vector(v) => {
+                (v.len() as u64).encode(encoder)?;
+                v.iter().try_for_each(|element| element.0.encode(encoder))
+            }
             Field::Null => Ok(()),
         }
     }
@@ -178,6 +199,7 @@ impl Ord for Field {
             (Self::Json(l), Self::Json(r)) => json_cmp(l, r),
             (Self::Point(l), Self::Point(r)) => l.cmp(r),
             (Self::Duration(l), Self::Duration(r)) => l.cmp(r),
+            (Self::Vector(l), Self::Vector(r)) => l.cmp(r),
             (Self::Null, Self::Null) => std::cmp::Ordering::Equal,
             (Self::Null, _) => std::cmp::Ordering::Greater,
             (_, Self::Null) => std::cmp::Ordering::Less,
@@ -198,6 +220,16 @@ pub(crate) fn arbitrary_float(
     Ok(OrderedFloat(arbitrary.arbitrary()?))
 }
 
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_vector(
+    arbitrary: &mut arbitrary::Unstructured,
+) -> arbitrary::Result<Vec<OrderedFloat<f32>>> {
+    arbitrary
+        .arbitrary_iter::<f32>()?
+        .map(|element| element.map(OrderedFloat))
+        .collect()
+}
+
 #[cfg(feature = "arbitrary")]
 mod arb_json {
     use arbitrary::Arbitrary;
@@ -268,6 +300,7 @@ impl Field {
             Field::Json(b) => json_to_bytes_size(b),
             Field::Point(_p) => 16,
             Field::Duration(_) => 17,
+            Field::Vector(v) => v.len() * 4,
             Field::Null => 0,
         }
     }
@@ -289,6 +322,7 @@ impl Field {
             Field::Json(b) => Cow::Owned(json_to_bytes(b)),
             Field::Point(p) => Cow::Owned(p.to_bytes().into()),
             Field::Duration(d) => Cow::Owned(d.to_bytes().into()),
+            Field::Vector(v) => Cow::Owned(v.iter().flat_map(|f| f.to_be_bytes()).collect()),
             Field::Null => Cow::Owned([].into()),
         }
     }
@@ -370,6 +404,16 @@ impl Field {
                 DozerDuration::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
             )),
             15 => Ok(Field::Null),
+            16 => {
+                if val.len() % 4 != 0 {
+                    return Err(DeserializationError::BadDataLength);
+                }
+                Ok(Field::Vector(
+                    val.chunks_exact(4)
+                        .map(|chunk| OrderedFloat(f32::from_be_bytes(chunk.try_into().unwrap())))
+                        .collect(),
+                ))
+            }
             other => Err(DeserializationError::UnrecognisedFieldType(other)),
         }
     }
@@ -392,6 +436,7 @@ impl Field {
             Field::Point(_) => 13,
             Field::Duration(_) => 14,
             Field::Null => 15,
+            Field::Vector(_) => 16,
         }
     }
 
@@ -412,6 +457,7 @@ impl Field {
             Field::Json(_) => Some(FieldType::Json),
             Field::Point(_) => Some(FieldType::Point),
             Field::Duration(_) => Some(FieldType::Duration),
+            Field::Vector(v) => Some(FieldType::Vector(v.len())),
             Field::Null => None,
         }
     }
@@ -534,6 +580,13 @@ impl Field {
         }
     }
 
+    pub fn as_vector(&self) -> Option<&[OrderedFloat<f32>]> {
+        match self {
+            Field::Vector(v) => Some(v),
+            _ => None,
+        }
+    }
+
     pub fn as_duration(&self) -> Option<DozerDuration> {
         match self {
             Field::UInt(d) => Some(DozerDuration(
@@ -820,6 +873,10 @@ impl Display for Field {
                 write!(f, "POINT({}, {})", x.0, y.0)
             }
             Field::Duration(d) => write!(f, "{:?}", d.0),
+            Field::Vector(v) => {
+                let elements: Vec<_> = v.iter().map(|element| element.0.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
             Field::Null => write!(f, ""),
         }
     }
@@ -874,13 +931,26 @@ pub enum FieldType {
     Point,
     /// Duration up to nanoseconds.
     Duration,
+    /// Fixed-length vector of 32-bit floats, such as an embedding. Holds the dimension.
+    Vector(usize),
 }
 
 impl TryFrom<&str> for FieldType {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let res = match value.to_lowercase().as_str() {
+        let lowercase = value.to_lowercase();
+        if let Some(dimension) = lowercase
+            .strip_prefix("vector(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return dimension
+                .trim()
+                .parse()
+                .map(FieldType::Vector)
+                .map_err(|_| format!("Unsupported '{value}' type"));
+        }
+        let res = match lowercase.as_str() {
             "uint" => FieldType::UInt,
             "u128" => FieldType::U128,
             "int" => FieldType::Int,
@@ -924,6 +994,7 @@ impl Display for FieldType {
             FieldType::Json => f.write_str("json"),
             FieldType::Point => f.write_str("point"),
             FieldType::Duration => f.write_str("duration"),
+            FieldType::Vector(dimension) => write!(f, "vector({dimension})"),
         }
     }
 }
@@ -964,13 +1035,17 @@ pub fn field_test_cases() -> impl Iterator<Item = Field> {
             ]
             .into(),
         ),
+        Field::Vector(vec![]),
+        Field::Vector(vec![OrderedFloat(1.0), OrderedFloat(-0.5)]),
         Field::Null,
     ]
     .into_iter()
 }
 
 pub fn arrow_field_test_cases() -> impl Iterator<Item = Field> {
-    field_test_cases().filter(|case| !case.is_u128() && !case.is_i128() && !case.is_decimal())
+    field_test_cases().filter(|case| {
+        !case.is_u128() && !case.is_i128() && !case.is_decimal() && case.as_vector().is_none()
+    })
 }
 
 pub fn arrow_field_test_cases_schema() -> Schema {
@@ -1193,6 +1268,11 @@ impl pyo3::ToPyObject for Field {
             Field::Json(_val) => todo!(),
             Field::Point(_val) => todo!(),
             Field::Duration(_d) => todo!(),
+            Field::Vector(val) => val
+                .iter()
+                .map(|element| element.0)
+                .collect::<Vec<_>>()
+                .to_object(py),
             Field::Null => unreachable!(),
         }
     }
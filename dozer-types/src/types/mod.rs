@@ -4,7 +4,7 @@ use ordered_float::OrderedFloat;
 use std::array::TryFromSliceError;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 use crate::errors::types::TypeError;
@@ -46,7 +46,18 @@ pub enum SourceDefinition {
     Dynamic,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    bincode::Encode,
+    bincode::Decode,
+)]
 pub struct FieldDefinition {
     pub name: String,
     pub typ: FieldType,
@@ -113,6 +124,22 @@ impl Schema {
         }
     }
 
+    /// Returns the primary key fields, in `primary_index` order.
+    pub fn primary_key_fields(&self) -> Vec<&FieldDefinition> {
+        self.primary_index
+            .iter()
+            .map(|index| &self.fields[*index])
+            .collect()
+    }
+
+    /// Returns the names of the primary key fields, in `primary_index` order.
+    pub fn primary_key_field_names(&self) -> Vec<&str> {
+        self.primary_key_fields()
+            .into_iter()
+            .map(|field| field.name.as_str())
+            .collect()
+    }
+
     pub fn print(&self) -> Table {
         let mut table = Table::new();
         table.add_row(row!["Field", "Type", "Nullable", "PK"]);
@@ -183,6 +210,27 @@ pub struct Record {
     pub lifetime: Option<Lifetime>,
 }
 
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
 impl Record {
     pub fn new(values: Vec<Field>) -> Record {
         Record {
@@ -191,6 +239,34 @@ impl Record {
         }
     }
 
+    /// Returns an FNV-1a hash of the record's non-null fields and their names, for telling
+    /// whether a record changed. It isn't stable across Dozer versions.
+    pub fn checksum(&self, schema: &Schema) -> u64 {
+        let mut hasher = Fnv1aHasher::default();
+        for (field, value) in schema.fields.iter().zip(&self.values) {
+            if *value != Field::Null {
+                field.name.hash(&mut hasher);
+                value.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// The fields that differ between `a` and `b`, two versions of a record of `schema`.
+    pub fn diff(a: &Record, b: &Record, schema: &Schema) -> RecordDiff {
+        debug_assert_eq!(a.values.len(), b.values.len());
+        debug_assert!(a.values.len() >= schema.fields.len());
+        let changed_fields = a
+            .values
+            .iter()
+            .zip(&b.values)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, new))| (index, old.clone(), new.clone()))
+            .collect();
+        RecordDiff { changed_fields }
+    }
+
     pub fn nulls_from_schema(schema: &Schema) -> Record {
         Self::nulls(schema.fields.len())
     }
@@ -272,6 +348,26 @@ impl Record {
     }
 }
 
+/// The fields that changed between two versions of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct RecordDiff {
+    /// `(field_index, old_value, new_value)` of every changed field, by ascending index.
+    pub changed_fields: Vec<(usize, Field, Field)>,
+}
+
+impl RecordDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields.is_empty()
+    }
+
+    /// Whether a field of the primary key changed, which makes the new version a different record.
+    pub fn is_primary_key_change(&self, schema: &Schema) -> bool {
+        self.changed_fields
+            .iter()
+            .any(|(index, _, _)| schema.primary_index.contains(index))
+    }
+}
+
 impl Display for Record {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let v = self
@@ -289,10 +385,36 @@ impl Display for Record {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, bincode::Encode, bincode::Decode)]
 /// A CDC event.
 pub enum Operation {
-    Delete { old: Record },
-    Insert { new: Record },
-    Update { old: Record, new: Record },
-    BatchInsert { new: Vec<Record> },
+    Delete {
+        old: Record,
+    },
+    Insert {
+        new: Record,
+    },
+    Update {
+        old: Record,
+        new: Record,
+    },
+    BatchInsert {
+        new: Vec<Record>,
+    },
+    /// Insert `new`, or replace the record with the same primary key. Only sent to sinks that support upserts.
+    Upsert {
+        new: Record,
+    },
+    /// `field` was appended to the schema of table `table_index`. Records sent before this operation don't have
+    /// the field, and are read as if it held `default_value`.
+    AddField {
+        table_index: usize,
+        field: FieldDefinition,
+        default_value: Field,
+    },
+    /// The field named `field_name` was removed from the schema of table `table_index`. Records sent after this
+    /// operation don't have the field.
+    RemoveField {
+        table_index: usize,
+        field_name: String,
+    },
 }
 
 pub type PortHandle = u16;
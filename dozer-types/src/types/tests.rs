@@ -1,4 +1,7 @@
-use crate::types::{field_test_cases, DozerDuration, DozerPoint, Field, TimeUnit};
+use crate::types::{
+    field_test_cases, DozerDuration, DozerPoint, Field, FieldDefinition, FieldType, Record,
+    RecordDiff, Schema, SourceDefinition, TimeUnit,
+};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
@@ -11,6 +14,88 @@ fn data_encoding_len_must_agree_with_encode() {
     }
 }
 
+#[test]
+fn test_primary_key_fields() {
+    let field = |name: &str| {
+        FieldDefinition::new(
+            name.to_string(),
+            FieldType::Int,
+            false,
+            SourceDefinition::Dynamic,
+        )
+    };
+    let schema = Schema {
+        fields: vec![field("a"), field("b"), field("c")],
+        primary_index: vec![2, 0],
+    };
+    assert_eq!(
+        schema.primary_key_fields(),
+        vec![&schema.fields[2], &schema.fields[0]]
+    );
+    assert_eq!(schema.primary_key_field_names(), vec!["c", "a"]);
+    assert!(Schema::new().primary_key_field_names().is_empty());
+}
+
+#[test]
+fn test_record_checksum() {
+    let field = |name: &str| {
+        FieldDefinition::new(
+            name.to_string(),
+            FieldType::String,
+            true,
+            SourceDefinition::Dynamic,
+        )
+    };
+    let schema = Schema {
+        fields: vec![field("a"), field("b")],
+        primary_index: vec![],
+    };
+    let record = |a: Field, b: Field| Record::new(vec![a, b]);
+    let string = |s: &str| Field::String(s.to_string());
+
+    let checksum = record(string("x"), string("y")).checksum(&schema);
+    assert_eq!(checksum, record(string("x"), string("y")).checksum(&schema));
+    assert_ne!(checksum, record(string("x"), string("z")).checksum(&schema));
+    assert_ne!(checksum, record(string("y"), string("x")).checksum(&schema));
+    assert_ne!(
+        record(string("x"), Field::Null).checksum(&schema),
+        record(Field::Null, string("x")).checksum(&schema)
+    );
+}
+
+#[test]
+fn test_record_diff() {
+    let field = |name: &str| {
+        FieldDefinition::new(
+            name.to_string(),
+            FieldType::Int,
+            true,
+            SourceDefinition::Dynamic,
+        )
+    };
+    let schema = Schema {
+        fields: vec![field("id"), field("a"), field("b")],
+        primary_index: vec![0],
+    };
+    let old = Record::new(vec![Field::Int(1), Field::Int(2), Field::Null]);
+
+    let diff = Record::diff(&old, &old, &schema);
+    assert!(diff.is_empty());
+
+    let new = Record::new(vec![Field::Int(1), Field::Int(2), Field::Int(3)]);
+    let diff = Record::diff(&old, &new, &schema);
+    assert_eq!(
+        diff,
+        RecordDiff {
+            changed_fields: vec![(2, Field::Null, Field::Int(3))]
+        }
+    );
+    assert!(!diff.is_primary_key_change(&schema));
+
+    let new = Record::new(vec![Field::Int(4), Field::Int(2), Field::Null]);
+    assert!(Record::diff(&old, &new, &schema).is_primary_key_change(&schema));
+}
+
 #[test]
 fn test_as_conversion() {
     let field = Field::UInt(1);